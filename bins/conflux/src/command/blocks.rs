@@ -0,0 +1,463 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Exports the blocks in the local database to a file, and imports them to
+//! another node without the network sync. The file is a stream of the RLP
+//! encoded blocks, ordered by their epochs, so that the parent and the
+//! referees of a block are always before it.
+
+use cfx_internal_common::StateAvailabilityBoundary;
+use cfxcore::{ConsensusGraphTrait, NodeType, SharedSynchronizationGraph};
+use clap;
+use client::{common::initialize_common_modules, configuration::Configuration};
+use parking_lot::{Condvar, Mutex};
+use primitives::Block;
+use rlp::Rlp;
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    sync::Arc,
+};
+
+/// The bound of the RLP size of an imported block besides its transactions,
+/// i.e., the header with the referees and the custom data.
+const MAX_BLOCK_HEADER_RLP_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, PartialEq)]
+pub enum BlocksCmd {
+    Export(ExportBlocks),
+    Import(ImportBlocks),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExportBlocks {
+    pub file: String,
+    pub from: u64,
+    /// Exports until the last executed epoch in the database if it's `None`.
+    pub to: Option<u64>,
+}
+
+impl ExportBlocks {
+    pub fn new(matches: &clap::ArgMatches) -> Result<Self, String> {
+        let parse_epoch = |name: &str| {
+            matches
+                .value_of(name)
+                .map(|s| {
+                    s.parse::<u64>()
+                        .map_err(|e| format!("Invalid --{}: {}", name, e))
+                })
+                .transpose()
+        };
+        Ok(Self {
+            file: matches
+                .value_of("file")
+                .expect("CLI argument is required; qed")
+                .to_string(),
+            from: parse_epoch("from")?.unwrap_or(1),
+            to: parse_epoch("to")?,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImportBlocks {
+    pub file: String,
+}
+
+impl ImportBlocks {
+    pub fn new(matches: &clap::ArgMatches) -> Self {
+        Self {
+            file: matches
+                .value_of("file")
+                .expect("CLI argument is required; qed")
+                .to_string(),
+        }
+    }
+}
+
+pub fn execute(
+    cmd: BlocksCmd, mut conf: Configuration,
+) -> Result<String, String> {
+    let node_type = conf.node_type();
+    if node_type == NodeType::Light {
+        return Err("Light nodes do not keep the block bodies".into());
+    }
+    let max_block_size =
+        conf.raw_conf.max_block_size_in_bytes + MAX_BLOCK_HEADER_RLP_SIZE;
+    let exit = Arc::new((Mutex::new(false), Condvar::new()));
+    let (
+        _machine,
+        _secret_store,
+        _genesis_accounts,
+        _data_man,
+        _pow,
+        _pos_verifier,
+        _txpool,
+        _consensus,
+        sync_graph,
+        ..,
+    ) = initialize_common_modules(&mut conf, exit.clone(), node_type)?;
+
+    let result = match cmd {
+        BlocksCmd::Export(export_cmd) => export(export_cmd, &sync_graph),
+        BlocksCmd::Import(import_cmd) => {
+            import(import_cmd, &sync_graph, max_block_size)
+        }
+    };
+    *exit.0.lock() = true;
+    exit.1.notify_all();
+    result
+}
+
+fn export(
+    export_cmd: ExportBlocks, sync_graph: &SharedSynchronizationGraph,
+) -> Result<String, String> {
+    let data_man = &sync_graph.data_man;
+    let file = File::create(&export_cmd.file)
+        .map_err(|e| format!("Failed to create {}: {}", export_cmd.file, e))?;
+    let mut writer = BufWriter::new(file);
+
+    let mut epoch = export_cmd.from;
+    let mut block_count = 0;
+    while export_cmd.to.map_or(true, |to| epoch <= to) {
+        let hashes = match data_man.all_epoch_set_hashes_from_db(epoch) {
+            Some(hashes) => hashes,
+            None if export_cmd.to.is_none() => break,
+            None => {
+                return Err(format!("Epoch {} is not in the database", epoch))
+            }
+        };
+        for hash in hashes {
+            let block =
+                data_man.block_by_hash(&hash, false).ok_or_else(|| {
+                    format!("Block {:?} of epoch {} is not found", hash, epoch)
+                })?;
+            write_block(&mut writer, &block)
+                .map_err(|e| format!("Failed to write blocks: {}", e))?;
+            block_count += 1;
+        }
+        epoch += 1;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write blocks: {}", e))?;
+
+    if epoch == export_cmd.from {
+        return Err(format!(
+            "Epoch {} is not in the database",
+            export_cmd.from
+        ));
+    }
+    Ok(format!(
+        "{} blocks of epochs [{}, {}] exported",
+        block_count,
+        export_cmd.from,
+        epoch - 1
+    ))
+}
+
+fn import(
+    import_cmd: ImportBlocks, sync_graph: &SharedSynchronizationGraph,
+    max_block_size: usize,
+) -> Result<String, String> {
+    let file = File::open(&import_cmd.file)
+        .map_err(|e| format!("Failed to open {}: {}", import_cmd.file, e))?;
+    let mut reader = BufReader::new(file);
+
+    recover_local_blocks(sync_graph)?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut last_hash = None;
+    while let Some(mut block) = read_block(&mut reader, max_block_size)
+        .map_err(|e| format!("Invalid block #{}: {}", imported + skipped, e))?
+    {
+        let hash = block.hash();
+        if sync_graph.contains_block_header(&hash) {
+            skipped += 1;
+            continue;
+        }
+        sync_graph.data_man.recover_block(&mut block).map_err(|e| {
+            format!("Failed to recover the transactions of {:?}: {:?}", hash, e)
+        })?;
+
+        let (insert_result, _) = sync_graph.insert_block_header(
+            &mut block.block_header,
+            true,  /* need_to_verify */
+            false, /* bench_mode */
+            false, /* insert_to_consensus */
+            true,  /* persistent */
+        );
+        if !insert_result.should_process_body() {
+            return Err(format!(
+                "Invalid block header {:?}: {:?}",
+                hash, insert_result
+            ));
+        }
+        let insert_result = sync_graph.insert_block(
+            block, true,  /* need_to_verify */
+            true,  /* persistent */
+            false, /* recover_from_db */
+        );
+        if !insert_result.is_valid() {
+            return Err(format!(
+                "Invalid block {:?}: {:?}",
+                hash, insert_result
+            ));
+        }
+        imported += 1;
+        last_hash = Some(hash);
+    }
+
+    if let Some(hash) = last_hash {
+        // The blocks are processed by the consensus worker asynchronously.
+        while sync_graph.is_consensus_worker_busy() {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        sync_graph.consensus.wait_for_generation(&hash);
+    }
+    Ok(format!(
+        "{} blocks imported, {} blocks already in the database, best epoch {}",
+        imported,
+        skipped,
+        sync_graph.consensus.best_epoch_number()
+    ))
+}
+
+/// Recovers the blocks already in the database, as the catch-up phases of the
+/// sync service do, so that the imported blocks are attached to them.
+fn recover_local_blocks(
+    sync_graph: &SharedSynchronizationGraph,
+) -> Result<(), String> {
+    sync_graph.recover_graph_from_db();
+
+    let data_man = &sync_graph.data_man;
+    let storage_config = data_man.storage_manager.config();
+    let stable_hash = data_man.get_cur_consensus_era_stable_hash();
+    let stable_height = data_man
+        .block_header_by_hash(&stable_hash)
+        .expect("stable era block header must exist")
+        .height();
+    *data_man.state_availability_boundary.write() =
+        StateAvailabilityBoundary::new(
+            stable_hash,
+            stable_height,
+            storage_config.full_state_start_height(),
+            storage_config.single_mpt_space,
+        );
+
+    let missing_bodies = sync_graph.consensus.get_blocks_needing_bodies();
+    if !missing_bodies.is_empty() {
+        return Err(format!(
+            "{} blocks in the database miss their bodies, sync the node first",
+            missing_bodies.len()
+        ));
+    }
+    if !sync_graph.complete_filling_block_bodies() {
+        return Err("Failed to recover the blocks in the database".into());
+    }
+    sync_graph.consensus.enter_normal_phase();
+    Ok(())
+}
+
+fn write_block<W: Write>(writer: &mut W, block: &Block) -> io::Result<()> {
+    writer.write_all(&rlp::encode(block))
+}
+
+/// Reads the next block from the stream, or returns `None` at the end of the
+/// stream. The transactions are not recovered.
+fn read_block<R: Read>(
+    reader: &mut R, max_size: usize,
+) -> Result<Option<Block>, String> {
+    match read_rlp_item(reader, max_size) {
+        Ok(Some(item)) => Rlp::new(&item)
+            .as_val()
+            .map(Some)
+            .map_err(|e| format!("{:?}", e)),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Reads the next RLP list from the stream, or returns `None` at the end of
+/// the stream. A list longer than `max_size` is rejected before its payload is
+/// read, so that a corrupted length does not exhaust the memory.
+fn read_rlp_item<R: Read>(
+    reader: &mut R, max_size: usize,
+) -> io::Result<Option<Vec<u8>>> {
+    let mut prefix = [0u8; 1];
+    match reader.read_exact(&mut prefix) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let mut item = prefix.to_vec();
+    let payload_len = match prefix[0] {
+        0xc0..=0xf7 => (prefix[0] - 0xc0) as u64,
+        0xf8..=0xff => {
+            let len_of_len = (prefix[0] - 0xf7) as usize;
+            let mut len_bytes = vec![0u8; len_of_len];
+            reader.read_exact(&mut len_bytes)?;
+            item.extend_from_slice(&len_bytes);
+            len_bytes
+                .iter()
+                .fold(0u64, |len, byte| (len << 8) | *byte as u64)
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "a block must be an RLP list",
+            ))
+        }
+    };
+    let header_len = item.len();
+    let size = usize::try_from(payload_len)
+        .ok()
+        .and_then(|len| len.checked_add(header_len))
+        .filter(|size| *size <= max_size)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the block of {} bytes exceeds the limit {}",
+                    payload_len, max_size
+                ),
+            )
+        })?;
+    item.resize(size, 0);
+    reader.read_exact(&mut item[header_len..])?;
+    Ok(Some(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_block, read_rlp_item, write_block};
+    use cfx_types::{Address, U256};
+    use cfxkey::{Generator, Random};
+    use primitives::{
+        block_header::BlockHeaderBuilder, transaction::NativeTransaction,
+        Action, Block, Transaction,
+    };
+    use rlp::RlpStream;
+    use std::{io, sync::Arc};
+
+    const MAX_SIZE: usize = 1024;
+
+    fn new_test_block(height: u64, tx_count: usize) -> Block {
+        let key = Random.generate().unwrap();
+        let transactions = (0..tx_count)
+            .map(|nonce| {
+                let tx: Transaction = NativeTransaction {
+                    nonce: U256::from(nonce),
+                    gas_price: U256::one(),
+                    gas: U256::from(21000),
+                    action: Action::Call(Address::random()),
+                    value: U256::zero(),
+                    storage_limit: 0,
+                    epoch_height: height,
+                    chain_id: 1,
+                    data: vec![],
+                }
+                .into();
+                Arc::new(tx.sign(key.secret()))
+            })
+            .collect();
+        let header = BlockHeaderBuilder::new().with_height(height).build();
+        Block::new(header, transactions)
+    }
+
+    #[test]
+    fn test_read_rlp_items() {
+        let mut short = RlpStream::new_list(2);
+        short.append(&1u64).append(&2u64);
+        let mut long = RlpStream::new_list(1);
+        long.append(&vec![7u8; 100]);
+        let (short, long) = (short.out().to_vec(), long.out().to_vec());
+
+        let mut stream = short.clone();
+        stream.extend_from_slice(&long);
+        let mut reader = stream.as_slice();
+        assert_eq!(read_rlp_item(&mut reader, MAX_SIZE).unwrap(), Some(short));
+        assert_eq!(
+            read_rlp_item(&mut reader, MAX_SIZE).unwrap(),
+            Some(long.clone())
+        );
+        assert_eq!(read_rlp_item(&mut reader, MAX_SIZE).unwrap(), None);
+
+        let mut truncated = &long[..long.len() - 1];
+        assert!(read_rlp_item(&mut truncated, MAX_SIZE).is_err());
+        // The size includes the header of the list.
+        assert!(read_rlp_item(&mut long.as_slice(), long.len()).is_ok());
+        assert!(read_rlp_item(&mut long.as_slice(), long.len() - 1).is_err());
+    }
+
+    #[test]
+    fn test_read_oversized_item() {
+        // A list claiming 2^64 - 1 bytes of payload, followed by nothing.
+        let mut stream = vec![0xff];
+        stream.extend_from_slice(&[0xff; 8]);
+        let err = read_rlp_item(&mut stream.as_slice(), MAX_SIZE).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut stream = vec![0xf9, 0x10, 0x00];
+        stream.resize(3 + 0x1000, 0);
+        let err = read_rlp_item(&mut stream.as_slice(), MAX_SIZE).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let blocks = vec![
+            new_test_block(1, 0),
+            new_test_block(2, 3),
+            new_test_block(3, 1),
+        ];
+        let mut file = Vec::new();
+        for block in &blocks {
+            write_block(&mut file, block).unwrap();
+        }
+
+        let mut reader = file.as_slice();
+        for block in &blocks {
+            let read = read_block(&mut reader, 64 * 1024).unwrap().unwrap();
+            assert_eq!(read.hash(), block.hash());
+            assert_eq!(
+                read.transactions
+                    .iter()
+                    .map(|tx| tx.hash())
+                    .collect::<Vec<_>>(),
+                block
+                    .transactions
+                    .iter()
+                    .map(|tx| tx.hash())
+                    .collect::<Vec<_>>()
+            );
+        }
+        assert!(read_block(&mut reader, 64 * 1024).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_read_truncated_and_corrupted_file() {
+        let mut file = Vec::new();
+        write_block(&mut file, &new_test_block(1, 1)).unwrap();
+        write_block(&mut file, &new_test_block(2, 2)).unwrap();
+
+        // The last block is cut in the middle.
+        let truncated = &file[..file.len() - 10];
+        let mut reader = truncated;
+        assert!(read_block(&mut reader, 64 * 1024).unwrap().is_some());
+        assert!(read_block(&mut reader, 64 * 1024).is_err());
+
+        // A list which is not a block.
+        let mut corrupted = RlpStream::new_list(3);
+        corrupted.append(&1u64).append(&2u64).append(&3u64);
+        let corrupted = corrupted.out().to_vec();
+        assert!(read_block(&mut corrupted.as_slice(), 64 * 1024).is_err());
+
+        // A string instead of a list.
+        let mut reader: &[u8] = &[0x83, 1, 2, 3];
+        assert!(read_block(&mut reader, 64 * 1024).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Dumps the state of both spaces at an epoch from the local database. The
+//! snapshot is streamed to the output, so the whole state is never loaded
+//! into memory.
+
+use cfx_storage::{StorageManagerTrait, StorageStateTrait};
+use cfx_types::{Address, H256};
+use cfxcore::{BlockDataManager, NodeType};
+use clap;
+use client::{common::initialize_common_modules, configuration::Configuration};
+use parking_lot::{Condvar, Mutex};
+use primitives::{
+    Account, CheckInput, CodeInfo, DepositList, SponsorInfo, StorageKey,
+    StorageKeyWithSpace, StorageLayout, StorageValue, VoteStakeList,
+};
+use rlp::{DecoderError, Rlp, RlpStream};
+use rustc_hex::ToHex;
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    sync::Arc,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum DumpFormat {
+    /// A JSON object per line, with the keys and the values decoded.
+    Jsonl,
+    /// A RLP list of the raw key and value per record.
+    Rlp,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DumpState {
+    pub epoch: u64,
+    pub file: String,
+    pub format: DumpFormat,
+}
+
+impl DumpState {
+    pub fn new(matches: &clap::ArgMatches) -> Result<Self, String> {
+        let epoch = matches
+            .value_of("epoch")
+            .expect("CLI argument is required; qed")
+            .parse()
+            .map_err(|e| format!("Invalid --epoch: {}", e))?;
+        let format = match matches.value_of("format") {
+            Some("rlp") => DumpFormat::Rlp,
+            _ => DumpFormat::Jsonl,
+        };
+        Ok(Self {
+            epoch,
+            file: matches
+                .value_of("file")
+                .expect("CLI argument is required; qed")
+                .to_string(),
+            format,
+        })
+    }
+}
+
+pub fn execute(
+    cmd: DumpState, mut conf: Configuration,
+) -> Result<String, String> {
+    let node_type = conf.node_type();
+    if node_type == NodeType::Light {
+        return Err("Light nodes do not keep the state".into());
+    }
+    let exit = Arc::new((Mutex::new(false), Condvar::new()));
+    let (_machine, _secret_store, _genesis_accounts, data_man, ..) =
+        initialize_common_modules(&mut conf, exit.clone(), node_type)?;
+
+    let result = dump(cmd, &data_man);
+    *exit.0.lock() = true;
+    exit.1.notify_all();
+    result
+}
+
+fn dump(cmd: DumpState, data_man: &BlockDataManager) -> Result<String, String> {
+    let pivot_hash = data_man
+        .executed_epoch_set_hashes_from_db(cmd.epoch)
+        .and_then(|hashes| hashes.last().cloned())
+        .ok_or_else(|| format!("Epoch {} is not in the database", cmd.epoch))?;
+    let state_index = data_man
+        .get_state_readonly_index(&pivot_hash)
+        .ok_or_else(|| format!("Epoch {} has not been executed", cmd.epoch))?;
+    let mut state = data_man
+        .storage_manager
+        .get_state_no_commit(
+            state_index,
+            /* try_open = */ false,
+            /* space = */ None,
+        )
+        .map_err(|e| format!("Failed to open the state: {}", e))?
+        .ok_or_else(|| {
+            format!("The state of epoch {} has been removed", cmd.epoch)
+        })?;
+
+    let file = File::create(&cmd.file)
+        .map_err(|e| format!("Failed to create {}: {}", cmd.file, e))?;
+    let mut writer = BufWriter::new(file);
+    let mut record_count = 0;
+    state
+        .iterate_all(&mut |(key, value)| {
+            match cmd.format {
+                DumpFormat::Jsonl => {
+                    let record = decode_record(&key, &value)?;
+                    writeln!(writer, "{}", record)?;
+                }
+                DumpFormat::Rlp => {
+                    let mut stream = RlpStream::new_list(2);
+                    stream.append(&key).append(&value.to_vec());
+                    writer.write_all(&stream.out())?;
+                }
+            }
+            record_count += 1;
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to dump the state: {}", e))?;
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write {}: {}", cmd.file, e))?;
+
+    Ok(format!(
+        "{} records of epoch {} ({:?}) dumped",
+        record_count, cmd.epoch, pivot_hash
+    ))
+}
+
+fn to_hex(bytes: &[u8]) -> String { format!("0x{}", bytes.to_hex::<String>()) }
+
+fn sponsor_info_json(sponsor_info: &SponsorInfo) -> Value {
+    json!({
+        "sponsorForGas": sponsor_info.sponsor_for_gas,
+        "sponsorForCollateral": sponsor_info.sponsor_for_collateral,
+        "sponsorGasBound": sponsor_info.sponsor_gas_bound,
+        "sponsorBalanceForGas": sponsor_info.sponsor_balance_for_gas,
+        "sponsorBalanceForCollateral":
+            sponsor_info.sponsor_balance_for_collateral,
+        "unusedStoragePoints": sponsor_info.storage_points.as_ref().map(|p| p.unused),
+        "usedStoragePoints": sponsor_info.storage_points.as_ref().map(|p| p.used),
+    })
+}
+
+/// Decodes a key value pair of the state into a typed record.
+fn decode_record(key: &[u8], value: &[u8]) -> Result<Value, String> {
+    let storage_key = StorageKeyWithSpace::from_key_bytes::<CheckInput>(key)?;
+    let space = storage_key.space;
+    let decode_err = |e: DecoderError| {
+        format!("Invalid value of key {}: {:?}", to_hex(key), e)
+    };
+    let record = match storage_key.key {
+        StorageKey::AccountKey(address_bytes) => {
+            let account = Account::new_from_rlp(
+                Address::from_slice(address_bytes),
+                &Rlp::new(value),
+            )
+            .map_err(|e| format!("Invalid account {}: {:?}", to_hex(key), e))?;
+            json!({
+                "type": "account",
+                "space": space,
+                "address": Address::from_slice(address_bytes),
+                "balance": account.balance,
+                "nonce": account.nonce,
+                "codeHash": account.code_hash,
+                "stakingBalance": account.staking_balance,
+                "collateralForStorage": account.collateral_for_storage,
+                "accumulatedInterestReturn": account.accumulated_interest_return,
+                "admin": account.admin,
+                "sponsorInfo": sponsor_info_json(&account.sponsor_info),
+            })
+        }
+        StorageKey::StorageRootKey(address_bytes) => {
+            let layout = StorageLayout::from_bytes(value)?;
+            let StorageLayout::Regular(version) = layout;
+            json!({
+                "type": "storageLayout",
+                "space": space,
+                "address": Address::from_slice(address_bytes),
+                "version": version,
+            })
+        }
+        StorageKey::StorageKey {
+            address_bytes,
+            storage_key,
+        } => {
+            let storage_value: StorageValue =
+                Rlp::new(value).as_val().map_err(decode_err)?;
+            json!({
+                "type": "storage",
+                "space": space,
+                "address": Address::from_slice(address_bytes),
+                "key": to_hex(storage_key),
+                "value": storage_value.value,
+                "owner": storage_value.owner,
+            })
+        }
+        StorageKey::CodeKey {
+            address_bytes,
+            code_hash_bytes,
+        } => {
+            let code_info: CodeInfo =
+                Rlp::new(value).as_val().map_err(decode_err)?;
+            json!({
+                "type": "code",
+                "space": space,
+                "address": Address::from_slice(address_bytes),
+                "codeHash": H256::from_slice(code_hash_bytes),
+                "code": to_hex(&code_info.code),
+                "owner": code_info.owner,
+            })
+        }
+        StorageKey::DepositListKey(address_bytes) => {
+            let deposit_list: DepositList =
+                Rlp::new(value).as_val().map_err(decode_err)?;
+            json!({
+                "type": "depositList",
+                "space": space,
+                "address": Address::from_slice(address_bytes),
+                "deposits": deposit_list.0,
+            })
+        }
+        StorageKey::VoteListKey(address_bytes) => {
+            let vote_list: VoteStakeList =
+                Rlp::new(value).as_val().map_err(decode_err)?;
+            json!({
+                "type": "voteList",
+                "space": space,
+                "address": Address::from_slice(address_bytes),
+                "votes": vote_list.0,
+            })
+        }
+        // The key is not written by the current execution.
+        StorageKey::CodeRootKey(_) => json!({
+            "type": "unknown",
+            "key": to_hex(key),
+            "value": to_hex(value),
+        }),
+    };
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_record;
+    use cfx_types::{Address, AddressSpaceUtil, U256};
+    use primitives::{Account, StorageKey, StorageValue};
+    use serde_json::json;
+
+    #[test]
+    fn test_decode_records() {
+        let address = Address::from_low_u64_be(0x1234);
+        let mut account = Account::new_empty(&address.with_evm_space());
+        account.balance = U256::from(100);
+        account.nonce = U256::from(2);
+        let key = StorageKey::new_account_key(&address)
+            .with_evm_space()
+            .to_key_bytes();
+        let record = decode_record(&key, &rlp::encode(&account)).unwrap();
+        assert_eq!(record["type"], "account");
+        assert_eq!(record["space"], "evm");
+        assert_eq!(record["balance"], json!(U256::from(100)));
+        assert_eq!(record["nonce"], json!(U256::from(2)));
+
+        let slot = [1u8; 32];
+        let key = StorageKey::new_storage_key(&address, &slot)
+            .with_native_space()
+            .to_key_bytes();
+        let value = StorageValue {
+            value: U256::from(7),
+            owner: Some(address),
+        };
+        let record = decode_record(&key, &rlp::encode(&value)).unwrap();
+        assert_eq!(record["type"], "storage");
+        assert_eq!(record["space"], "native");
+        assert_eq!(record["key"], format!("0x{}", "01".repeat(32)));
+        assert_eq!(record["value"], json!(U256::from(7)));
+        assert_eq!(record["owner"], json!(address));
+    }
+}
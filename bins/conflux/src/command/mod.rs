@@ -3,5 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 pub mod account;
+pub mod blocks;
+pub mod dump_state;
 pub mod helpers;
 pub mod rpc;
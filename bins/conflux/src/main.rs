@@ -17,7 +17,11 @@ use client::{
     full::FullClient,
     light::LightClient,
 };
-use command::account::{AccountCmd, ImportAccounts, ListAccounts, NewAccount};
+use command::{
+    account::{AccountCmd, ImportAccounts, ListAccounts, NewAccount},
+    blocks::{BlocksCmd, ExportBlocks, ImportBlocks},
+    dump_state::DumpState,
+};
 use log::{info, LevelFilter};
 use log4rs::{
     append::{console::ConsoleAppender, file::FileAppender},
@@ -203,6 +207,30 @@ fn handle_sub_command(matches: &ArgMatches) -> Result<Option<String>, String> {
         return Ok(Some(execute_output));
     }
 
+    // block export/import sub-commands
+    let blocks_cmd = match matches.subcommand() {
+        ("export-blocks", Some(export_matches)) => {
+            Some(BlocksCmd::Export(ExportBlocks::new(export_matches)?))
+        }
+        ("import-blocks", Some(import_matches)) => {
+            Some(BlocksCmd::Import(ImportBlocks::new(import_matches)))
+        }
+        _ => None,
+    };
+    if let Some(blocks_cmd) = blocks_cmd {
+        let conf = Configuration::parse(matches)?;
+        let execute_output = command::blocks::execute(blocks_cmd, conf)?;
+        return Ok(Some(execute_output));
+    }
+
+    // state dump sub-command
+    if let ("dump-state", Some(dump_matches)) = matches.subcommand() {
+        let dump_cmd = DumpState::new(dump_matches)?;
+        let conf = Configuration::parse(matches)?;
+        let execute_output = command::dump_state::execute(dump_cmd, conf)?;
+        return Ok(Some(execute_output));
+    }
+
     // general RPC commands
     let mut subcmd_matches = matches;
     while let Some(m) = subcmd_matches.subcommand().1 {
@@ -0,0 +1,145 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! The JSON format of the filled GeneralStateTests of Ethereum. The numbers
+//! and the bytes are kept as hex strings and parsed when the test runs, since
+//! the fixtures are not strict about leading zeros.
+
+use cfx_types::{Address, H256, U256};
+use primitives::AccessList;
+use serde_derive::Deserialize;
+use std::{collections::BTreeMap, str::FromStr};
+
+/// The tests in a fixture file, keyed by the test names.
+pub type StateTests = BTreeMap<String, StateTest>;
+
+#[derive(Debug, Deserialize)]
+pub struct StateTest {
+    pub env: TestEnv,
+    pub pre: BTreeMap<String, TestAccount>,
+    pub transaction: TestTransaction,
+    /// The expected results of each fork.
+    pub post: BTreeMap<String, Vec<PostResult>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestEnv {
+    pub current_coinbase: String,
+    #[serde(default)]
+    pub current_difficulty: Option<String>,
+    pub current_gas_limit: String,
+    pub current_number: String,
+    pub current_timestamp: String,
+    #[serde(default)]
+    pub current_base_fee: Option<String>,
+    #[serde(default)]
+    pub previous_hash: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TestAccount {
+    pub balance: String,
+    pub code: String,
+    pub nonce: String,
+    pub storage: BTreeMap<String, String>,
+}
+
+/// The transaction template. A test case picks the data, the gas limit and
+/// the value by the indexes.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestTransaction {
+    pub data: Vec<String>,
+    pub gas_limit: Vec<String>,
+    pub value: Vec<String>,
+    pub nonce: String,
+    pub secret_key: String,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub gas_price: Option<String>,
+    #[serde(default)]
+    pub max_fee_per_gas: Option<String>,
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<String>,
+    #[serde(default)]
+    pub access_lists: Option<Vec<Option<AccessList>>>,
+    #[serde(default)]
+    pub authorization_list: Option<Vec<TestAuthorization>>,
+    #[serde(default)]
+    pub blob_versioned_hashes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestAuthorization {
+    pub chain_id: String,
+    pub address: String,
+    pub nonce: String,
+    #[serde(default)]
+    pub y_parity: Option<String>,
+    #[serde(default)]
+    pub v: Option<String>,
+    pub r: String,
+    pub s: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostResult {
+    /// The expected state root.
+    pub hash: String,
+    /// The expected hash of the RLP encoded logs.
+    pub logs: String,
+    pub indexes: TxIndexes,
+    #[serde(default)]
+    pub expect_exception: Option<String>,
+    /// The expected post state, which is only filled by the recent fixtures.
+    #[serde(default)]
+    pub state: Option<BTreeMap<String, TestAccount>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TxIndexes {
+    pub data: usize,
+    pub gas: usize,
+    pub value: usize,
+}
+
+fn strip_hex_prefix(s: &str) -> &str { s.strip_prefix("0x").unwrap_or(s) }
+
+pub fn parse_u256(s: &str) -> Result<U256, String> {
+    let hex = strip_hex_prefix(s);
+    if hex.is_empty() {
+        return Ok(U256::zero());
+    }
+    U256::from_str(hex).map_err(|e| format!("invalid number {}: {:?}", s, e))
+}
+
+pub fn parse_u64(s: &str) -> Result<u64, String> {
+    let value = parse_u256(s)?;
+    if value > U256::from(u64::MAX) {
+        return Err(format!("number {} overflows u64", s));
+    }
+    Ok(value.as_u64())
+}
+
+pub fn parse_bytes(s: &str) -> Result<Vec<u8>, String> {
+    rustc_hex::FromHex::from_hex(strip_hex_prefix(s))
+        .map_err(|e| format!("invalid bytes {}: {:?}", s, e))
+}
+
+pub fn parse_address(s: &str) -> Result<Address, String> {
+    Address::from_str(strip_hex_prefix(s))
+        .map_err(|e| format!("invalid address {}: {:?}", s, e))
+}
+
+/// Parses a 32-byte word, which may be written as a number without the
+/// leading zeros.
+pub fn parse_h256(s: &str) -> Result<H256, String> {
+    let mut word = H256::zero();
+    parse_u256(s)?.to_big_endian(word.as_bytes_mut());
+    Ok(word)
+}
@@ -0,0 +1,208 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Runs the GeneralStateTests of Ethereum through the eSpace execution path.
+
+mod fixture;
+mod memory_storage;
+mod runner;
+mod trie;
+
+use cfx_executor::machine::{new_machine_with_builtin, Machine, VmFactory};
+use fixture::StateTests;
+use runner::{fork_params, run_case, CaseOutcome};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+struct Config {
+    paths: Vec<PathBuf>,
+    forks: Vec<String>,
+    test_filter: Option<String>,
+    quiet: bool,
+}
+
+fn parse_config() -> Config {
+    let matches = clap::App::new("evm-state-test")
+        .version("0.1")
+        .about(
+"Run the GeneralStateTests of Ethereum through the eSpace execution path
+Example usage:
+    evm-state-test --fork Cancun --test sstore ./GeneralStateTests/stSStoreTest")
+        .arg(
+            clap::Arg::with_name("path")
+                .value_name("PATH")
+                .help("Specifies the test files, or the directories to search for the test files")
+                .multiple(true)
+                .required(true),
+        )
+        .arg(
+            clap::Arg::with_name("fork")
+                .long("fork")
+                .value_name("NAME")
+                .help("Only runs the cases of the fork, e.g. Cancun. Runs the cases of all the forks if not set")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            clap::Arg::with_name("test")
+                .long("test")
+                .value_name("PATTERN")
+                .help("Only runs the tests whose names contain the pattern")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("quiet")
+                .long("quiet")
+                .help("Only reports the failed cases and the summary"),
+        )
+        .get_matches();
+
+    Config {
+        paths: matches
+            .values_of("path")
+            .unwrap()
+            .map(PathBuf::from)
+            .collect(),
+        forks: matches
+            .values_of("fork")
+            .map(|forks| forks.map(String::from).collect())
+            .unwrap_or_default(),
+        test_filter: matches.value_of("test").map(String::from),
+        quiet: matches.is_present("quiet"),
+    }
+}
+
+fn collect_test_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> = match fs::read_dir(path) {
+            Ok(entries) => {
+                entries.filter_map(|e| e.ok()).map(|e| e.path()).collect()
+            }
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", path.display(), e);
+                return;
+            }
+        };
+        entries.sort();
+        for entry in entries {
+            collect_test_files(&entry, files);
+        }
+    } else if path.extension().map_or(false, |ext| ext == "json") {
+        files.push(path.to_path_buf());
+    }
+}
+
+fn load_tests(path: &Path) -> Result<StateTests, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+#[derive(Default)]
+struct Summary {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+}
+
+fn main() {
+    let config = parse_config();
+
+    let mut files = Vec::new();
+    for path in &config.paths {
+        collect_test_files(path, &mut files);
+    }
+
+    // The machines with the upgrades of each fork, or `None` for the forks
+    // not supported.
+    let mut machines: BTreeMap<String, Option<Machine>> = BTreeMap::new();
+    let mut summary = Summary::default();
+    for file in files {
+        let tests = match load_tests(&file) {
+            Ok(tests) => tests,
+            Err(e) => {
+                println!(
+                    "FAIL {}: invalid state test file, {}",
+                    file.display(),
+                    e
+                );
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        for (name, test) in &tests {
+            if let Some(filter) = &config.test_filter {
+                if !name.contains(filter.as_str()) {
+                    continue;
+                }
+            }
+            for (fork, posts) in &test.post {
+                if !config.forks.is_empty() && !config.forks.contains(fork) {
+                    continue;
+                }
+                let machine =
+                    machines.entry(fork.clone()).or_insert_with(|| {
+                        fork_params(fork).map(|params| {
+                            new_machine_with_builtin(
+                                params,
+                                VmFactory::new(1024 * 32),
+                            )
+                        })
+                    });
+                for (index, post) in posts.iter().enumerate() {
+                    let case = format!(
+                        "{} [{}] #{} (d={}, g={}, v={})",
+                        name,
+                        fork,
+                        index,
+                        post.indexes.data,
+                        post.indexes.gas,
+                        post.indexes.value
+                    );
+                    let outcome = match machine {
+                        Some(machine) => run_case(machine, test, post),
+                        None => CaseOutcome::Skipped(format!(
+                            "fork {} is not supported",
+                            fork
+                        )),
+                    };
+                    match outcome {
+                        CaseOutcome::Passed => {
+                            summary.passed += 1;
+                            if !config.quiet {
+                                println!("PASS {}", case);
+                            }
+                        }
+                        CaseOutcome::Failed(errors) => {
+                            summary.failed += 1;
+                            println!("FAIL {}", case);
+                            for error in errors {
+                                println!("    {}", error);
+                            }
+                        }
+                        CaseOutcome::Skipped(reason) => {
+                            summary.skipped += 1;
+                            if !config.quiet {
+                                println!("SKIP {}: {}", case, reason);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    println!(
+        "{} passed, {} failed, {} skipped",
+        summary.passed, summary.failed, summary.skipped
+    );
+    if summary.failed > 0 {
+        process::exit(1);
+    }
+}
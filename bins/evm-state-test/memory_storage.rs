@@ -0,0 +1,118 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_internal_common::StateRootWithAuxInfo;
+use cfx_storage::{
+    ErrorKind, MptKeyValue, Result as StorageResult, StorageStateTrait,
+};
+use parking_lot::RwLock;
+use primitives::{EpochId, StorageKeyWithSpace, MERKLE_NULL_NODE};
+use std::{collections::BTreeMap, sync::Arc};
+
+type RawStorage = BTreeMap<Vec<u8>, Box<[u8]>>;
+
+/// A storage keeping the raw key-values in memory without any merkle tree.
+/// The clones share the same key-values, so the storage committed by a
+/// `State` can be opened again by another `State`.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    contents: Arc<RwLock<RawStorage>>,
+}
+
+impl MemoryStorage {
+    /// Returns all the raw key-values ordered by the keys.
+    pub fn entries(&self) -> Vec<(Vec<u8>, Box<[u8]>)> {
+        self.contents
+            .read()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    fn entries_with_prefix(&self, prefix: &[u8]) -> Vec<MptKeyValue> {
+        self.contents
+            .read()
+            .iter()
+            .filter(|(k, _)| k.starts_with(prefix))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+impl StorageStateTrait for MemoryStorage {
+    fn get(
+        &self, access_key: StorageKeyWithSpace,
+    ) -> StorageResult<Option<Box<[u8]>>> {
+        Ok(self
+            .contents
+            .read()
+            .get(&access_key.to_key_bytes())
+            .cloned())
+    }
+
+    fn set(
+        &mut self, access_key: StorageKeyWithSpace, value: Box<[u8]>,
+    ) -> StorageResult<()> {
+        self.contents
+            .write()
+            .insert(access_key.to_key_bytes(), value);
+        Ok(())
+    }
+
+    fn delete(&mut self, access_key: StorageKeyWithSpace) -> StorageResult<()> {
+        self.contents.write().remove(&access_key.to_key_bytes());
+        Ok(())
+    }
+
+    fn delete_test_only(
+        &mut self, access_key: StorageKeyWithSpace,
+    ) -> StorageResult<Option<Box<[u8]>>> {
+        Ok(self.contents.write().remove(&access_key.to_key_bytes()))
+    }
+
+    fn delete_all(
+        &mut self, access_key_prefix: StorageKeyWithSpace,
+    ) -> StorageResult<Option<Vec<MptKeyValue>>> {
+        let deleted =
+            self.entries_with_prefix(&access_key_prefix.to_key_bytes());
+        let mut contents = self.contents.write();
+        for (k, _) in &deleted {
+            contents.remove(k);
+        }
+        Ok(Some(deleted))
+    }
+
+    fn read_all(
+        &mut self, access_key_prefix: StorageKeyWithSpace,
+    ) -> StorageResult<Option<Vec<MptKeyValue>>> {
+        Ok(Some(
+            self.entries_with_prefix(&access_key_prefix.to_key_bytes()),
+        ))
+    }
+
+    fn iterate_all(
+        &mut self, callback: &mut dyn FnMut(MptKeyValue) -> StorageResult<()>,
+    ) -> StorageResult<()> {
+        for kv in self.entries_with_prefix(&[]) {
+            callback(kv)?;
+        }
+        Ok(())
+    }
+
+    // The state root of Conflux is not used by the state tests, which check
+    // the root of the Ethereum state trie instead.
+    fn compute_state_root(&mut self) -> StorageResult<StateRootWithAuxInfo> {
+        Ok(StateRootWithAuxInfo::genesis(&MERKLE_NULL_NODE))
+    }
+
+    fn get_state_root(&self) -> StorageResult<StateRootWithAuxInfo> {
+        Err(ErrorKind::Msg("No state root".to_owned()).into())
+    }
+
+    fn commit(
+        &mut self, _epoch: EpochId,
+    ) -> StorageResult<StateRootWithAuxInfo> {
+        self.compute_state_root()
+    }
+}
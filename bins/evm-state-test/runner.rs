@@ -0,0 +1,643 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{
+    fixture::{
+        parse_address, parse_bytes, parse_h256, parse_u256, parse_u64,
+        PostResult, StateTest, TestAccount, TestAuthorization, TestTransaction,
+        TxIndexes,
+    },
+    memory_storage::MemoryStorage,
+    trie::sec_trie_root,
+};
+use cfx_executor::{
+    executive::{
+        ExecutionError, ExecutionOutcome, ExecutiveContext, TransactOptions,
+    },
+    machine::Machine,
+    spec::CommonParams,
+    state::{CleanupMode, State},
+    substate::Substate,
+};
+use cfx_statedb::{Error as DbError, StateDb};
+use cfx_types::{
+    Address, AddressSpaceUtil, AddressWithSpace, Space, SpaceMap, H256, U256,
+};
+use cfx_vm_types::Env;
+use cfxkey::Secret;
+use keccak_hash::keccak;
+use primitives::{
+    transaction::{
+        Eip1559Transaction, Eip155Transaction, Eip2930Transaction,
+        Eip7702Transaction, EthereumTransaction,
+    },
+    Action, AuthorizationListItem, CheckInput, LogEntry, SignedTransaction,
+    StorageKey, StorageKeyWithSpace, Transaction,
+};
+use rlp::RlpStream;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The chain id of the transactions in the state tests.
+const CHAIN_ID: u32 = 1;
+
+pub enum CaseOutcome {
+    Passed,
+    Failed(Vec<String>),
+    Skipped(String),
+}
+
+/// An account of the eSpace, in the form to compare with the fixtures.
+#[derive(Debug, PartialEq)]
+struct PlainAccount {
+    balance: U256,
+    nonce: U256,
+    code: Vec<u8>,
+    /// The non-zero storage entries.
+    storage: BTreeMap<H256, U256>,
+}
+
+type PlainState = BTreeMap<Address, PlainAccount>;
+
+/// The transition heights activating the upgrades of the Ethereum fork, or
+/// `None` if the fork is not supported. The eSpace features introduced by a
+/// later fork are disabled by moving their transitions to the infinity.
+pub fn fork_params(fork: &str) -> Option<CommonParams> {
+    const NEVER: u64 = u64::MAX;
+
+    let rank = match fork {
+        "Berlin" => 0,
+        "London" | "Merge" | "Paris" => 1,
+        "Shanghai" => 2,
+        "Cancun" => 3,
+        "Prague" => 4,
+        _ => return None,
+    };
+    let mut params = CommonParams::default();
+    let numbers = &mut params.transition_numbers;
+    let heights = &mut params.transition_heights;
+    if rank < 1 {
+        heights.cip1559 = NEVER;
+    }
+    if rank < 2 {
+        numbers.cip119 = NEVER;
+    }
+    if rank < 3 {
+        numbers.cancun_opcodes = NEVER;
+        numbers.cip144 = NEVER;
+    }
+    if rank < 4 {
+        numbers.cip2537 = NEVER;
+        heights.cip7702 = NEVER;
+    }
+    // RIP-7212 is not adopted by any of the supported forks.
+    numbers.cip7212 = NEVER;
+    Some(params)
+}
+
+/// Runs the transaction picked by `post` on the pre-state of `test` through
+/// the eSpace execution path, and checks the state root, the logs hash and
+/// the post-state, if filled, against the expectation.
+pub fn run_case(
+    machine: &Machine, test: &StateTest, post: &PostResult,
+) -> CaseOutcome {
+    if test.transaction.blob_versioned_hashes.is_some() {
+        return CaseOutcome::Skipped(
+            "blob transactions are not supported by eSpace".into(),
+        );
+    }
+    match check_case(machine, test, post) {
+        Ok(errors) if errors.is_empty() => CaseOutcome::Passed,
+        Ok(errors) => CaseOutcome::Failed(errors),
+        Err(e) => CaseOutcome::Failed(vec![e]),
+    }
+}
+
+fn check_case(
+    machine: &Machine, test: &StateTest, post: &PostResult,
+) -> Result<Vec<String>, String> {
+    let storage = MemoryStorage::default();
+    let mut state = open_state(&storage)?;
+    for (address, account) in &test.pre {
+        let address = parse_address(address)?.with_evm_space();
+        write_account(&mut state, &address, &parse_account(account)?)?;
+    }
+    state
+        .commit(H256::zero(), None)
+        .map_err(|e| format!("failed to commit the pre-state: {}", e))?;
+
+    let env = make_env(test)?;
+    let spec = machine.spec(env.number, env.epoch_height);
+
+    let mut errors = Vec::new();
+
+    // A transaction which fails in the EVM is still valid, while the others
+    // are expected to be rejected with an exception.
+    let (rejection, logs) = if let Some(reason) =
+        invalid_encoding(&test.transaction)?
+    {
+        (Some(reason), vec![])
+    } else {
+        let tx = make_transaction(&test.transaction, &post.indexes)?;
+        let mut state = open_state(&storage)?;
+        let outcome = ExecutiveContext::new(&mut state, &env, machine, &spec)
+            .transact(&tx, TransactOptions::default())
+            .map_err(|e| format!("failed to execute: {}", e))?;
+        state
+            .commit(H256::zero(), None)
+            .map_err(|e| format!("failed to commit the post-state: {}", e))?;
+        execution_result(outcome)
+    };
+    match (&post.expect_exception, rejection) {
+        (Some(expected), None) => errors.push(format!(
+            "expected exception {}, but the transaction is valid",
+            expected
+        )),
+        (None, Some(rejection)) => errors.push(format!(
+            "unexpected rejection of the transaction: {}",
+            rejection
+        )),
+        _ => {}
+    }
+
+    let logs_hash = logs_hash(&logs);
+    let expected_logs_hash = parse_h256(&post.logs)?;
+    if logs_hash != expected_logs_hash {
+        errors.push(format!(
+            "logs hash mismatch: expected {:?}, got {:?}",
+            expected_logs_hash, logs_hash
+        ));
+    }
+
+    let post_state = read_state(&storage)?;
+    let state_root = state_root(&post_state);
+    let expected_state_root = parse_h256(&post.hash)?;
+    if state_root != expected_state_root {
+        errors.push(format!(
+            "state root mismatch: expected {:?}, got {:?}",
+            expected_state_root, state_root
+        ));
+    }
+
+    if let Some(expected) = &post.state {
+        let mut expected_state = PlainState::new();
+        for (address, account) in expected {
+            expected_state
+                .insert(parse_address(address)?, parse_account(account)?);
+        }
+        compare_state(&expected_state, &post_state, &mut errors);
+    }
+
+    Ok(errors)
+}
+
+/// The rejection and the logs of the executed transaction.
+fn execution_result(
+    outcome: ExecutionOutcome,
+) -> (Option<String>, Vec<LogEntry>) {
+    match outcome {
+        ExecutionOutcome::NotExecutedDrop(e) => {
+            (Some(format!("{:?}", e)), vec![])
+        }
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            (Some(format!("{:?}", e)), vec![])
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(_),
+            executed,
+        )
+        | ExecutionOutcome::Finished(executed) => (None, executed.logs),
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, executed) => {
+            (Some(format!("{:?}", e)), executed.logs)
+        }
+    }
+}
+
+/// The reason why the transaction is rejected before its execution, if any
+/// of its fields is out of the range allowed by the encoding.
+fn invalid_encoding(tx: &TestTransaction) -> Result<Option<String>, String> {
+    for auth in tx.authorization_list.iter().flatten() {
+        let y_parity = parse_u64(authorization_y_parity(auth)?)?;
+        if y_parity > 1 {
+            return Ok(Some(format!(
+                "yParity {} of the authorization is out of range",
+                y_parity
+            )));
+        }
+    }
+    Ok(None)
+}
+
+fn open_state(storage: &MemoryStorage) -> Result<State, String> {
+    State::new(StateDb::new(Box::new(storage.clone())))
+        .map_err(|e| format!("failed to open the state: {}", e))
+}
+
+fn parse_account(account: &TestAccount) -> Result<PlainAccount, String> {
+    let mut storage = BTreeMap::new();
+    for (key, value) in &account.storage {
+        let value = parse_u256(value)?;
+        if !value.is_zero() {
+            storage.insert(parse_h256(key)?, value);
+        }
+    }
+    Ok(PlainAccount {
+        balance: parse_u256(&account.balance)?,
+        nonce: parse_u256(&account.nonce)?,
+        code: parse_bytes(&account.code)?,
+        storage,
+    })
+}
+
+fn write_account(
+    state: &mut State, address: &AddressWithSpace, account: &PlainAccount,
+) -> Result<(), String> {
+    let db_err =
+        |e: DbError| format!("failed to write account {:?}: {}", address, e);
+    state.set_nonce(address, &account.nonce).map_err(db_err)?;
+    state
+        .add_balance(address, &account.balance, CleanupMode::ForceCreate)
+        .map_err(db_err)?;
+    if !account.code.is_empty() {
+        state
+            .init_code(address, account.code.clone(), Address::zero())
+            .map_err(db_err)?;
+    }
+    let mut substate = Substate::new();
+    for (key, value) in &account.storage {
+        state
+            .set_storage(
+                address,
+                key.as_bytes().to_vec(),
+                *value,
+                Address::zero(),
+                &mut substate,
+            )
+            .map_err(db_err)?;
+    }
+    Ok(())
+}
+
+fn make_env(test: &StateTest) -> Result<Env, String> {
+    let env = &test.env;
+    let number = parse_u64(&env.current_number)?;
+    let base_fee = match &env.current_base_fee {
+        Some(base_fee) => parse_u256(base_fee)?,
+        None => U256::zero(),
+    };
+
+    let mut chain_id = BTreeMap::new();
+    chain_id.insert(Space::Ethereum, CHAIN_ID);
+    Ok(Env {
+        chain_id,
+        number,
+        author: parse_address(&env.current_coinbase)?,
+        timestamp: parse_u64(&env.current_timestamp)?,
+        difficulty: match &env.current_difficulty {
+            Some(difficulty) => parse_u256(difficulty)?,
+            None => U256::zero(),
+        },
+        gas_limit: parse_u256(&env.current_gas_limit)?,
+        last_hash: match &env.previous_hash {
+            Some(hash) => parse_h256(hash)?,
+            None => H256::zero(),
+        },
+        epoch_height: number,
+        base_gas_price: SpaceMap::new(U256::zero(), base_fee),
+        burnt_gas_price: SpaceMap::new(U256::zero(), base_fee),
+        ..Default::default()
+    })
+}
+
+fn make_transaction(
+    tx: &TestTransaction, indexes: &TxIndexes,
+) -> Result<SignedTransaction, String> {
+    let pick = |values: &[String], index: usize, name: &str| {
+        values
+            .get(index)
+            .cloned()
+            .ok_or_else(|| format!("{} index {} out of range", name, index))
+    };
+    let data = parse_bytes(&pick(&tx.data, indexes.data, "data")?)?;
+    let gas = parse_u256(&pick(&tx.gas_limit, indexes.gas, "gas")?)?;
+    let value = parse_u256(&pick(&tx.value, indexes.value, "value")?)?;
+    let nonce = parse_u256(&tx.nonce)?;
+    let action = match tx.to.as_deref() {
+        None | Some("") => Action::Create,
+        Some(to) => Action::Call(parse_address(to)?),
+    };
+    let access_list = tx
+        .access_lists
+        .as_ref()
+        .and_then(|lists| lists.get(indexes.data).cloned().flatten());
+    let secret = Secret::from(parse_h256(&tx.secret_key)?);
+
+    let transaction = if let Some(authorization_list) = &tx.authorization_list {
+        EthereumTransaction::Eip7702(Eip7702Transaction {
+            chain_id: CHAIN_ID,
+            nonce,
+            max_priority_fee_per_gas: parse_fee(
+                &tx.max_priority_fee_per_gas,
+                "maxPriorityFeePerGas",
+            )?,
+            max_fee_per_gas: parse_fee(&tx.max_fee_per_gas, "maxFeePerGas")?,
+            gas,
+            action,
+            value,
+            data,
+            access_list: access_list.unwrap_or_default(),
+            authorization_list: authorization_list
+                .iter()
+                .map(make_authorization)
+                .collect::<Result<_, _>>()?,
+        })
+    } else if tx.max_fee_per_gas.is_some() {
+        EthereumTransaction::Eip1559(Eip1559Transaction {
+            chain_id: CHAIN_ID,
+            nonce,
+            max_priority_fee_per_gas: parse_fee(
+                &tx.max_priority_fee_per_gas,
+                "maxPriorityFeePerGas",
+            )?,
+            max_fee_per_gas: parse_fee(&tx.max_fee_per_gas, "maxFeePerGas")?,
+            gas,
+            action,
+            value,
+            data,
+            access_list: access_list.unwrap_or_default(),
+        })
+    } else if let Some(access_list) = access_list {
+        EthereumTransaction::Eip2930(Eip2930Transaction {
+            chain_id: CHAIN_ID,
+            nonce,
+            gas_price: parse_fee(&tx.gas_price, "gasPrice")?,
+            gas,
+            action,
+            value,
+            data,
+            access_list,
+        })
+    } else {
+        EthereumTransaction::Eip155(Eip155Transaction {
+            nonce,
+            gas_price: parse_fee(&tx.gas_price, "gasPrice")?,
+            gas,
+            action,
+            value,
+            chain_id: Some(CHAIN_ID),
+            data,
+        })
+    };
+    Ok(Transaction::Ethereum(transaction).sign(&secret))
+}
+
+fn parse_fee(fee: &Option<String>, name: &str) -> Result<U256, String> {
+    parse_u256(
+        fee.as_deref()
+            .ok_or_else(|| format!("{} is missing", name))?,
+    )
+}
+
+fn make_authorization(
+    auth: &TestAuthorization,
+) -> Result<AuthorizationListItem, String> {
+    let y_parity = parse_u64(authorization_y_parity(auth)?)?;
+    if y_parity > 1 {
+        return Err(format!("yParity {} is out of range", y_parity));
+    }
+    Ok(AuthorizationListItem {
+        chain_id: parse_u256(&auth.chain_id)?,
+        address: parse_address(&auth.address)?,
+        nonce: parse_u64(&auth.nonce)?,
+        y_parity: y_parity as u8,
+        r: parse_u256(&auth.r)?,
+        s: parse_u256(&auth.s)?,
+    })
+}
+
+fn authorization_y_parity(auth: &TestAuthorization) -> Result<&str, String> {
+    auth.y_parity
+        .as_deref()
+        .or(auth.v.as_deref())
+        .ok_or_else(|| "yParity of the authorization is missing".into())
+}
+
+/// The hash of the RLP encoded logs, in the encoding of Ethereum.
+fn logs_hash(logs: &[LogEntry]) -> H256 {
+    let mut stream = RlpStream::new_list(logs.len());
+    for log in logs {
+        stream.begin_list(3);
+        stream.append(&log.address);
+        stream.append_list(&log.topics);
+        stream.append(&log.data);
+    }
+    keccak(stream.out())
+}
+
+/// Reads all the accounts of the eSpace from the storage.
+fn read_state(storage: &MemoryStorage) -> Result<PlainState, String> {
+    let mut addresses = BTreeSet::new();
+    let mut storage_keys: BTreeMap<Address, Vec<Vec<u8>>> = BTreeMap::new();
+    for (key, _) in storage.entries() {
+        let key = match StorageKeyWithSpace::from_key_bytes::<CheckInput>(&key)
+        {
+            Ok(key) if key.space == Space::Ethereum => key.key,
+            _ => continue,
+        };
+        match key {
+            StorageKey::AccountKey(address) => {
+                addresses.insert(Address::from_slice(address));
+            }
+            StorageKey::StorageKey {
+                address_bytes,
+                storage_key,
+            } => storage_keys
+                .entry(Address::from_slice(address_bytes))
+                .or_default()
+                .push(storage_key.to_vec()),
+            _ => {}
+        }
+    }
+
+    let state = open_state(storage)?;
+    let db_err = |e: DbError| format!("failed to read the post-state: {}", e);
+    let mut accounts = PlainState::new();
+    for address in addresses {
+        let address_with_space = address.with_evm_space();
+        let mut account_storage = BTreeMap::new();
+        for key in storage_keys.remove(&address).unwrap_or_default() {
+            let value = state
+                .storage_at(&address_with_space, &key)
+                .map_err(db_err)?;
+            if !value.is_zero() {
+                account_storage.insert(H256::from_slice(&key), value);
+            }
+        }
+        let code = state
+            .code(&address_with_space)
+            .map_err(db_err)?
+            .map_or_else(Vec::new, |code| code.to_vec());
+        accounts.insert(
+            address,
+            PlainAccount {
+                balance: state.balance(&address_with_space).map_err(db_err)?,
+                nonce: state.nonce(&address_with_space).map_err(db_err)?,
+                code,
+                storage: account_storage,
+            },
+        );
+    }
+    Ok(accounts)
+}
+
+/// The root of the Ethereum state trie of the accounts.
+fn state_root(state: &PlainState) -> H256 {
+    sec_trie_root(state.iter().map(|(address, account)| {
+        let storage_root = sec_trie_root(
+            account
+                .storage
+                .iter()
+                .map(|(key, value)| (*key, rlp::encode(value))),
+        );
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&account.nonce);
+        stream.append(&account.balance);
+        stream.append(&storage_root);
+        stream.append(&keccak(&account.code));
+        (*address, stream.out())
+    }))
+}
+
+fn compare_state(
+    expected: &PlainState, actual: &PlainState, errors: &mut Vec<String>,
+) {
+    for (address, expected_account) in expected {
+        let actual_account = match actual.get(address) {
+            Some(account) => account,
+            None => {
+                errors.push(format!("account {:?} is missing", address));
+                continue;
+            }
+        };
+        if expected_account.balance != actual_account.balance {
+            errors.push(format!(
+                "balance of {:?} mismatch: expected {}, got {}",
+                address, expected_account.balance, actual_account.balance
+            ));
+        }
+        if expected_account.nonce != actual_account.nonce {
+            errors.push(format!(
+                "nonce of {:?} mismatch: expected {}, got {}",
+                address, expected_account.nonce, actual_account.nonce
+            ));
+        }
+        if expected_account.code != actual_account.code {
+            errors.push(format!("code of {:?} mismatch", address));
+        }
+        let keys: BTreeSet<_> = expected_account
+            .storage
+            .keys()
+            .chain(actual_account.storage.keys())
+            .collect();
+        for key in keys {
+            let expected_value = expected_account.storage.get(key);
+            let actual_value = actual_account.storage.get(key);
+            if expected_value != actual_value {
+                errors.push(format!(
+                    "storage {:?} of {:?} mismatch: expected {}, got {}",
+                    key,
+                    address,
+                    expected_value.cloned().unwrap_or_default(),
+                    actual_value.cloned().unwrap_or_default()
+                ));
+            }
+        }
+    }
+    for address in actual.keys().filter(|a| !expected.contains_key(*a)) {
+        errors.push(format!("unexpected account {:?}", address));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fork_params, invalid_encoding, make_authorization};
+    use crate::fixture::{TestAuthorization, TestTransaction};
+
+    fn authorization(y_parity: &str) -> TestAuthorization {
+        TestAuthorization {
+            chain_id: "0x01".into(),
+            address: "0x000000000000000000000000000000000000aaaa".into(),
+            nonce: "0x00".into(),
+            y_parity: Some(y_parity.into()),
+            v: None,
+            r: "0x01".into(),
+            s: "0x01".into(),
+        }
+    }
+
+    fn transaction(y_parity: &str) -> TestTransaction {
+        serde_json::from_value(serde_json::json!({
+            "data": ["0x"],
+            "gasLimit": ["0x0186a0"],
+            "value": ["0x00"],
+            "nonce": "0x00",
+            "secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+            "to": "0x000000000000000000000000000000000000bbbb",
+            "maxFeePerGas": "0x0a",
+            "maxPriorityFeePerGas": "0x00",
+            "authorizationList": [{
+                "chainId": "0x01",
+                "address": "0x000000000000000000000000000000000000aaaa",
+                "nonce": "0x00",
+                "yParity": y_parity,
+                "r": "0x01",
+                "s": "0x01",
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fork_params() {
+        let never = u64::MAX;
+
+        let berlin = fork_params("Berlin").unwrap();
+        assert_eq!(berlin.transition_heights.cip1559, never);
+        assert_eq!(berlin.transition_numbers.cip119, never);
+
+        let shanghai = fork_params("Shanghai").unwrap();
+        assert_eq!(shanghai.transition_heights.cip1559, 0);
+        assert_eq!(shanghai.transition_numbers.cip119, 0);
+        assert_eq!(shanghai.transition_numbers.cancun_opcodes, never);
+        assert_eq!(shanghai.transition_numbers.cip144, never);
+
+        let cancun = fork_params("Cancun").unwrap();
+        assert_eq!(cancun.transition_numbers.cancun_opcodes, 0);
+        assert_eq!(cancun.transition_numbers.cip144, 0);
+        assert_eq!(cancun.transition_numbers.cip2537, never);
+        assert_eq!(cancun.transition_heights.cip7702, never);
+
+        let prague = fork_params("Prague").unwrap();
+        assert_eq!(prague.transition_numbers.cip2537, 0);
+        assert_eq!(prague.transition_heights.cip7702, 0);
+        assert_eq!(prague.transition_numbers.cip7212, never);
+
+        assert!(fork_params("Frontier").is_none());
+        assert!(fork_params("Osaka").is_none());
+    }
+
+    #[test]
+    fn test_y_parity_range() {
+        assert_eq!(
+            make_authorization(&authorization("0x01")).unwrap().y_parity,
+            1
+        );
+        // Not truncated to 0 or 1.
+        assert!(make_authorization(&authorization("0x02")).is_err());
+        assert!(make_authorization(&authorization("0x0100")).is_err());
+
+        assert_eq!(invalid_encoding(&transaction("0x00")).unwrap(), None);
+        assert!(invalid_encoding(&transaction("0x02")).unwrap().is_some());
+        assert!(invalid_encoding(&transaction("0x0101")).unwrap().is_some());
+    }
+}
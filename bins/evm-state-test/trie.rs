@@ -0,0 +1,168 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Computes the root of the Merkle Patricia Trie of Ethereum, which is needed
+//! to compare the post states with the state roots in the fixtures.
+
+use cfx_types::H256;
+use keccak_hash::keccak;
+use rlp::RlpStream;
+
+/// The root of the trie whose keys are hashed by keccak, as in the state trie
+/// and the storage tries.
+pub fn sec_trie_root<I, K, V>(input: I) -> H256
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    trie_root(input.into_iter().map(|(k, v)| (keccak(k), v)))
+}
+
+pub fn trie_root<I, K, V>(input: I) -> H256
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    let mut items: Vec<(Vec<u8>, Vec<u8>)> = input
+        .into_iter()
+        .map(|(k, v)| (to_nibbles(k.as_ref()), v.as_ref().to_vec()))
+        .collect();
+    items.sort();
+    items.dedup_by(|a, b| a.0 == b.0);
+
+    let mut stream = RlpStream::new();
+    encode_node(&items, 0, &mut stream);
+    keccak(stream.out())
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// The hex-prefix encoding of the nibbles in a leaf or an extension node.
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 0x20 } else { 0 };
+    let mut out = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        out.push(flag | 0x10 | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        out.push(flag);
+        nibbles
+    };
+    out.extend(rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+    out
+}
+
+/// Encodes the node of the sorted `items`, which share the first `depth`
+/// nibbles of their keys.
+fn encode_node(
+    items: &[(Vec<u8>, Vec<u8>)], depth: usize, stream: &mut RlpStream,
+) {
+    if items.is_empty() {
+        stream.append_empty_data();
+        return;
+    }
+
+    if items.len() == 1 {
+        let (key, value) = &items[0];
+        stream.begin_list(2);
+        stream.append(&hex_prefix(&key[depth..], true));
+        stream.append(value);
+        return;
+    }
+
+    // The items are sorted, so the first and the last items have the shortest
+    // common prefix.
+    let first = &items[0].0;
+    let last = &items[items.len() - 1].0;
+    let shared = first
+        .iter()
+        .zip(last.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if shared > depth {
+        stream.begin_list(2);
+        stream.append(&hex_prefix(&first[depth..shared], false));
+        encode_child(items, shared, stream);
+        return;
+    }
+
+    stream.begin_list(17);
+    let (value, mut begin) = if first.len() == depth {
+        (Some(&items[0].1), 1)
+    } else {
+        (None, 0)
+    };
+    for nibble in 0..16 {
+        let len = items[begin..]
+            .iter()
+            .take_while(|(key, _)| key[depth] == nibble)
+            .count();
+        if len == 0 {
+            stream.append_empty_data();
+        } else {
+            encode_child(&items[begin..begin + len], depth + 1, stream);
+        }
+        begin += len;
+    }
+    match value {
+        Some(value) => stream.append(value),
+        None => stream.append_empty_data(),
+    };
+}
+
+/// A child node is inlined if its encoding is shorter than 32 bytes, or
+/// referred by its hash otherwise.
+fn encode_child(
+    items: &[(Vec<u8>, Vec<u8>)], depth: usize, stream: &mut RlpStream,
+) {
+    let mut child = RlpStream::new();
+    encode_node(items, depth, &mut child);
+    let encoded = child.out();
+    if encoded.len() < 32 {
+        stream.append_raw(&encoded, 1);
+    } else {
+        stream.append(&keccak(&encoded));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keccak_hash::KECCAK_NULL_RLP;
+    use std::str::FromStr;
+
+    #[test]
+    fn empty_trie() {
+        assert_eq!(
+            trie_root(Vec::<(Vec<u8>, Vec<u8>)>::new()),
+            KECCAK_NULL_RLP
+        );
+        assert_eq!(
+            sec_trie_root(Vec::<(Vec<u8>, Vec<u8>)>::new()),
+            KECCAK_NULL_RLP
+        );
+    }
+
+    #[test]
+    fn puppy_trie() {
+        // The `puppy` case of the trie tests of Ethereum.
+        let root = trie_root(vec![
+            ("do", "verb"),
+            ("horse", "stallion"),
+            ("doge", "coin"),
+            ("dog", "puppy"),
+        ]);
+        assert_eq!(
+            root,
+            H256::from_str(
+                "5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84"
+            )
+            .unwrap()
+        );
+    }
+}
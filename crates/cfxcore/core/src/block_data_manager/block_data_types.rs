@@ -1,6 +1,8 @@
 use cfx_execute_helper::exec_tracer::BlockExecTraces;
 use cfx_internal_common::{DatabaseDecodable, DatabaseEncodable};
-use cfx_types::{Address, Bloom, H256, U256};
+use cfx_types::{
+    Address, AddressSpaceUtil, AddressWithSpace, Bloom, Space, H256, U256,
+};
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
 use primitives::BlockReceipts;
@@ -361,6 +363,53 @@ impl PosRewardInfo {
     }
 }
 
+/// The entries of an address in the address transaction index are numbered
+/// in the order they are inserted, and the ones in `[first, next)` are kept.
+#[derive(Clone, Copy, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct AddressTxRange {
+    pub first: u64,
+    pub next: u64,
+}
+
+/// A transaction involving an address, executed in `epoch_number`.
+///
+/// An entry is not removed when the epoch is reverted by a pivot chain
+/// reorg, so it should be checked against the transaction index.
+#[derive(Clone, Debug, RlpEncodable, RlpDecodable)]
+pub struct AddressTxEntry {
+    pub tx_hash: H256,
+    pub epoch_number: u64,
+}
+
+/// The addresses with entries inserted in an epoch, whose entries are
+/// removed when the epoch is garbage collected.
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct EpochIndexedAddresses {
+    pub native: Vec<Address>,
+    pub ethereum: Vec<Address>,
+}
+
+impl FromIterator<AddressWithSpace> for EpochIndexedAddresses {
+    fn from_iter<I: IntoIterator<Item = AddressWithSpace>>(iter: I) -> Self {
+        let mut addresses = Self::default();
+        for address in iter {
+            match address.space {
+                Space::Native => addresses.native.push(address.address),
+                Space::Ethereum => addresses.ethereum.push(address.address),
+            }
+        }
+        addresses
+    }
+}
+
+impl EpochIndexedAddresses {
+    pub fn iter(&self) -> impl Iterator<Item = AddressWithSpace> + '_ {
+        let native = self.native.iter().map(|a| a.with_native_space());
+        let ethereum = self.ethereum.iter().map(|a| a.with_evm_space());
+        native.chain(ethereum)
+    }
+}
+
 pub fn db_encode_list<T>(list: &[T]) -> Vec<u8>
 where T: DatabaseEncodable {
     let mut rlp_stream = RlpStream::new();
@@ -388,3 +437,6 @@ impl_db_encoding_as_rlp!(EpochExecutionContext);
 impl_db_encoding_as_rlp!(BlockRewardResult);
 impl_db_encoding_as_rlp!(BlamedHeaderVerifiedRoots);
 impl_db_encoding_as_rlp!(PosRewardInfo);
+impl_db_encoding_as_rlp!(AddressTxRange);
+impl_db_encoding_as_rlp!(AddressTxEntry);
+impl_db_encoding_as_rlp!(EpochIndexedAddresses);
@@ -0,0 +1,220 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! The bloom-bits log index. The epochs are grouped into sections of
+//! `BLOOM_BITS_SECTION_SIZE` epochs, and the log blooms of the epochs of a
+//! section are rotated into `BLOOM_BITS` bit vectors: the i-th bit of the j-th
+//! vector is the j-th bit of the bloom of the i-th epoch of the section. A log
+//! filter reads only the vectors of the bits set by its blooms to find the
+//! candidate epochs of a section, instead of the blooms of all the epochs.
+
+use cfx_parameters::rpc::{BLOOM_BITS_CONFIRMATIONS, BLOOM_BITS_SECTION_SIZE};
+use cfx_types::Bloom;
+use parking_lot::{Condvar, Mutex};
+use std::collections::HashMap;
+
+/// The number of bits of a log bloom.
+pub const BLOOM_BITS: usize = 2048;
+
+/// Rotates the log blooms of the epochs of a section into bit vectors.
+pub struct BloomBitsGenerator {
+    section_size: u64,
+    bit_vectors: Vec<Vec<u8>>,
+}
+
+impl BloomBitsGenerator {
+    pub fn new(section_size: u64) -> Self {
+        Self {
+            section_size,
+            bit_vectors: vec![
+                vec![0; bit_vector_len(section_size)];
+                BLOOM_BITS
+            ],
+        }
+    }
+
+    /// Adds the log bloom of the `index`-th epoch of the section.
+    pub fn add_bloom(&mut self, index: u64, bloom: &Bloom) {
+        assert!(index < self.section_size);
+        let (byte, mask) = ((index / 8) as usize, 0x80 >> (index % 8));
+        for bit in bloom_bit_indices(bloom) {
+            self.bit_vectors[bit][byte] |= mask;
+        }
+    }
+
+    pub fn into_bit_vectors(self) -> Vec<Vec<u8>> { self.bit_vectors }
+}
+
+fn bit_vector_len(section_size: u64) -> usize {
+    ((section_size + 7) / 8) as usize
+}
+
+/// Returns the indices of the bits set in `bloom`.
+pub fn bloom_bit_indices(bloom: &Bloom) -> impl Iterator<Item = usize> + '_ {
+    bloom
+        .as_bytes()
+        .iter()
+        .enumerate()
+        .flat_map(|(index, byte)| {
+            (0..8)
+                .filter(move |offset| byte & (0x80 >> offset) != 0)
+                .map(move |offset| index * 8 + offset)
+        })
+}
+
+/// Returns the bit vector of the epochs of a section whose blooms may contain
+/// one of `blooms`. `bit_vector` returns the bit vector of a bloom bit, or
+/// `None` if no epoch of the section sets the bit.
+pub fn match_section<F>(
+    section_size: u64, blooms: &[Bloom], mut bit_vector: F,
+) -> Vec<u8>
+where F: FnMut(usize) -> Option<Box<[u8]>> {
+    let len = bit_vector_len(section_size);
+    let mut bit_vectors = HashMap::new();
+    let mut matches = vec![0u8; len];
+
+    for bloom in blooms {
+        let mut bloom_matches = vec![0xffu8; len];
+
+        for bit in bloom_bit_indices(bloom) {
+            let vector = bit_vectors.entry(bit).or_insert_with(|| {
+                bit_vector(bit).unwrap_or_else(|| vec![0; len].into())
+            });
+            for (byte, vector_byte) in
+                bloom_matches.iter_mut().zip(vector.iter())
+            {
+                *byte &= vector_byte;
+            }
+        }
+
+        for (byte, bloom_byte) in matches.iter_mut().zip(bloom_matches) {
+            *byte |= bloom_byte;
+        }
+    }
+
+    matches
+}
+
+/// Returns whether the `index`-th epoch is set in the bit vector of a section.
+pub fn epoch_matches(bit_vector: &[u8], index: u64) -> bool {
+    bit_vector[(index / 8) as usize] & (0x80 >> (index % 8)) != 0
+}
+
+#[derive(Default, Debug)]
+pub struct BloomBitsProgress {
+    // The sections before it are indexed.
+    // This is the only field that we persist to disk as the progress.
+    pub next_section: u64,
+
+    // The latest epoch computed on the pivot chain.
+    pub latest_epoch: Option<u64>,
+
+    // Increased when indexed sections are invalidated by a pivot chain reorg,
+    // so that a section built on the previous pivot chain is discarded.
+    pub reorg_count: u64,
+}
+
+impl BloomBitsProgress {
+    /// Returns the next section to build if its epochs are confirmed.
+    pub fn next_section_to_build(&self) -> Option<u64> {
+        let section_end = (self.next_section + 1) * BLOOM_BITS_SECTION_SIZE;
+        if section_end + BLOOM_BITS_CONFIRMATIONS <= self.latest_epoch? {
+            Some(self.next_section)
+        } else {
+            None
+        }
+    }
+}
+
+/// The progress of the bloom-bits log index, shared with the worker building
+/// the sections.
+#[derive(Default)]
+pub struct BloomBitsIndex {
+    pub progress: Mutex<BloomBitsProgress>,
+    pub new_epoch: Condvar,
+}
+
+impl BloomBitsIndex {
+    pub fn new(next_section: u64) -> Self {
+        Self {
+            progress: Mutex::new(BloomBitsProgress {
+                next_section,
+                ..Default::default()
+            }),
+            new_epoch: Condvar::new(),
+        }
+    }
+
+    /// Returns whether `section` is still the next section to build on the
+    /// pivot chain of `reorg_count`.
+    pub fn is_current(&self, section: u64, reorg_count: u64) -> bool {
+        let progress = self.progress.lock();
+        progress.reorg_count == reorg_count && progress.next_section == section
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        bloom_bit_indices, epoch_matches, match_section, BloomBitsGenerator,
+    };
+    use cfx_types::{Address, Bloom, BloomInput, H256};
+
+    fn bloom_of(inputs: &[&[u8]]) -> Bloom {
+        let mut bloom = Bloom::zero();
+        for input in inputs {
+            bloom.accrue(BloomInput::Raw(input));
+        }
+        bloom
+    }
+
+    #[test]
+    fn test_match_section() {
+        let address = Address::from_low_u64_be(1);
+        let topic = H256::from_low_u64_be(2);
+        let other_topic = H256::from_low_u64_be(3);
+
+        let mut generator = BloomBitsGenerator::new(16);
+        generator.add_bloom(1, &bloom_of(&[address.as_bytes()]));
+        generator
+            .add_bloom(9, &bloom_of(&[address.as_bytes(), topic.as_bytes()]));
+        generator.add_bloom(15, &bloom_of(&[other_topic.as_bytes()]));
+        let bit_vectors = generator.into_bit_vectors();
+
+        let candidates = |blooms: &[Bloom]| {
+            let matches = match_section(16, blooms, |bit| {
+                Some(bit_vectors[bit].clone().into())
+            });
+            (0..16)
+                .filter(|index| epoch_matches(&matches, *index))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(candidates(&[bloom_of(&[address.as_bytes()])]), vec![1, 9]);
+        assert_eq!(
+            candidates(&[bloom_of(&[address.as_bytes(), topic.as_bytes()])]),
+            vec![9]
+        );
+        assert_eq!(
+            candidates(&[
+                bloom_of(&[topic.as_bytes()]),
+                bloom_of(&[other_topic.as_bytes()])
+            ]),
+            vec![9, 15]
+        );
+        assert_eq!(candidates(&[Bloom::zero()]).len(), 16);
+        assert!(candidates(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_bloom_bit_indices() {
+        let mut bloom = Bloom::zero();
+        bloom.0[0] = 0x80;
+        bloom.0[255] = 0x01;
+        assert_eq!(
+            bloom_bit_indices(&bloom).collect::<Vec<_>>(),
+            vec![0, 2047]
+        );
+    }
+}
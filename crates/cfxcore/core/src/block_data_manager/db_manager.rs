@@ -1,14 +1,16 @@
 use crate::{
     block_data_manager::{
-        db_decode_list, db_encode_list, BlamedHeaderVerifiedRoots,
-        BlockExecutionResultWithEpoch, BlockRewardResult, BlockTracesWithEpoch,
-        CheckpointHashes, DataVersionTuple, EpochExecutionContext,
+        db_decode_list, db_encode_list, AddressTxEntry, AddressTxRange,
+        BlamedHeaderVerifiedRoots, BlockExecutionResultWithEpoch,
+        BlockRewardResult, BlockTracesWithEpoch, CheckpointHashes,
+        DataVersionTuple, EpochExecutionContext, EpochIndexedAddresses,
         LocalBlockInfo, PosRewardInfo,
     },
     db::{
-        COL_BLAMED_HEADER_VERIFIED_ROOTS, COL_BLOCKS, COL_BLOCK_TRACES,
-        COL_EPOCH_NUMBER, COL_HASH_BY_BLOCK_NUMBER, COL_MISC,
-        COL_REWARD_BY_POS_EPOCH, COL_TX_INDEX,
+        COL_ADDRESS_TX_INDEX, COL_BLAMED_HEADER_VERIFIED_ROOTS, COL_BLOCKS,
+        COL_BLOCK_TRACES, COL_BLOOM_BITS, COL_EPOCH_NUMBER,
+        COL_HASH_BY_BLOCK_NUMBER, COL_MISC, COL_REWARD_BY_POS_EPOCH,
+        COL_TX_INDEX,
     },
     pow::PowComputer,
     verification::VerificationConfig,
@@ -20,7 +22,7 @@ use cfx_internal_common::{
 use cfx_storage::{
     storage_db::KeyValueDbTrait, KvdbRocksdb, KvdbSqlite, KvdbSqliteStatements,
 };
-use cfx_types::H256;
+use cfx_types::{AddressWithSpace, Space, H256};
 use db::SystemDB;
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use primitives::{Block, BlockHeader, SignedTransaction, TransactionIndex};
@@ -37,8 +39,10 @@ const EPOCH_CONSENSUS_EXECUTION_INFO_SUFFIX_BYTE: u8 = 5;
 const EPOCH_EXECUTED_BLOCK_SET_SUFFIX_BYTE: u8 = 6;
 const EPOCH_SKIPPED_BLOCK_SET_SUFFIX_BYTE: u8 = 7;
 const BLOCK_REWARD_RESULT_SUFFIX_BYTE: u8 = 8;
+const EPOCH_INDEXED_ADDRESSES_SUFFIX_BYTE: u8 = 9;
 const BLOCK_TERMINAL_KEY: &[u8] = b"block_terminals";
 const GC_PROGRESS_KEY: &[u8] = b"gc_progress";
+const BLOOM_BITS_PROGRESS_KEY: &[u8] = b"bloom_bits_progress";
 
 #[derive(Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq, EnumIter)]
 enum DBTable {
@@ -50,6 +54,8 @@ enum DBTable {
     BlockTraces,
     HashByBlockNumber,
     RewardByPosEpoch,
+    BloomBits,
+    AddressTxIndex,
 }
 
 fn rocks_db_col(table: DBTable) -> u32 {
@@ -62,6 +68,8 @@ fn rocks_db_col(table: DBTable) -> u32 {
         DBTable::BlockTraces => COL_BLOCK_TRACES,
         DBTable::HashByBlockNumber => COL_HASH_BY_BLOCK_NUMBER,
         DBTable::RewardByPosEpoch => COL_REWARD_BY_POS_EPOCH,
+        DBTable::BloomBits => COL_BLOOM_BITS,
+        DBTable::AddressTxIndex => COL_ADDRESS_TX_INDEX,
     }
 }
 
@@ -75,6 +83,8 @@ fn sqlite_db_table(table: DBTable) -> String {
         DBTable::BlockTraces => "block_traces",
         DBTable::HashByBlockNumber => "hash_by_block_number",
         DBTable::RewardByPosEpoch => "reward_by_pos_epoch",
+        DBTable::BloomBits => "bloom_bits",
+        DBTable::AddressTxIndex => "address_tx_index",
     }
     .into()
 }
@@ -506,6 +516,134 @@ impl DBManager {
         )
     }
 
+    pub fn insert_bloom_bits_to_db(
+        &self, section: u64, bit: usize, bit_vector: Vec<u8>,
+    ) {
+        self.insert_to_db(
+            DBTable::BloomBits,
+            &bloom_bits_key(section, bit),
+            bit_vector,
+        );
+    }
+
+    pub fn bloom_bits_from_db(
+        &self, section: u64, bit: usize,
+    ) -> Option<Box<[u8]>> {
+        self.load_from_db(DBTable::BloomBits, &bloom_bits_key(section, bit))
+    }
+
+    pub fn remove_bloom_bits_from_db(&self, section: u64, bit: usize) {
+        self.remove_from_db(DBTable::BloomBits, &bloom_bits_key(section, bit))
+    }
+
+    pub fn insert_bloom_section_head_to_db(&self, section: u64, head: &H256) {
+        self.insert_encodable_val(
+            DBTable::BloomBits,
+            &section.to_be_bytes(),
+            head,
+        );
+    }
+
+    pub fn bloom_section_head_from_db(&self, section: u64) -> Option<H256> {
+        self.load_decodable_val(DBTable::BloomBits, &section.to_be_bytes())
+    }
+
+    pub fn remove_bloom_section_head_from_db(&self, section: u64) {
+        self.remove_from_db(DBTable::BloomBits, &section.to_be_bytes())
+    }
+
+    pub fn insert_bloom_bits_progress_to_db(&self, next_section: u64) {
+        self.insert_encodable_val(
+            DBTable::Misc,
+            BLOOM_BITS_PROGRESS_KEY,
+            &next_section,
+        );
+    }
+
+    pub fn bloom_bits_progress_from_db(&self) -> Option<u64> {
+        self.load_decodable_val(DBTable::Misc, BLOOM_BITS_PROGRESS_KEY)
+    }
+
+    pub fn insert_address_tx_range_to_db(
+        &self, address: &AddressWithSpace, range: &AddressTxRange,
+    ) {
+        self.insert_encodable_val(
+            DBTable::AddressTxIndex,
+            &address_tx_range_key(address),
+            range,
+        );
+    }
+
+    pub fn address_tx_range_from_db(
+        &self, address: &AddressWithSpace,
+    ) -> Option<AddressTxRange> {
+        self.load_decodable_val(
+            DBTable::AddressTxIndex,
+            &address_tx_range_key(address),
+        )
+    }
+
+    pub fn remove_address_tx_range_from_db(&self, address: &AddressWithSpace) {
+        self.remove_from_db(
+            DBTable::AddressTxIndex,
+            &address_tx_range_key(address),
+        )
+    }
+
+    pub fn insert_address_tx_entry_to_db(
+        &self, address: &AddressWithSpace, index: u64, entry: &AddressTxEntry,
+    ) {
+        self.insert_encodable_val(
+            DBTable::AddressTxIndex,
+            &address_tx_entry_key(address, index),
+            entry,
+        );
+    }
+
+    pub fn address_tx_entry_from_db(
+        &self, address: &AddressWithSpace, index: u64,
+    ) -> Option<AddressTxEntry> {
+        self.load_decodable_val(
+            DBTable::AddressTxIndex,
+            &address_tx_entry_key(address, index),
+        )
+    }
+
+    pub fn remove_address_tx_entry_from_db(
+        &self, address: &AddressWithSpace, index: u64,
+    ) {
+        self.remove_from_db(
+            DBTable::AddressTxIndex,
+            &address_tx_entry_key(address, index),
+        )
+    }
+
+    pub fn insert_epoch_indexed_addresses_to_db(
+        &self, epoch_number: u64, addresses: &EpochIndexedAddresses,
+    ) {
+        self.insert_encodable_val(
+            DBTable::AddressTxIndex,
+            &epoch_indexed_addresses_key(epoch_number),
+            addresses,
+        );
+    }
+
+    pub fn epoch_indexed_addresses_from_db(
+        &self, epoch_number: u64,
+    ) -> Option<EpochIndexedAddresses> {
+        self.load_decodable_val(
+            DBTable::AddressTxIndex,
+            &epoch_indexed_addresses_key(epoch_number),
+        )
+    }
+
+    pub fn remove_epoch_indexed_addresses_from_db(&self, epoch_number: u64) {
+        self.remove_from_db(
+            DBTable::AddressTxIndex,
+            &epoch_indexed_addresses_key(epoch_number),
+        )
+    }
+
     /// The functions below are private utils used by the DBManager to access
     /// database
     fn insert_to_db(&self, table: DBTable, db_key: &[u8], value: Vec<u8>) {
@@ -620,6 +758,44 @@ fn epoch_consensus_epoch_execution_commitment_key(hash: &H256) -> Vec<u8> {
     append_suffix(hash, EPOCH_CONSENSUS_EXECUTION_INFO_SUFFIX_BYTE)
 }
 
+fn bloom_bits_key(section: u64, bit: usize) -> [u8; 10] {
+    let mut key = [0; 10];
+    key[0..8].copy_from_slice(&section.to_be_bytes());
+    key[8..10].copy_from_slice(&(bit as u16).to_be_bytes());
+    key
+}
+
+fn space_byte(space: Space) -> u8 {
+    match space {
+        Space::Native => 1,
+        Space::Ethereum => 2,
+    }
+}
+
+// The keys of the address transaction index have different lengths: 21 bytes
+// for the range of an address, 29 bytes for an entry of an address, and 9
+// bytes for the addresses indexed in an epoch.
+fn address_tx_range_key(address: &AddressWithSpace) -> [u8; 21] {
+    let mut key = [0; 21];
+    key[0] = space_byte(address.space);
+    key[1..21].copy_from_slice(address.address.as_bytes());
+    key
+}
+
+fn address_tx_entry_key(address: &AddressWithSpace, index: u64) -> [u8; 29] {
+    let mut key = [0; 29];
+    key[0..21].copy_from_slice(&address_tx_range_key(address));
+    key[21..29].copy_from_slice(&index.to_be_bytes());
+    key
+}
+
+fn epoch_indexed_addresses_key(epoch_number: u64) -> [u8; 9] {
+    let mut key = [0; 9];
+    key[0..8].copy_from_slice(&epoch_number.to_be_bytes());
+    key[8] = EPOCH_INDEXED_ADDRESSES_SUFFIX_BYTE;
+    key
+}
+
 impl MallocSizeOf for DBManager {
     fn size_of(&self, ops: &mut MallocSizeOfOps) -> usize {
         // Here we only handle the case that all columns are stored within the
@@ -14,7 +14,7 @@ use cfx_storage::{
     state_manager::StateIndex, utils::guarded_value::*, StorageManager,
     StorageManagerTrait,
 };
-use cfx_types::{Bloom, Space, H256};
+use cfx_types::{AddressWithSpace, Bloom, Space, H256};
 use malloc_size_of::{new_malloc_size_ops, MallocSizeOf, MallocSizeOfOps};
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockUpgradableReadGuard};
@@ -26,17 +26,23 @@ use primitives::{
 };
 use rlp::DecoderError;
 use std::{
-    collections::{HashMap, HashSet},
+    cmp::{max, min},
+    collections::{BTreeSet, HashMap, HashSet},
     sync::Arc,
 };
 use threadpool::ThreadPool;
 pub mod block_data_types;
+pub mod bloom_bits;
 pub mod db_gc_manager;
 pub mod db_manager;
+#[cfg(test)]
+mod tests;
 pub mod tx_data_manager;
 use crate::{
     block_data_manager::{
-        db_manager::DBManager, tx_data_manager::TransactionDataManager,
+        bloom_bits::{match_section, BloomBitsGenerator, BloomBitsIndex},
+        db_manager::DBManager,
+        tx_data_manager::TransactionDataManager,
     },
     consensus::pos_handler::PosVerifier,
 };
@@ -48,10 +54,11 @@ use cfx_execute_helper::{
 use cfx_internal_common::{
     EpochExecutionCommitment, StateAvailabilityBoundary, StateRootWithAuxInfo,
 };
+use cfx_parameters::rpc::BLOOM_BITS_SECTION_SIZE;
 use db_gc_manager::GCProgress;
 use metrics::{register_meter_with_group, Meter, MeterTimer};
 use primitives::pos::PosBlockId;
-use std::{hash::Hash, path::Path, time::Duration};
+use std::{hash::Hash, path::Path, thread, time::Duration};
 
 lazy_static! {
     static ref TX_POOL_RECOVER_TIMER: Arc<dyn Meter> =
@@ -144,6 +151,11 @@ pub struct BlockDataManager {
     cache_man: Arc<Mutex<CacheManager<CacheId>>>,
     pub target_difficulty_manager: TargetDifficultyManager,
     gc_progress: Arc<Mutex<GCProgress>>,
+    #[ignore_malloc_size_of = "Small"]
+    bloom_bits_index: Arc<BloomBitsIndex>,
+    /// Serializes the updates of the address transaction index, which read
+    /// and write the entry ranges of the addresses.
+    address_tx_index_lock: Mutex<()>,
 
     /// This maintains the boundary height of available state and commitments
     /// (executed but not deleted or in `ExecutionTaskQueue`).
@@ -196,6 +208,8 @@ impl BlockDataManager {
         };
         let previous_db_progress =
             db_manager.gc_progress_from_db().unwrap_or(0);
+        let bloom_bits_progress =
+            db_manager.bloom_bits_progress_from_db().unwrap_or(0);
 
         let data_man = Self {
             block_headers: RwLock::new(HashMap::new()),
@@ -237,6 +251,10 @@ impl BlockDataManager {
             gc_progress: Arc::new(Mutex::new(GCProgress::new(
                 previous_db_progress,
             ))),
+            bloom_bits_index: Arc::new(BloomBitsIndex::new(
+                bloom_bits_progress,
+            )),
+            address_tx_index_lock: Mutex::new(()),
         };
 
         data_man.initialize_instance_id();
@@ -803,6 +821,193 @@ impl BlockDataManager {
         }
     }
 
+    pub fn address_tx_index_enabled(&self) -> bool {
+        self.config.persist_address_tx_index
+    }
+
+    /// Appends the transactions executed in the epoch `epoch_number` to the
+    /// entries of the addresses involved in them. `txs` are the transactions
+    /// in the execution order, with their involved addresses.
+    pub fn insert_address_tx_index(
+        &self, epoch_number: u64, txs: Vec<(H256, BTreeSet<AddressWithSpace>)>,
+    ) {
+        if !self.config.persist_address_tx_index {
+            return;
+        }
+
+        let _lock = self.address_tx_index_lock.lock();
+
+        // An epoch executed again after a pivot chain reorg keeps the
+        // addresses of the previous execution, so that their entries are
+        // still removed when the epoch is garbage collected.
+        let mut epoch_addresses: BTreeSet<_> = self
+            .db_manager
+            .epoch_indexed_addresses_from_db(epoch_number)
+            .map(|addresses| addresses.iter().collect())
+            .unwrap_or_default();
+        let mut ranges = HashMap::new();
+
+        for (tx_hash, addresses) in txs {
+            for address in addresses {
+                let range = ranges.entry(address).or_insert_with(|| {
+                    self.db_manager
+                        .address_tx_range_from_db(&address)
+                        .unwrap_or_default()
+                });
+                self.db_manager.insert_address_tx_entry_to_db(
+                    &address,
+                    range.next,
+                    &AddressTxEntry {
+                        tx_hash,
+                        epoch_number,
+                    },
+                );
+                range.next += 1;
+                epoch_addresses.insert(address);
+            }
+        }
+
+        for (address, range) in ranges {
+            self.db_manager
+                .insert_address_tx_range_to_db(&address, &range);
+        }
+        self.db_manager.insert_epoch_indexed_addresses_to_db(
+            epoch_number,
+            &epoch_addresses.into_iter().collect(),
+        );
+    }
+
+    /// Returns the transactions involving `address` from the newest to the
+    /// oldest, reading at most `limit` entries from the entry `cursor` (the
+    /// newest entry if `None`), and the cursor of the next page if there are
+    /// older entries. The entries reverted by pivot chain reorgs are skipped,
+    /// so a page may have less than `limit` transactions. A zero `limit`
+    /// reads nothing and has no next page, which would be the same page.
+    pub fn transactions_by_address(
+        &self, address: &AddressWithSpace, cursor: Option<u64>, limit: u64,
+    ) -> (Vec<(AddressTxEntry, TransactionIndex)>, Option<u64>) {
+        if limit == 0 {
+            return (vec![], None);
+        }
+        let range = match self.db_manager.address_tx_range_from_db(address) {
+            Some(range) => range,
+            None => return (vec![], None),
+        };
+
+        let end = cursor.map_or(range.next, |cursor| {
+            min(cursor.saturating_add(1), range.next)
+        });
+        let start = max(range.first, end.saturating_sub(limit));
+
+        let mut txs = vec![];
+        let mut visited = HashSet::new();
+
+        for index in (start..end).rev() {
+            let entry = match self
+                .db_manager
+                .address_tx_entry_from_db(address, index)
+            {
+                Some(entry) => entry,
+                // removed by the garbage collection
+                None => continue,
+            };
+            if !visited.insert(entry.tx_hash) {
+                continue;
+            }
+
+            let tx_index =
+                match self.transaction_index_by_hash(&entry.tx_hash, false) {
+                    Some(tx_index) => tx_index,
+                    None => continue,
+                };
+            if self.block_epoch_number(&tx_index.block_hash)
+                != Some(entry.epoch_number)
+            {
+                // The transaction is executed in another epoch after a pivot
+                // chain reorg, where it has another entry.
+                continue;
+            }
+
+            txs.push((entry, tx_index));
+        }
+
+        let next_cursor = if start > range.first {
+            Some(start - 1)
+        } else {
+            None
+        };
+        (txs, next_cursor)
+    }
+
+    /// Removes the entries of the transactions executed in the epochs up to
+    /// `epoch_number` from the address transaction index.
+    fn gc_address_tx_index(&self, epoch_number: u64) {
+        let _lock = self.address_tx_index_lock.lock();
+
+        let epoch_addresses = match self
+            .db_manager
+            .epoch_indexed_addresses_from_db(epoch_number)
+        {
+            Some(addresses) => addresses,
+            None => return,
+        };
+
+        // After a pivot chain reorg, the entries of an epoch may be appended
+        // after the entries of a later epoch. The removal stops at the first
+        // entry of a later epoch, and the address is kept in the addresses of
+        // that epoch, so that the entries behind it are removed with it.
+        let mut deferred: HashMap<u64, Vec<AddressWithSpace>> = HashMap::new();
+
+        for address in epoch_addresses.iter() {
+            let mut range =
+                match self.db_manager.address_tx_range_from_db(&address) {
+                    Some(range) => range,
+                    None => continue,
+                };
+
+            while range.first < range.next {
+                if let Some(entry) = self
+                    .db_manager
+                    .address_tx_entry_from_db(&address, range.first)
+                {
+                    if entry.epoch_number > epoch_number {
+                        deferred
+                            .entry(entry.epoch_number)
+                            .or_default()
+                            .push(address);
+                        break;
+                    }
+                }
+                self.db_manager
+                    .remove_address_tx_entry_from_db(&address, range.first);
+                range.first += 1;
+            }
+
+            if range.first == range.next {
+                self.db_manager.remove_address_tx_range_from_db(&address);
+            } else {
+                self.db_manager
+                    .insert_address_tx_range_to_db(&address, &range);
+            }
+        }
+
+        for (later_epoch, addresses) in deferred {
+            let mut later_addresses: BTreeSet<_> = self
+                .db_manager
+                .epoch_indexed_addresses_from_db(later_epoch)
+                .map(|addresses| addresses.iter().collect())
+                .unwrap_or_default();
+            later_addresses.extend(addresses);
+            self.db_manager.insert_epoch_indexed_addresses_to_db(
+                later_epoch,
+                &later_addresses.into_iter().collect(),
+            );
+        }
+
+        self.db_manager
+            .remove_epoch_indexed_addresses_from_db(epoch_number);
+    }
+
     pub fn hash_by_block_number(
         &self, block_number: u64, update_cache: bool,
     ) -> Option<H256> {
@@ -1654,6 +1859,198 @@ impl BlockDataManager {
         }
     }
 
+    /// Starts the worker building the sections of the bloom-bits log index.
+    /// The sections of the epochs executed before the index is enabled are
+    /// built first.
+    pub fn start_bloom_bits_indexer(self: &Arc<Self>) {
+        if !self.config.persist_bloom_bits_index {
+            return;
+        }
+
+        let index = self.bloom_bits_index.clone();
+        // hold a weak reference so that the data manager can be dropped on
+        // shutdown
+        let data_man = Arc::downgrade(self);
+
+        thread::Builder::new()
+            .name("Bloom Bits Indexer".into())
+            .spawn(move || loop {
+                let maybe_section = {
+                    let mut progress = index.progress.lock();
+                    if progress.next_section_to_build().is_none() {
+                        index
+                            .new_epoch
+                            .wait_for(&mut progress, Duration::from_secs(1));
+                    }
+                    progress
+                        .next_section_to_build()
+                        .map(|section| (section, progress.reorg_count))
+                };
+
+                let data_man = match data_man.upgrade() {
+                    Some(data_man) => data_man,
+                    None => break,
+                };
+
+                if let Some((section, reorg_count)) = maybe_section {
+                    data_man.build_bloom_bits_section(section, reorg_count);
+                }
+            })
+            .expect("Bloom bits indexer thread spawn failure");
+    }
+
+    /// Called when `epoch` is computed on the pivot chain. The indexed
+    /// sections are rebuilt if the pivot chain has changed.
+    pub fn update_bloom_bits_index(&self, epoch: u64, pivot_hash: &H256) {
+        if !self.config.persist_bloom_bits_index {
+            return;
+        }
+
+        let mut progress = self.bloom_bits_index.progress.lock();
+        let section = epoch / BLOOM_BITS_SECTION_SIZE;
+
+        if section == progress.next_section {
+            // The section being built may mix the epochs of two pivot chains.
+            progress.reorg_count += 1;
+        } else if section < progress.next_section
+            && epoch % BLOOM_BITS_SECTION_SIZE == BLOOM_BITS_SECTION_SIZE - 1
+            && self
+                .db_manager
+                .bloom_section_head_from_db(section)
+                .map_or(false, |head| head != *pivot_hash)
+        {
+            info!(
+                "Pivot chain changed at epoch {}, rebuild the bloom bits \
+                 index from section {}",
+                epoch, section
+            );
+            progress.next_section = section;
+            progress.reorg_count += 1;
+            self.db_manager.insert_bloom_bits_progress_to_db(section);
+        }
+
+        progress.latest_epoch = Some(epoch);
+        self.bloom_bits_index.new_epoch.notify_one();
+    }
+
+    /// Returns the bit vector of the epochs of `section` whose log blooms may
+    /// contain one of `blooms`. Return `None` if the section is not indexed,
+    /// or if it is indexed on another pivot chain than the one whose last
+    /// epoch of the section is `pivot_hash`.
+    pub fn bloom_bits_candidates(
+        &self, section: u64, pivot_hash: &H256, blooms: &[Bloom],
+    ) -> Option<Vec<u8>> {
+        if !self.config.persist_bloom_bits_index
+            || section >= self.bloom_bits_index.progress.lock().next_section
+        {
+            return None;
+        }
+
+        if self.db_manager.bloom_section_head_from_db(section)? != *pivot_hash {
+            return None;
+        }
+
+        Some(match_section(BLOOM_BITS_SECTION_SIZE, blooms, |bit| {
+            self.db_manager.bloom_bits_from_db(section, bit)
+        }))
+    }
+
+    fn build_bloom_bits_section(&self, section: u64, reorg_count: u64) {
+        let start_epoch = section * BLOOM_BITS_SECTION_SIZE;
+        let mut generator = BloomBitsGenerator::new(BLOOM_BITS_SECTION_SIZE);
+        let mut head = None;
+
+        for index in 0..BLOOM_BITS_SECTION_SIZE {
+            match self.epoch_log_bloom(start_epoch + index) {
+                Some((pivot_hash, bloom)) => {
+                    generator.add_bloom(index, &bloom);
+                    head = Some(pivot_hash);
+                }
+                None => {
+                    // The execution results are not available, e.g. they
+                    // are garbage collected, or the node is synced from a
+                    // snapshot. The log filters scan the epochs of the
+                    // section instead.
+                    debug!(
+                        "Skip bloom bits section {}: epoch {} has no \
+                         execution results",
+                        section,
+                        start_epoch + index
+                    );
+                    head = None;
+                    break;
+                }
+            }
+        }
+
+        // The progress is not locked while the section is written, so that
+        // the consensus is not blocked on `update_bloom_bits_index`. The
+        // section is not read before `next_section` passes it.
+        if !self.bloom_bits_index.is_current(section, reorg_count) {
+            // The section is built on a previous pivot chain.
+            return;
+        }
+
+        match head {
+            Some(head) => {
+                for (bit, bit_vector) in
+                    generator.into_bit_vectors().into_iter().enumerate()
+                {
+                    if bit_vector.iter().all(|byte| *byte == 0) {
+                        self.db_manager.remove_bloom_bits_from_db(section, bit);
+                    } else {
+                        self.db_manager
+                            .insert_bloom_bits_to_db(section, bit, bit_vector);
+                    }
+                }
+                self.db_manager
+                    .insert_bloom_section_head_to_db(section, &head);
+            }
+            None => self.db_manager.remove_bloom_section_head_from_db(section),
+        }
+
+        let mut progress = self.bloom_bits_index.progress.lock();
+        if progress.reorg_count != reorg_count
+            || progress.next_section != section
+        {
+            // The pivot chain has changed while the section is written, so
+            // the section is built again.
+            return;
+        }
+
+        // note: the progress is persisted under the lock, so that it is not
+        // overwritten by an older one.
+        progress.next_section = section + 1;
+        self.db_manager
+            .insert_bloom_bits_progress_to_db(section + 1);
+        debug!("Bloom bits index progress: {:?}", *progress);
+    }
+
+    /// Returns the pivot block of `epoch` and the union of the log blooms of
+    /// its executed blocks. The logs of both spaces are included, as the
+    /// phantom transactions of the eSpace are not recorded separately.
+    fn epoch_log_bloom(&self, epoch: u64) -> Option<(H256, Bloom)> {
+        let hashes = self.executed_epoch_set_hashes_from_db(epoch)?;
+        let pivot_hash = *hashes.last()?;
+
+        // the true genesis has no execution results
+        if epoch == 0 {
+            return Some((pivot_hash, Bloom::zero()));
+        }
+
+        let mut bloom = Bloom::zero();
+        for hash in &hashes {
+            let result = self.block_execution_result_by_hash_with_epoch(
+                hash,
+                &pivot_hash,
+                false, /* update_pivot_assumption */
+                false, /* update_cache */
+            )?;
+            bloom.accrue_bloom(&result.bloom);
+        }
+        Some((pivot_hash, bloom))
+    }
+
     pub fn new_checkpoint(
         &self, new_checkpoint_height: u64, best_epoch_number: u64,
     ) {
@@ -1691,6 +2088,9 @@ impl BlockDataManager {
         {
             if base_epoch > defer_epochs as u64 {
                 let epoch_to_remove = base_epoch - defer_epochs as u64;
+                if self.config.persist_address_tx_index {
+                    self.gc_address_tx_index(epoch_to_remove);
+                }
                 match self.all_epoch_set_hashes_from_db(epoch_to_remove) {
                     None => warn!(
                         "GC epoch set is missing! epoch_to_remove: {}",
@@ -1800,6 +2200,8 @@ pub struct DataManagerConfiguration {
     pub additional_maintained_transaction_index_epoch_count: Option<usize>,
     pub checkpoint_gc_time_in_epoch_count: usize,
     pub strict_tx_index_gc: bool,
+    pub persist_bloom_bits_index: bool,
+    pub persist_address_tx_index: bool,
 }
 
 impl MallocSizeOf for DataManagerConfiguration {
@@ -1823,6 +2225,8 @@ impl DataManagerConfiguration {
             additional_maintained_transaction_index_epoch_count: None,
             checkpoint_gc_time_in_epoch_count: 1,
             strict_tx_index_gc: true,
+            persist_bloom_bits_index: false,
+            persist_address_tx_index: false,
         }
     }
 }
@@ -0,0 +1,510 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{
+    block_data_types::AddressTxEntry, bloom_bits::epoch_matches,
+    BlockDataManager, DataManagerConfiguration, DbType,
+};
+use crate::{
+    pow::PowComputer, sync::utils::initialize_data_manager_with_config,
+};
+use cfx_executor::machine::VmFactory;
+use cfx_parameters::rpc::{BLOOM_BITS_CONFIRMATIONS, BLOOM_BITS_SECTION_SIZE};
+use cfx_types::{
+    Address, AddressSpaceUtil, AddressWithSpace, Bloom, BloomInput, H256,
+};
+use primitives::{
+    BlockHeaderBuilder, BlockReceipts, Receipt, TransactionIndex,
+};
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+use tempdir::TempDir;
+
+fn new_data_manager(
+    dir: &TempDir, configure: impl FnOnce(&mut DataManagerConfiguration),
+) -> Arc<BlockDataManager> {
+    let mut config = DataManagerConfiguration::new(
+        false, /* do not persist transaction address */
+        false, /* do not persist block number index */
+        Duration::from_millis(300_000),
+        DbType::Rocksdb,
+    );
+    configure(&mut config);
+    initialize_data_manager_with_config(
+        dir.path().to_str().unwrap(),
+        config,
+        Arc::new(PowComputer::new(true)),
+        VmFactory::new(1024 * 32),
+    )
+    .0
+}
+
+/// The pivot block of `epoch` on the pivot chain `chain`.
+fn pivot_hash(epoch: u64, chain: u64) -> H256 {
+    H256::from_low_u64_be(chain << 32 | epoch)
+}
+
+fn address_bloom(address: &Address) -> Bloom {
+    Bloom::from(BloomInput::Raw(address.as_bytes()))
+}
+
+/// Executes the epochs of the first bloom-bits section on the pivot chain
+/// `chain`, where the epochs `logged` have a log of `address`.
+fn execute_first_section(
+    data_man: &BlockDataManager, chain: u64, logged: &[u64], address: &Address,
+) {
+    for epoch in 1..BLOOM_BITS_SECTION_SIZE {
+        let hash = pivot_hash(epoch, chain);
+        let mut receipt = Receipt::default();
+        if logged.contains(&epoch) {
+            receipt.log_bloom = address_bloom(address);
+        }
+        data_man.insert_executed_epoch_set_hashes_to_db(epoch, &vec![hash]);
+        data_man.insert_block_execution_result(
+            hash,
+            hash,
+            Arc::new(BlockReceipts {
+                receipts: vec![receipt],
+                block_number: epoch,
+                secondary_reward: 0.into(),
+                tx_execution_error_messages: vec!["".into()],
+            }),
+            true, /* persistent */
+        );
+    }
+}
+
+/// Confirms the epochs of the first bloom-bits section and returns the
+/// `reorg_count` to build it with.
+fn confirm_first_section(data_man: &BlockDataManager) -> u64 {
+    let confirmed = BLOOM_BITS_SECTION_SIZE + BLOOM_BITS_CONFIRMATIONS;
+    data_man.update_bloom_bits_index(confirmed - 1, &H256::random());
+    assert_eq!(
+        data_man
+            .bloom_bits_index
+            .progress
+            .lock()
+            .next_section_to_build(),
+        None
+    );
+
+    data_man.update_bloom_bits_index(confirmed, &H256::random());
+    let progress = data_man.bloom_bits_index.progress.lock();
+    assert_eq!(progress.next_section_to_build(), Some(0));
+    progress.reorg_count
+}
+
+fn matched_epochs(candidates: &[u8]) -> Vec<u64> {
+    (0..BLOOM_BITS_SECTION_SIZE)
+        .filter(|index| epoch_matches(candidates, *index))
+        .collect()
+}
+
+#[test]
+fn test_bloom_bits_section() {
+    let dir = TempDir::new("bloom_bits_section").unwrap();
+    let data_man =
+        new_data_manager(&dir, |config| config.persist_bloom_bits_index = true);
+    let address = Address::random();
+    let blooms = [address_bloom(&address)];
+    let head = pivot_hash(BLOOM_BITS_SECTION_SIZE - 1, 0);
+
+    execute_first_section(&data_man, 0, &[10, 4000], &address);
+    let reorg_count = confirm_first_section(&data_man);
+    assert_eq!(data_man.bloom_bits_candidates(0, &head, &blooms), None);
+
+    data_man.build_bloom_bits_section(0, reorg_count);
+    assert_eq!(data_man.bloom_bits_index.progress.lock().next_section, 1);
+    assert_eq!(data_man.db_manager.bloom_bits_progress_from_db(), Some(1));
+    assert_eq!(
+        data_man.db_manager.bloom_section_head_from_db(0),
+        Some(head)
+    );
+
+    let candidates = data_man.bloom_bits_candidates(0, &head, &blooms).unwrap();
+    assert_eq!(matched_epochs(&candidates), vec![10, 4000]);
+    let candidates = data_man
+        .bloom_bits_candidates(0, &head, &[address_bloom(&Address::random())])
+        .unwrap();
+    assert!(matched_epochs(&candidates).is_empty());
+
+    // The section is indexed on another pivot chain.
+    assert_eq!(
+        data_man.bloom_bits_candidates(0, &H256::random(), &blooms),
+        None
+    );
+    // The section is not indexed.
+    assert_eq!(data_man.bloom_bits_candidates(1, &head, &blooms), None);
+}
+
+#[test]
+fn test_bloom_bits_section_reorg() {
+    let dir = TempDir::new("bloom_bits_section_reorg").unwrap();
+    let data_man =
+        new_data_manager(&dir, |config| config.persist_bloom_bits_index = true);
+    let address = Address::random();
+    let blooms = [address_bloom(&address)];
+    let last = BLOOM_BITS_SECTION_SIZE - 1;
+
+    execute_first_section(&data_man, 0, &[10], &address);
+    let reorg_count = confirm_first_section(&data_man);
+    data_man.build_bloom_bits_section(0, reorg_count);
+
+    // The pivot chain changes at the last epoch of the indexed section.
+    execute_first_section(&data_man, 1, &[20], &address);
+    data_man.update_bloom_bits_index(last, &pivot_hash(last, 1));
+    assert_eq!(data_man.db_manager.bloom_bits_progress_from_db(), Some(0));
+    assert_eq!(
+        data_man.bloom_bits_candidates(0, &pivot_hash(last, 0), &blooms),
+        None
+    );
+
+    // A section built on the previous pivot chain is discarded.
+    data_man.build_bloom_bits_section(0, reorg_count);
+    assert_eq!(data_man.bloom_bits_index.progress.lock().next_section, 0);
+    assert_eq!(data_man.db_manager.bloom_bits_progress_from_db(), Some(0));
+
+    let reorg_count = confirm_first_section(&data_man);
+    data_man.build_bloom_bits_section(0, reorg_count);
+    assert_eq!(data_man.db_manager.bloom_bits_progress_from_db(), Some(1));
+    let candidates = data_man
+        .bloom_bits_candidates(0, &pivot_hash(last, 1), &blooms)
+        .unwrap();
+    assert_eq!(matched_epochs(&candidates), vec![20]);
+    assert_eq!(
+        data_man.bloom_bits_candidates(0, &pivot_hash(last, 0), &blooms),
+        None
+    );
+}
+
+#[test]
+fn test_bloom_bits_section_without_execution_results() {
+    let dir = TempDir::new("bloom_bits_section_skipped").unwrap();
+    let data_man =
+        new_data_manager(&dir, |config| config.persist_bloom_bits_index = true);
+    let address = Address::random();
+    let head = pivot_hash(BLOOM_BITS_SECTION_SIZE - 1, 0);
+
+    // The section is skipped, and the log filters scan its epochs instead.
+    let reorg_count = confirm_first_section(&data_man);
+    data_man.build_bloom_bits_section(0, reorg_count);
+    assert_eq!(data_man.db_manager.bloom_bits_progress_from_db(), Some(1));
+    assert_eq!(data_man.db_manager.bloom_section_head_from_db(0), None);
+    assert_eq!(
+        data_man.bloom_bits_candidates(0, &head, &[address_bloom(&address)]),
+        None
+    );
+}
+
+#[test]
+fn test_bloom_bits_index_disabled() {
+    let dir = TempDir::new("bloom_bits_index_disabled").unwrap();
+    let data_man = new_data_manager(&dir, |_| {});
+    let address = Address::random();
+    let head = pivot_hash(BLOOM_BITS_SECTION_SIZE - 1, 0);
+
+    execute_first_section(&data_man, 0, &[10], &address);
+    data_man.update_bloom_bits_index(
+        BLOOM_BITS_SECTION_SIZE + BLOOM_BITS_CONFIRMATIONS,
+        &H256::random(),
+    );
+    assert_eq!(data_man.bloom_bits_index.progress.lock().latest_epoch, None);
+    assert_eq!(
+        data_man.bloom_bits_candidates(0, &head, &[address_bloom(&address)]),
+        None
+    );
+}
+
+/// Executes the transaction `tx_hash` in the pivot block of `epoch` on the
+/// pivot chain `chain`, and returns the pivot block.
+fn execute_tx(
+    data_man: &BlockDataManager, epoch: u64, chain: u64, tx_hash: &H256,
+) -> H256 {
+    let header = Arc::new(
+        BlockHeaderBuilder::new()
+            .with_height(epoch)
+            .with_nonce(chain.into())
+            .build(),
+    );
+    let hash = header.hash();
+    data_man.insert_block_header(hash, header, true /* persistent */);
+    data_man.insert_executed_epoch_set_hashes_to_db(epoch, &vec![hash]);
+    data_man.insert_skipped_epoch_set_hashes_to_db(epoch, &vec![]);
+    data_man.insert_block_execution_result(
+        hash,
+        hash,
+        Arc::new(BlockReceipts {
+            receipts: vec![Receipt::default()],
+            block_number: epoch,
+            secondary_reward: 0.into(),
+            tx_execution_error_messages: vec!["".into()],
+        }),
+        true, /* persistent */
+    );
+    data_man.insert_transaction_index(
+        tx_hash,
+        &TransactionIndex {
+            block_hash: hash,
+            ..Default::default()
+        },
+    );
+    hash
+}
+
+fn index_tx(
+    data_man: &BlockDataManager, epoch: u64, tx_hash: &H256,
+    addresses: &[AddressWithSpace],
+) {
+    let addresses: BTreeSet<_> = addresses.iter().cloned().collect();
+    data_man.insert_address_tx_index(epoch, vec![(*tx_hash, addresses)]);
+}
+
+fn tx_epochs(txs: &[(AddressTxEntry, TransactionIndex)]) -> Vec<u64> {
+    txs.iter().map(|(entry, _)| entry.epoch_number).collect()
+}
+
+fn with_address_tx_index(config: &mut DataManagerConfiguration) {
+    config.persist_tx_index = true;
+    config.persist_address_tx_index = true;
+}
+
+#[test]
+fn test_transactions_by_address_pages() {
+    let dir = TempDir::new("address_tx_index_pages").unwrap();
+    let data_man = new_data_manager(&dir, with_address_tx_index);
+    let address = Address::random().with_native_space();
+    let other = Address::random().with_evm_space();
+    let tx_hashes: Vec<_> = (1..=5).map(H256::from_low_u64_be).collect();
+
+    let mut blocks = vec![];
+    for (epoch, tx_hash) in (1..=5).zip(&tx_hashes) {
+        blocks.push(execute_tx(&data_man, epoch, 0, tx_hash));
+        if epoch == 3 {
+            index_tx(&data_man, epoch, tx_hash, &[address, other]);
+        } else {
+            index_tx(&data_man, epoch, tx_hash, &[address]);
+        }
+    }
+
+    // From the newest to the oldest, with the cursor of the next page.
+    let (txs, cursor) = data_man.transactions_by_address(&address, None, 2);
+    assert_eq!(tx_epochs(&txs), vec![5, 4]);
+    assert_eq!(txs[0].0.tx_hash, tx_hashes[4]);
+    assert_eq!(txs[0].1.block_hash, blocks[4]);
+    assert_eq!(cursor, Some(2));
+
+    let (txs, cursor) = data_man.transactions_by_address(&address, cursor, 2);
+    assert_eq!(tx_epochs(&txs), vec![3, 2]);
+    assert_eq!(cursor, Some(0));
+
+    let (txs, cursor) = data_man.transactions_by_address(&address, cursor, 2);
+    assert_eq!(tx_epochs(&txs), vec![1]);
+    assert_eq!(cursor, None);
+
+    // A cursor beyond the newest entry starts from the newest one.
+    let (txs, cursor) =
+        data_man.transactions_by_address(&address, Some(100), 10);
+    assert_eq!(tx_epochs(&txs), vec![5, 4, 3, 2, 1]);
+    assert_eq!(cursor, None);
+
+    let (txs, cursor) = data_man.transactions_by_address(&other, None, 10);
+    assert_eq!(tx_epochs(&txs), vec![3]);
+    assert_eq!(txs[0].0.tx_hash, tx_hashes[2]);
+    assert_eq!(cursor, None);
+
+    let unknown = Address::random().with_native_space();
+    let (txs, cursor) = data_man.transactions_by_address(&unknown, None, 10);
+    assert!(txs.is_empty());
+    assert_eq!(cursor, None);
+}
+
+#[test]
+fn test_transactions_by_address_zero_limit() {
+    let dir = TempDir::new("address_tx_index_zero_limit").unwrap();
+    let data_man = new_data_manager(&dir, with_address_tx_index);
+    let address = Address::random().with_native_space();
+
+    for epoch in 1..=3 {
+        let tx_hash = H256::from_low_u64_be(epoch);
+        execute_tx(&data_man, epoch, 0, &tx_hash);
+        index_tx(&data_man, epoch, &tx_hash, &[address]);
+    }
+
+    // An empty page with a next cursor would return the same page forever.
+    for cursor in [None, Some(1), Some(100)] {
+        let (txs, next_cursor) =
+            data_man.transactions_by_address(&address, cursor, 0);
+        assert!(txs.is_empty());
+        assert_eq!(next_cursor, None);
+    }
+}
+
+#[test]
+fn test_transactions_by_address_reorg() {
+    let dir = TempDir::new("address_tx_index_reorg").unwrap();
+    let data_man = new_data_manager(&dir, with_address_tx_index);
+    let address = Address::random().with_native_space();
+    let tx_hashes: Vec<_> = (1..=3).map(H256::from_low_u64_be).collect();
+
+    for (epoch, tx_hash) in (1..=3).zip(&tx_hashes) {
+        execute_tx(&data_man, epoch, 0, tx_hash);
+        index_tx(&data_man, epoch, tx_hash, &[address]);
+    }
+
+    // The transaction of epoch 2 is executed in epoch 4 after a pivot chain
+    // reorg, and only its new entry is returned.
+    let block = execute_tx(&data_man, 4, 1, &tx_hashes[1]);
+    index_tx(&data_man, 4, &tx_hashes[1], &[address]);
+
+    let (txs, cursor) = data_man.transactions_by_address(&address, None, 10);
+    assert_eq!(tx_epochs(&txs), vec![4, 3, 1]);
+    assert_eq!(txs[0].0.tx_hash, tx_hashes[1]);
+    assert_eq!(txs[0].1.block_hash, block);
+    assert_eq!(cursor, None);
+
+    // A page of the reverted entry only is empty, but has a next page.
+    let (txs, cursor) = data_man.transactions_by_address(&address, Some(1), 1);
+    assert!(txs.is_empty());
+    assert_eq!(cursor, Some(0));
+}
+
+#[test]
+fn test_address_tx_index_gc() {
+    let dir = TempDir::new("address_tx_index_gc").unwrap();
+    let data_man = new_data_manager(&dir, |config| {
+        with_address_tx_index(config);
+        config.additional_maintained_transaction_index_epoch_count = Some(1);
+    });
+    let address = Address::random().with_native_space();
+    let other = Address::random().with_native_space();
+    let tx_hashes: Vec<_> = (1..=3).map(H256::from_low_u64_be).collect();
+
+    for (epoch, tx_hash) in (1..=3).zip(&tx_hashes) {
+        execute_tx(&data_man, epoch, 0, tx_hash);
+        index_tx(&data_man, epoch, tx_hash, &[address]);
+    }
+
+    // Epoch 2 is executed again on another pivot chain without the
+    // transactions of `address`, whose entry of epoch 2 is still collected.
+    let tx_hash = H256::from_low_u64_be(100);
+    execute_tx(&data_man, 2, 1, &tx_hash);
+    index_tx(&data_man, 2, &tx_hash, &[other]);
+
+    // The epochs up to 2 are garbage collected.
+    data_man.gc_base_epoch(2);
+    data_man.gc_base_epoch(3);
+
+    let range = data_man
+        .db_manager
+        .address_tx_range_from_db(&address)
+        .unwrap();
+    assert_eq!((range.first, range.next), (2, 3));
+    let (txs, cursor) = data_man.transactions_by_address(&address, None, 10);
+    assert_eq!(tx_epochs(&txs), vec![3]);
+    assert_eq!(cursor, None);
+
+    assert!(data_man
+        .db_manager
+        .address_tx_range_from_db(&other)
+        .is_none());
+    let (txs, _) = data_man.transactions_by_address(&other, None, 10);
+    assert!(txs.is_empty());
+
+    for epoch in 1..=2 {
+        assert!(data_man
+            .db_manager
+            .epoch_indexed_addresses_from_db(epoch)
+            .is_none());
+    }
+    assert!(data_man
+        .db_manager
+        .epoch_indexed_addresses_from_db(3)
+        .is_some());
+}
+
+#[test]
+fn test_address_tx_index_gc_after_reorg() {
+    let dir = TempDir::new("address_tx_index_gc_after_reorg").unwrap();
+    let data_man = new_data_manager(&dir, |config| {
+        with_address_tx_index(config);
+        config.additional_maintained_transaction_index_epoch_count = Some(1);
+    });
+    let address = Address::random().with_native_space();
+    let tx_hashes: Vec<_> = (1..=3).map(H256::from_low_u64_be).collect();
+
+    for (epoch, tx_hash) in (1..=3).zip(&tx_hashes) {
+        execute_tx(&data_man, epoch, 0, tx_hash);
+        index_tx(&data_man, epoch, tx_hash, &[address]);
+    }
+
+    // After a pivot chain reorg, the entry of epoch 2 is appended after the
+    // entry of epoch 3.
+    let tx_hash = H256::from_low_u64_be(100);
+    execute_tx(&data_man, 2, 1, &tx_hash);
+    index_tx(&data_man, 2, &tx_hash, &[address]);
+
+    // The epochs up to 2 are garbage collected, and the removal stops at the
+    // entry of epoch 3.
+    data_man.gc_base_epoch(2);
+    data_man.gc_base_epoch(3);
+
+    let range = data_man
+        .db_manager
+        .address_tx_range_from_db(&address)
+        .unwrap();
+    assert_eq!((range.first, range.next), (2, 4));
+    assert!(data_man
+        .db_manager
+        .epoch_indexed_addresses_from_db(2)
+        .is_none());
+    let addresses: Vec<_> = data_man
+        .db_manager
+        .epoch_indexed_addresses_from_db(3)
+        .unwrap()
+        .iter()
+        .collect();
+    assert_eq!(addresses, vec![address]);
+
+    // The entry of epoch 2 behind it is removed with epoch 3.
+    data_man.gc_base_epoch(4);
+
+    assert!(data_man
+        .db_manager
+        .address_tx_range_from_db(&address)
+        .is_none());
+    for index in 0..4 {
+        assert!(data_man
+            .db_manager
+            .address_tx_entry_from_db(&address, index)
+            .is_none());
+    }
+    assert!(data_man
+        .db_manager
+        .epoch_indexed_addresses_from_db(3)
+        .is_none());
+}
+
+#[test]
+fn test_address_tx_index_disabled() {
+    let dir = TempDir::new("address_tx_index_disabled").unwrap();
+    let data_man =
+        new_data_manager(&dir, |config| config.persist_tx_index = true);
+    let address = Address::random().with_native_space();
+    let tx_hash = H256::from_low_u64_be(1);
+
+    execute_tx(&data_man, 1, 0, &tx_hash);
+    index_tx(&data_man, 1, &tx_hash, &[address]);
+
+    assert!(!data_man.address_tx_index_enabled());
+    assert!(data_man
+        .db_manager
+        .address_tx_range_from_db(&address)
+        .is_none());
+    assert!(data_man
+        .db_manager
+        .epoch_indexed_addresses_from_db(1)
+        .is_none());
+    let (txs, cursor) = data_man.transactions_by_address(&address, None, 10);
+    assert!(txs.is_empty());
+    assert_eq!(cursor, None);
+}
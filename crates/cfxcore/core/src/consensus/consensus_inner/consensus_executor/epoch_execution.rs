@@ -1,12 +1,14 @@
 use super::ConsensusExecutionHandler;
-use std::{convert::From, sync::Arc};
+use std::{collections::BTreeSet, convert::From, sync::Arc};
 
 use alloy_rpc_types_trace::geth::GethDebugTracingOptions;
 use geth_tracer::{GethTraceWithHash, GethTracer, TxExecContext};
 use pow_types::StakingEvent;
 
 use cfx_statedb::{ErrorKind as DbErrorKind, Result as DbResult};
-use cfx_types::{Space, SpaceMap, H256, U256};
+use cfx_types::{
+    AddressSpaceUtil, AddressWithSpace, Space, SpaceMap, H256, U256,
+};
 use primitives::{
     receipt::BlockReceipts, Action, Block, BlockNumber, EpochId, Receipt,
     SignedTransaction, TransactionIndex,
@@ -19,7 +21,7 @@ use crate::{
 };
 use cfx_execute_helper::{
     exec_tracer::TransactionExecTraces,
-    observer::Observer,
+    observer::{participants::ParticipantsTracer, Observer},
     tx_outcome::{make_process_tx_outcome, ProcessTxOutcome},
 };
 use cfx_executor::{
@@ -120,6 +122,10 @@ impl ConsensusExecutionHandler {
 
         if !dry_run && on_local_pivot {
             self.tx_pool.recycle_transactions(epoch_recorder.repack_tx);
+            self.data_man.insert_address_tx_index(
+                pivot_block.block_header.height(),
+                epoch_recorder.address_txs,
+            );
         }
 
         debug!("Finish processing tx for epoch");
@@ -312,6 +318,7 @@ impl ConsensusExecutionHandler {
 
         let tx_skipped = r.receipt.tx_skipped();
         let phantom_txs = r.phantom_txs.clone();
+        let tx_participants = r.tx_participants.clone();
 
         recorder.receive_tx_outcome(r, transaction, block_context);
 
@@ -332,6 +339,24 @@ impl ConsensusExecutionHandler {
             },
         );
 
+        let index_addresses = self.data_man.address_tx_index_enabled();
+
+        if index_addresses {
+            // A transaction is indexed for the addresses in its own space.
+            // The cross-space transfers are indexed in the Ethereum space by
+            // the phantom transactions.
+            let space = transaction.space();
+            let mut addresses: BTreeSet<_> = tx_participants
+                .into_iter()
+                .filter(|address| address.space == space)
+                .collect();
+            addresses.insert(transaction.sender());
+            if let Action::Call(to) = transaction.action() {
+                addresses.insert(to.with_space(space));
+            }
+            recorder.address_txs.push((hash, addresses));
+        }
+
         // persist tx index for phantom transactions.
         // note: in some cases, pivot chain reorgs will result in
         // different phantom txs (with different hashes) for the
@@ -342,8 +367,22 @@ impl ConsensusExecutionHandler {
         let evm_tx_index = &mut recorder.tx_idx[Space::Ethereum];
 
         for ptx in phantom_txs {
+            let addresses = index_addresses.then(|| {
+                let mut addresses = BTreeSet::new();
+                addresses.insert(ptx.from.with_evm_space());
+                if let Action::Call(to) = &ptx.action {
+                    addresses.insert(to.with_evm_space());
+                }
+                addresses
+            });
+
+            let phantom_hash = ptx.into_eip155(evm_chain_id).hash();
+            if let Some(addresses) = addresses {
+                recorder.address_txs.push((phantom_hash, addresses));
+            }
+
             self.data_man.insert_transaction_index(
-                &ptx.into_eip155(evm_chain_id).hash(),
+                &phantom_hash,
                 &TransactionIndex {
                     block_hash: block.hash(),
                     real_index: idx,
@@ -363,7 +402,8 @@ impl ConsensusExecutionHandler {
         block_context: &BlockProcessContext,
     ) -> Observer {
         use alloy_rpc_types_trace::geth::{
-            GethDebugBuiltInTracerType::*, GethDebugTracerType::BuiltInTracer,
+            GethDebugBuiltInTracerType::*,
+            GethDebugTracerType::{BuiltInTracer, JsTracer},
         };
 
         let mut observer = if self.config.executive_trace {
@@ -372,6 +412,18 @@ impl ConsensusExecutionHandler {
             Observer::with_no_tracing()
         };
 
+        let EpochProcessContext {
+            on_local_pivot,
+            dry_run,
+            ..
+        } = *block_context.epoch_context;
+        if on_local_pivot
+            && !dry_run
+            && self.data_man.address_tx_index_enabled()
+        {
+            observer.participants = Some(ParticipantsTracer::default());
+        }
+
         if let Some(VirtualCall::GethTrace(ref task)) =
             block_context.epoch_context.virtual_call
         {
@@ -380,8 +432,13 @@ impl ConsensusExecutionHandler {
             let support_tracer = matches!(
                 task.opts.tracer,
                 Some(BuiltInTracer(
-                    FourByteTracer | CallTracer | PreStateTracer | NoopTracer
-                )) | None
+                    FourByteTracer
+                        | CallTracer
+                        | PreStateTracer
+                        | NoopTracer
+                        | MuxTracer
+                )) | Some(JsTracer(_))
+                    | None
             );
             let tx_gas_limit = transaction.gas_limit().as_u64();
 
@@ -523,6 +580,7 @@ struct EpochProcessRecorder {
     staking_events: Vec<StakingEvent>,
     repack_tx: Vec<Arc<SignedTransaction>>,
     geth_traces: Vec<GethTraceWithHash>,
+    address_txs: Vec<(H256, BTreeSet<AddressWithSpace>)>,
 
     evm_tx_idx: usize,
 }
@@ -538,6 +596,7 @@ struct BlockProcessRecorder {
     geth_traces: Vec<GethTraceWithHash>,
     repack_tx: Vec<Arc<SignedTransaction>>,
     staking_events: Vec<StakingEvent>,
+    address_txs: Vec<(H256, BTreeSet<AddressWithSpace>)>,
 
     tx_idx: SpaceMap<usize>,
 }
@@ -553,6 +612,7 @@ impl BlockProcessRecorder {
             geth_traces: vec![],
             repack_tx: vec![],
             staking_events: vec![],
+            address_txs: vec![],
             tx_idx,
         }
     }
@@ -632,6 +692,7 @@ impl BlockProcessRecorder {
         epoch_recorder.staking_events.extend(self.staking_events);
         epoch_recorder.repack_tx.extend(self.repack_tx);
         epoch_recorder.geth_traces.extend(self.geth_traces);
+        epoch_recorder.address_txs.extend(self.address_txs);
 
         epoch_recorder.evm_tx_idx = self.tx_idx[Space::Ethereum];
 
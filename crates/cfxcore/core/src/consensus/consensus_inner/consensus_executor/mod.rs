@@ -58,15 +58,16 @@ use crate::{
     },
     SharedTransactionPool,
 };
-use cfx_execute_helper::estimation::{
-    EstimateExt, EstimateRequest, EstimationContext,
+use cfx_execute_helper::{
+    estimation::{EstimateExt, EstimateRequest, EstimationContext},
+    simulation::{SimulateBlock, SimulatedBlock, SimulationContext},
 };
 use cfx_executor::{
     executive::ExecutionOutcome,
     machine::Machine,
     state::{
         distribute_pos_interest, update_pos_status, CleanupMode, State,
-        StateCommitResult,
+        StateCommitResult, StateOverride,
     },
 };
 use cfx_vm_types::{Env, Spec};
@@ -644,12 +645,39 @@ impl ConsensusExecutor {
         self.handler.call_virtual(tx, epoch_id, epoch_size, request)
     }
 
+    pub fn call_virtual_with_state(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+        request: EstimateRequest, state: State,
+    ) -> RpcResult<(ExecutionOutcome, EstimateExt)> {
+        self.handler.call_virtual_with_state(
+            tx,
+            epoch_id,
+            epoch_size,
+            request,
+            Some(state),
+        )
+    }
+
+    pub fn simulate_virtual(
+        &self, blocks: Vec<SimulateBlock>, epoch_id: &H256, epoch_size: usize,
+        space: Space, validation: bool,
+    ) -> RpcResult<Vec<SimulatedBlock>> {
+        self.handler
+            .simulate_virtual(blocks, epoch_id, epoch_size, space, validation)
+    }
+
     pub fn collect_blocks_geth_trace(
         &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
         opts: GethDebugTracingOptions, tx_hash: Option<H256>,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<GethTraceWithHash>> {
         self.handler.collect_blocks_geth_trace(
-            epoch_id, epoch_num, blocks, opts, tx_hash,
+            epoch_id,
+            epoch_num,
+            blocks,
+            opts,
+            tx_hash,
+            state_override,
         )
     }
 
@@ -1014,6 +1042,11 @@ impl ConsensusExecutionHandler {
             .block_header_by_hash(epoch_hash)
             .expect("must exists");
 
+        // the bloom-bits index is built from the confirmed epochs, and is
+        // rebuilt if this epoch replaces an indexed one
+        self.data_man
+            .update_bloom_bits_index(pivot_block_header.height(), epoch_hash);
+
         // Check if epoch is computed
         if !force_recompute
             && debug_record.is_none()
@@ -1587,6 +1620,16 @@ impl ConsensusExecutionHandler {
     pub fn call_virtual(
         &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
         request: EstimateRequest,
+    ) -> RpcResult<(ExecutionOutcome, EstimateExt)> {
+        self.call_virtual_with_state(tx, epoch_id, epoch_size, request, None)
+    }
+
+    /// Executes `tx` on `state` if it's given, e.g. a state built from the
+    /// entries retrieved by a light node, or on the local state of `epoch_id`
+    /// otherwise.
+    pub fn call_virtual_with_state(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+        request: EstimateRequest, state: Option<State>,
     ) -> RpcResult<(ExecutionOutcome, EstimateExt)> {
         let best_block_header = self.data_man.block_header_by_hash(epoch_id);
         if best_block_header.is_none() {
@@ -1625,11 +1668,14 @@ impl ConsensusExecutionHandler {
             Space::Native => None,
             Space::Ethereum => Some(Space::Ethereum),
         };
-        let mut state = self.get_state_by_epoch_id_and_space(
-            epoch_id,
-            best_block_header.height(),
-            state_space,
-        )?;
+        let mut state = match state {
+            Some(state) => state,
+            None => self.get_state_by_epoch_id_and_space(
+                epoch_id,
+                best_block_header.height(),
+                state_space,
+            )?,
+        };
 
         let time_stamp = best_block_header.timestamp();
 
@@ -1676,10 +1722,166 @@ impl ConsensusExecutionHandler {
         Ok(r?)
     }
 
+    /// Executes the transactions of `blocks` one by one on top of the state of
+    /// `epoch_id`, as if each block were the pivot block of a new epoch.
+    pub fn simulate_virtual(
+        &self, blocks: Vec<SimulateBlock>, epoch_id: &H256, epoch_size: usize,
+        space: Space, validation: bool,
+    ) -> RpcResult<Vec<SimulatedBlock>> {
+        let best_block_header =
+            match self.data_man.block_header_by_hash(epoch_id) {
+                Some(header) => header,
+                None => bail!("invalid epoch id"),
+            };
+
+        let pos_id = best_block_header.pos_reference().as_ref();
+        let pos_view_number =
+            pos_id.and_then(|id| self.pos_verifier.get_pos_view(id));
+        let pivot_decision_epoch = pos_id
+            .and_then(|id| self.pos_verifier.get_pivot_decision(id))
+            .and_then(|hash| self.data_man.block_header_by_hash(&hash))
+            .map(|header| header.height());
+
+        let start_block_number = match self.data_man.get_epoch_execution_context(epoch_id) {
+            Some(v) => v.start_block_number + epoch_size as u64,
+            None => bail!("cannot obtain the execution context. Database is potentially corrupted!"),
+        };
+
+        let state_space = match space {
+            Space::Native => None,
+            Space::Ethereum => Some(Space::Ethereum),
+        };
+        let mut state = self.get_state_by_epoch_id_and_space(
+            epoch_id,
+            best_block_header.height(),
+            state_space,
+        )?;
+        let mut simulator = SimulationContext::new(
+            &mut state,
+            self.machine.as_ref(),
+            validation,
+        );
+        let transitions = &self.machine.params().transition_heights;
+
+        let mut parent_hash = *epoch_id;
+        let mut number = start_block_number - 1;
+        let mut epoch_height = best_block_header.height();
+        let mut timestamp = best_block_header.timestamp();
+        let mut base_gas_price =
+            best_block_header.base_price().unwrap_or_default();
+
+        let mut simulated_blocks = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let SimulateBlock {
+                block_overrides,
+                state_override,
+                calls,
+            } = block;
+
+            let next_number = block_overrides.number.unwrap_or(number + 1);
+            let next_epoch_height =
+                block_overrides.epoch_height.unwrap_or(epoch_height + 1);
+            let next_timestamp =
+                block_overrides.timestamp.unwrap_or(timestamp + 1);
+            if next_number <= number || next_epoch_height <= epoch_height {
+                bail!("block numbers of simulated blocks must be increasing");
+            }
+            if next_timestamp <= timestamp {
+                bail!("timestamps of simulated blocks must be increasing");
+            }
+            number = next_number;
+            epoch_height = next_epoch_height;
+            timestamp = next_timestamp;
+
+            if let Some(price) = block_overrides.base_gas_price {
+                base_gas_price[space] = price;
+            }
+            let burnt_gas_price =
+                base_gas_price.map_all(|x| simulator.burnt_gas_price(x));
+            let gas_limit = block_overrides
+                .gas_limit
+                .unwrap_or(*best_block_header.gas_limit());
+            let author = block_overrides.author.unwrap_or_default();
+
+            if let Some(state_override) = &state_override {
+                simulator.apply_override(state_override)?;
+            }
+
+            let hash = BlockHeaderBuilder::new()
+                .with_parent_hash(parent_hash)
+                .with_height(epoch_height)
+                .with_timestamp(timestamp)
+                .with_gas_limit(gas_limit)
+                .with_author(author)
+                .with_base_price(Some(base_gas_price))
+                .build()
+                .hash();
+
+            let mut env = Env {
+                chain_id: self.machine.params().chain_id_map(epoch_height),
+                number,
+                author,
+                timestamp,
+                difficulty: Default::default(),
+                accumulated_gas_used: U256::zero(),
+                last_hash: parent_hash,
+                gas_limit,
+                epoch_height,
+                pos_view: pos_view_number,
+                finalized_epoch: pivot_decision_epoch,
+                transaction_epoch_bound: self
+                    .verification_config
+                    .transaction_epoch_bound,
+                base_gas_price,
+                burnt_gas_price,
+            };
+            let spec = self.machine.spec(env.number, env.epoch_height);
+
+            let mut simulated_calls = Vec::with_capacity(calls.len());
+            for call in calls {
+                invalid_params_check(
+                    "tx",
+                    self.verification_config.verify_transaction_common(
+                        &call.tx,
+                        AllChainID::fake_for_virtual(
+                            call.tx.chain_id().unwrap_or(1),
+                        ),
+                        epoch_height,
+                        transitions,
+                        VerifyTxMode::Local(VerifyTxLocalMode::Full, &spec),
+                    ),
+                )?;
+
+                let simulated = simulator.transact(call, &env, &spec)?;
+                if let Some(executed) = simulated.outcome.try_as_executed() {
+                    env.accumulated_gas_used += executed.gas_used;
+                }
+                simulated_calls.push(simulated);
+            }
+
+            simulated_blocks.push(SimulatedBlock {
+                hash,
+                parent_hash,
+                number,
+                epoch_height,
+                timestamp,
+                gas_limit,
+                gas_used: env.accumulated_gas_used,
+                author,
+                base_gas_price: base_gas_price[space],
+                calls: simulated_calls,
+            });
+            parent_hash = hash;
+        }
+
+        Ok(simulated_blocks)
+    }
+
     /// Execute transactions in the blocks to collect traces.
     pub fn collect_blocks_geth_trace(
         &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
         opts: GethDebugTracingOptions, tx_hash: Option<H256>,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<GethTraceWithHash>> {
         let state_space = None;
         let mut state = self.get_state_by_epoch_id_and_space(
@@ -1688,6 +1890,10 @@ impl ConsensusExecutionHandler {
             state_space,
         )?;
 
+        if let Some(state_override) = state_override {
+            state.apply_override(&state_override)?;
+        }
+
         let start_block_number = self
             .data_man
             .get_epoch_execution_context(&epoch_id)
@@ -20,7 +20,8 @@ pub use crate::consensus::{
 };
 use crate::{
     block_data_manager::{
-        BlockDataManager, BlockExecutionResultWithEpoch, DataVersionTuple,
+        bloom_bits::epoch_matches, BlockDataManager,
+        BlockExecutionResultWithEpoch, DataVersionTuple,
     },
     consensus::{
         consensus_inner::{
@@ -42,8 +43,12 @@ use cfx_execute_helper::{
         TraceFilter, TransactionExecTraces,
     },
     phantom_tx::build_bloom_and_recover_phantom,
+    simulation::{SimulateBlock, SimulatedBlock},
+};
+use cfx_executor::{
+    executive::ExecutionOutcome,
+    state::{State, StateOverride},
 };
-use cfx_executor::{executive::ExecutionOutcome, state::State};
 use geth_tracer::GethTraceWithHash;
 
 use alloy_rpc_types_trace::geth::GethDebugTracingOptions;
@@ -52,8 +57,8 @@ use cfx_parameters::{
     consensus::*,
     consensus_internal::REWARD_EPOCH_COUNT,
     rpc::{
-        GAS_PRICE_BLOCK_SAMPLE_SIZE, GAS_PRICE_DEFAULT_VALUE,
-        GAS_PRICE_TRANSACTION_SAMPLE_SIZE,
+        BLOOM_BITS_SECTION_SIZE, GAS_PRICE_BLOCK_SAMPLE_SIZE,
+        GAS_PRICE_DEFAULT_VALUE, GAS_PRICE_TRANSACTION_SAMPLE_SIZE,
     },
 };
 use cfx_statedb::StateDb;
@@ -759,7 +764,7 @@ impl ConsensusGraph {
         self.data_man.earliest_epoch_with_trace()
     }
 
-    fn filter_block_receipts<'a>(
+    pub(crate) fn filter_block_receipts<'a>(
         &self, filter: &'a LogFilter, epoch_number: u64, block_hash: H256,
         mut receipts: Vec<Receipt>, mut tx_hashes: Vec<H256>,
     ) -> impl Iterator<Item = LocalizedLogEntry> + 'a {
@@ -1095,14 +1100,39 @@ impl ConsensusGraph {
         // that we can check whether it changed between batches
         let mut consistency_check_data: Option<(u64, H256)> = None;
 
+        // the sections of the epochs are checked one by one as the epochs are
+        // iterated in order
+        let mut section_candidates: Option<(u64, Option<Vec<u8>>)> = None;
+
         let mut logs = self
             // iterate over epochs in reverse order
             .get_log_filter_epoch_range(from_epoch, to_epoch, check_range)?
+            // skip the epochs ruled out by the bloom-bits index
+            .filter(|epoch| {
+                let section = epoch / BLOOM_BITS_SECTION_SIZE;
+                if section_candidates.as_ref().map(|(s, _)| *s) != Some(section)
+                {
+                    section_candidates = Some((
+                        section,
+                        self.bloom_bits_candidates(
+                            section,
+                            &bloom_possibilities,
+                        ),
+                    ));
+                }
+                match &section_candidates {
+                    Some((_, Some(candidates))) => epoch_matches(
+                        candidates,
+                        epoch % BLOOM_BITS_SECTION_SIZE,
+                    ),
+                    _ => true,
+                }
+            })
             // we process epochs in each batch in parallel
             // but batches are processed one-by-one
             .chunks(self.config.get_logs_epoch_batch_size)
             .into_iter()
-            .map(move |epochs| {
+            .map(|epochs| {
                 self.filter_epoch_batch(
                     &filter,
                     &bloom_possibilities,
@@ -1134,6 +1164,26 @@ impl ConsensusGraph {
         Ok(logs)
     }
 
+    /// Returns the bit vector of the epochs of `section` whose log blooms may
+    /// match the filter, or `None` if the section is not indexed on the
+    /// current pivot chain.
+    fn bloom_bits_candidates(
+        &self, section: u64, bloom_possibilities: &Vec<Bloom>,
+    ) -> Option<Vec<u8>> {
+        let last_epoch = (section + 1) * BLOOM_BITS_SECTION_SIZE - 1;
+        let pivot_hash = self
+            .inner
+            .read_recursive()
+            .get_pivot_hash_from_epoch_number(last_epoch)
+            .ok()?;
+
+        self.data_man.bloom_bits_candidates(
+            section,
+            &pivot_hash,
+            bloom_possibilities,
+        )
+    }
+
     // collect epoch number, block index in epoch, block hash, pivot hash
     fn collect_block_info(
         &self, block_hash: H256,
@@ -1437,6 +1487,35 @@ impl ConsensusGraph {
             .call_virtual(tx, &epoch_id, epoch_size, request)
     }
 
+    /// Executes `tx` on `state`, which is the state of the pivot block
+    /// `epoch_id` provided by the caller, e.g. built from the state entries
+    /// retrieved by a light node.
+    pub fn call_virtual_with_state(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+        request: EstimateRequest, state: State,
+    ) -> RpcResult<(ExecutionOutcome, EstimateExt)> {
+        self.executor
+            .call_virtual_with_state(tx, epoch_id, epoch_size, request, state)
+    }
+
+    /// Simulates the transactions of `blocks` on top of the state of `epoch`.
+    pub fn simulate_virtual(
+        &self, blocks: Vec<SimulateBlock>, epoch: EpochNumber, space: Space,
+        validation: bool,
+    ) -> RpcResult<Vec<SimulatedBlock>> {
+        // only allow to simulate against stated epoch
+        self.validate_stated_epoch(&epoch)?;
+        let (epoch_id, epoch_size) = if let Ok(v) =
+            self.get_block_hashes_by_epoch(epoch)
+        {
+            (v.last().expect("pivot block always exist").clone(), v.len())
+        } else {
+            bail!("cannot get block hashes in the specified epoch, maybe it does not exist?");
+        };
+        self.executor
+            .simulate_virtual(blocks, &epoch_id, epoch_size, space, validation)
+    }
+
     pub fn collect_epoch_geth_trace(
         &self, epoch_num: u64, tx_hash: Option<H256>,
         opts: GethDebugTracingOptions,
@@ -1470,15 +1549,25 @@ impl ConsensusGraph {
             &blocks,
             opts,
             tx_hash,
+            /* state_override = */ None,
         )
     }
 
+    /// Execute the transactions in `blocks` on top of the state of `epoch_id`
+    /// to collect geth traces. `state_override` is applied to the state
+    /// before execution.
     pub fn collect_blocks_geth_trace(
         &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
         opts: GethDebugTracingOptions, tx_hash: Option<H256>,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<GethTraceWithHash>> {
         self.executor.collect_blocks_geth_trace(
-            epoch_id, epoch_num, blocks, opts, tx_hash,
+            epoch_id,
+            epoch_num,
+            blocks,
+            opts,
+            tx_hash,
+            state_override,
         )
     }
 
@@ -2487,7 +2576,8 @@ impl ConsensusGraphTrait for ConsensusGraph {
         self.txpool.set_ready();
         self.txpool
             .notify_new_best_info(self.best_info.read_recursive().clone())
-            .expect("No DB error")
+            .expect("No DB error");
+        self.txpool.load_journal();
     }
 
     /// Reset the information in consensus graph with only checkpoint
@@ -43,8 +43,17 @@ pub const COL_BLOCK_TRACES: u32 = 5;
 pub const COL_HASH_BY_BLOCK_NUMBER: u32 = 6;
 /// Column for PoS interest reward info.
 pub const COL_REWARD_BY_POS_EPOCH: u32 = 7;
+/// Column for the bloom-bits log index
+pub const COL_BLOOM_BITS: u32 = 8;
+/// Column for the address transaction index
+pub const COL_ADDRESS_TX_INDEX: u32 = 9;
 /// Number of columns in DB
-pub const NUM_COLUMNS: u32 = 8;
+///
+/// The columns added to an existing database are created when it is opened,
+/// but a database with more columns can no longer be opened by a release with
+/// fewer columns, e.g. the databases opened since `COL_BLOOM_BITS` is added
+/// cannot be opened by 2.0.2 or earlier.
+pub const NUM_COLUMNS: u32 = 10;
 
 /// Modes for updating caches.
 #[derive(Clone, Copy)]
@@ -3,7 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs::File,
     io::{BufRead, BufReader, Read},
     sync::Arc,
@@ -21,9 +21,12 @@ use cfx_parameters::{
         GENESIS_TOKEN_COUNT_IN_CFX, TWO_YEAR_UNLOCK_TOKEN_COUNT_IN_CFX,
     },
     genesis::*,
-    staking::POS_VOTE_PRICE,
+    staking::{
+        code_collateral_units, COLLATERAL_UNITS_PER_STORAGE_KEY,
+        DRIPS_PER_STORAGE_COLLATERAL_UNIT, POS_VOTE_PRICE,
+    },
 };
-use cfx_statedb::StateDb;
+use cfx_statedb::{Result as DbResult, StateDb};
 use cfx_storage::{StorageManager, StorageManagerTrait};
 use cfx_types::{
     address_util::AddressUtil, Address, AddressSpaceUtil, AddressWithSpace,
@@ -46,6 +49,7 @@ use cfx_executor::{
     },
     machine::Machine,
     state::{CleanupMode, State},
+    substate::Substate,
 };
 use cfx_vm_types::{CreateContractAddress, Env};
 use diem_types::account_address::AccountAddress;
@@ -103,9 +107,9 @@ pub fn load_secrets_file(
 pub fn genesis_block(
     storage_manager: &Arc<StorageManager>,
     genesis_accounts: HashMap<AddressWithSpace, U256>,
-    test_net_version: Address, initial_difficulty: U256, machine: Arc<Machine>,
-    need_to_execute: bool, genesis_chain_id: Option<u32>,
-    initial_nodes: &Option<GenesisPosState>,
+    genesis_alloc: &GenesisAlloc, test_net_version: Address,
+    initial_difficulty: U256, machine: Arc<Machine>, need_to_execute: bool,
+    genesis_chain_id: Option<u32>, initial_nodes: &Option<GenesisPosState>,
 ) -> Block {
     let mut state =
         State::new(StateDb::new(storage_manager.get_state_for_genesis_write()))
@@ -129,6 +133,9 @@ pub fn genesis_block(
             state.add_total_evm_tokens(balance);
         }
     }
+    for (addr, account) in genesis_alloc {
+        apply_alloc_account(&mut state, addr, account).expect("no db error");
+    }
     let genesis_account_address = GENESIS_ACCOUNT_ADDRESS.with_native_space();
 
     let genesis_token_count =
@@ -540,6 +547,231 @@ pub fn load_file(
     Ok(accounts)
 }
 
+/// An account of the genesis allocation file, which sets the full state of an
+/// address at genesis instead of only its balance.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GenesisAllocAccount {
+    pub balance: U256,
+    pub nonce: U256,
+    pub code: Vec<u8>,
+    pub storage: BTreeMap<H256, U256>,
+}
+
+pub type GenesisAlloc = HashMap<AddressWithSpace, GenesisAllocAccount>;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenesisAllocFile {
+    #[serde(default)]
+    native: BTreeMap<String, GenesisAllocFileAccount>,
+    #[serde(default)]
+    evm: BTreeMap<String, GenesisAllocFileAccount>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenesisAllocFileAccount {
+    balance: Option<String>,
+    nonce: Option<String>,
+    code: Option<String>,
+    #[serde(default)]
+    storage: BTreeMap<String, String>,
+}
+
+/// Loads the genesis allocation from a JSON file in the form of
+/// `{"native": {<address>: <account>}, "evm": {<address>: <account>}}`, where
+/// an account is a geth style `alloc` entry with the optional `balance`,
+/// `nonce`, `code` and `storage` fields. The numbers are decimal or `0x`
+/// prefixed hex strings. A native account with code or storage pays the
+/// storage collateral from its own balance.
+pub fn load_alloc_file(
+    path: &String, address_parser: impl Fn(&str) -> Result<Address, String>,
+) -> Result<GenesisAlloc, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read file {}: {:?}", path, e))?;
+    parse_alloc(&content, address_parser)
+}
+
+fn parse_alloc(
+    content: &str, address_parser: impl Fn(&str) -> Result<Address, String>,
+) -> Result<GenesisAlloc, String> {
+    let alloc_file: GenesisAllocFile = serde_json::from_str(content)
+        .map_err(|e| format!("failed to parse genesis alloc: {:?}", e))?;
+
+    let mut alloc = GenesisAlloc::new();
+    for (addr_str, account) in alloc_file.native {
+        let address = address_parser(&addr_str).map_err(|e| {
+            format!(
+                "failed to parse address: value = {}, error = {:?}",
+                addr_str, e
+            )
+        })?;
+        let account = parse_alloc_account(&addr_str, account)?;
+        if !account.code.is_empty() && !address.is_contract_address() {
+            return Err(format!(
+                "native address {} with code is not a contract address",
+                addr_str
+            ));
+        }
+        if !account.storage.is_empty() && account.code.is_empty() {
+            return Err(format!(
+                "native address {} with storage has no code",
+                addr_str
+            ));
+        }
+        let collateral = alloc_collateral(&account);
+        if account.balance < collateral {
+            return Err(format!(
+                "native address {} has insufficient balance for the storage \
+                 collateral: required = {}, balance = {}",
+                addr_str, collateral, account.balance
+            ));
+        }
+        alloc.insert(address.with_native_space(), account);
+    }
+    for (addr_str, account) in alloc_file.evm {
+        let address = addr_str
+            .trim_start_matches("0x")
+            .parse::<Address>()
+            .map_err(|e| {
+                format!(
+                    "failed to parse address: value = {}, error = {:?}",
+                    addr_str, e
+                )
+            })?;
+        let account = parse_alloc_account(&addr_str, account)?;
+        alloc.insert(address.with_evm_space(), account);
+    }
+    Ok(alloc)
+}
+
+fn parse_alloc_account(
+    addr_str: &str, account: GenesisAllocFileAccount,
+) -> Result<GenesisAllocAccount, String> {
+    let parse_u256 = |value: &str| {
+        let parsed = match value.strip_prefix("0x") {
+            Some(hex) => {
+                U256::from_str_radix(hex, 16).map_err(|e| format!("{:?}", e))
+            }
+            None => U256::from_dec_str(value).map_err(|e| format!("{:?}", e)),
+        };
+        parsed.map_err(|e| {
+            format!(
+                "failed to parse number of {}: value = {}, error = {}",
+                addr_str, value, e
+            )
+        })
+    };
+    let parse_bytes = |value: &str| {
+        value
+            .trim_start_matches("0x")
+            .from_hex::<Vec<u8>>()
+            .map_err(|e| {
+                format!(
+                    "failed to parse hex of {}: value = {}, error = {:?}",
+                    addr_str, value, e
+                )
+            })
+    };
+
+    let mut storage = BTreeMap::new();
+    for (key, value) in &account.storage {
+        let key_bytes = parse_bytes(key)?;
+        if key_bytes.len() > 32 {
+            return Err(format!(
+                "storage key of {} is longer than 32 bytes: {}",
+                addr_str, key
+            ));
+        }
+        let mut key = H256::zero();
+        key.as_bytes_mut()[32 - key_bytes.len()..].copy_from_slice(&key_bytes);
+        let value = parse_u256(value)?;
+        // A zero value is the same as an absent slot.
+        if !value.is_zero() {
+            storage.insert(key, value);
+        }
+    }
+    Ok(GenesisAllocAccount {
+        balance: account
+            .balance
+            .as_deref()
+            .map_or(Ok(U256::zero()), parse_u256)?,
+        nonce: account
+            .nonce
+            .as_deref()
+            .map_or(Ok(U256::zero()), parse_u256)?,
+        code: account.code.as_deref().map_or(Ok(vec![]), parse_bytes)?,
+        storage,
+    })
+}
+
+/// The storage collateral in drips for the code and storage of a native
+/// account.
+fn alloc_collateral(account: &GenesisAllocAccount) -> U256 {
+    let code_units = if account.code.is_empty() {
+        0
+    } else {
+        code_collateral_units(account.code.len())
+    };
+    let storage_units =
+        account.storage.len() as u64 * COLLATERAL_UNITS_PER_STORAGE_KEY;
+    U256::from(code_units + storage_units) * *DRIPS_PER_STORAGE_COLLATERAL_UNIT
+}
+
+fn apply_alloc_account(
+    state: &mut State, address: &AddressWithSpace,
+    account: &GenesisAllocAccount,
+) -> DbResult<()> {
+    // Creates the account even if it only has a nonce, code or storage.
+    state.set_nonce(address, &account.nonce)?;
+    state.add_balance(address, &account.balance, CleanupMode::NoEmpty)?;
+    state.add_total_issued(account.balance);
+    if address.space == Space::Ethereum {
+        state.add_total_evm_tokens(account.balance);
+    }
+    // A native contract owns its code and storage. The entries in the eSpace
+    // have no owner.
+    let owner = match address.space {
+        Space::Native => address.address,
+        Space::Ethereum => Address::zero(),
+    };
+    if !account.code.is_empty() {
+        state.init_code(address, account.code.clone(), owner)?;
+    }
+    let mut substate = Substate::new();
+    for (key, value) in &account.storage {
+        state.set_storage(
+            address,
+            key.as_bytes().to_vec(),
+            *value,
+            owner,
+            &mut substate,
+        )?;
+    }
+
+    if address.space == Space::Native {
+        // The collateral of a contract is paid from its sponsor balance. The
+        // contract sponsors itself, so the sponsor balance is returned to it
+        // when it is destroyed.
+        let collateral = alloc_collateral(account);
+        if !collateral.is_zero() {
+            state.sub_balance(
+                address,
+                &collateral,
+                &mut CleanupMode::NoEmpty,
+            )?;
+            state.set_sponsor_for_collateral(
+                &address.address,
+                &address.address,
+                &collateral,
+                false,
+            )?;
+            state.add_collateral_for_storage(&address.address, &collateral)?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct GenesisPosNodeInfo {
     pub address: Address,
@@ -555,3 +787,264 @@ pub struct GenesisPosState {
     pub initial_committee: Vec<(AccountAddress, u64)>,
     pub initial_seed: H256,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_alloc_account, parse_alloc, GenesisAllocAccount};
+    use cfx_executor::{
+        executive::{ExecutionOutcome, ExecutiveContext, TransactOptions},
+        machine::{new_machine_with_builtin, VmFactory},
+        spec::CommonParams,
+        state::State,
+    };
+    use cfx_parameters::{
+        consensus::ONE_CFX_IN_DRIP,
+        staking::{
+            code_collateral_units, COLLATERAL_UNITS_PER_STORAGE_KEY,
+            DRIPS_PER_STORAGE_COLLATERAL_UNIT,
+        },
+    };
+    use cfx_statedb::StateDb;
+    use cfx_storage::{
+        new_storage_manager_for_testing, StateIndex, StorageManagerTrait,
+    };
+    use cfx_types::{Address, AddressSpaceUtil, Space, H256, U256};
+    use cfx_vm_types::Env;
+    use keylib::{public_to_address, Generator, Random};
+    use primitives::{
+        transaction::Eip155Transaction, Action, EpochId, Transaction,
+    };
+    use std::str::FromStr;
+
+    fn parse_hex_address(addr: &str) -> Result<Address, String> {
+        Address::from_str(addr.trim_start_matches("0x"))
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    #[test]
+    fn test_parse_alloc() {
+        let content = r#"{
+            "native": {
+                "0x8000000000000000000000000000000000000001": {
+                    "balance": "1000000000000000000",
+                    "code": "0x6000"
+                }
+            },
+            "evm": {
+                "0x0000000000000000000000000000000000000002": {
+                    "balance": "0x10",
+                    "nonce": "3",
+                    "storage": {"0x01": "0x2a"}
+                }
+            }
+        }"#;
+        let alloc = parse_alloc(content, parse_hex_address).unwrap();
+        assert_eq!(alloc.len(), 2);
+
+        let contract =
+            Address::from_str("8000000000000000000000000000000000000001")
+                .unwrap();
+        assert_eq!(
+            alloc[&contract.with_native_space()],
+            GenesisAllocAccount {
+                balance: U256::from(ONE_CFX_IN_DRIP),
+                nonce: U256::zero(),
+                code: vec![0x60, 0x00],
+                storage: Default::default(),
+            }
+        );
+        let account = &alloc[&Address::from_low_u64_be(2).with_evm_space()];
+        assert_eq!(account.balance, U256::from(16));
+        assert_eq!(account.nonce, U256::from(3));
+        assert!(account.code.is_empty());
+        assert_eq!(
+            account.storage,
+            vec![(H256::from_low_u64_be(1), U256::from(42))]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn test_parse_alloc_native_storage() {
+        // Storage without code.
+        let content = r#"{
+            "native": {
+                "0x8000000000000000000000000000000000000001": {
+                    "balance": "1000000000000000000",
+                    "storage": {"0x01": "0x2a"}
+                }
+            }
+        }"#;
+        assert!(parse_alloc(content, parse_hex_address).is_err());
+
+        // The balance does not cover the collateral of the code and the
+        // storage.
+        let content = r#"{
+            "native": {
+                "0x8000000000000000000000000000000000000001": {
+                    "balance": "1000",
+                    "code": "0x6000",
+                    "storage": {"0x01": "0x2a"}
+                }
+            }
+        }"#;
+        assert!(parse_alloc(content, parse_hex_address).is_err());
+    }
+
+    #[test]
+    fn test_apply_alloc_native_collateral() {
+        let content = r#"{
+            "native": {
+                "0x8000000000000000000000000000000000000001": {
+                    "balance": "1000000000000000000",
+                    "code": "0x6000",
+                    "storage": {"0x01": "0x2a", "0x02": "0x2b"}
+                }
+            }
+        }"#;
+        let alloc = parse_alloc(content, parse_hex_address).unwrap();
+        let contract =
+            Address::from_str("8000000000000000000000000000000000000001")
+                .unwrap();
+
+        let storage_manager = new_storage_manager_for_testing();
+        let mut state = State::new(StateDb::new(
+            storage_manager.get_state_for_genesis_write(),
+        ))
+        .unwrap();
+        for (address, account) in &alloc {
+            apply_alloc_account(&mut state, address, account).unwrap();
+        }
+
+        // 1 KiB of code and 2 storage entries.
+        let collateral = U256::from(
+            code_collateral_units(2) + 2 * COLLATERAL_UNITS_PER_STORAGE_KEY,
+        ) * *DRIPS_PER_STORAGE_COLLATERAL_UNIT;
+        let balance = U256::from(ONE_CFX_IN_DRIP);
+        assert_eq!(
+            state.balance(&contract.with_native_space()).unwrap(),
+            balance - collateral
+        );
+        assert_eq!(
+            state.collateral_for_storage(&contract).unwrap(),
+            collateral
+        );
+        assert_eq!(
+            state.sponsor_for_collateral(&contract).unwrap(),
+            Some(contract)
+        );
+        assert_eq!(
+            state.code_owner(&contract.with_native_space()).unwrap(),
+            contract
+        );
+        assert_eq!(state.total_storage_tokens(), collateral);
+        assert_eq!(state.total_issued_tokens(), balance);
+    }
+
+    #[test]
+    fn test_overwrite_alloc_storage() {
+        let content = r#"{
+            "evm": {
+                "0x000000000000000000000000000000000000aaaa": {
+                    "code": "0x6007600155600060025500",
+                    "storage": {"0x01": "0x2a", "0x02": "0x2b"}
+                }
+            }
+        }"#;
+        let alloc = parse_alloc(content, parse_hex_address).unwrap();
+        let contract = Address::from_low_u64_be(0xaaaa).with_evm_space();
+        let sender_key = Random.generate().unwrap();
+        let sender = public_to_address(sender_key.public(), false);
+
+        let storage_manager = new_storage_manager_for_testing();
+        let mut state = State::new(StateDb::new(
+            storage_manager.get_state_for_genesis_write(),
+        ))
+        .unwrap();
+        for (address, account) in &alloc {
+            apply_alloc_account(&mut state, address, account).unwrap();
+        }
+        apply_alloc_account(
+            &mut state,
+            &sender.with_evm_space(),
+            &GenesisAllocAccount {
+                balance: U256::from(ONE_CFX_IN_DRIP),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let total_storage_tokens = state.total_storage_tokens();
+        let genesis_epoch_id = EpochId::default();
+        state.commit(genesis_epoch_id, None).unwrap();
+
+        let mut state = State::new(StateDb::new(
+            storage_manager
+                .get_state_for_next_epoch(
+                    StateIndex::new_for_test_only_delta_mpt(&genesis_epoch_id),
+                    false,
+                )
+                .unwrap()
+                .unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(
+            state.storage_at(&contract, &u256_key(1)).unwrap(),
+            U256::from(42)
+        );
+
+        // code:
+        //
+        // 60 07 - push 7
+        // 60 01 - push 1
+        // 55 - sstore
+        // 60 00 - push 0
+        // 60 02 - push 2
+        // 55 - sstore
+        // 00 - stop
+        let tx = Transaction::from(Eip155Transaction {
+            nonce: U256::zero(),
+            gas_price: U256::one(),
+            gas: U256::from(100_000),
+            action: Action::Call(contract.address),
+            value: U256::zero(),
+            chain_id: Some(1),
+            data: vec![],
+        })
+        .sign(sender_key.secret());
+        let mut env = Env::default();
+        env.chain_id.insert(Space::Ethereum, 1);
+        env.gas_limit = U256::from(1_000_000);
+        let machine = new_machine_with_builtin(
+            CommonParams::default(),
+            VmFactory::new(1024 * 32),
+        );
+        let spec = machine.spec(env.number, env.epoch_height);
+        let outcome = ExecutiveContext::new(&mut state, &env, &machine, &spec)
+            .transact(&tx, TransactOptions::default())
+            .unwrap();
+        assert!(matches!(outcome, ExecutionOutcome::Finished(_)));
+
+        assert_eq!(
+            state.storage_at(&contract, &u256_key(1)).unwrap(),
+            U256::from(7)
+        );
+        assert!(state.storage_at(&contract, &u256_key(2)).unwrap().is_zero());
+        assert_eq!(state.total_storage_tokens(), total_storage_tokens);
+    }
+
+    fn u256_key(key: u64) -> Vec<u8> {
+        H256::from_low_u64_be(key).as_bytes().to_vec()
+    }
+
+    #[test]
+    fn test_parse_alloc_native_code_on_user_address() {
+        let content = r#"{
+            "native": {
+                "0x1000000000000000000000000000000000000001": {"code": "0x00"}
+            }
+        }"#;
+        assert!(parse_alloc(content, parse_hex_address).is_err());
+        assert!(parse_alloc(r#"{"eth": {}}"#, parse_hex_address).is_err());
+    }
+}
@@ -11,7 +11,9 @@ use std::{
     time::Instant,
 };
 
-use crate::message::MsgId;
+use crate::{
+    light_protocol::message::request_version_introduced, message::MsgId,
+};
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
 use network::{node_table::NodeId, service::ProtocolVersion};
@@ -127,7 +129,13 @@ impl FullPeerFilter {
     }
 
     pub fn select_all(self, peers: Arc<Peers<FullPeerState>>) -> Vec<NodeId> {
+        let min_protocol_version = request_version_introduced(self.msg_id);
+
         peers.all_peers_satisfying(|peer| {
+            if peer.protocol_version < min_protocol_version {
+                return false;
+            }
+
             if peer.throttled_msgs.check_throttled(&self.msg_id) {
                 return false;
             }
@@ -60,6 +60,11 @@ error_chain! {
             display("Logs bloom hash validation for epoch {} failed, expected={:?}, received={:?}", epoch, expected, received),
         }
 
+        InvalidEthReceipts{ epoch: u64, reason: &'static str } {
+            description("Invalid eSpace receipts"),
+            display("Invalid eSpace receipts for epoch {}: {}", epoch, reason),
+        }
+
         InvalidHeader {
             description("Header verification failed"),
             display("Header verification failed"),
@@ -232,6 +237,7 @@ pub fn handle(
         }
 
         ErrorKind::InvalidBloom{..}
+        | ErrorKind::InvalidEthReceipts{..}
         | ErrorKind::InvalidLedgerProofSize{..}
         | ErrorKind::InvalidMessageFormat
         | ErrorKind::InvalidPreviousStateRoot{..}
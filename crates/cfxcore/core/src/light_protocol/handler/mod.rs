@@ -8,15 +8,18 @@ use crate::{
     block_data_manager::BlockDataManager,
     consensus::SharedConsensusGraph,
     light_protocol::{
-        common::{validate_chain_id, FullPeerState, Peers},
+        common::{validate_chain_id, FullPeerFilter, FullPeerState, Peers},
         error::*,
         handle_error,
+        local_tx_pool::LocalTxPool,
         message::{
             msgid, BlockHashes as GetBlockHashesResponse,
             BlockHeaders as GetBlockHeadersResponse,
             BlockTxs as GetBlockTxsResponse, Blooms as GetBloomsResponse,
-            NewBlockHashes, NodeType, Receipts as GetReceiptsResponse,
-            SendRawTx, StateEntries as GetStateEntriesResponse,
+            EthReceipts as GetEthReceiptsResponse,
+            EthStateEntries as GetEthStateEntriesResponse, NewBlockHashes,
+            NodeType, Receipts as GetReceiptsResponse, SendRawTx,
+            StateEntries as GetStateEntriesResponse,
             StateRoots as GetStateRootsResponse, StatusPingDeprecatedV1,
             StatusPingV2, StatusPongDeprecatedV1, StatusPongV2,
             StorageRoots as GetStorageRootsResponse,
@@ -32,7 +35,8 @@ use crate::{
 };
 use cfx_internal_common::ChainIdParamsDeprecated;
 use cfx_parameters::light::{
-    CATCH_UP_EPOCH_LAG_THRESHOLD, CLEANUP_PERIOD, HEARTBEAT_PERIOD, SYNC_PERIOD,
+    CATCH_UP_EPOCH_LAG_THRESHOLD, CLEANUP_PERIOD, HEARTBEAT_PERIOD,
+    LOCAL_TX_REBROADCAST_PERIOD, LOCAL_TX_TIMEOUT, SYNC_PERIOD,
 };
 use cfx_types::H256;
 use diem_types::validator_config::{ConsensusPublicKey, ConsensusVRFPublicKey};
@@ -42,7 +46,7 @@ use network::{
     NetworkProtocolHandler,
 };
 use parking_lot::RwLock;
-use rlp::Rlp;
+use rlp::{Encodable, Rlp};
 use std::{
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -52,8 +56,9 @@ use std::{
     time::{Duration, Instant},
 };
 use sync::{
-    BlockTxs, Blooms, Epochs, HashSource, Headers, Receipts, StateEntries,
-    StateRoots, StorageRoots, TxInfos, Txs, Witnesses,
+    BlockTxs, Blooms, Epochs, EthReceipts, EthStateEntries, HashSource,
+    Headers, Receipts, StateEntries, StateRoots, StorageRoots, TxInfos, Txs,
+    Witnesses,
 };
 use throttling::token_bucket::TokenBucketManager;
 
@@ -62,6 +67,7 @@ const REQUEST_CLEANUP_TIMER: TimerToken = 1;
 const LOG_STATISTICS_TIMER: TimerToken = 2;
 const HEARTBEAT_TIMER: TimerToken = 3;
 const TOTAL_WEIGHT_IN_PAST_TIMER: TimerToken = 4;
+const LOCAL_TX_TIMER: TimerToken = 5;
 const CHECK_SYNC_NOT_READY_BLOCKS_TIMER: TimerToken = 7;
 
 /// Handler is responsible for maintaining peer meta-information and
@@ -81,12 +87,21 @@ pub struct Handler {
     // epoch sync manager
     epochs: Epochs,
 
+    // eSpace receipt sync manager
+    pub eth_receipts: EthReceipts,
+
+    // eSpace state entry sync manager
+    pub eth_state_entries: EthStateEntries,
+
     // header sync manager
     headers: Arc<Headers>,
 
     // join handle for witness worker thread
     join_handle: Option<thread::JoinHandle<()>>,
 
+    // transactions submitted through the local RPC
+    pub local_txs: Arc<LocalTxPool>,
+
     // collection of all peers available
     pub peers: Arc<Peers<FullPeerState>>,
 
@@ -182,6 +197,12 @@ impl Handler {
             request_id_allocator.clone(),
         );
 
+        let eth_state_entries = EthStateEntries::new(
+            peers.clone(),
+            state_roots.clone(),
+            request_id_allocator.clone(),
+        );
+
         let txs =
             Arc::new(Txs::new(peers.clone(), request_id_allocator.clone()));
 
@@ -192,6 +213,14 @@ impl Handler {
             txs.clone(),
         ));
 
+        let eth_receipts = EthReceipts::new(
+            consensus.clone(),
+            peers.clone(),
+            request_id_allocator.clone(),
+            receipts.clone(),
+            block_txs.clone(),
+        );
+
         let tx_infos = TxInfos::new(
             consensus.clone(),
             peers.clone(),
@@ -213,8 +242,11 @@ impl Handler {
             blooms,
             consensus,
             epochs,
+            eth_receipts,
+            eth_state_entries,
             headers,
             join_handle,
+            local_txs: Arc::new(LocalTxPool::new()),
             peers,
             protocol_version: LIGHT_PROTOCOL_VERSION,
             receipts,
@@ -391,6 +423,8 @@ impl Handler {
             msgid::BLOCK_HEADERS => self.on_block_headers(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::BLOCK_TXS => self.on_block_txs(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::BLOOMS => self.on_blooms(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
+            msgid::ETH_RECEIPTS => self.on_eth_receipts(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
+            msgid::ETH_STATE_ENTRIES => self.on_eth_state_entries(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::NEW_BLOCK_HASHES => self.on_new_block_hashes(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::RECEIPTS => self.on_receipts(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::STATE_ENTRIES => self.on_state_entries(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
@@ -544,6 +578,47 @@ impl Handler {
         Ok(())
     }
 
+    /// Stops tracking the local txs whose receipts are verified, and
+    /// rebroadcasts the others.
+    fn maintain_local_txs(&self, io: &dyn NetworkContext) {
+        for hash in self.local_txs.hashes() {
+            if self.tx_infos.is_verified(&hash) {
+                debug!("Local tx {:?} is included", hash);
+                self.local_txs.remove(&hash);
+            }
+        }
+
+        let txs = self
+            .local_txs
+            .due_for_broadcast(*LOCAL_TX_REBROADCAST_PERIOD, *LOCAL_TX_TIMEOUT);
+
+        if txs.is_empty() {
+            return;
+        }
+
+        let peers = FullPeerFilter::new(msgid::SEND_RAW_TX)
+            .select_all(self.peers.clone());
+
+        for tx in txs {
+            let raw = tx.transaction.rlp_bytes();
+
+            for peer in &peers {
+                if let Err(e) = self.send_raw_tx(io, peer, raw.clone()) {
+                    warn!(
+                        "Failed to rebroadcast tx {:?} to peer={:?}: {:?}",
+                        tx.hash(),
+                        peer,
+                        e
+                    );
+                }
+            }
+
+            // the tx info is verified only if the tx has been included;
+            // it is checked in the next round
+            self.tx_infos.request(io, tx.hash());
+        }
+    }
+
     fn on_status_v2(
         &self, io: &dyn NetworkContext, peer: &NodeId, status: StatusPongV2,
     ) -> Result<()> {
@@ -677,6 +752,48 @@ impl Handler {
         Ok(())
     }
 
+    fn on_eth_receipts(
+        &self, io: &dyn NetworkContext, peer: &NodeId,
+        resp: GetEthReceiptsResponse,
+    ) -> Result<()> {
+        debug!(
+            "received {} eth receipts (request id = {})",
+            resp.receipts.len(),
+            resp.request_id
+        );
+        trace!("on_eth_receipts resp={:?}", resp);
+
+        self.eth_receipts.receive(
+            peer,
+            resp.request_id,
+            resp.receipts.into_iter(),
+        )?;
+
+        self.eth_receipts.sync(io);
+        Ok(())
+    }
+
+    fn on_eth_state_entries(
+        &self, io: &dyn NetworkContext, peer: &NodeId,
+        resp: GetEthStateEntriesResponse,
+    ) -> Result<()> {
+        debug!(
+            "received {} eth state entries (request id = {})",
+            resp.entries.len(),
+            resp.request_id
+        );
+        trace!("on_eth_state_entries resp={:?}", resp);
+
+        self.eth_state_entries.receive(
+            peer,
+            resp.request_id,
+            resp.entries.into_iter(),
+        )?;
+
+        self.eth_state_entries.sync(io);
+        Ok(())
+    }
+
     fn on_new_block_hashes(
         &self, io: &dyn NetworkContext, peer: &NodeId, msg: NewBlockHashes,
     ) -> Result<()> {
@@ -857,6 +974,8 @@ impl Handler {
         self.blooms.sync(io);
         self.receipts.sync(io);
         self.block_txs.sync(io);
+        self.eth_receipts.sync(io);
+        self.eth_state_entries.sync(io);
         self.state_entries.sync(io);
         self.state_roots.sync(io);
         self.storage_roots.sync(io);
@@ -868,6 +987,8 @@ impl Handler {
         self.block_txs.clean_up();
         self.blooms.clean_up();
         self.epochs.clean_up();
+        self.eth_receipts.clean_up();
+        self.eth_state_entries.clean_up();
         self.headers.clean_up();
         self.receipts.clean_up();
         self.state_entries.clean_up();
@@ -959,6 +1080,9 @@ impl NetworkProtocolHandler for Handler {
             Duration::from_millis(1000),
         )
         .expect("Error registering CHECK_FUTURE_BLOCK_TIMER");
+
+        io.register_timer(LOCAL_TX_TIMER, *LOCAL_TX_REBROADCAST_PERIOD)
+            .expect("Error registering local tx timer");
     }
 
     fn on_message(&self, io: &dyn NetworkContext, peer: &NodeId, raw: &[u8]) {
@@ -1033,6 +1157,8 @@ impl NetworkProtocolHandler for Handler {
                 self.block_txs.print_stats();
                 self.blooms.print_stats();
                 self.epochs.print_stats();
+                self.eth_receipts.print_stats();
+                self.eth_state_entries.print_stats();
                 self.headers.print_stats();
                 self.receipts.print_stats();
                 self.state_entries.print_stats();
@@ -1053,6 +1179,7 @@ impl NetworkProtocolHandler for Handler {
                     .graph
                     .check_not_ready_frontier(true /* header_only */);
             }
+            LOCAL_TX_TIMER => self.maintain_local_txs(io),
             // TODO(thegaram): add other timers (e.g. data_man gc)
             _ => warn!("Unknown timer {} triggered.", timer),
         }
@@ -0,0 +1,284 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+extern crate lru_time_cache;
+
+use super::{
+    common::{FutureItem, KeyOrdered, PendingItem, SyncManager},
+    BlockTxs, Receipts,
+};
+use crate::{
+    consensus::SharedConsensusGraph,
+    light_protocol::{
+        common::{FullPeerState, LedgerInfo, Peers},
+        error::*,
+        message::{msgid, EthReceiptsWithEpoch, GetEthReceipts},
+    },
+    message::{Message, RequestId},
+    UniqueId,
+};
+use cfx_parameters::light::{
+    CACHE_TIMEOUT, ETH_RECEIPT_REQUEST_BATCH_SIZE, ETH_RECEIPT_REQUEST_TIMEOUT,
+    MAX_ETH_RECEIPTS_IN_FLIGHT,
+};
+use futures::future::FutureExt;
+use lru_time_cache::LruCache;
+use network::{node_table::NodeId, NetworkContext};
+use parking_lot::RwLock;
+use primitives::{BlockReceipts, SignedTransaction};
+use std::{future::Future, sync::Arc};
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Statistics {
+    cached: usize,
+    in_flight: usize,
+    waiting: usize,
+}
+
+/// The receipts and the transactions of all blocks in an epoch, from which
+/// the eSpace receipts and logs of the epoch are derived.
+#[derive(Clone, Debug, Default)]
+pub struct EthReceiptsValidated {
+    pub epoch_receipts: Vec<BlockReceipts>,
+    pub epoch_txs: Vec<Vec<SignedTransaction>>,
+}
+
+// prioritize higher epochs
+type MissingEthReceipts = KeyOrdered<u64>;
+
+type PendingEthReceipts = PendingItem<EthReceiptsValidated, ClonableError>;
+
+pub struct EthReceipts {
+    // block tx sync manager
+    block_txs: Arc<BlockTxs>,
+
+    // helper API for retrieving ledger information
+    ledger: LedgerInfo,
+
+    // receipt sync manager
+    receipts: Arc<Receipts>,
+
+    // series of unique request ids
+    request_id_allocator: Arc<UniqueId>,
+
+    // sync and request manager
+    sync_manager: SyncManager<u64, MissingEthReceipts>,
+
+    // epoch receipts and txs received from full node
+    verified: Arc<RwLock<LruCache<u64, PendingEthReceipts>>>,
+}
+
+impl EthReceipts {
+    pub fn new(
+        consensus: SharedConsensusGraph, peers: Arc<Peers<FullPeerState>>,
+        request_id_allocator: Arc<UniqueId>, receipts: Arc<Receipts>,
+        block_txs: Arc<BlockTxs>,
+    ) -> Self {
+        let ledger = LedgerInfo::new(consensus.clone());
+        let sync_manager =
+            SyncManager::new(peers.clone(), msgid::GET_ETH_RECEIPTS);
+
+        let cache = LruCache::with_expiry_duration(*CACHE_TIMEOUT);
+        let verified = Arc::new(RwLock::new(cache));
+
+        EthReceipts {
+            block_txs,
+            ledger,
+            receipts,
+            request_id_allocator,
+            sync_manager,
+            verified,
+        }
+    }
+
+    #[inline]
+    pub fn print_stats(&self) {
+        debug!(
+            "eth receipt sync statistics: {:?}",
+            Statistics {
+                cached: self.verified.read().len(),
+                in_flight: self.sync_manager.num_in_flight(),
+                waiting: self.sync_manager.num_waiting(),
+            }
+        );
+    }
+
+    #[inline]
+    pub fn request(
+        &self, epoch: u64,
+    ) -> impl Future<Output = Result<EthReceiptsValidated>> {
+        let mut verified = self.verified.write();
+
+        if epoch == 0 {
+            verified.insert(0, PendingItem::ready(Default::default()));
+        }
+
+        if !verified.contains_key(&epoch) {
+            let missing = MissingEthReceipts::new(epoch);
+            self.sync_manager.insert_waiting(std::iter::once(missing));
+        }
+
+        verified
+            .entry(epoch)
+            .or_insert(PendingItem::pending())
+            .clear_error();
+
+        FutureItem::new(epoch, self.verified.clone())
+            .map(|res| res.map_err(|e| e.into()))
+    }
+
+    #[inline]
+    pub fn receive(
+        &self, peer: &NodeId, id: RequestId,
+        receipts: impl Iterator<Item = EthReceiptsWithEpoch>,
+    ) -> Result<()> {
+        for item in receipts {
+            trace!(
+                "Validating eth receipts {:?} with epoch {}",
+                item.epoch_receipts,
+                item.epoch
+            );
+
+            match self
+                .sync_manager
+                .check_if_requested(peer, id, &item.epoch)?
+            {
+                None => continue,
+                Some(_) => self.validate_and_store(item)?,
+            };
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn validate_and_store(&self, item: EthReceiptsWithEpoch) -> Result<()> {
+        let epoch = item.epoch;
+
+        // validate receipts and txs
+        let validated = match self.validate_eth_receipts(item) {
+            Ok(validated) => validated,
+            Err(e) => {
+                // forward error to both rpc caller(s) and sync handler
+                // so we need to make it clonable
+                let e = ClonableError::from(e);
+
+                self.verified
+                    .write()
+                    .entry(epoch)
+                    .or_insert(PendingItem::pending())
+                    .set_error(e.clone());
+
+                bail!(e);
+            }
+        };
+
+        // store receipts and txs by epoch
+        self.verified
+            .write()
+            .entry(epoch)
+            .or_insert(PendingItem::pending())
+            .set(validated);
+
+        self.sync_manager.remove_in_flight(&epoch);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn clean_up(&self) {
+        // remove timeout in-flight requests
+        let timeout = *ETH_RECEIPT_REQUEST_TIMEOUT;
+        let receipts = self.sync_manager.remove_timeout_requests(timeout);
+        trace!("Timeout eth receipts ({}): {:?}", receipts.len(), receipts);
+        self.sync_manager.insert_waiting(receipts.into_iter());
+
+        // trigger cache cleanup
+        self.verified.write().get(&Default::default());
+    }
+
+    #[inline]
+    fn send_request(
+        &self, io: &dyn NetworkContext, peer: &NodeId, epochs: Vec<u64>,
+    ) -> Result<Option<RequestId>> {
+        if epochs.is_empty() {
+            return Ok(None);
+        }
+
+        let request_id = self.request_id_allocator.next();
+
+        trace!(
+            "send_request GetEthReceipts peer={:?} id={:?} epochs={:?}",
+            peer,
+            request_id,
+            epochs
+        );
+
+        let msg: Box<dyn Message> =
+            Box::new(GetEthReceipts { request_id, epochs });
+
+        msg.send(io, peer)?;
+        Ok(Some(request_id))
+    }
+
+    #[inline]
+    pub fn sync(&self, io: &dyn NetworkContext) {
+        self.sync_manager.sync(
+            MAX_ETH_RECEIPTS_IN_FLIGHT,
+            ETH_RECEIPT_REQUEST_BATCH_SIZE,
+            |peer, epochs| self.send_request(io, peer, epochs),
+        );
+    }
+
+    #[inline]
+    fn validate_eth_receipts(
+        &self, item: EthReceiptsWithEpoch,
+    ) -> Result<EthReceiptsValidated> {
+        let EthReceiptsWithEpoch {
+            epoch,
+            epoch_receipts,
+            block_txs,
+        } = item;
+
+        // validate receipts against the local receipts root
+        self.receipts.validate_receipts(epoch, &epoch_receipts)?;
+
+        // the txs must be those of the local blocks of the epoch, in order
+        let hashes = self.ledger.block_hashes_in(epoch)?;
+
+        if block_txs.len() != hashes.len()
+            || block_txs.iter().zip(&hashes).any(|(b, h)| b.hash != *h)
+        {
+            bail!(ErrorKind::InvalidEthReceipts {
+                epoch,
+                reason: "Block hashes do not match the local epoch",
+            });
+        }
+
+        if epoch_receipts.len() != block_txs.len()
+            || epoch_receipts
+                .iter()
+                .zip(&block_txs)
+                .any(|(r, b)| r.receipts.len() != b.block_txs.len())
+        {
+            bail!(ErrorKind::InvalidEthReceipts {
+                epoch,
+                reason: "Number of receipts and txs do not match",
+            });
+        }
+
+        // validate txs against the local transactions roots
+        let mut epoch_txs = Vec::with_capacity(block_txs.len());
+
+        for b in block_txs {
+            self.block_txs.validate_block_txs(b.hash, &b.block_txs)?;
+            epoch_txs.push(b.block_txs);
+        }
+
+        Ok(EthReceiptsValidated {
+            epoch_receipts,
+            epoch_txs,
+        })
+    }
+}
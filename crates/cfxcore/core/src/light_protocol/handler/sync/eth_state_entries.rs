@@ -0,0 +1,222 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+extern crate lru_time_cache;
+
+use super::{
+    common::{FutureItem, PendingItem, SyncManager, TimeOrdered},
+    state_entries::{validate_state_entry, StateEntry},
+    state_roots::StateRoots,
+};
+use crate::{
+    light_protocol::{
+        common::{FullPeerState, Peers},
+        error::*,
+        message::{
+            msgid, EthStateEntryWithKey, EthStateKey, GetEthStateEntries,
+            StateEntryProof,
+        },
+    },
+    message::{Message, RequestId},
+    UniqueId,
+};
+use cfx_parameters::light::{
+    CACHE_TIMEOUT, ETH_STATE_ENTRY_REQUEST_BATCH_SIZE,
+    ETH_STATE_ENTRY_REQUEST_TIMEOUT, MAX_ETH_STATE_ENTRIES_IN_FLIGHT,
+};
+use futures::future::FutureExt;
+use lru_time_cache::LruCache;
+use network::{node_table::NodeId, NetworkContext};
+use parking_lot::RwLock;
+use std::{future::Future, sync::Arc};
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Statistics {
+    cached: usize,
+    in_flight: usize,
+    waiting: usize,
+}
+
+type MissingEthStateEntry = TimeOrdered<EthStateKey>;
+
+type PendingEthStateEntry = PendingItem<StateEntry, ClonableError>;
+
+pub struct EthStateEntries {
+    // series of unique request ids
+    request_id_allocator: Arc<UniqueId>,
+
+    // state_root sync manager
+    state_roots: Arc<StateRoots>,
+
+    // sync and request manager
+    sync_manager: SyncManager<EthStateKey, MissingEthStateEntry>,
+
+    // eSpace state entries received from full node
+    verified: Arc<RwLock<LruCache<EthStateKey, PendingEthStateEntry>>>,
+}
+
+impl EthStateEntries {
+    pub fn new(
+        peers: Arc<Peers<FullPeerState>>, state_roots: Arc<StateRoots>,
+        request_id_allocator: Arc<UniqueId>,
+    ) -> Self {
+        let sync_manager =
+            SyncManager::new(peers.clone(), msgid::GET_ETH_STATE_ENTRIES);
+
+        let cache = LruCache::with_expiry_duration(*CACHE_TIMEOUT);
+        let verified = Arc::new(RwLock::new(cache));
+
+        EthStateEntries {
+            request_id_allocator,
+            sync_manager,
+            verified,
+            state_roots,
+        }
+    }
+
+    #[inline]
+    pub fn print_stats(&self) {
+        debug!(
+            "eth state entry sync statistics: {:?}",
+            Statistics {
+                cached: self.verified.read().len(),
+                in_flight: self.sync_manager.num_in_flight(),
+                waiting: self.sync_manager.num_waiting(),
+            }
+        );
+    }
+
+    #[inline]
+    pub fn request_now(
+        &self, io: &dyn NetworkContext, key: EthStateKey,
+    ) -> impl Future<Output = Result<StateEntry>> {
+        let mut verified = self.verified.write();
+
+        if !verified.contains_key(&key) {
+            let missing =
+                std::iter::once(MissingEthStateEntry::new(key.clone()));
+
+            self.sync_manager.request_now(missing, |peer, keys| {
+                self.send_request(io, peer, keys)
+            });
+        }
+
+        verified
+            .entry(key.clone())
+            .or_insert(PendingItem::pending())
+            .clear_error();
+
+        FutureItem::new(key, self.verified.clone())
+            .map(|res| res.map_err(|e| e.into()))
+    }
+
+    #[inline]
+    pub fn receive(
+        &self, peer: &NodeId, id: RequestId,
+        entries: impl Iterator<Item = EthStateEntryWithKey>,
+    ) -> Result<()> {
+        for EthStateEntryWithKey { key, entry, proof } in entries {
+            trace!(
+                "Validating eth state entry {:?} with key {:?} and proof {:?}",
+                entry,
+                key,
+                proof
+            );
+
+            match self.sync_manager.check_if_requested(peer, id, &key)? {
+                None => continue,
+                Some(_) => self.validate_and_store(key, entry, proof)?,
+            };
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn validate_and_store(
+        &self, key: EthStateKey, entry: Option<Vec<u8>>, proof: StateEntryProof,
+    ) -> Result<()> {
+        // validate state entry against the eSpace key in the state trie
+        if let Err(e) = validate_state_entry(
+            &self.state_roots,
+            key.epoch,
+            &key.to_key_bytes(),
+            &entry,
+            proof,
+        ) {
+            // forward error to both rpc caller(s) and sync handler
+            // so we need to make it clonable
+            let e = ClonableError::from(e);
+
+            self.verified
+                .write()
+                .entry(key.clone())
+                .or_insert(PendingItem::pending())
+                .set_error(e.clone());
+
+            bail!(e);
+        }
+
+        // store state entry by state key
+        self.verified
+            .write()
+            .entry(key.clone())
+            .or_insert(PendingItem::pending())
+            .set(entry);
+
+        self.sync_manager.remove_in_flight(&key);
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn clean_up(&self) {
+        // remove timeout in-flight requests
+        let timeout = *ETH_STATE_ENTRY_REQUEST_TIMEOUT;
+        let entries = self.sync_manager.remove_timeout_requests(timeout);
+        trace!(
+            "Timeout eth state-entries ({}): {:?}",
+            entries.len(),
+            entries
+        );
+        self.sync_manager.insert_waiting(entries.into_iter());
+
+        // trigger cache cleanup
+        self.verified.write().get(&Default::default());
+    }
+
+    #[inline]
+    fn send_request(
+        &self, io: &dyn NetworkContext, peer: &NodeId, keys: Vec<EthStateKey>,
+    ) -> Result<Option<RequestId>> {
+        if keys.is_empty() {
+            return Ok(None);
+        }
+
+        let request_id = self.request_id_allocator.next();
+
+        trace!(
+            "send_request GetEthStateEntries peer={:?} id={:?} keys={:?}",
+            peer,
+            request_id,
+            keys
+        );
+
+        let msg: Box<dyn Message> =
+            Box::new(GetEthStateEntries { request_id, keys });
+
+        msg.send(io, peer)?;
+        Ok(Some(request_id))
+    }
+
+    #[inline]
+    pub fn sync(&self, io: &dyn NetworkContext) {
+        self.sync_manager.sync(
+            MAX_ETH_STATE_ENTRIES_IN_FLIGHT,
+            ETH_STATE_ENTRY_REQUEST_BATCH_SIZE,
+            |peer, keys| self.send_request(io, peer, keys),
+        );
+    }
+}
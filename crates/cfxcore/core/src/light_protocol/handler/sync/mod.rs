@@ -6,6 +6,8 @@ mod block_txs;
 mod blooms;
 mod common;
 mod epochs;
+mod eth_receipts;
+mod eth_state_entries;
 mod headers;
 mod receipts;
 mod state_entries;
@@ -18,6 +20,8 @@ mod witnesses;
 pub use block_txs::BlockTxs;
 pub use blooms::Blooms;
 pub use epochs::Epochs;
+pub use eth_receipts::{EthReceipts, EthReceiptsValidated};
+pub use eth_state_entries::EthStateEntries;
 pub use headers::{HashSource, Headers};
 pub use receipts::Receipts;
 pub use state_entries::StateEntries;
@@ -211,7 +211,7 @@ impl Receipts {
     }
 
     #[inline]
-    fn validate_receipts(
+    pub fn validate_receipts(
         &self, epoch: u64, receipts: &Vec<BlockReceipts>,
     ) -> Result<()> {
         // calculate received receipts root
@@ -219,53 +219,62 @@ impl StateEntries {
         &self, epoch: u64, key: &Vec<u8>, value: &Option<Vec<u8>>,
         proof: StateEntryProof,
     ) -> Result<()> {
-        // validate state root
-        let state_root = proof.state_root;
-
-        self.state_roots
-            .validate_state_root(epoch, &state_root)
-            .chain_err(|| ErrorKind::InvalidStateProof {
-                epoch,
-                key: key.clone(),
-                value: value.clone(),
-                reason: "Validation of current state root failed",
-            })?;
-
-        // validate previous state root
-        let maybe_prev_root = proof.prev_snapshot_state_root;
-
-        self.state_roots
-            .validate_prev_snapshot_state_root(epoch, &maybe_prev_root)
-            .chain_err(|| ErrorKind::InvalidStateProof {
-                epoch,
-                key: key.clone(),
-                value: value.clone(),
-                reason: "Validation of previous state root failed",
-            })?;
-
-        // construct padding
-        let maybe_intermediate_padding = maybe_prev_root.map(|root| {
-            StorageKeyWithSpace::delta_mpt_padding(
-                &root.snapshot_root,
-                &root.intermediate_delta_root,
-            )
-        });
-
-        // validate state entry
-        if !proof.state_proof.is_valid_kv(
-            key,
-            value.as_ref().map(|v| &**v),
-            state_root,
-            maybe_intermediate_padding,
-        ) {
-            bail!(ErrorKind::InvalidStateProof {
-                epoch,
-                key: key.clone(),
-                value: value.clone(),
-                reason: "Validation of merkle proof failed",
-            });
-        }
+        validate_state_entry(&self.state_roots, epoch, key, value, proof)
+    }
+}
 
-        Ok(())
+/// Validates the state entry `key` => `value` in `epoch` against the state
+/// roots retrieved previously.
+pub fn validate_state_entry(
+    state_roots: &StateRoots, epoch: u64, key: &Vec<u8>,
+    value: &Option<Vec<u8>>, proof: StateEntryProof,
+) -> Result<()> {
+    // validate state root
+    let state_root = proof.state_root;
+
+    state_roots
+        .validate_state_root(epoch, &state_root)
+        .chain_err(|| ErrorKind::InvalidStateProof {
+            epoch,
+            key: key.clone(),
+            value: value.clone(),
+            reason: "Validation of current state root failed",
+        })?;
+
+    // validate previous state root
+    let maybe_prev_root = proof.prev_snapshot_state_root;
+
+    state_roots
+        .validate_prev_snapshot_state_root(epoch, &maybe_prev_root)
+        .chain_err(|| ErrorKind::InvalidStateProof {
+            epoch,
+            key: key.clone(),
+            value: value.clone(),
+            reason: "Validation of previous state root failed",
+        })?;
+
+    // construct padding
+    let maybe_intermediate_padding = maybe_prev_root.map(|root| {
+        StorageKeyWithSpace::delta_mpt_padding(
+            &root.snapshot_root,
+            &root.intermediate_delta_root,
+        )
+    });
+
+    // validate state entry
+    if !proof.state_proof.is_valid_kv(
+        key,
+        value.as_ref().map(|v| &**v),
+        state_root,
+        maybe_intermediate_padding,
+    ) {
+        bail!(ErrorKind::InvalidStateProof {
+            epoch,
+            key: key.clone(),
+            value: value.clone(),
+            reason: "Validation of merkle proof failed",
+        });
     }
+
+    Ok(())
 }
@@ -109,6 +109,16 @@ impl TxInfos {
     pub fn request_now(
         &self, io: &dyn NetworkContext, hash: H256,
     ) -> impl Future<Output = Result<TxInfoValidated>> {
+        self.request(io, hash);
+
+        FutureItem::new(hash, self.verified.clone())
+            .map(|res| res.map_err(|e| e.into()))
+    }
+
+    /// Requests the tx info of `hash` without waiting for the result, which
+    /// is kept in the cache of verified tx infos.
+    #[inline]
+    pub fn request(&self, io: &dyn NetworkContext, hash: H256) {
         let mut verified = self.verified.write();
 
         if !verified.contains_key(&hash) {
@@ -123,9 +133,12 @@ impl TxInfos {
             .entry(hash)
             .or_insert(PendingItem::pending())
             .clear_error();
+    }
 
-        FutureItem::new(hash, self.verified.clone())
-            .map(|res| res.map_err(|e| e.into()))
+    /// Returns whether the tx info of `hash` has been retrieved and verified.
+    #[inline]
+    pub fn is_verified(&self, hash: &H256) -> bool {
+        matches!(self.verified.read().peek(hash), Some(PendingItem::Ready(_)))
     }
 
     #[inline]
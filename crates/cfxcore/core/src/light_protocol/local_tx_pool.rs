@@ -0,0 +1,389 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_parameters::light::{MAX_LOCAL_TXS, MAX_LOCAL_TXS_PER_SENDER};
+use cfx_types::{AddressWithSpace, H256, U256};
+use parking_lot::RwLock;
+use primitives::SignedTransaction;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+struct LocalTx {
+    tx: Arc<SignedTransaction>,
+    // the order in which the transactions are inserted
+    seq: u64,
+    received_at: Instant,
+    last_broadcast: Instant,
+}
+
+#[derive(Default)]
+struct LocalTxPoolInner {
+    txs: HashMap<H256, LocalTx>,
+
+    // the hashes of the transactions of each sender, ordered by their nonces
+    by_sender: HashMap<AddressWithSpace, BTreeMap<U256, H256>>,
+
+    // the hashes of all transactions, from the oldest to the newest
+    by_seq: BTreeMap<u64, H256>,
+
+    next_seq: u64,
+}
+
+impl LocalTxPoolInner {
+    fn remove(&mut self, hash: &H256) -> Option<LocalTx> {
+        let local_tx = self.txs.remove(hash)?;
+        let sender = local_tx.tx.sender();
+        self.by_seq.remove(&local_tx.seq);
+
+        if let Some(nonces) = self.by_sender.get_mut(&sender) {
+            nonces.remove(local_tx.tx.nonce());
+
+            if nonces.is_empty() {
+                self.by_sender.remove(&sender);
+            }
+        }
+
+        Some(local_tx)
+    }
+
+    /// Returns the oldest transaction of `sender`.
+    fn oldest_of(&self, sender: &AddressWithSpace) -> Option<H256> {
+        self.by_sender
+            .get(sender)?
+            .values()
+            .min_by_key(|hash| self.txs.get(hash).map(|local_tx| local_tx.seq))
+            .cloned()
+    }
+}
+
+/// The transactions submitted through the local RPC of a light node. Light
+/// nodes have no transaction pool, so these transactions are tracked until
+/// their receipts are verified, in order to rebroadcast them and to account
+/// for them in the nonces reported to the wallets.
+#[derive(Default)]
+pub struct LocalTxPool {
+    inner: RwLock<LocalTxPoolInner>,
+}
+
+impl LocalTxPool {
+    pub fn new() -> Self { Self::default() }
+
+    /// Tracks `tx`. A transaction of the same sender and nonce is replaced.
+    /// If too many transactions are tracked, either of the sender or in total,
+    /// the oldest one is no longer tracked.
+    pub fn insert(&self, tx: SignedTransaction) {
+        let mut inner = self.inner.write();
+        let hash = tx.hash();
+
+        if inner.txs.contains_key(&hash) {
+            return;
+        }
+
+        let sender = tx.sender();
+        let nonce = *tx.nonce();
+
+        let replaced = inner
+            .by_sender
+            .get(&sender)
+            .and_then(|nonces| nonces.get(&nonce))
+            .cloned();
+
+        let sender_full = inner
+            .by_sender
+            .get(&sender)
+            .map_or(false, |nonces| nonces.len() >= MAX_LOCAL_TXS_PER_SENDER);
+
+        let evicted = match replaced {
+            Some(old_hash) => Some(old_hash),
+            None if sender_full => inner.oldest_of(&sender),
+            None if inner.txs.len() >= MAX_LOCAL_TXS => {
+                inner.by_seq.values().next().cloned()
+            }
+            None => None,
+        };
+
+        if let Some(old_hash) = evicted {
+            debug!(
+                "Local tx {:?} is no longer tracked for {:?}",
+                old_hash, hash
+            );
+            inner.remove(&old_hash);
+        }
+
+        let now = Instant::now();
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+
+        inner.txs.insert(
+            hash,
+            LocalTx {
+                tx: Arc::new(tx),
+                seq,
+                received_at: now,
+                last_broadcast: now,
+            },
+        );
+
+        inner
+            .by_sender
+            .entry(sender)
+            .or_default()
+            .insert(nonce, hash);
+
+        inner.by_seq.insert(seq, hash);
+    }
+
+    pub fn remove(&self, hash: &H256) -> Option<Arc<SignedTransaction>> {
+        self.inner.write().remove(hash).map(|local_tx| local_tx.tx)
+    }
+
+    pub fn len(&self) -> usize { self.inner.read().txs.len() }
+
+    pub fn hashes(&self) -> Vec<H256> {
+        self.inner.read().txs.keys().cloned().collect()
+    }
+
+    pub fn get(&self, hash: &H256) -> Option<Arc<SignedTransaction>> {
+        self.inner
+            .read()
+            .txs
+            .get(hash)
+            .map(|local_tx| local_tx.tx.clone())
+    }
+
+    pub fn get_by_nonce(
+        &self, sender: &AddressWithSpace, nonce: &U256,
+    ) -> Option<Arc<SignedTransaction>> {
+        let inner = self.inner.read();
+        let hash = inner.by_sender.get(sender)?.get(nonce)?;
+        inner.txs.get(hash).map(|local_tx| local_tx.tx.clone())
+    }
+
+    /// Returns the transactions of `sender` with a nonce not less than
+    /// `start_nonce`, ordered by their nonces.
+    pub fn pending_transactions(
+        &self, sender: &AddressWithSpace, start_nonce: U256,
+    ) -> Vec<Arc<SignedTransaction>> {
+        let inner = self.inner.read();
+
+        let nonces = match inner.by_sender.get(sender) {
+            None => return vec![],
+            Some(nonces) => nonces,
+        };
+
+        nonces
+            .range(start_nonce..)
+            .filter_map(|(_, hash)| inner.txs.get(hash))
+            .map(|local_tx| local_tx.tx.clone())
+            .collect()
+    }
+
+    /// Removes the transactions of `sender` with a nonce less than the
+    /// verified `state_nonce`, i.e. the ones executed or replaced.
+    pub fn remove_stale(&self, sender: &AddressWithSpace, state_nonce: U256) {
+        let mut inner = self.inner.write();
+
+        let stale: Vec<_> = match inner.by_sender.get(sender) {
+            None => return,
+            Some(nonces) => {
+                nonces.range(..state_nonce).map(|(_, hash)| *hash).collect()
+            }
+        };
+
+        for hash in stale {
+            inner.remove(&hash);
+        }
+    }
+
+    /// Returns the nonce of the next transaction of `sender`, that is, the
+    /// first nonce from `state_nonce` that is not used by a local
+    /// transaction.
+    pub fn next_nonce(
+        &self, sender: &AddressWithSpace, state_nonce: U256,
+    ) -> U256 {
+        let inner = self.inner.read();
+        let mut next_nonce = state_nonce;
+
+        if let Some(nonces) = inner.by_sender.get(sender) {
+            for nonce in nonces.range(state_nonce..).map(|(nonce, _)| nonce) {
+                if *nonce != next_nonce {
+                    break;
+                }
+                next_nonce += 1.into();
+            }
+        }
+
+        next_nonce
+    }
+
+    /// Returns the transactions not broadcast within `period`, and marks them
+    /// as broadcast. The transactions received before `timeout` are removed.
+    pub fn due_for_broadcast(
+        &self, period: Duration, timeout: Duration,
+    ) -> Vec<Arc<SignedTransaction>> {
+        let mut inner = self.inner.write();
+        let now = Instant::now();
+
+        let expired: Vec<_> = inner
+            .txs
+            .iter()
+            .filter(|(_, local_tx)| now - local_tx.received_at >= timeout)
+            .map(|(hash, _)| *hash)
+            .collect();
+
+        for hash in expired {
+            debug!("Local tx {:?} expired", hash);
+            inner.remove(&hash);
+        }
+
+        inner
+            .txs
+            .values_mut()
+            .filter(|local_tx| now - local_tx.last_broadcast >= period)
+            .map(|local_tx| {
+                local_tx.last_broadcast = now;
+                local_tx.tx.clone()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalTxPool;
+    use cfx_parameters::light::{MAX_LOCAL_TXS, MAX_LOCAL_TXS_PER_SENDER};
+    use cfx_types::{Address, AddressSpaceUtil, U256};
+    use keylib::{Generator, KeyPair, Random};
+    use primitives::{
+        transaction::native_transaction::NativeTransaction, Action,
+        SignedTransaction, Transaction,
+    };
+    use std::time::Duration;
+
+    fn new_test_tx(
+        key_pair: &KeyPair, nonce: usize, gas_price: usize,
+    ) -> SignedTransaction {
+        let tx: Transaction = NativeTransaction {
+            nonce: U256::from(nonce),
+            gas_price: U256::from(gas_price),
+            gas: U256::from(21000),
+            action: Action::Call(Address::random()),
+            value: U256::zero(),
+            storage_limit: 0,
+            epoch_height: 0,
+            chain_id: 1,
+            data: Vec::new(),
+        }
+        .into();
+        tx.sign(key_pair.secret())
+    }
+
+    #[test]
+    fn test_next_nonce() {
+        let key_pair = Random.generate().unwrap();
+        let sender = key_pair.address().with_native_space();
+        let pool = LocalTxPool::new();
+
+        for nonce in &[3, 4, 6] {
+            pool.insert(new_test_tx(&key_pair, *nonce, 1));
+        }
+
+        assert_eq!(pool.next_nonce(&sender, 2.into()), 2.into());
+        assert_eq!(pool.next_nonce(&sender, 3.into()), 5.into());
+        assert_eq!(pool.next_nonce(&sender, 6.into()), 7.into());
+
+        pool.remove_stale(&sender, 4.into());
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.pending_transactions(&sender, 0.into()).len(), 2);
+        assert!(pool.get_by_nonce(&sender, &3.into()).is_none());
+    }
+
+    #[test]
+    fn test_replace_and_broadcast() {
+        let key_pair = Random.generate().unwrap();
+        let sender = key_pair.address().with_native_space();
+        let pool = LocalTxPool::new();
+
+        let tx = new_test_tx(&key_pair, 0, 1);
+        let replacement = new_test_tx(&key_pair, 0, 2);
+        pool.insert(tx.clone());
+        pool.insert(replacement.clone());
+
+        assert_eq!(pool.len(), 1);
+        assert!(pool.get(&tx.hash()).is_none());
+        assert_eq!(
+            pool.get_by_nonce(&sender, &0.into()).unwrap().hash(),
+            replacement.hash()
+        );
+
+        let hour = Duration::from_secs(60 * 60);
+        assert!(pool.due_for_broadcast(hour, hour).is_empty());
+        assert_eq!(
+            pool.due_for_broadcast(Duration::from_secs(0), hour).len(),
+            1
+        );
+
+        assert!(pool
+            .due_for_broadcast(Duration::from_secs(0), Duration::from_secs(0))
+            .is_empty());
+        assert_eq!(pool.len(), 0);
+        assert_eq!(pool.next_nonce(&sender, 0.into()), 0.into());
+    }
+
+    #[test]
+    fn test_evict_oldest_of_sender() {
+        let key_pair = Random.generate().unwrap();
+        let sender = key_pair.address().with_native_space();
+        let other = Random.generate().unwrap();
+        let pool = LocalTxPool::new();
+
+        pool.insert(new_test_tx(&other, 0, 1));
+        for nonce in 0..MAX_LOCAL_TXS_PER_SENDER {
+            pool.insert(new_test_tx(&key_pair, nonce, 1));
+        }
+        assert_eq!(pool.len(), MAX_LOCAL_TXS_PER_SENDER + 1);
+
+        // the oldest tx of the sender is evicted, not the one of the other
+        // sender
+        pool.insert(new_test_tx(&key_pair, MAX_LOCAL_TXS_PER_SENDER, 1));
+        assert_eq!(pool.len(), MAX_LOCAL_TXS_PER_SENDER + 1);
+        assert!(pool.get_by_nonce(&sender, &0.into()).is_none());
+        assert!(pool
+            .get_by_nonce(&other.address().with_native_space(), &0.into())
+            .is_some());
+        assert_eq!(
+            pool.pending_transactions(&sender, 0.into()).len(),
+            MAX_LOCAL_TXS_PER_SENDER
+        );
+    }
+
+    #[test]
+    fn test_evict_oldest() {
+        let num_senders = MAX_LOCAL_TXS / MAX_LOCAL_TXS_PER_SENDER;
+        let key_pairs: Vec<_> = (0..num_senders)
+            .map(|_| Random.generate().unwrap())
+            .collect();
+        let pool = LocalTxPool::new();
+
+        let mut oldest = None;
+        for nonce in 0..MAX_LOCAL_TXS_PER_SENDER {
+            for key_pair in &key_pairs {
+                let tx = new_test_tx(key_pair, nonce, 1);
+                oldest.get_or_insert(tx.hash());
+                pool.insert(tx);
+            }
+        }
+        assert_eq!(pool.len(), MAX_LOCAL_TXS);
+
+        // a new tx is tracked in place of the oldest one
+        let tx = new_test_tx(&Random.generate().unwrap(), 0, 1);
+        pool.insert(tx.clone());
+        assert_eq!(pool.len(), MAX_LOCAL_TXS);
+        assert!(pool.get(&tx.hash()).is_some());
+        assert!(pool.get(&oldest.unwrap()).is_none());
+    }
+}
@@ -6,13 +6,15 @@ mod message;
 mod protocol;
 
 pub use crate::NodeType;
-pub use message::msgid;
+pub use message::{msgid, request_version_introduced};
 pub use protocol::{
     BlockHashes, BlockHeaders, BlockTxs, BlockTxsWithHash, BloomWithEpoch,
-    Blooms, GetBlockHashesByEpoch, GetBlockHeaders, GetBlockTxs, GetBlooms,
-    GetReceipts, GetStateEntries, GetStateRoots, GetStorageRoots, GetTxInfos,
-    GetTxs, GetWitnessInfo, NewBlockHashes, Receipts, ReceiptsWithEpoch,
-    SendRawTx, StateEntries, StateEntryProof, StateEntryWithKey, StateKey,
+    Blooms, EthReceipts, EthReceiptsWithEpoch, EthStateEntries,
+    EthStateEntryWithKey, EthStateKey, GetBlockHashesByEpoch, GetBlockHeaders,
+    GetBlockTxs, GetBlooms, GetEthReceipts, GetEthStateEntries, GetReceipts,
+    GetStateEntries, GetStateRoots, GetStorageRoots, GetTxInfos, GetTxs,
+    GetWitnessInfo, NewBlockHashes, Receipts, ReceiptsWithEpoch, SendRawTx,
+    StateEntries, StateEntryProof, StateEntryWithKey, StateKey,
     StateRootWithEpoch, StateRoots, StatusPingDeprecatedV1, StatusPingV2,
     StatusPongDeprecatedV1, StatusPongV2, StorageRootKey, StorageRootProof,
     StorageRootWithKey, StorageRoots, TxInfo, TxInfos, Txs, WitnessInfo,
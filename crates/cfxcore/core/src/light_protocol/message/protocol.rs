@@ -11,7 +11,7 @@ use cfx_internal_common::ChainIdParamsDeprecated;
 use cfx_storage::{NodeMerkleProof, StateProof, TrieProof};
 use primitives::{
     BlockHeader, BlockReceipts, Receipt, SignedTransaction, StateRoot,
-    StorageRoot,
+    StorageKey, StorageRoot,
 };
 
 #[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
@@ -314,3 +314,79 @@ pub struct StorageRoots {
     pub request_id: RequestId,
     pub roots: Vec<StorageRootWithKey>,
 }
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct GetEthReceipts {
+    pub request_id: RequestId,
+    pub epochs: Vec<u64>,
+}
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct EthReceiptsWithEpoch {
+    pub epoch: u64,
+
+    // receipts are validated against the receipts root and block txs are
+    // validated against the transactions roots, both retrieved previously;
+    // the eSpace receipts are then derived from them locally
+    pub epoch_receipts: Vec<BlockReceipts>,
+    pub block_txs: Vec<BlockTxsWithHash>,
+}
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct EthReceipts {
+    pub request_id: RequestId,
+    pub receipts: Vec<EthReceiptsWithEpoch>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    RlpEncodable,
+    RlpDecodable,
+)]
+pub struct EthStateKey {
+    pub epoch: u64,
+    pub address: H160,
+
+    // `None` for the account entry, or the storage position
+    pub position: Option<H256>,
+}
+
+impl EthStateKey {
+    /// Returns the key of the eSpace state entry in the state trie.
+    pub fn to_key_bytes(&self) -> Vec<u8> {
+        let key = match &self.position {
+            None => StorageKey::new_account_key(&self.address),
+            Some(position) => {
+                StorageKey::new_storage_key(&self.address, position.as_ref())
+            }
+        };
+
+        key.with_evm_space().to_key_bytes()
+    }
+}
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct GetEthStateEntries {
+    pub request_id: RequestId,
+    pub keys: Vec<EthStateKey>,
+}
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct EthStateEntryWithKey {
+    pub key: EthStateKey,
+    pub entry: Option<Vec<u8>>,
+    pub proof: StateEntryProof,
+}
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct EthStateEntries {
+    pub request_id: RequestId,
+    pub entries: Vec<EthStateEntryWithKey>,
+}
@@ -6,6 +6,7 @@ mod common;
 mod config;
 mod error;
 mod handler;
+mod local_tx_pool;
 mod message;
 mod provider;
 pub mod query_service;
@@ -13,17 +14,19 @@ pub mod query_service;
 use network::{service::ProtocolVersion, ProtocolId};
 
 const LIGHT_PROTOCOL_ID: ProtocolId = *b"clp"; // Conflux Light Protocol
-pub const LIGHT_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion(2);
+pub const LIGHT_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion(3);
 /// Support at most this number of old versions.
 const LIGHT_PROTOCOL_OLD_VERSIONS_TO_SUPPORT: u8 = 2;
 /// The version to pass to Message for their lifetime declaration.
 pub const LIGHT_PROTO_V1: ProtocolVersion = ProtocolVersion(1);
 pub const LIGHT_PROTO_V2: ProtocolVersion = ProtocolVersion(2);
+pub const LIGHT_PROTO_V3: ProtocolVersion = ProtocolVersion(3);
 
 use error::handle as handle_error;
 
 pub use config::Configuration as LightNodeConfiguration;
 pub use error::{Error, ErrorKind};
 pub use handler::Handler;
+pub use local_tx_pool::LocalTxPool;
 pub use provider::Provider;
 pub use query_service::QueryService;
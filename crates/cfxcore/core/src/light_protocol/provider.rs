@@ -17,11 +17,15 @@ use crate::{
             msgid, BlockHashes as GetBlockHashesResponse,
             BlockHeaders as GetBlockHeadersResponse,
             BlockTxs as GetBlockTxsResponse, BlockTxsWithHash, BloomWithEpoch,
-            Blooms as GetBloomsResponse, GetBlockHashesByEpoch,
-            GetBlockHeaders, GetBlockTxs, GetBlooms, GetReceipts,
-            GetStateEntries, GetStateRoots, GetStorageRoots, GetTxInfos,
-            GetTxs, GetWitnessInfo, NewBlockHashes, NodeType,
-            Receipts as GetReceiptsResponse, ReceiptsWithEpoch, SendRawTx,
+            Blooms as GetBloomsResponse, EthReceipts as GetEthReceiptsResponse,
+            EthReceiptsWithEpoch,
+            EthStateEntries as GetEthStateEntriesResponse,
+            EthStateEntryWithKey, EthStateKey, GetBlockHashesByEpoch,
+            GetBlockHeaders, GetBlockTxs, GetBlooms, GetEthReceipts,
+            GetEthStateEntries, GetReceipts, GetStateEntries, GetStateRoots,
+            GetStorageRoots, GetTxInfos, GetTxs, GetWitnessInfo,
+            NewBlockHashes, NodeType, Receipts as GetReceiptsResponse,
+            ReceiptsWithEpoch, SendRawTx,
             StateEntries as GetStateEntriesResponse, StateEntryProof,
             StateEntryWithKey, StateKey, StateRootWithEpoch,
             StateRoots as GetStateRootsResponse, StatusPingDeprecatedV1,
@@ -200,6 +204,8 @@ impl Provider {
             msgid::GET_BLOCK_TXS => self.on_get_block_txs(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::GET_TX_INFOS => self.on_get_tx_infos(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::GET_STORAGE_ROOTS => self.on_get_storage_roots(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
+            msgid::GET_ETH_RECEIPTS => self.on_get_eth_receipts(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
+            msgid::GET_ETH_STATE_ENTRIES => self.on_get_eth_state_entries(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             _ => bail!(ErrorKind::UnknownMessage{id: msg_id}),
         }
     }
@@ -785,6 +791,22 @@ impl Provider {
         Ok(())
     }
 
+    fn block_txs(&self, hash: H256) -> Result<BlockTxsWithHash> {
+        let block = self.ledger.block(hash)?;
+
+        let block_txs = block
+            .transactions
+            .clone()
+            .into_iter()
+            .map(|arc_tx| (*arc_tx).clone())
+            .collect();
+
+        Ok(BlockTxsWithHash {
+            hash: block.hash(),
+            block_txs,
+        })
+    }
+
     fn on_get_block_txs(
         &self, io: &dyn NetworkContext, peer: &NodeId, req: GetBlockTxs,
     ) -> Result<()> {
@@ -796,21 +818,7 @@ impl Provider {
             .hashes
             .into_iter()
             .take(MAX_ITEMS_TO_SEND)
-            .map::<Result<_>, _>(|h| {
-                let block = self.ledger.block(h)?;
-
-                let block_txs = block
-                    .transactions
-                    .clone()
-                    .into_iter()
-                    .map(|arc_tx| (*arc_tx).clone())
-                    .collect();
-
-                Ok(BlockTxsWithHash {
-                    hash: block.hash(),
-                    block_txs,
-                })
-            });
+            .map(|h| self.block_txs(h));
 
         let (block_txs, errors) = partition_results(it);
 
@@ -911,6 +919,102 @@ impl Provider {
         Ok(())
     }
 
+    fn eth_receipts(&self, epoch: u64) -> Result<EthReceiptsWithEpoch> {
+        // genesis has no receipts
+        if epoch == 0 {
+            return Ok(EthReceiptsWithEpoch {
+                epoch,
+                ..Default::default()
+            });
+        }
+
+        let epoch_receipts = self.ledger.receipts_of(epoch)?;
+
+        let block_txs = self
+            .ledger
+            .block_hashes_in(epoch)?
+            .into_iter()
+            .map(|h| self.block_txs(h))
+            .collect::<Result<_>>()?;
+
+        Ok(EthReceiptsWithEpoch {
+            epoch,
+            epoch_receipts,
+            block_txs,
+        })
+    }
+
+    fn on_get_eth_receipts(
+        &self, io: &dyn NetworkContext, peer: &NodeId, req: GetEthReceipts,
+    ) -> Result<()> {
+        debug!("on_get_eth_receipts req={:?}", req);
+        self.throttle(peer, &req)?;
+        let request_id = req.request_id;
+
+        let it = req
+            .epochs
+            .into_iter()
+            .take(MAX_ITEMS_TO_SEND)
+            .map(|epoch| self.eth_receipts(epoch));
+
+        let (receipts, errors) = partition_results(it);
+
+        if !errors.is_empty() {
+            debug!("Errors while serving GetEthReceipts request: {:?}", errors);
+        }
+
+        let msg: Box<dyn Message> = Box::new(GetEthReceiptsResponse {
+            request_id,
+            receipts,
+        });
+
+        msg.send(io, peer)?;
+        Ok(())
+    }
+
+    fn eth_state_entry(
+        &self, key: EthStateKey,
+    ) -> Result<EthStateEntryWithKey> {
+        let StateEntryWithKey { entry, proof, .. } =
+            self.state_entry(StateKey {
+                epoch: key.epoch,
+                key: key.to_key_bytes(),
+            })?;
+
+        Ok(EthStateEntryWithKey { key, entry, proof })
+    }
+
+    fn on_get_eth_state_entries(
+        &self, io: &dyn NetworkContext, peer: &NodeId, req: GetEthStateEntries,
+    ) -> Result<()> {
+        debug!("on_get_eth_state_entries req={:?}", req);
+        self.throttle(peer, &req)?;
+        let request_id = req.request_id;
+
+        let it = req
+            .keys
+            .into_iter()
+            .take(MAX_ITEMS_TO_SEND)
+            .map(|key| self.eth_state_entry(key));
+
+        let (entries, errors) = partition_results(it);
+
+        if !errors.is_empty() {
+            debug!(
+                "Errors while serving GetEthStateEntries request: {:?}",
+                errors
+            );
+        }
+
+        let msg: Box<dyn Message> = Box::new(GetEthStateEntriesResponse {
+            request_id,
+            entries,
+        });
+
+        msg.send(io, peer)?;
+        Ok(())
+    }
+
     fn broadcast(
         &self, io: &dyn NetworkContext, mut peers: Vec<NodeId>,
         msg: &dyn Message,
@@ -3,34 +3,46 @@
 // See http://www.gnu.org/licenses/
 
 use crate::{
-    consensus::SharedConsensusGraph,
+    consensus::{PhantomBlock, SharedConsensusGraph},
     light_protocol::{
         common::{FullPeerFilter, LedgerInfo},
-        handler::sync::TxInfoValidated,
-        message::msgid,
+        handler::sync::{EthReceiptsValidated, TxInfoValidated},
+        message::{msgid, EthStateKey},
         Error, ErrorKind, Handler as LightHandler, LightNodeConfiguration,
-        LIGHT_PROTOCOL_ID, LIGHT_PROTOCOL_VERSION,
+        LocalTxPool, LIGHT_PROTOCOL_ID, LIGHT_PROTOCOL_VERSION,
     },
     rpc_errors::{account_result_to_rpc_result, Error as RpcError},
     sync::SynchronizationGraph,
     ConsensusGraph, Notifications,
 };
 use cfx_addr::Network;
-use cfx_executor::state::COMMISSION_PRIVILEGE_SPECIAL_KEY;
+use cfx_execute_helper::{
+    estimation::{EstimateExt, EstimateRequest},
+    phantom_tx::build_bloom_and_recover_phantom,
+};
+use cfx_executor::{
+    executive::ExecutionOutcome,
+    state::{State, COMMISSION_PRIVILEGE_SPECIAL_KEY},
+};
 use cfx_parameters::{
     consensus::DEFERRED_STATE_EPOCH_COUNT,
     internal_contract_addresses::SPONSOR_WHITELIST_CONTROL_CONTRACT_ADDRESS,
     light::{
         GAS_PRICE_BATCH_SIZE, GAS_PRICE_BLOCK_SAMPLE_SIZE,
         GAS_PRICE_TRANSACTION_SAMPLE_SIZE, LOG_FILTERING_LOOKAHEAD,
-        MAX_POLL_TIME, TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_LOW,
+        MAX_POLL_TIME, MAX_VIRTUAL_CALL_ROUNDS,
+        TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_LOW,
         TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_MEDIUM,
     },
 };
-use cfx_statedb::global_params::{self, GlobalParamKey};
+use cfx_statedb::{
+    global_params::{self, GlobalParamKey},
+    StateDb,
+};
+use cfx_storage::PartialStorage;
 use cfx_types::{
-    address_util::AddressUtil, AllChainID, BigEndianHash, Bloom, H160, H256,
-    KECCAK_EMPTY_BLOOM, U256,
+    address_util::AddressUtil, AddressSpaceUtil, AddressWithSpace, AllChainID,
+    BigEndianHash, Bloom, Space, H160, H256, KECCAK_EMPTY_BLOOM, U256,
 };
 use futures::{
     future::{self, Either},
@@ -40,12 +52,18 @@ use network::{service::ProtocolVersion, NetworkContext, NetworkService};
 use primitives::{
     filter::{FilterError, LogFilter},
     log_entry::{LocalizedLogEntry, LogEntry},
-    Account, Block, BlockReceipts, CodeInfo, DepositList, EpochNumber, Receipt,
-    SignedTransaction, StorageKey, StorageRoot, StorageValue, TransactionIndex,
-    VoteStakeList,
+    Account, Block, BlockHeader, BlockReceipts, CodeInfo, DepositList,
+    EpochNumber, Receipt, SignedTransaction, StorageKey, StorageRoot,
+    StorageValue, TransactionIndex, TransactionStatus,
+    TransactionWithSignature, VoteStakeList,
 };
 use rlp::Rlp;
-use std::{collections::BTreeSet, future::Future, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeSet, HashMap},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
 
 pub struct TxInfo {
     pub tx: SignedTransaction,
@@ -155,6 +173,24 @@ impl QueryService {
         .await
     }
 
+    async fn retrieve_eth_state_entry(
+        &self, key: EthStateKey,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        trace!("retrieve_eth_state_entry key = {:?}", key);
+
+        with_timeout(
+            *MAX_POLL_TIME,
+            format!(
+                "Timeout while retrieving eth state entry with key {:?}",
+                key
+            ),
+            self.with_io(|io| {
+                self.handler.eth_state_entries.request_now(io, key)
+            }),
+        )
+        .await
+    }
+
     async fn retrieve_state_entry<T: rlp::Decodable>(
         &self, epoch: u64, key: Vec<u8>,
     ) -> Result<Option<T>, Error> {
@@ -211,6 +247,22 @@ impl QueryService {
         .map(|receipts| (epoch, receipts))
     }
 
+    async fn retrieve_eth_receipts(
+        &self, epoch: u64,
+    ) -> Result<EthReceiptsValidated, Error> {
+        trace!("retrieve_eth_receipts epoch = {}", epoch);
+
+        with_timeout(
+            *MAX_POLL_TIME,
+            format!(
+                "Timeout while retrieving eth receipts for epoch {:?}",
+                epoch
+            ),
+            self.handler.eth_receipts.request(epoch),
+        )
+        .await
+    }
+
     pub async fn retrieve_block_txs(
         &self, hash: H256,
     ) -> Result<Vec<SignedTransaction>, Error> {
@@ -370,9 +422,9 @@ impl QueryService {
         }
     }
 
-    fn account_key(address: &H160) -> Vec<u8> {
-        StorageKey::new_account_key(&address)
-            .with_native_space()
+    fn account_key(address: &AddressWithSpace) -> Vec<u8> {
+        StorageKey::new_account_key(&address.address)
+            .with_space(address.space)
             .to_key_bytes()
     }
 
@@ -402,17 +454,39 @@ impl QueryService {
 
     pub async fn get_account(
         &self, epoch: EpochNumber, address: H160,
+    ) -> Result<Option<Account>, Error> {
+        self.get_account_with_space(epoch, address.with_native_space())
+            .await
+    }
+
+    pub async fn get_account_with_space(
+        &self, epoch: EpochNumber, address: AddressWithSpace,
     ) -> Result<Option<Account>, Error> {
         debug!("get_account epoch={:?} address={:?}", epoch, address);
 
         let epoch = self.get_height_from_epoch_number(epoch)?;
-        let key = Self::account_key(&address);
 
-        match self.retrieve_state_entry_raw(epoch, key).await? {
-            None => Ok(None),
-            Some(rlp) => {
-                Ok(Some(Account::new_from_rlp(address, &Rlp::new(&rlp))?))
+        let entry = match address.space {
+            Space::Native => {
+                let key = Self::account_key(&address);
+                self.retrieve_state_entry_raw(epoch, key).await?
             }
+            Space::Ethereum => {
+                let key = EthStateKey {
+                    epoch,
+                    address: address.address,
+                    position: None,
+                };
+                self.retrieve_eth_state_entry(key).await?
+            }
+        };
+
+        match entry {
+            None => Ok(None),
+            Some(rlp) => Ok(Some(Account::new_from_rlp(
+                address.address,
+                &Rlp::new(&rlp),
+            )?)),
         }
     }
 
@@ -443,7 +517,7 @@ impl QueryService {
         }
 
         let epoch = self.get_height_from_epoch_number(epoch)?;
-        let key = Self::account_key(&address);
+        let key = Self::account_key(&address.with_native_space());
 
         let code_hash = match self.retrieve_state_entry_raw(epoch, key).await {
             Err(e) => bail!(e),
@@ -489,6 +563,31 @@ impl QueryService {
         }
     }
 
+    pub async fn get_eth_storage(
+        &self, epoch: EpochNumber, address: H160, position: H256,
+    ) -> Result<Option<H256>, Error> {
+        debug!(
+            "get_eth_storage epoch={:?} address={:?} position={:?}",
+            epoch, address, position
+        );
+
+        let epoch = self.get_height_from_epoch_number(epoch)?;
+        let key = EthStateKey {
+            epoch,
+            address,
+            position: Some(position),
+        };
+
+        match self.retrieve_eth_state_entry(key).await? {
+            None => Ok(None),
+            Some(raw) => {
+                let entry = rlp::decode::<StorageValue>(raw.as_ref())
+                    .map_err(|e| format!("{}", e))?;
+                Ok(Some(H256::from_uint(&entry.value)))
+            }
+        }
+    }
+
     pub async fn is_user_sponsored(
         &self, epoch: EpochNumber, contract: H160, user: H160,
     ) -> Result<bool, Error> {
@@ -618,6 +717,9 @@ impl QueryService {
             prior_gas_used,
         } = self.retrieve_tx_info(hash).await?;
 
+        // the receipt is verified, so the tx no longer needs to be tracked
+        self.handler.local_txs.remove(&hash);
+
         let block_hash = tx_index.block_hash;
         let maybe_epoch = self.consensus.get_block_epoch_number(&block_hash);
         let maybe_block_number =
@@ -637,6 +739,58 @@ impl QueryService {
         })
     }
 
+    /// Constructs the phantom block of `epoch`, i.e. the view of the epoch in
+    /// eSpace, from the receipts and block transactions retrieved through
+    /// `GetEthReceipts`.
+    pub async fn get_phantom_block(
+        &self, epoch: EpochNumber,
+    ) -> Result<PhantomBlock, Error> {
+        debug!("get_phantom_block epoch={:?}", epoch);
+
+        let epoch = self.get_height_from_epoch_number(epoch)?;
+        let pivot_header = self.ledger.pivot_header_of(epoch)?;
+        let evm_chain_id = self.consensus.best_chain_id().in_evm_space();
+
+        let validated = self.retrieve_eth_receipts(epoch).await?;
+
+        Ok(build_phantom_block(pivot_header, validated, evm_chain_id)?)
+    }
+
+    /// Executes `tx` on the state of `epoch`. The execution is repeated on the
+    /// state entries retrieved so far, and the entries it reads but are not
+    /// retrieved yet are requested from the peers and verified against the
+    /// state root before the next round.
+    pub async fn call_virtual(
+        &self, tx: SignedTransaction, epoch: EpochNumber,
+        request: EstimateRequest,
+    ) -> Result<(ExecutionOutcome, EstimateExt), RpcError> {
+        debug!("call_virtual tx={:?} epoch={:?}", tx, epoch);
+
+        let epoch = self.get_height_from_epoch_number(epoch)?;
+        let hashes = self.ledger.block_hashes_in(epoch)?;
+        let epoch_id = *hashes.last().expect("pivot block always exist");
+
+        let consensus = self
+            .consensus
+            .as_any()
+            .downcast_ref::<ConsensusGraph>()
+            .expect("downcast should succeed");
+
+        execute_on_partial_state(
+            |state| {
+                consensus.call_virtual_with_state(
+                    &tx,
+                    &epoch_id,
+                    hashes.len(),
+                    request,
+                    state,
+                )
+            },
+            |key| self.retrieve_state_entry_raw(epoch, key),
+        )
+        .await?
+    }
+
     /// Relay raw transaction to all peers.
     // TODO(thegaram): consider returning TxStatus instead of bool,
     // e.g. Failed, Sent/Pending, Confirmed, etc.
@@ -676,6 +830,74 @@ impl QueryService {
         }
     }
 
+    /// Tracks a tx submitted through the local RPC, so that it is
+    /// rebroadcast and accounted for in the next nonce of its sender, until
+    /// its receipt is verified.
+    pub fn track_local_tx(
+        &self, tx: TransactionWithSignature,
+    ) -> Result<(), Error> {
+        debug!("track_local_tx hash={:?}", tx.hash());
+
+        let public = tx.recover_public().map_err(|e| {
+            ErrorKind::InternalError(format!(
+                "Failed to recover the sender of tx {:?}: {:?}",
+                tx.hash(),
+                e
+            ))
+        })?;
+
+        self.handler
+            .local_txs
+            .insert(SignedTransaction::new(public, tx));
+
+        Ok(())
+    }
+
+    /// The txs submitted through the local RPC whose receipts are not
+    /// verified yet.
+    pub fn local_txs(&self) -> &LocalTxPool { &self.handler.local_txs }
+
+    /// Returns the nonce of `address` in the latest verifiable state. The
+    /// local txs with a lower nonce are no longer tracked.
+    async fn get_state_nonce(
+        &self, address: AddressWithSpace,
+    ) -> Result<U256, Error> {
+        let state_nonce = self
+            .get_account_with_space(EpochNumber::LatestState, address)
+            .await?
+            .map(|account| account.nonce)
+            .unwrap_or_default();
+
+        self.handler.local_txs.remove_stale(&address, state_nonce);
+        Ok(state_nonce)
+    }
+
+    /// Returns the next nonce of `address`, taking the pending local txs
+    /// into account.
+    pub async fn get_next_nonce(
+        &self, address: AddressWithSpace,
+    ) -> Result<U256, Error> {
+        debug!("get_next_nonce address={:?}", address);
+
+        let state_nonce = self.get_state_nonce(address).await?;
+        Ok(self.handler.local_txs.next_nonce(&address, state_nonce))
+    }
+
+    /// Returns the nonce of `address` in the latest verifiable state and its
+    /// pending local txs, ordered by their nonces.
+    pub async fn get_pending_local_txs(
+        &self, address: AddressWithSpace,
+    ) -> Result<(U256, Vec<Arc<SignedTransaction>>), Error> {
+        debug!("get_pending_local_txs address={:?}", address);
+
+        let state_nonce = self.get_state_nonce(address).await?;
+        let txs = self
+            .handler
+            .local_txs
+            .pending_transactions(&address, state_nonce);
+        Ok((state_nonce, txs))
+    }
+
     pub async fn get_tx(&self, hash: H256) -> Result<SignedTransaction, Error> {
         debug!("get_tx hash={:?}", hash);
 
@@ -911,6 +1133,11 @@ impl QueryService {
     ) -> Result<Vec<LocalizedLogEntry>, Error> {
         debug!("get_logs filter = {:?}", filter);
 
+        // eSpace logs are located in the phantom blocks
+        if filter.space == Space::Ethereum {
+            return self.get_phantom_logs(filter).await;
+        }
+
         // find epochs and blocks to match against
         let (epochs, block_filter) = self
             .get_filter_epochs(&filter)
@@ -1029,7 +1256,531 @@ impl QueryService {
         Ok(matching)
     }
 
+    /// Filters the logs of eSpace. The logs are matched against the phantom
+    /// block of each epoch, so the log and transaction indices are the same as
+    /// those returned by a full node.
+    async fn get_phantom_logs(
+        &self, filter: LogFilter,
+    ) -> Result<Vec<LocalizedLogEntry>, Error> {
+        let (epochs, block_filter) = self
+            .get_filter_epochs(&filter)
+            .map_err(|e| format!("{}", e))?;
+
+        debug!("Executing eSpace filter on epochs {:?}", epochs);
+
+        let consensus = self
+            .consensus
+            .as_any()
+            .downcast_ref::<ConsensusGraph>()
+            .expect("downcast should succeed");
+
+        // the epoch blooms cover the logs of both spaces
+        let blooms = filter.bloom_possibilities();
+        let bloom_match = move |block_log_bloom: &Bloom| {
+            blooms
+                .iter()
+                .any(|bloom| block_log_bloom.contains_bloom(bloom))
+        };
+
+        let stream = stream::iter(epochs)
+            .map(|epoch| self.retrieve_bloom(epoch))
+            .buffered(LOG_FILTERING_LOOKAHEAD)
+            .try_filter_map(move |(epoch, bloom)| match bloom_match(&bloom) {
+                true => future::ready(Ok(Some(epoch))),
+                false => future::ready(Ok(None)),
+            })
+            .map(|res| match res {
+                Err(e) => Either::Left(future::err(e)),
+                Ok(epoch) => Either::Right(
+                    self.get_phantom_block(EpochNumber::Number(epoch))
+                        .map_ok(move |pb| (epoch, pb)),
+                ),
+            })
+            .buffered(LOG_FILTERING_LOOKAHEAD)
+            .map_ok(|(epoch, pb)| {
+                let pivot_hash = pb.pivot_header.hash();
+                let tx_hashes =
+                    pb.transactions.iter().map(|t| t.hash()).collect();
+
+                // the logs are returned in reverse order
+                let logs: Vec<_> = consensus
+                    .filter_block_receipts(
+                        &filter,
+                        epoch,
+                        pivot_hash,
+                        pb.receipts,
+                        tx_hashes,
+                    )
+                    .map(Ok)
+                    .collect();
+
+                stream::iter(logs)
+            })
+            .try_flatten()
+            .try_filter(move |log| future::ready(block_filter(log.block_hash)))
+            // Limit logs can return
+            .take(
+                self.consensus
+                    .get_config()
+                    .get_logs_filter_max_limit
+                    .unwrap_or(::std::usize::MAX - 1)
+                    + 1,
+            )
+            .try_collect();
+
+        let mut matching: Vec<_> = stream.await?;
+        matching.reverse();
+        debug!("Collected matching eSpace logs = {:?}", matching);
+        Ok(matching)
+    }
+
     pub fn get_network_type(&self) -> &Network {
         self.network.get_network_type()
     }
 }
+
+/// Runs `execute` on the state entries retrieved so far. The entries it reads
+/// but are not retrieved yet are retrieved with `retrieve` and the execution is
+/// repeated, until it reads no missing entry or `MAX_VIRTUAL_CALL_ROUNDS` is
+/// reached.
+///
+/// note: `RecordingStorage` records the proofs of the entries read from a
+/// complete local state, which is what a full node needs to serve them. A light
+/// node has no local state, so it executes on a `PartialStorage` of the entries
+/// it has verified one by one against the state root, and learns from it which
+/// entries are still missing.
+async fn execute_on_partial_state<T, Fut>(
+    mut execute: impl FnMut(State) -> T, retrieve: impl Fn(Vec<u8>) -> Fut,
+) -> Result<T, RpcError>
+where Fut: Future<Output = Result<Option<Vec<u8>>, Error>> {
+    let mut entries = HashMap::new();
+
+    for round in 0..MAX_VIRTUAL_CALL_ROUNDS {
+        // note: the execution is scoped so that nothing it returns is held
+        // across the retrieval below
+        let missing_keys: Vec<_> = {
+            let storage = PartialStorage::new(entries.clone());
+            let missing_keys = storage.missing_keys();
+            let state = State::new(StateDb::new(Box::new(storage)))?;
+
+            let result = execute(state);
+
+            let missing_keys = std::mem::take(&mut *missing_keys.lock());
+
+            // the result is only valid if the execution read no entry
+            // missing from the state
+            if missing_keys.is_empty() {
+                return Ok(result);
+            }
+
+            missing_keys.into_iter().collect()
+        };
+
+        trace!(
+            "execution round {} reads {} missing entries",
+            round,
+            missing_keys.len()
+        );
+
+        let values =
+            future::try_join_all(missing_keys.iter().cloned().map(&retrieve))
+                .await?;
+
+        entries.extend(
+            missing_keys
+                .into_iter()
+                .zip(values.into_iter().map(|v| v.map(Into::into))),
+        );
+    }
+
+    bail!(format!(
+        "Unable to retrieve the state entries read by the call in {} rounds",
+        MAX_VIRTUAL_CALL_ROUNDS
+    ))
+}
+
+/// Constructs the phantom block of an epoch from the receipts and transactions
+/// of its blocks. This follows `ConsensusGraph::get_phantom_block_by_number`
+/// without traces.
+fn build_phantom_block(
+    pivot_header: BlockHeader, validated: EthReceiptsValidated,
+    evm_chain_id: u32,
+) -> Result<PhantomBlock, Error> {
+    let epoch = pivot_header.height();
+
+    let mut phantom_block = PhantomBlock {
+        pivot_header,
+        transactions: vec![],
+        receipts: vec![],
+        errors: vec![],
+        bloom: Default::default(),
+        traces: vec![],
+    };
+
+    let EthReceiptsValidated {
+        epoch_receipts,
+        epoch_txs,
+    } = validated;
+
+    if epoch_receipts.len() != epoch_txs.len() {
+        bail!(ErrorKind::InternalError(format!(
+            "Epoch {} has {} blocks but {} block receipts",
+            epoch,
+            epoch_txs.len(),
+            epoch_receipts.len()
+        )));
+    }
+
+    let mut accumulated_gas_used = U256::zero();
+
+    for (block_receipts, txs) in epoch_receipts.into_iter().zip(epoch_txs) {
+        let gas_used_offset = accumulated_gas_used;
+        let receipts = block_receipts.receipts;
+        // note: the error messages are not covered by the receipts root,
+        // so they are not verified.
+        let errors = block_receipts.tx_execution_error_messages;
+
+        if txs.len() != receipts.len() {
+            bail!(ErrorKind::InternalError(format!(
+                "Block of epoch {} has {} transactions but {} receipts",
+                epoch,
+                txs.len(),
+                receipts.len()
+            )));
+        }
+
+        for (id, tx) in txs.into_iter().enumerate() {
+            let receipt = &receipts[id];
+
+            match tx.space() {
+                Space::Ethereum => {
+                    // we do not return non-executed transaction
+                    if receipt.outcome_status == TransactionStatus::Skipped {
+                        continue;
+                    }
+
+                    accumulated_gas_used =
+                        gas_used_offset + receipt.accumulated_gas_used;
+
+                    phantom_block.transactions.push(Arc::new(tx));
+                    phantom_block.receipts.push(Receipt {
+                        accumulated_gas_used,
+                        ..receipt.clone()
+                    });
+                    phantom_block
+                        .errors
+                        .push(errors.get(id).cloned().unwrap_or_default());
+                    phantom_block.bloom.accrue_bloom(&receipt.log_bloom);
+                }
+                Space::Native => {
+                    // note: failing transactions will not produce any
+                    // phantom txs
+                    if receipt.outcome_status != TransactionStatus::Success {
+                        continue;
+                    }
+
+                    let (phantom_txs, _) = build_bloom_and_recover_phantom(
+                        &receipt.logs[..],
+                        tx.hash(),
+                    );
+
+                    for p in phantom_txs {
+                        phantom_block.transactions.push(Arc::new(
+                            p.clone().into_eip155(evm_chain_id),
+                        ));
+
+                        // note: phantom txs consume no gas
+                        let phantom_receipt =
+                            p.into_receipt(accumulated_gas_used);
+
+                        phantom_block
+                            .bloom
+                            .accrue_bloom(&phantom_receipt.log_bloom);
+                        phantom_block.receipts.push(phantom_receipt);
+
+                        // note: phantom txs never fail
+                        phantom_block.errors.push("".into());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(phantom_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_phantom_block, execute_on_partial_state};
+    use crate::light_protocol::{handler::sync::EthReceiptsValidated, Error};
+    use cfx_executor::{
+        internal_contract::{
+            cross_space_events::WithdrawEvent, SolidityEventTrait,
+        },
+        state::State,
+    };
+    use cfx_parameters::{
+        internal_contract_addresses::CROSS_SPACE_CONTRACT_ADDRESS,
+        light::MAX_VIRTUAL_CALL_ROUNDS,
+    };
+    use cfx_statedb::Result as DbResult;
+    use cfx_types::{Address, AddressSpaceUtil, Space, H256, U256};
+    use futures::{executor::block_on, future};
+    use keylib::{Generator, Random};
+    use primitives::{
+        transaction::{
+            native_transaction::NativeTransaction, Eip155Transaction,
+        },
+        Account, Action, BlockHeaderBuilder, BlockReceipts, LogEntry, Receipt,
+        SignedTransaction, StorageKey, Transaction, TransactionStatus,
+    };
+    use solidity_abi::ABIEncodable;
+    use std::{cell::Cell, collections::HashMap};
+
+    fn new_native_tx(nonce: u64) -> SignedTransaction {
+        let tx: Transaction = NativeTransaction {
+            nonce: nonce.into(),
+            gas_price: U256::one(),
+            gas: 21000.into(),
+            action: Action::Call(Address::random()),
+            value: U256::zero(),
+            storage_limit: 0,
+            epoch_height: 0,
+            chain_id: 1,
+            data: vec![],
+        }
+        .into();
+        tx.sign(Random.generate().unwrap().secret())
+    }
+
+    fn new_eth_tx(nonce: u64) -> SignedTransaction {
+        let tx: Transaction = Eip155Transaction {
+            nonce: nonce.into(),
+            gas_price: U256::one(),
+            gas: 21000.into(),
+            action: Action::Call(Address::random()),
+            value: U256::zero(),
+            chain_id: Some(2),
+            data: vec![],
+        }
+        .into();
+        tx.sign(Random.generate().unwrap().secret())
+    }
+
+    fn new_receipt(
+        accumulated_gas_used: u64, outcome_status: TransactionStatus,
+        logs: Vec<LogEntry>,
+    ) -> Receipt {
+        Receipt {
+            accumulated_gas_used: accumulated_gas_used.into(),
+            outcome_status,
+            logs,
+            ..Default::default()
+        }
+    }
+
+    fn block_receipts(receipts: Vec<Receipt>) -> BlockReceipts {
+        BlockReceipts {
+            receipts,
+            block_number: 0,
+            secondary_reward: U256::zero(),
+            tx_execution_error_messages: vec![],
+        }
+    }
+
+    // The log of `CrossSpaceCall.withdrawFromMapped`.
+    fn withdraw_log(from: Address, value: u64, nonce: u64) -> LogEntry {
+        LogEntry {
+            address: CROSS_SPACE_CONTRACT_ADDRESS,
+            topics: vec![
+                WithdrawEvent::EVENT_SIG,
+                H256::from_slice(&from.0.abi_encode()),
+                H256::from(Address::random()),
+            ],
+            data: (U256::from(value), U256::from(nonce)).abi_encode(),
+            space: Space::Native,
+        }
+    }
+
+    #[test]
+    fn test_build_phantom_block() {
+        let pivot_header = BlockHeaderBuilder::new().with_height(7).build();
+        let withdrawer = Address::random();
+
+        // block 0: an executed eSpace tx, a skipped eSpace tx, a native
+        // withdrawal and a failed native withdrawal
+        let txs0 = vec![
+            new_eth_tx(0),
+            new_eth_tx(1),
+            new_native_tx(0),
+            new_native_tx(1),
+        ];
+        let receipts0 = vec![
+            new_receipt(21000, TransactionStatus::Success, vec![]),
+            new_receipt(21000, TransactionStatus::Skipped, vec![]),
+            new_receipt(
+                50000,
+                TransactionStatus::Success,
+                vec![withdraw_log(withdrawer, 100, 3)],
+            ),
+            new_receipt(
+                80000,
+                TransactionStatus::Failure,
+                vec![withdraw_log(withdrawer, 200, 4)],
+            ),
+        ];
+
+        // block 1: the gas used is accumulated over the blocks of the epoch
+        let txs1 = vec![new_eth_tx(0)];
+        let receipts1 =
+            vec![new_receipt(30000, TransactionStatus::Failure, vec![])];
+
+        let validated = EthReceiptsValidated {
+            epoch_receipts: vec![
+                block_receipts(receipts0),
+                block_receipts(receipts1),
+            ],
+            epoch_txs: vec![txs0.clone(), txs1.clone()],
+        };
+
+        let block = build_phantom_block(pivot_header, validated, 2).unwrap();
+        assert_eq!(block.pivot_header.height(), 7);
+        assert_eq!(block.transactions.len(), 3);
+        assert_eq!(block.receipts.len(), 3);
+        assert_eq!(block.errors.len(), 3);
+
+        assert_eq!(block.transactions[0].hash(), txs0[0].hash());
+        assert_eq!(block.receipts[0].accumulated_gas_used, 21000.into());
+
+        let phantom = &block.transactions[1];
+        assert_eq!(phantom.sender(), withdrawer.with_evm_space());
+        assert_eq!(phantom.value(), &U256::from(100));
+        assert_eq!(phantom.nonce(), &U256::from(3));
+        assert_eq!(phantom.action(), &Action::Call(Address::zero()));
+        assert_eq!(block.receipts[1].accumulated_gas_used, 21000.into());
+        assert_eq!(
+            block.receipts[1].outcome_status,
+            TransactionStatus::Success
+        );
+
+        assert_eq!(block.transactions[2].hash(), txs1[0].hash());
+        assert_eq!(block.receipts[2].accumulated_gas_used, 51000.into());
+
+        let mut bloom = block.receipts[0].log_bloom;
+        for receipt in &block.receipts[1..] {
+            bloom.accrue_bloom(&receipt.log_bloom);
+        }
+        assert_eq!(block.bloom, bloom);
+    }
+
+    #[test]
+    fn test_build_phantom_block_mismatch() {
+        let pivot_header = BlockHeaderBuilder::new().with_height(7).build();
+
+        let validated = EthReceiptsValidated {
+            epoch_receipts: vec![block_receipts(vec![])],
+            epoch_txs: vec![],
+        };
+        assert!(
+            build_phantom_block(pivot_header.clone(), validated, 2).is_err()
+        );
+
+        let validated = EthReceiptsValidated {
+            epoch_receipts: vec![block_receipts(vec![])],
+            epoch_txs: vec![vec![new_eth_tx(0)]],
+        };
+        assert!(build_phantom_block(pivot_header, validated, 2).is_err());
+    }
+
+    fn account_entry(address: &Address, balance: u64) -> (Vec<u8>, Vec<u8>) {
+        let key = StorageKey::new_account_key(address)
+            .with_native_space()
+            .to_key_bytes();
+        let account = Account::new_empty_with_balance(
+            &address.with_native_space(),
+            &balance.into(),
+            &U256::zero(),
+        );
+        (key, rlp::encode(&account).to_vec())
+    }
+
+    #[test]
+    fn test_execute_on_partial_state() {
+        // the balance of `pointer` is the address whose balance is returned,
+        // so the second read depends on the result of the first one
+        let pointer = Address::from_low_u64_be(1);
+        let target = Address::from_low_u64_be(5);
+        let full_state: HashMap<_, _> =
+            vec![account_entry(&pointer, 5), account_entry(&target, 42)]
+                .into_iter()
+                .collect();
+
+        let rounds = Cell::new(0);
+        let retrieved = Cell::new(vec![]);
+
+        let result = block_on(execute_on_partial_state(
+            |state: State| -> DbResult<U256> {
+                rounds.set(rounds.get() + 1);
+                let address = state.balance(&pointer.with_native_space())?;
+                let address = Address::from_low_u64_be(address.as_u64());
+                state.balance(&address.with_native_space())
+            },
+            |key| {
+                let mut keys = retrieved.take();
+                keys.push(key.clone());
+                retrieved.set(keys);
+                future::ready(Ok::<_, Error>(full_state.get(&key).cloned()))
+            },
+        ))
+        .unwrap();
+
+        assert_eq!(result.unwrap(), 42.into());
+        assert_eq!(rounds.get(), 3);
+
+        // every entry is retrieved once, including the absent ones
+        let retrieved = retrieved.take();
+        let (pointer_key, _) = account_entry(&pointer, 0);
+        let (target_key, _) = account_entry(&target, 0);
+        for key in &[pointer_key, target_key] {
+            assert_eq!(retrieved.iter().filter(|k| *k == key).count(), 1);
+        }
+        let (absent_key, _) = account_entry(&Address::zero(), 0);
+        assert!(retrieved.contains(&absent_key));
+    }
+
+    #[test]
+    fn test_execute_on_partial_state_rounds_exceeded() {
+        // every round reads a new entry
+        let rounds = Cell::new(0u64);
+
+        let result = block_on(execute_on_partial_state(
+            |state: State| -> DbResult<U256> {
+                rounds.set(rounds.get() + 1);
+                let address = Address::from_low_u64_be(rounds.get());
+                state.balance(&address.with_native_space())
+            },
+            |_| future::ready(Ok::<_, Error>(None)),
+        ));
+
+        assert!(result.is_err());
+        assert_eq!(rounds.get(), MAX_VIRTUAL_CALL_ROUNDS as u64);
+    }
+
+    #[test]
+    fn test_execute_on_partial_state_retrieval_error() {
+        let rounds = Cell::new(0);
+
+        let result = block_on(execute_on_partial_state(
+            |state: State| -> DbResult<U256> {
+                rounds.set(rounds.get() + 1);
+                state.balance(&Address::random().with_native_space())
+            },
+            |_| {
+                future::ready(Err::<Option<Vec<u8>>, _>(Error::from("timeout")))
+            },
+        ));
+
+        assert!(result.is_err());
+        assert_eq!(rounds.get(), 1);
+    }
+}
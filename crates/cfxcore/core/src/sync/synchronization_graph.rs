@@ -42,7 +42,9 @@ use crate::{
     pow::{PowComputer, ProofOfWorkConfig},
     state_exposer::{SyncGraphBlockState, STATE_EXPOSER},
     statistics::SharedStatistics,
-    sync::synchronization_protocol_handler::FutureBlockContainer,
+    sync::{
+        synchronization_protocol_handler::FutureBlockContainer, SyncPhaseType,
+    },
     verification::*,
     ConsensusGraph, Notifications,
 };
@@ -1001,6 +1003,9 @@ pub struct SynchronizationGraph {
     /// Each element is <block_hash, ignore_body>
     new_block_hashes: Arc<Channel<H256>>,
 
+    /// Channel used to notify PubSub of the sync phase transitions.
+    pub sync_phase_changes: Arc<Channel<SyncPhaseType>>,
+
     /// The blocks whose timestamps are near future.
     /// They will be inserted into sync graph inner at their timestamp.
     pub future_blocks: FutureBlockContainer,
@@ -1072,6 +1077,7 @@ impl SynchronizationGraph {
             statistics: consensus.get_statistics().clone(),
             consensus_unprocessed_count: consensus_unprocessed_count.clone(),
             new_block_hashes: notifications.new_block_hashes.clone(),
+            sync_phase_changes: notifications.sync_phase_changes.clone(),
             machine,
         };
 
@@ -2118,6 +2124,7 @@ impl RichTreeGraph for SynchronizationGraphInner {
     }
 }
 
+#[derive(Debug)]
 pub enum BlockInsertionResult {
     // The block is valid and already processed before.
     AlreadyProcessed,
@@ -2161,6 +2168,7 @@ impl BlockInsertionResult {
     }
 }
 
+#[derive(Debug)]
 pub enum BlockHeaderInsertionResult {
     // The block is valid and already processed consensus before.
     // We should not process this block again.
@@ -158,6 +158,7 @@ impl SynchronizationPhaseManager {
         sync_handler: &SynchronizationProtocolHandler,
     ) {
         self.inner.write().change_phase_to(phase_type);
+        sync_handler.graph.sync_phase_changes.send(phase_type);
         let current_phase = self.get_current_phase();
         current_phase.start(io, sync_handler);
     }
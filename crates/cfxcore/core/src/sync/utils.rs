@@ -109,6 +109,24 @@ pub fn create_simple_block(
 
 pub fn initialize_data_manager(
     db_dir: &str, dbtype: DbType, pow: Arc<PowComputer>, vm: VmFactory,
+) -> (Arc<BlockDataManager>, Arc<Block>) {
+    initialize_data_manager_with_config(
+        db_dir,
+        DataManagerConfiguration::new(
+            false,                          /* do not persist transaction
+                                             * address */
+            false, /* do not persist block number index */
+            Duration::from_millis(300_000), /* max cached tx count */
+            dbtype,
+        ),
+        pow,
+        vm,
+    )
+}
+
+pub fn initialize_data_manager_with_config(
+    db_dir: &str, config: DataManagerConfiguration, pow: Arc<PowComputer>,
+    vm: VmFactory,
 ) -> (Arc<BlockDataManager>, Arc<Block>) {
     let ledger_db = db::open_database(
         db_dir,
@@ -150,6 +168,7 @@ pub fn initialize_data_manager(
     let genesis_block = Arc::new(genesis_block(
         &storage_manager,
         genesis_accounts,
+        &Default::default(),
         Address::from_str("1000000000000000000000000000000000000008").unwrap(),
         U256::from(10),
         machine.clone(),
@@ -168,13 +187,7 @@ pub fn initialize_data_manager(
         ledger_db.clone(),
         storage_manager,
         worker_thread_pool,
-        DataManagerConfiguration::new(
-            false,                          /* do not persist transaction
-                                             * address */
-            false, /* do not persist block number index */
-            Duration::from_millis(300_000), /* max cached tx count */
-            dbtype,
-        ),
+        config,
         pow,
     ));
     (data_man, genesis_block)
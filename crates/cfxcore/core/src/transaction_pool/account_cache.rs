@@ -25,6 +25,11 @@ impl AccountCache {
         self.state.nonce(address)
     }
 
+    /// Returns whether the account is delegated by EIP-7702.
+    pub fn is_delegated(&self, address: &AddressWithSpace) -> DbResult<bool> {
+        Ok(self.state.delegation(address)?.is_some())
+    }
+
     pub fn get_sponsor_info(
         &self, contract_address: &Address,
     ) -> DbResult<Option<SponsorInfo>> {
@@ -1,4 +1,4 @@
-use cfx_types::{H256, U256};
+use cfx_types::{Address, H256, U256};
 use primitives::transaction::TransactionError;
 
 pub const SAME_NONCE_HIGH_GAS_PRICE_NEEED: &str = "Tx with same nonce already inserted. To replace it, you need to specify a gas price";
@@ -30,6 +30,12 @@ pub enum TransactionPoolError {
     #[error("txpool is full")]
     TxPoolFull,
 
+    #[error("the sender already has {max} transactions in txpool")]
+    SenderSlotsFull { max: usize },
+
+    #[error("the authority {authority:?} has transactions in txpool")]
+    AuthorityReserved { authority: Address },
+
     #[error("{SAME_NONCE_HIGH_GAS_PRICE_NEEED}")]
     HigherGasPriceNeeded,
 
@@ -0,0 +1,223 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_types::H256;
+use primitives::TransactionWithSignature;
+use rlp::{Encodable, Rlp};
+use std::{
+    collections::HashSet,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+/// The on-disk journal of the transactions received from the local RPC, so
+/// that they are not lost after the node restarts. The transactions are
+/// appended to the file in their RLP encoding, and the file is rewritten with
+/// the transactions still in the pool on rotation.
+pub struct TransactionJournal {
+    path: PathBuf,
+    /// The file opened for appending. It is opened on the first write.
+    writer: Option<File>,
+    /// The hashes of the journaled transactions, in the order they are
+    /// received.
+    local_hashes: Vec<H256>,
+    local_hash_set: HashSet<H256>,
+    /// The journal is not rotated before it's loaded, or the transactions
+    /// from the last run are dropped.
+    loaded: bool,
+    last_rotation: Instant,
+}
+
+impl TransactionJournal {
+    pub fn new(path: PathBuf) -> Self {
+        TransactionJournal {
+            path,
+            writer: None,
+            local_hashes: Vec::new(),
+            local_hash_set: HashSet::new(),
+            loaded: false,
+            last_rotation: Instant::now(),
+        }
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    pub fn is_loaded(&self) -> bool { self.loaded }
+
+    pub fn last_rotation(&self) -> Instant { self.last_rotation }
+
+    pub fn local_hashes(&self) -> &[H256] { &self.local_hashes }
+
+    /// Reads the journaled transactions. A corrupted record, e.g. the last
+    /// record written partially before a crash, ends the journal.
+    pub fn load(&mut self) -> io::Result<Vec<TransactionWithSignature>> {
+        self.loaded = true;
+        let content = match fs::read(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        let mut transactions = Vec::new();
+        let mut offset = 0;
+        while offset < content.len() {
+            let rlp = Rlp::new(&content[offset..]);
+            let record_len = match rlp.payload_info() {
+                Ok(info) => info.header_len + info.value_len,
+                Err(e) => {
+                    warn!("Corrupted transaction journal record: {:?}", e);
+                    break;
+                }
+            };
+            if offset + record_len > content.len() {
+                warn!("Truncated transaction journal record");
+                break;
+            }
+            match Rlp::new(&content[offset..offset + record_len]).as_val() {
+                Ok(tx) => transactions.push(tx),
+                Err(e) => {
+                    warn!("Corrupted transaction journal record: {:?}", e);
+                    break;
+                }
+            }
+            offset += record_len;
+        }
+        for tx in &transactions {
+            self.track(tx.hash());
+        }
+        Ok(transactions)
+    }
+
+    /// Appends a transaction to the journal.
+    pub fn insert(&mut self, tx: &TransactionWithSignature) -> io::Result<()> {
+        if self.writer.is_none() {
+            self.writer = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            );
+        }
+        let writer = self.writer.as_mut().expect("opened above");
+        writer.write_all(&tx.rlp_bytes())?;
+        writer.flush()?;
+        self.track(tx.hash());
+        Ok(())
+    }
+
+    /// Rewrites the journal with `transactions`, which are the journaled
+    /// transactions still in the pool.
+    pub fn rotate(
+        &mut self, transactions: Vec<TransactionWithSignature>,
+    ) -> io::Result<()> {
+        self.last_rotation = Instant::now();
+        self.writer = None;
+
+        let tmp_path = self.path.with_extension("new");
+        {
+            let mut writer = BufWriter::new(File::create(&tmp_path)?);
+            for tx in &transactions {
+                writer.write_all(&tx.rlp_bytes())?;
+            }
+            writer.into_inner()?.sync_all()?;
+        }
+        fs::rename(&tmp_path, &self.path)?;
+
+        self.local_hashes.clear();
+        self.local_hash_set.clear();
+        for tx in &transactions {
+            self.track(tx.hash());
+        }
+        Ok(())
+    }
+
+    fn track(&mut self, hash: H256) {
+        if self.local_hash_set.insert(hash) {
+            self.local_hashes.push(hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransactionJournal;
+    use cfx_types::{Address, H256, U256};
+    use keylib::{Generator, Random};
+    use primitives::{
+        transaction::Eip155Transaction, Action, Transaction,
+        TransactionWithSignature,
+    };
+    use std::{fs::OpenOptions, io::Write};
+    use tempdir::TempDir;
+
+    fn hashes(txs: &[TransactionWithSignature]) -> Vec<H256> {
+        txs.iter().map(|tx| tx.hash()).collect()
+    }
+
+    fn new_test_tx(nonce: usize) -> TransactionWithSignature {
+        let tx: Transaction = Eip155Transaction {
+            nonce: U256::from(nonce),
+            gas_price: U256::one(),
+            gas: U256::from(21000),
+            action: Action::Call(Address::random()),
+            value: U256::zero(),
+            chain_id: Some(1),
+            data: Vec::new(),
+        }
+        .into();
+        tx.sign(Random.generate().unwrap().secret()).transaction
+    }
+
+    #[test]
+    fn test_insert_load_and_rotate() {
+        let dir = TempDir::new("txpool_journal").unwrap();
+        let path = dir.path().join("transactions.rlp");
+        let txs: Vec<_> = (0..3).map(new_test_tx).collect();
+
+        let mut journal = TransactionJournal::new(path.clone());
+        for tx in &txs {
+            journal.insert(tx).unwrap();
+        }
+
+        let mut journal = TransactionJournal::new(path.clone());
+        assert_eq!(hashes(&journal.load().unwrap()), hashes(&txs));
+        assert_eq!(journal.local_hashes().len(), 3);
+
+        journal.rotate(vec![txs[1].clone()]).unwrap();
+        journal.insert(&txs[2]).unwrap();
+        let mut journal = TransactionJournal::new(path);
+        assert_eq!(hashes(&journal.load().unwrap()), hashes(&txs[1..]));
+    }
+
+    #[test]
+    fn test_load_truncated_journal() {
+        let dir = TempDir::new("txpool_journal").unwrap();
+        let path = dir.path().join("transactions.rlp");
+        let tx = new_test_tx(0);
+
+        let mut journal = TransactionJournal::new(path.clone());
+        journal.insert(&tx).unwrap();
+        let partial = rlp::encode(&new_test_tx(1));
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&partial[..partial.len() / 2])
+            .unwrap();
+
+        let mut journal = TransactionJournal::new(path);
+        assert_eq!(hashes(&journal.load().unwrap()), vec![tx.hash()]);
+    }
+
+    #[test]
+    fn test_load_missing_journal() {
+        let dir = TempDir::new("txpool_journal").unwrap();
+        let mut journal =
+            TransactionJournal::new(dir.path().join("transactions.rlp"));
+        assert!(journal.load().unwrap().is_empty());
+        assert!(journal.is_loaded());
+    }
+}
@@ -5,17 +5,23 @@
 mod account_cache;
 mod error;
 mod garbage_collector;
+mod journal;
 mod nonce_pool;
+mod tip_index;
 mod transaction_pool_inner;
 
 extern crate rand;
 
-pub use self::transaction_pool_inner::TransactionStatus;
+pub use self::transaction_pool_inner::{
+    DropReason, DroppedTransaction, PendingReason, TransactionStatus,
+};
 use crate::{
     block_data_manager::BlockDataManager,
+    channel::Channel,
     consensus::BestInformation,
     transaction_pool::{
-        nonce_pool::TxWithReadyInfo, transaction_pool_inner::PendingReason,
+        nonce_pool::{ReplacementPolicy, TxWithReadyInfo},
+        transaction_pool_inner::PendingReason,
     },
     verification::{VerificationConfig, VerifyTxLocalMode, VerifyTxMode},
 };
@@ -34,12 +40,13 @@ use cfx_types::{
 };
 use cfx_vm_types::Spec;
 pub use error::{TransactionPoolError, SAME_NONCE_HIGH_GAS_PRICE_NEEED};
+use journal::TransactionJournal;
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use metrics::{
     register_meter_with_group, Gauge, GaugeUsize, Lock, Meter, MeterTimer,
     RwLockExtensions,
 };
-use parking_lot::{Mutex, RwLock};
+use parking_lot::{Mutex, MutexGuard, RwLock};
 use primitives::{
     block::BlockHeight, block_header::compute_next_price_tuple, Account,
     SignedTransaction, Transaction, TransactionWithSignature,
@@ -49,10 +56,12 @@ use std::{
     collections::{hash_map::HashMap, BTreeSet},
     mem,
     ops::DerefMut,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::Duration,
 };
 use transaction_pool_inner::TransactionPoolInner;
 
@@ -99,6 +108,24 @@ pub struct TxPoolConfig {
     pub max_packing_batch_gas_limit: u64,
     pub max_packing_batch_size: usize,
     pub packing_pool_degree: u8,
+    /// The file to journal the transactions received from the local RPC. The
+    /// journal is disabled if it's `None`.
+    pub journal_path: Option<PathBuf>,
+    /// The interval to rewrite the journal with the journaled transactions
+    /// still in the pool.
+    pub journal_rotate_interval: Duration,
+    /// The minimum percentage of the gas price bump to replace a transaction
+    /// of the same sender and nonce. It also applies to the max fee per gas
+    /// of the EIP-1559 transactions.
+    pub price_bump_percent: u64,
+    /// The minimum percentage of the max priority fee per gas bump to replace
+    /// an EIP-1559 transaction. It is not checked if it's zero.
+    pub priority_fee_bump_percent: u64,
+    /// The maximum number of the not executed transactions of a sender.
+    pub max_txs_per_sender: Option<usize>,
+    /// Whether to evict the transaction with the lowest tip when the pool is
+    /// full, instead of rejecting the new transaction.
+    pub evict_lowest_tip: bool,
 }
 
 impl MallocSizeOf for TxPoolConfig {
@@ -119,6 +146,12 @@ impl Default for TxPoolConfig {
             max_packing_batch_gas_limit: DEFAULT_TARGET_BLOCK_GAS_LIMIT / 10,
             packing_pool_degree: 4,
             target_block_gas_limit: DEFAULT_TARGET_BLOCK_GAS_LIMIT,
+            journal_path: None,
+            journal_rotate_interval: Duration::from_secs(3600),
+            price_bump_percent: 2,
+            priority_fee_bump_percent: 0,
+            max_txs_per_sender: None,
+            evict_lowest_tip: false,
         }
     }
 }
@@ -138,6 +171,16 @@ pub struct TransactionPool {
     /// If it's `false`, operations on the tx pool will be ignored to save
     /// memory/CPU cost.
     ready_for_mining: AtomicBool,
+
+    /// The journal of the locally received transactions, if enabled.
+    journal: Option<Mutex<TransactionJournal>>,
+
+    /// The transactions newly inserted into the pool.
+    new_pending_transactions: Arc<Channel<Arc<SignedTransaction>>>,
+
+    /// The transactions replaced, evicted or garbage collected before they
+    /// are packed.
+    dropped_transactions: Arc<Channel<DroppedTransaction>>,
 }
 
 impl MallocSizeOf for TransactionPool {
@@ -174,6 +217,12 @@ impl TransactionPool {
             config.max_packing_batch_gas_limit as usize,
             config.max_packing_batch_size,
             config.packing_pool_degree,
+            ReplacementPolicy {
+                price_bump_percent: config.price_bump_percent,
+                priority_fee_bump_percent: config.priority_fee_bump_percent,
+            },
+            config.max_txs_per_sender,
+            config.evict_lowest_tip,
         );
         let best_executed_state = Mutex::new(
             Self::best_executed_state(
@@ -185,6 +234,10 @@ impl TransactionPool {
             )
             .expect("The genesis state is guaranteed to exist."),
         );
+        let journal = config
+            .journal_path
+            .clone()
+            .map(|path| Mutex::new(TransactionJournal::new(path)));
         TransactionPool {
             config,
             verification_config,
@@ -197,11 +250,50 @@ impl TransactionPool {
             recycle_tx_requests: Mutex::new(Default::default()),
             machine,
             ready_for_mining: AtomicBool::new(false),
+            journal,
+            new_pending_transactions: Arc::new(Channel::new(
+                "txpool-new-pending-transactions",
+            )),
+            dropped_transactions: Arc::new(Channel::new(
+                "txpool-dropped-transactions",
+            )),
         }
     }
 
     pub fn machine(&self) -> Arc<Machine> { self.machine.clone() }
 
+    /// The stream of the transactions newly inserted into the pool.
+    pub fn new_pending_transactions(
+        &self,
+    ) -> &Arc<Channel<Arc<SignedTransaction>>> {
+        &self.new_pending_transactions
+    }
+
+    /// The stream of the transactions dropped from the pool before they are
+    /// packed, with the reasons.
+    pub fn dropped_transactions(&self) -> &Arc<Channel<DroppedTransaction>> {
+        &self.dropped_transactions
+    }
+
+    /// Notifies the subscribers of the transactions dropped by the last
+    /// updates of `inner`.
+    fn notify_dropped_transactions(&self, inner: &mut TransactionPoolInner) {
+        for dropped in inner.take_dropped_transactions() {
+            self.dropped_transactions.send(dropped);
+        }
+    }
+
+    fn notify_new_pending_transactions(
+        &self, transactions: &[Arc<SignedTransaction>],
+    ) {
+        if self.new_pending_transactions.num_subscriptions() == 0 {
+            return;
+        }
+        for tx in transactions {
+            self.new_pending_transactions.send(tx.clone());
+        }
+    }
+
     pub fn get_transaction(
         &self, tx_hash: &H256,
     ) -> Option<Arc<SignedTransaction>> {
@@ -460,6 +552,8 @@ impl TransactionPool {
                         }
                     }
                 }
+                self.notify_dropped_transactions(&mut *inner);
+                self.notify_new_pending_transactions(&passed_transactions);
             }
             Err(e) => {
                 for tx in transactions {
@@ -484,6 +578,134 @@ impl TransactionPool {
         (passed_transactions, failure)
     }
 
+    /// Try to insert the `transactions` received from the local RPC into
+    /// transaction pool, and journal the inserted ones if the journal is
+    /// enabled.
+    pub fn insert_new_local_transactions(
+        &self, transactions: Vec<TransactionWithSignature>,
+    ) -> (
+        Vec<Arc<SignedTransaction>>,
+        HashMap<H256, TransactionPoolError>,
+    ) {
+        let (passed_transactions, failure) =
+            self.insert_new_transactions(transactions);
+        if let Some(journal) = &self.journal {
+            let mut journal = journal.lock();
+            for tx in &passed_transactions {
+                if let Err(e) = journal.insert(&tx.transaction) {
+                    warn!(
+                        "Failed to journal transaction {:?} to {:?}: {}",
+                        tx.hash,
+                        journal.path(),
+                        e
+                    );
+                }
+            }
+        }
+        (passed_transactions, failure)
+    }
+
+    /// Inserts the journaled transactions of the last run with the readiness
+    /// checks, and rewrites the journal with the ones still in the pool. It
+    /// should be called once the node is ready for mining.
+    pub fn load_journal(&self) {
+        let journal = match &self.journal {
+            Some(journal) => journal,
+            None => return,
+        };
+        // The journal is not locked during the insertion, as the lock of the
+        // journal is always acquired after the lock of `inner`.
+        let transactions = {
+            let mut journal = journal.lock();
+            if journal.is_loaded() {
+                return;
+            }
+            match journal.load() {
+                Ok(transactions) => transactions,
+                Err(e) => {
+                    warn!(
+                        "Failed to load transaction journal {:?}: {}",
+                        journal.path(),
+                        e
+                    );
+                    return;
+                }
+            }
+        };
+        let total = transactions.len();
+        let (passed_transactions, failure) =
+            self.insert_new_transactions(transactions);
+        info!(
+            "Loaded {} transactions from the journal, {} inserted, {} dropped",
+            total,
+            passed_transactions.len(),
+            failure.len()
+        );
+
+        let (journal, transactions) = {
+            let inner = self.inner.read();
+            let journal = journal.lock();
+            let transactions = Self::journaled_transactions(&journal, &inner);
+            (journal, transactions)
+        };
+        Self::rotate_journal(journal, transactions);
+    }
+
+    /// Takes the journaled transactions still in the pool if the rotation
+    /// interval has passed. The journal is rewritten with them by
+    /// `rotate_journal` after the lock of `inner` is released, since the
+    /// rewrite syncs the file to the disk. The lock of the journal is kept in
+    /// between, so that a transaction journaled after the snapshot is not
+    /// dropped by the rewrite.
+    fn journal_rotation(
+        &self, inner: &TransactionPoolInner,
+    ) -> Option<(
+        MutexGuard<TransactionJournal>,
+        Vec<TransactionWithSignature>,
+    )> {
+        let journal = self.journal.as_ref()?.lock();
+        if !journal.is_loaded()
+            || journal.last_rotation().elapsed()
+                < self.config.journal_rotate_interval
+        {
+            return None;
+        }
+        let transactions = Self::journaled_transactions(&journal, inner);
+        Some((journal, transactions))
+    }
+
+    /// The journaled transactions still in the pool.
+    fn journaled_transactions(
+        journal: &TransactionJournal, inner: &TransactionPoolInner,
+    ) -> Vec<TransactionWithSignature> {
+        journal
+            .local_hashes()
+            .iter()
+            .filter_map(|hash| inner.get(hash))
+            .map(|tx| tx.transaction.clone())
+            .collect()
+    }
+
+    /// Rewrites the journal with `transactions`.
+    fn rotate_journal(
+        mut journal: MutexGuard<TransactionJournal>,
+        transactions: Vec<TransactionWithSignature>,
+    ) {
+        let total = transactions.len();
+        match journal.rotate(transactions) {
+            Ok(()) => debug!(
+                "Rotated transaction journal {:?}, {} transactions kept",
+                journal.path(),
+                total
+            ),
+            Err(e) => warn!(
+                "Failed to rotate transaction journal {:?}: {}",
+                journal.path(),
+                e
+            ),
+        }
+    }
+
     /// Try to insert `signed_transaction` into transaction pool.
     ///
     /// If some tx is already in our tx_cache, it will be ignored and will not
@@ -585,6 +807,8 @@ impl TransactionPool {
                     to_prop.insert(tx.hash, tx);
                 }
             }
+            self.notify_dropped_transactions(&mut *inner);
+            self.notify_new_pending_transactions(&passed_transactions);
             //RwLock is dropped here
         }
 
@@ -909,8 +1133,16 @@ impl TransactionPool {
         *self.config.max_tx_gas.write() = self.calc_max_tx_gas();
 
         let account_cache = self.get_best_state_account_cache();
-        let mut inner = self.inner.write_with_metric(&NOTIFY_BEST_INFO_LOCK);
-        let inner = inner.deref_mut();
+        let best_base_price = self
+            .data_man
+            .block_header_by_hash(&best_info.best_block_hash)
+            .and_then(|header| header.base_price());
+        let mut inner_guard =
+            self.inner.write_with_metric(&NOTIFY_BEST_INFO_LOCK);
+        let inner = inner_guard.deref_mut();
+        if let Some(base_price) = best_base_price {
+            inner.set_base_price(base_price);
+        }
 
         while let Some(tx) = set_tx_buffer.pop() {
             let tx_hash = tx.hash();
@@ -969,11 +1201,18 @@ impl TransactionPool {
                 warn!("recycle tx err: e={:?}", e);
             }
         }
+        self.notify_dropped_transactions(inner);
         debug!(
             "notify_new_best_info: {:?}",
             self.consensus_best_info.lock()
         );
 
+        let journal_rotation = self.journal_rotation(inner);
+        drop(inner_guard);
+        if let Some((journal, transactions)) = journal_rotation {
+            Self::rotate_journal(journal, transactions);
+        }
+
         Ok(())
     }
 
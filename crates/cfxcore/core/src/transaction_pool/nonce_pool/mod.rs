@@ -19,6 +19,49 @@ use std::{ops::Deref, sync::Arc};
 
 use self::nonce_pool_map::NoncePoolMap;
 
+/// The rules for a transaction to replace the one of the same sender and
+/// nonce in the pool.
+#[derive(Clone, Copy, Debug)]
+pub struct ReplacementPolicy {
+    /// The minimum percentage by which the gas price, or the max fee per gas
+    /// of an EIP-1559 transaction, must be increased. The price must be
+    /// increased by at least one in any case.
+    pub price_bump_percent: u64,
+    /// The minimum percentage by which the max priority fee per gas must be
+    /// increased if both transactions are EIP-1559 transactions. It is not
+    /// checked if it's zero.
+    pub priority_fee_bump_percent: u64,
+}
+
+impl Default for ReplacementPolicy {
+    fn default() -> Self {
+        ReplacementPolicy {
+            price_bump_percent: 2,
+            priority_fee_bump_percent: 0,
+        }
+    }
+}
+
+impl ReplacementPolicy {
+    /// The minimum gas price to replace a transaction with gas price `price`.
+    #[inline]
+    pub fn next_price(&self, price: U256) -> U256 {
+        Self::bump(price, self.price_bump_percent).max(price + 1)
+    }
+
+    /// The minimum max priority fee per gas to replace a transaction with
+    /// `priority_price`.
+    #[inline]
+    pub fn next_priority_price(&self, priority_price: U256) -> U256 {
+        Self::bump(priority_price, self.priority_fee_bump_percent)
+    }
+
+    #[inline]
+    fn bump(price: U256, percent: u64) -> U256 {
+        price.saturating_add(price / 100 * percent)
+    }
+}
+
 #[derive(Clone, Debug, DeriveMallocSizeOf)]
 pub struct TxWithReadyInfo {
     pub transaction: Arc<SignedTransaction>,
@@ -63,7 +106,9 @@ impl TxWithReadyInfo {
 
     pub fn calc_tx_cost(&self) -> U256 { self.tx_cost }
 
-    pub fn should_replace(&self, x: &Self, force: bool) -> bool {
+    pub fn should_replace(
+        &self, x: &Self, force: bool, policy: &ReplacementPolicy,
+    ) -> bool {
         if force {
             return true;
         }
@@ -100,17 +145,16 @@ impl TxWithReadyInfo {
             } else {
                 false
             };
-        self.gas_price() >= &Self::compute_next_price(*x.gas_price())
-            || self.gas_price() >= x.gas_price() && higher_epoch_height
-    }
-
-    #[inline]
-    pub fn compute_next_price(price: U256) -> U256 {
-        if price < 100.into() {
-            price + 1
-        } else {
-            price + (price / 100) * 2
+        if policy.priority_fee_bump_percent > 0
+            && self.after_1559()
+            && x.after_1559()
+            && *self.max_priority_gas_price()
+                < policy.next_priority_price(*x.max_priority_gas_price())
+        {
+            return false;
         }
+        self.gas_price() >= &policy.next_price(*x.gas_price())
+            || self.gas_price() >= x.gas_price() && higher_epoch_height
     }
 
     pub fn make_tx_cost(
@@ -186,8 +230,9 @@ impl NoncePool {
     //  the FURTHEST_FUTURE_TRANSACTION_NONCE_OFFSET roughly doing this job
     pub fn insert(
         &mut self, tx: &TxWithReadyInfo, force: bool,
+        policy: &ReplacementPolicy,
     ) -> InsertResult {
-        self.map.insert(tx, force)
+        self.map.insert(tx, force, policy)
     }
 
     pub fn mark_packed(&mut self, nonce: &U256, packed: bool) -> bool {
@@ -218,6 +263,11 @@ impl NoncePool {
         self.map.remove(nonce)
     }
 
+    #[inline]
+    pub fn get_highest_nonce_tx(&self) -> Option<&TxWithReadyInfo> {
+        self.map.rightmost()
+    }
+
     #[inline]
     pub fn remove_lowest_nonce(&mut self) -> Option<TxWithReadyInfo> {
         let nonce = *self.get_lowest_nonce_tx()?.nonce();
@@ -385,14 +435,17 @@ impl NoncePool {
 
 #[cfg(test)]
 mod nonce_pool_test {
-    use super::{InsertResult, NoncePool, TxWithReadyInfo};
+    use super::{InsertResult, NoncePool, ReplacementPolicy, TxWithReadyInfo};
     use crate::transaction_pool::SAME_NONCE_HIGH_GAS_PRICE_NEEED;
     use cfx_parameters::staking::DRIPS_PER_STORAGE_COLLATERAL_UNIT;
     use cfx_types::{Address, U128, U256};
     use keylib::{Generator, KeyPair, Random};
     use primitives::{
-        transaction::native_transaction::NativeTransaction, Action,
-        SignedTransaction, Transaction,
+        transaction::{
+            native_transaction::NativeTransaction, Cip1559Transaction,
+            TypedNativeTransaction,
+        },
+        Action, SignedTransaction, Transaction,
     };
     use rand::{RngCore, SeedableRng};
     use rand_xorshift::XorShiftRng;
@@ -432,6 +485,60 @@ mod nonce_pool_test {
         )
     }
 
+    fn new_test_1559_tx(
+        sender: &KeyPair, max_fee: u64, max_priority_fee: u64,
+    ) -> TxWithReadyInfo {
+        let transaction = Transaction::Native(TypedNativeTransaction::Cip1559(
+            Cip1559Transaction {
+                nonce: 0.into(),
+                max_priority_fee_per_gas: max_priority_fee.into(),
+                max_fee_per_gas: max_fee.into(),
+                gas: 21000.into(),
+                action: Action::Call(Address::random()),
+                value: 0.into(),
+                storage_limit: 0,
+                epoch_height: 0,
+                chain_id: 1,
+                data: Vec::new(),
+                access_list: Vec::new(),
+            },
+        ))
+        .sign(sender.secret());
+        TxWithReadyInfo::new(Arc::new(transaction), false, 0.into(), 0)
+    }
+
+    #[test]
+    fn test_replacement_policy() {
+        let me = Random.generate().unwrap();
+        let default_policy = ReplacementPolicy::default();
+        let policy = ReplacementPolicy {
+            price_bump_percent: 10,
+            priority_fee_bump_percent: 10,
+        };
+        let old = new_test_1559_tx(&me, 1000, 100);
+
+        // The price must be increased by at least one.
+        assert!(!new_test_1559_tx(&me, 1000, 100).should_replace(
+            &old,
+            false,
+            &default_policy
+        ));
+        assert!(new_test_1559_tx(&me, 1020, 100).should_replace(
+            &old,
+            false,
+            &default_policy
+        ));
+        assert!(!new_test_1559_tx(&me, 1099, 110)
+            .should_replace(&old, false, &policy));
+        // The priority fee is not bumped enough.
+        assert!(!new_test_1559_tx(&me, 1100, 109)
+            .should_replace(&old, false, &policy));
+        assert!(new_test_1559_tx(&me, 1100, 110)
+            .should_replace(&old, false, &policy));
+        assert!(new_test_1559_tx(&me, 1000, 100)
+            .should_replace(&old, true, &policy));
+    }
+
     #[test]
     fn test_tx_cost() {
         let me = Random.generate().unwrap();
@@ -596,7 +703,11 @@ mod nonce_pool_test {
         assert_eq!(nonce_pool.is_empty(), true);
         for i in 0..10 {
             assert_eq!(
-                nonce_pool.insert(&tx1[i as usize], false /* force */),
+                nonce_pool.insert(
+                    &tx1[i as usize],
+                    false, /* force */
+                    &ReplacementPolicy::default()
+                ),
                 InsertResult::NewAdded
             );
             assert_eq!(
@@ -604,14 +715,22 @@ mod nonce_pool_test {
                 Some(tx1[i].clone())
             );
             assert_eq!(
-                nonce_pool.insert(&tx2[i as usize], false /* force */),
+                nonce_pool.insert(
+                    &tx2[i as usize],
+                    false, /* force */
+                    &ReplacementPolicy::default()
+                ),
                 InsertResult::Failed(format!(
                     "{SAME_NONCE_HIGH_GAS_PRICE_NEEED} > {}",
                     &tx1[i as usize].gas_price()
                 ))
             );
             assert_eq!(
-                nonce_pool.insert(&tx2[i as usize], true /* force */),
+                nonce_pool.insert(
+                    &tx2[i as usize],
+                    true, /* force */
+                    &ReplacementPolicy::default()
+                ),
                 InsertResult::Updated(tx1[i as usize].clone())
             );
             assert_eq!(nonce_pool.is_empty(), false);
@@ -672,7 +791,11 @@ mod nonce_pool_test {
 
         for i in vec![0, 1, 3, 4] {
             assert_eq!(
-                nonce_pool.insert(&tx[i], false /* force */),
+                nonce_pool.insert(
+                    &tx[i],
+                    false, /* force */
+                    &ReplacementPolicy::default()
+                ),
                 InsertResult::NewAdded
             );
             assert_eq!(
@@ -704,7 +827,11 @@ mod nonce_pool_test {
             None
         );
         assert_eq!(
-            nonce_pool.insert(&tx[2], false /* force */),
+            nonce_pool.insert(
+                &tx[2],
+                false, /* force */
+                &ReplacementPolicy::default()
+            ),
             InsertResult::NewAdded
         );
         assert_eq!(
@@ -830,12 +957,20 @@ mod nonce_pool_test {
             let nonce: usize = rng.next_u64() as usize % count;
             if mock_nonce_pool.contains_key(&nonce.into()) {
                 assert_eq!(
-                    nonce_pool.insert(&tx[nonce], true /* force */),
+                    nonce_pool.insert(
+                        &tx[nonce],
+                        true, /* force */
+                        &ReplacementPolicy::default()
+                    ),
                     InsertResult::Updated(tx[nonce].clone())
                 );
             } else {
                 assert_eq!(
-                    nonce_pool.insert(&tx[nonce], false /* force */),
+                    nonce_pool.insert(
+                        &tx[nonce],
+                        false, /* force */
+                        &ReplacementPolicy::default()
+                    ),
                     InsertResult::NewAdded
                 );
                 mock_nonce_pool.insert(nonce.into(), tx[nonce].clone());
@@ -897,12 +1032,20 @@ mod nonce_pool_test {
             let nonce: usize = rng.next_u64() as usize % count;
             if mock_nonce_pool.contains_key(&nonce.into()) {
                 assert_eq!(
-                    nonce_pool.insert(&tx[nonce], true /* force */),
+                    nonce_pool.insert(
+                        &tx[nonce],
+                        true, /* force */
+                        &ReplacementPolicy::default()
+                    ),
                     InsertResult::Updated(tx[nonce].clone())
                 );
             } else {
                 assert_eq!(
-                    nonce_pool.insert(&tx[nonce], false /* force */),
+                    nonce_pool.insert(
+                        &tx[nonce],
+                        false, /* force */
+                        &ReplacementPolicy::default()
+                    ),
                     InsertResult::NewAdded
                 );
                 mock_nonce_pool.insert(nonce.into(), tx[nonce].clone());
@@ -9,7 +9,7 @@ use treap_map::{
 
 use super::{
     super::error::SAME_NONCE_HIGH_GAS_PRICE_NEEED, weight::NoncePoolWeight,
-    InsertResult, TxWithReadyInfo,
+    InsertResult, ReplacementPolicy, TxWithReadyInfo,
 };
 
 struct NoncePoolConfig;
@@ -52,12 +52,13 @@ impl NoncePoolMap {
     /// will replace with higher gas price transaction
     pub fn insert(
         &mut self, tx: &TxWithReadyInfo, force: bool,
+        policy: &ReplacementPolicy,
     ) -> InsertResult {
         self.0
             .update(
                 tx.transaction.nonce(),
                 |node| -> Result<_, Infallible> {
-                    if tx.should_replace(&node.value, force) {
+                    if tx.should_replace(&node.value, force, policy) {
                         let old_value =
                             std::mem::replace(&mut node.value, tx.clone());
                         node.weight =
@@ -224,6 +225,18 @@ impl NoncePoolMap {
         }
     }
 
+    /// return the rightmost node
+    pub fn rightmost(&self) -> Option<&TxWithReadyInfo> {
+        let ret = self
+            .0
+            .search_no_weight(|_| SearchDirection::RightOrStop(()));
+        if let Some(SearchResult::Found { node, .. }) = ret {
+            Some(&node.value)
+        } else {
+            None
+        }
+    }
+
     /// return the leftmost node
     pub fn leftmost(&self) -> Option<&TxWithReadyInfo> {
         let ret = self.0.search_no_weight(|_| SearchDirection::LeftOrStop);
@@ -0,0 +1,232 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_types::{AddressWithSpace, U256};
+use heap_map::HeapMap;
+use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
+use primitives::SignedTransaction;
+use std::cmp::{Ord, Ordering, PartialEq, PartialOrd};
+
+/// A fee of the transaction indexed by `TipIndex`. A lower fee is considered
+/// as larger, so the topmost node of a `HeapMap` has the lowest fee.
+#[derive(Default, Eq, PartialEq, Copy, Clone, Debug, DeriveMallocSizeOf)]
+struct LowestFee(U256);
+
+impl Ord for LowestFee {
+    fn cmp(&self, other: &Self) -> Ordering { other.0.cmp(&self.0) }
+}
+
+impl PartialOrd for LowestFee {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The `TipIndex` finds the sender with the lowest effective tip among the
+/// indexed transactions, one per sender, without scanning all the senders.
+///
+/// The effective tip of a transaction is `min(max_priority_gas_price,
+/// gas_price - base_price)`, where the base price changes with the blocks.
+/// Since the lowest one of the minimums is the minimum of the lowest
+/// priority gas price and the lowest gas price minus the base price, the two
+/// fees are maintained in two priority queues and the base price is only
+/// applied on query.
+#[derive(Default, DeriveMallocSizeOf)]
+pub struct TipIndex {
+    by_priority_gas_price: HeapMap<AddressWithSpace, LowestFee>,
+    by_gas_price: HeapMap<AddressWithSpace, LowestFee>,
+}
+
+impl TipIndex {
+    /// Indexes `tx` as the transaction of `sender`, or removes `sender` from
+    /// the index if `tx` is `None`.
+    pub fn update(
+        &mut self, sender: &AddressWithSpace, tx: Option<&SignedTransaction>,
+    ) {
+        match tx {
+            Some(tx) => {
+                self.by_priority_gas_price
+                    .insert(sender, LowestFee(*tx.max_priority_gas_price()));
+                self.by_gas_price.insert(sender, LowestFee(*tx.gas_price()));
+            }
+            None => {
+                self.by_priority_gas_price.remove(sender);
+                self.by_gas_price.remove(sender);
+            }
+        }
+    }
+
+    /// Returns the sender whose indexed transaction has the lowest effective
+    /// tip above `base_price`, and the tip. The transaction of `exclude` is
+    /// not considered.
+    pub fn lowest(
+        &mut self, base_price: &U256, exclude: &AddressWithSpace,
+    ) -> Option<(AddressWithSpace, U256)> {
+        // The excluded sender is taken out of the queues during the query.
+        let excluded = (
+            self.by_priority_gas_price.remove(exclude),
+            self.by_gas_price.remove(exclude),
+        );
+        let lowest = self.lowest_tip(base_price);
+        if let (Some(priority_gas_price), Some(gas_price)) = excluded {
+            self.by_priority_gas_price
+                .insert(exclude, priority_gas_price);
+            self.by_gas_price.insert(exclude, gas_price);
+        }
+        lowest
+    }
+
+    fn lowest_tip(
+        &self, base_price: &U256,
+    ) -> Option<(AddressWithSpace, U256)> {
+        let (priority_sender, priority_gas_price) =
+            self.by_priority_gas_price.top()?;
+        let (price_sender, gas_price) = self.by_gas_price.top()?;
+        let price_tip = gas_price.0.saturating_sub(*base_price);
+        Some(
+            if priority_gas_price.0 <= price_tip {
+                (*priority_sender, priority_gas_price.0)
+            } else {
+                (*price_sender, price_tip)
+            },
+        )
+    }
+
+    pub fn clear(&mut self) {
+        self.by_priority_gas_price.clear();
+        self.by_gas_price.clear();
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize { self.by_gas_price.len() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TipIndex;
+    use cfx_types::{Address, AddressSpaceUtil, AddressWithSpace, U256};
+    use keylib::{Generator, Random};
+    use primitives::{
+        transaction::{Eip1559Transaction, EthereumTransaction},
+        Action, SignedTransaction, Transaction,
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    fn new_test_tx(
+        max_priority_fee_per_gas: u64, max_fee_per_gas: u64,
+    ) -> SignedTransaction {
+        Transaction::Ethereum(EthereumTransaction::Eip1559(
+            Eip1559Transaction {
+                chain_id: 1,
+                nonce: U256::zero(),
+                max_priority_fee_per_gas: max_priority_fee_per_gas.into(),
+                max_fee_per_gas: max_fee_per_gas.into(),
+                gas: 21000.into(),
+                action: Action::Call(Address::random()),
+                value: U256::zero(),
+                data: vec![],
+                access_list: vec![],
+            },
+        ))
+        .sign(Random.generate().unwrap().secret())
+    }
+
+    fn effective_tip(tx: &SignedTransaction, base_price: u64) -> U256 {
+        std::cmp::min(
+            *tx.max_priority_gas_price(),
+            tx.gas_price().saturating_sub(base_price.into()),
+        )
+    }
+
+    #[test]
+    fn test_lowest_tip() {
+        let senders: Vec<AddressWithSpace> =
+            (0..3).map(|_| Address::random().with_evm_space()).collect();
+        let mut index = TipIndex::default();
+        let none = Address::zero().with_evm_space();
+        assert_eq!(index.lowest(&0.into(), &none), None);
+
+        // (priority, max fee): the order of the tips changes with the base
+        // price.
+        index.update(&senders[0], Some(&new_test_tx(10, 100)));
+        index.update(&senders[1], Some(&new_test_tx(100, 20)));
+        assert_eq!(
+            index.lowest(&0.into(), &none),
+            Some((senders[0], 10.into()))
+        );
+        assert_eq!(
+            index.lowest(&15.into(), &none),
+            Some((senders[1], 5.into()))
+        );
+        assert_eq!(
+            index.lowest(&30.into(), &none),
+            Some((senders[1], 0.into()))
+        );
+
+        // The excluded sender is kept in the index.
+        assert_eq!(
+            index.lowest(&15.into(), &senders[1]),
+            Some((senders[0], 10.into()))
+        );
+        assert_eq!(index.len(), 2);
+
+        // The fees of a sender are replaced.
+        index.update(&senders[1], Some(&new_test_tx(100, 200)));
+        assert_eq!(
+            index.lowest(&15.into(), &none),
+            Some((senders[0], 10.into()))
+        );
+        index.update(&senders[2], Some(&new_test_tx(1, 100)));
+        assert_eq!(
+            index.lowest(&15.into(), &none),
+            Some((senders[2], 1.into()))
+        );
+
+        index.update(&senders[2], None);
+        index.update(&senders[0], None);
+        assert_eq!(index.len(), 1);
+        assert_eq!(
+            index.lowest(&15.into(), &none),
+            Some((senders[1], 100.into()))
+        );
+        assert_eq!(index.lowest(&15.into(), &senders[1]), None);
+
+        index.clear();
+        assert_eq!(index.lowest(&0.into(), &none), None);
+    }
+
+    #[test]
+    fn test_lowest_tip_matches_scan() {
+        let mut rng = XorShiftRng::seed_from_u64(7);
+        let txs: Vec<(AddressWithSpace, SignedTransaction)> = (0..50)
+            .map(|_| {
+                let priority = rng.gen_range(0, 100);
+                let max_fee = rng.gen_range(0, 200);
+                (
+                    Address::random().with_evm_space(),
+                    new_test_tx(priority, max_fee),
+                )
+            })
+            .collect();
+        let mut index = TipIndex::default();
+        for (sender, tx) in &txs {
+            index.update(sender, Some(tx));
+        }
+        for base_price in (0..200).step_by(13) {
+            let exclude = txs[base_price as usize % txs.len()].0;
+            let expected = txs
+                .iter()
+                .filter(|(sender, _)| *sender != exclude)
+                .map(|(_, tx)| effective_tip(tx, base_price))
+                .min();
+            let (sender, tip) =
+                index.lowest(&base_price.into(), &exclude).unwrap();
+            assert_eq!(Some(tip), expected);
+            let (_, tx) =
+                txs.iter().find(|(address, _)| *address == sender).unwrap();
+            assert_eq!(effective_tip(tx, base_price), tip);
+        }
+    }
+}
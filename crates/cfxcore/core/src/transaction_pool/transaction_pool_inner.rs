@@ -1,7 +1,8 @@
 use super::{
     account_cache::AccountCache,
     garbage_collector::GarbageCollector,
-    nonce_pool::{InsertResult, NoncePool, TxWithReadyInfo},
+    nonce_pool::{InsertResult, NoncePool, ReplacementPolicy, TxWithReadyInfo},
+    tip_index::TipIndex,
     TransactionPoolError, SAME_NONCE_HIGH_GAS_PRICE_NEEED,
 };
 
@@ -15,8 +16,8 @@ use cfx_parameters::{
 
 use cfx_statedb::Result as StateDbResult;
 use cfx_types::{
-    address_util::AddressUtil, AddressWithSpace, Space, SpaceMap, H256, U128,
-    U256, U512,
+    address_util::AddressUtil, AddressSpaceUtil, AddressWithSpace, Space,
+    SpaceMap, H256, U128, U256, U512,
 };
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
 use metrics::{
@@ -78,16 +79,25 @@ struct DeferredPool {
     /// Store transactions that are ready to be packed for each address, and
     /// implements random sampling logic.
     packing_pool: SpaceMap<PackingPool<Arc<SignedTransaction>>>,
+    /// Index the unpacked transactions with the highest nonce of the senders
+    /// by their tips, to find the transaction to evict when the pool is full.
+    tip_index: SpaceMap<TipIndex>,
+    #[ignore_malloc_size_of = "no heap allocation"]
+    replacement_policy: ReplacementPolicy,
 }
 
 impl DeferredPool {
-    fn new(config: PackingPoolConfig) -> Self {
+    fn new(
+        config: PackingPoolConfig, replacement_policy: ReplacementPolicy,
+    ) -> Self {
         DeferredPool {
             buckets: Default::default(),
             packing_pool: SpaceMap::new(
                 PackingPool::new(config),
                 PackingPool::new(config),
             ),
+            tip_index: Default::default(),
+            replacement_policy,
         }
     }
 
@@ -100,12 +110,27 @@ impl DeferredPool {
                 PackingPool::new(config),
                 PackingPool::new(config),
             ),
+            tip_index: Default::default(),
+            replacement_policy: Default::default(),
         }
     }
 
     fn clear(&mut self) {
         self.buckets.clear();
         self.packing_pool.apply_all(|x| x.clear());
+        self.tip_index.apply_all(|x| x.clear());
+    }
+
+    /// Updates the tip index with the transaction with the highest nonce of
+    /// `addr`, which must be called after the transactions of `addr` change.
+    fn update_tip_index(&mut self, addr: &AddressWithSpace) {
+        let tx = self
+            .buckets
+            .get(addr)
+            .and_then(|bucket| bucket.get_highest_nonce_tx())
+            .filter(|tx| !tx.is_already_packed())
+            .map(|tx| &*tx.transaction);
+        self.tip_index.in_space_mut(addr.space).update(addr, tx);
     }
 
     fn estimate_packing_gas_limit(
@@ -237,7 +262,7 @@ impl DeferredPool {
             .entry(tx.sender())
             .or_insert_with(|| NoncePool::new());
 
-        let res = bucket.insert(&tx, force);
+        let res = bucket.insert(&tx, force, &self.replacement_policy);
         if matches!(res, InsertResult::Updated(_)) {
             // The transactions in the packing_pool must be consistent with the
             // nonce pool. However, the replaced transactions have not undergone
@@ -247,6 +272,7 @@ impl DeferredPool {
                 .in_space_mut(tx.space())
                 .split_off_suffix(tx.sender(), tx.nonce());
         }
+        self.update_tip_index(&tx.sender());
         res
     }
 
@@ -254,7 +280,9 @@ impl DeferredPool {
         &mut self, addr: AddressWithSpace, nonce: &U256, packed: bool,
     ) -> bool {
         if let Some(bucket) = self.buckets.get_mut(&addr) {
-            bucket.mark_packed(&nonce, packed)
+            let changed = bucket.mark_packed(&nonce, packed);
+            self.update_tip_index(&addr);
+            changed
         } else {
             false
         }
@@ -282,6 +310,14 @@ impl DeferredPool {
         }
     }
 
+    fn count_from(&self, sender: &AddressWithSpace, nonce: &U256) -> usize {
+        if let Some(bucket) = self.buckets.get(sender) {
+            bucket.count_from(nonce)
+        } else {
+            0
+        }
+    }
+
     fn remove_lowest_nonce(
         &mut self, addr: &AddressWithSpace,
     ) -> Option<TxWithReadyInfo> {
@@ -290,6 +326,7 @@ impl DeferredPool {
         if bucket.is_empty() {
             self.buckets.remove(addr);
             self.packing_pool.in_space_mut(addr.space).remove(*addr);
+            self.update_tip_index(addr);
             return ret;
         }
 
@@ -311,6 +348,39 @@ impl DeferredPool {
         ret
     }
 
+    /// Removes the transaction with the highest nonce of `addr`, so that the
+    /// rest transactions of `addr` keep their readiness.
+    fn remove_highest_nonce(
+        &mut self, addr: &AddressWithSpace,
+    ) -> Option<TxWithReadyInfo> {
+        let bucket = self.buckets.get_mut(addr)?;
+        let nonce = *bucket.get_highest_nonce_tx()?.nonce();
+        let ret = bucket.remove(&nonce);
+        if bucket.is_empty() {
+            self.buckets.remove(addr);
+            self.packing_pool.in_space_mut(addr.space).remove(*addr);
+        } else {
+            self.packing_pool
+                .in_space_mut(addr.space)
+                .split_off_suffix(*addr, &nonce);
+        }
+        self.update_tip_index(addr);
+        ret
+    }
+
+    /// Finds the sender whose transaction with the highest nonce has the
+    /// lowest effective tip among the senders of `space`. Only the last
+    /// transactions of the senders are considered, as evicting them does not
+    /// leave a nonce gap. The packed transactions and the transactions of
+    /// `exclude` are not considered.
+    fn lowest_tip_sender(
+        &mut self, space: Space, base_price: &U256, exclude: &AddressWithSpace,
+    ) -> Option<(AddressWithSpace, U256)> {
+        self.tip_index
+            .in_space_mut(space)
+            .lowest(base_price, exclude)
+    }
+
     #[inline]
     fn get_lowest_nonce(&self, addr: &AddressWithSpace) -> Option<&U256> {
         Some(self.get_lowest_nonce_tx(addr)?.nonce())
@@ -489,6 +559,35 @@ impl DeferredPool {
     }
 }
 
+/// The tip per gas a transaction pays to the miner above `base_price`.
+fn effective_tip(tx: &SignedTransaction, base_price: &U256) -> U256 {
+    std::cmp::min(
+        *tx.max_priority_gas_price(),
+        tx.gas_price().saturating_sub(*base_price),
+    )
+}
+
+/// A transaction removed from the pool before it's packed.
+#[derive(Clone, Debug)]
+pub struct DroppedTransaction {
+    pub transaction: Arc<SignedTransaction>,
+    pub reason: DropReason,
+}
+
+/// The reason why a transaction is dropped, serialized as an object with the
+/// `reason` field and the fields of the reason, e.g.,
+/// `{"reason": "replaced", "by": "0x..."}`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "camelCase")]
+pub enum DropReason {
+    /// Replaced by the transaction of the same sender and nonce.
+    Replaced { by: H256 },
+    /// Evicted for the transaction with a higher tip when the pool is full.
+    Evicted { by: H256 },
+    /// Garbage collected before it's executed when the pool is full.
+    GarbageCollected,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TransactionStatus {
@@ -570,12 +669,28 @@ pub struct TransactionPoolInner {
     /// Keeps all transactions in the transaction pool.
     /// It should contain the same transaction set as `deferred_pool`.
     txs: TransactionSet,
+    /// The maximum number of the transactions of a sender with the nonce not
+    /// less than its state nonce. It's unlimited if it's `None`.
+    max_txs_per_sender: Option<usize>,
+    /// Evicts the unpacked transaction with the lowest tip for a new
+    /// transaction with a higher tip when the pool is full, instead of
+    /// rejecting the new transaction.
+    evict_lowest_tip: bool,
+    /// The base prices of the best block, which are used to compute the tips
+    /// of the transactions for the eviction.
+    base_price: SpaceMap<U256>,
+    /// The transactions removed from the pool before they're packed, which
+    /// are taken by `take_dropped_transactions` to notify the subscribers.
+    #[ignore_malloc_size_of = "drained after each update"]
+    dropped_transactions: Vec<DroppedTransaction>,
 }
 
 impl TransactionPoolInner {
     pub fn new(
         capacity: usize, max_packing_batch_gas_limit: usize,
         max_packing_batch_size: usize, packing_pool_degree: u8,
+        replacement_policy: ReplacementPolicy,
+        max_txs_per_sender: Option<usize>, evict_lowest_tip: bool,
     ) -> Self {
         let config = PackingPoolConfig::new(
             max_packing_batch_gas_limit.into(),
@@ -586,18 +701,25 @@ impl TransactionPoolInner {
             capacity,
             total_received_count: 0,
             unpacked_transaction_count: 0,
-            deferred_pool: DeferredPool::new(config),
+            deferred_pool: DeferredPool::new(config, replacement_policy),
             ready_nonces_and_balances: HashMap::new(),
             garbage_collector: SpaceMap::default(),
             txs: TransactionSet::default(),
+            max_txs_per_sender,
+            evict_lowest_tip,
+            base_price: SpaceMap::default(),
+            dropped_transactions: Vec::new(),
         }
     }
 
     #[cfg(test)]
-    pub fn new_for_test() -> Self { Self::new(50_000, 3_000_000, 50, 4) }
+    pub fn new_for_test() -> Self {
+        Self::new(50_000, 3_000_000, 50, 4, Default::default(), None, false)
+    }
 
     pub fn clear(&mut self) {
         self.deferred_pool.clear();
+        self.dropped_transactions.clear();
         self.ready_nonces_and_balances.clear();
         self.garbage_collector.apply_all(|x| x.clear());
         self.txs.clear();
@@ -644,6 +766,15 @@ impl TransactionPoolInner {
         bucket.get_tx_by_nonce(nonce).map(|tx| tx.transaction)
     }
 
+    pub fn set_base_price(&mut self, base_price: SpaceMap<U256>) {
+        self.base_price = base_price;
+    }
+
+    /// Takes the transactions dropped from the pool since the last call.
+    pub fn take_dropped_transactions(&mut self) -> Vec<DroppedTransaction> {
+        std::mem::take(&mut self.dropped_transactions)
+    }
+
     pub fn is_full(&self, space: Space) -> bool {
         return self.total_deferred(Some(space)) >= self.capacity;
     }
@@ -730,6 +861,12 @@ impl TransactionPoolInner {
                 assert_eq!(victim.count, 0);
                 GC_UNEXECUTED_COUNTER.inc(1);
                 warn!("an unexecuted tx is garbage-collected.");
+                if !tx_with_ready_info.is_already_packed() {
+                    self.dropped_transactions.push(DroppedTransaction {
+                        transaction: to_remove_tx.clone(),
+                        reason: DropReason::GarbageCollected,
+                    });
+                }
             }
 
             if !tx_with_ready_info.is_already_packed() {
@@ -797,6 +934,42 @@ impl TransactionPoolInner {
 
     pub fn capacity(&self) -> usize { self.capacity }
 
+    /// With EIP-7702, the transactions of others may change the nonce and
+    /// the balance of a delegated account or of an authority, which makes the
+    /// queued transactions of the account invalid. So a delegated sender can
+    /// only have one transaction in the pool, and an account with
+    /// transactions in the pool can't be an authority of another sender.
+    fn check_delegation_slots(
+        &self, transaction: &SignedTransaction, sender_delegated: bool,
+        state_nonce: &U256,
+    ) -> Result<(), TransactionPoolError> {
+        let sender = transaction.sender();
+        if sender_delegated
+            && !self
+                .deferred_pool
+                .check_sender_and_nonce_exists(&sender, transaction.nonce())
+            && self.deferred_pool.count_from(&sender, state_nonce) >= 1
+        {
+            return Err(TransactionPoolError::SenderSlotsFull { max: 1 });
+        }
+
+        for auth in transaction.authorization_list().into_iter().flatten() {
+            // The invalid authorizations are skipped in execution.
+            let authority = match auth.authority() {
+                Some(authority) => authority.with_evm_space(),
+                None => continue,
+            };
+            if authority != sender
+                && self.deferred_pool.contain_address(&authority)
+            {
+                return Err(TransactionPoolError::AuthorityReserved {
+                    authority: authority.address,
+                });
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(test)]
     fn insert_transaction_for_test(
         &mut self, transaction: Arc<SignedTransaction>, sender_nonce: U256,
@@ -827,7 +1000,10 @@ impl TransactionPoolInner {
             &transaction.nonce(),
         ) {
             self.collect_garbage(transaction.as_ref());
-            if self.is_full(transaction.space()) {
+            if self.is_full(transaction.space())
+                && !(self.evict_lowest_tip
+                    && self.evict_lowest_tip_transaction(&transaction))
+            {
                 return InsertResult::Failed("txpool is full".into());
             }
         }
@@ -875,12 +1051,76 @@ impl TransactionPoolInner {
                 if !packed {
                     self.unpacked_transaction_count += 1;
                 }
+                if !replaced_tx.is_already_packed() {
+                    self.dropped_transactions.push(DroppedTransaction {
+                        transaction: replaced_tx.transaction.clone(),
+                        reason: DropReason::Replaced {
+                            by: transaction.hash(),
+                        },
+                    });
+                }
             }
         }
 
         result
     }
 
+    /// Evicts the unpacked transaction with the lowest tip of the other
+    /// senders in the space of `new_tx` if its tip is lower than the one of
+    /// `new_tx`. Returns whether a transaction is evicted.
+    fn evict_lowest_tip_transaction(
+        &mut self, new_tx: &Arc<SignedTransaction>,
+    ) -> bool {
+        let space = new_tx.space();
+        let base_price = self.base_price[space];
+        let (victim_address, victim_tip) = match self
+            .deferred_pool
+            .lowest_tip_sender(space, &base_price, &new_tx.sender())
+        {
+            Some(victim) => victim,
+            None => return false,
+        };
+        if victim_tip >= effective_tip(new_tx, &base_price) {
+            trace!(
+                "txpool::evict_lowest_tip_transaction fails, victim={:?} \
+                 victim_tip={:?} new_tx={:?}",
+                victim_address,
+                victim_tip,
+                new_tx.hash()
+            );
+            return false;
+        }
+
+        let victim = self
+            .deferred_pool
+            .remove_highest_nonce(&victim_address)
+            .expect("sender exists");
+        debug!(
+            "txpool evicts tx {:?} with tip {:?} for tx {:?}",
+            victim.hash(),
+            victim_tip,
+            new_tx.hash()
+        );
+        self.unpacked_transaction_count = self
+            .unpacked_transaction_count
+            .checked_sub(1)
+            .unwrap_or_else(|| {
+                error!("unpacked_transaction_count under-flows.");
+                0
+            });
+        self.txs.remove(&victim.hash());
+        if self.deferred_pool.contain_address(&victim_address) {
+            self.recalculate_readiness_with_local_info(&victim_address);
+        } else {
+            self.ready_nonces_and_balances.remove(&victim_address);
+        }
+        self.dropped_transactions.push(DroppedTransaction {
+            transaction: victim.transaction.clone(),
+            reason: DropReason::Evicted { by: new_tx.hash() },
+        });
+        true
+    }
+
     #[allow(dead_code)]
     fn mark_packed(&mut self, tx: &SignedTransaction, packed: bool) {
         let changed =
@@ -1007,7 +1247,6 @@ impl TransactionPoolInner {
             .unwrap_or(state_nonce)
     }
 
-    #[allow(dead_code)]
     fn recalculate_readiness_with_local_info(
         &mut self, addr: &AddressWithSpace,
     ) {
@@ -1389,6 +1628,44 @@ impl TransactionPoolInner {
             });
         }
 
+        if let Some(max_txs_per_sender) = self.max_txs_per_sender {
+            if !packed
+                && !force
+                && !self.deferred_pool.check_sender_and_nonce_exists(
+                    &transaction.sender(),
+                    transaction.nonce(),
+                )
+                && self
+                    .deferred_pool
+                    .count_from(&transaction.sender(), &state_nonce)
+                    >= max_txs_per_sender
+            {
+                trace!(
+                    "Transaction {:?} is discarded due to too many transactions of the sender",
+                    transaction.hash()
+                );
+                return Err(TransactionPoolError::SenderSlotsFull {
+                    max: max_txs_per_sender,
+                });
+            }
+        }
+
+        if !packed && !force {
+            let sender_delegated = account_cache
+                .is_delegated(&transaction.sender())
+                .map_err(|e| {
+                    TransactionPoolError::Other(format!(
+                        "Failed to read account_cache from storage: {}",
+                        e
+                    ))
+                })?;
+            self.check_delegation_slots(
+                &transaction,
+                sender_delegated,
+                &state_nonce,
+            )?;
+        }
+
         // check balance
         if !packed && !force {
             let mut need_balance = U256::from(0);
@@ -1540,19 +1817,21 @@ mod tests {
     };
 
     use super::{
-        DeferredPool, InsertResult, TransactionPoolInner, TxWithReadyInfo,
+        DeferredPool, DropReason, InsertResult, TransactionPoolError,
+        TransactionPoolInner, TxWithReadyInfo,
     };
     use cfx_executor::{
         machine::{new_machine, Machine, VmFactory},
         spec::CommonParams,
     };
-    use cfx_types::{Address, AddressSpaceUtil, Space, SpaceMap, U256};
+    use cfx_types::{Address, AddressSpaceUtil, Space, SpaceMap, H256, U256};
     use itertools::Itertools;
-    use keylib::{Generator, KeyPair, Random};
+    use keylib::{public_to_address, Generator, KeyPair, Random};
     use primitives::{
         block_header::compute_next_price_tuple,
         transaction::{
-            native_transaction::NativeTransaction, Eip155Transaction,
+            native_transaction::NativeTransaction, AuthorizationListItem,
+            Eip155Transaction, Eip7702Transaction, EthereumTransaction,
         },
         Action, SignedTransaction, Transaction,
     };
@@ -1589,6 +1868,40 @@ mod tests {
         Arc::new(tx.sign(sender.secret()))
     }
 
+    fn new_test_set_code_tx(
+        sender: &KeyPair, nonce: usize, authority: &KeyPair,
+    ) -> Arc<SignedTransaction> {
+        let mut auth = AuthorizationListItem {
+            chain_id: U256::zero(),
+            address: Address::random(),
+            nonce: 0,
+            y_parity: 0,
+            r: U256::zero(),
+            s: U256::zero(),
+        };
+        let sig =
+            keylib::sign(authority.secret(), &auth.signature_hash()).unwrap();
+        auth.y_parity = sig.v();
+        auth.r = U256::from_big_endian(sig.r());
+        auth.s = U256::from_big_endian(sig.s());
+
+        let tx = Transaction::Ethereum(EthereumTransaction::Eip7702(
+            Eip7702Transaction {
+                chain_id: 1,
+                nonce: U256::from(nonce),
+                max_priority_fee_per_gas: U256::from(10),
+                max_fee_per_gas: U256::from(10),
+                gas: U256::from(100_000),
+                action: Action::Call(Address::random()),
+                value: U256::zero(),
+                data: vec![],
+                access_list: vec![],
+                authorization_list: vec![auth],
+            },
+        ));
+        Arc::new(tx.sign(sender.secret()))
+    }
+
     fn new_test_tx_with_read_info(
         sender: &KeyPair, nonce: usize, gas_price: usize, value: usize,
         packed: bool,
@@ -1941,4 +2254,237 @@ mod tests {
             pool.clear();
         }
     }
+
+    #[test]
+    fn test_deferred_pool_tip_index() {
+        let mut deferred_pool = DeferredPool::new_for_test();
+        let alice = Random.generate().unwrap();
+        let alice_addr_s = alice.address().with_native_space();
+        let bob = Random.generate().unwrap();
+        let bob_addr_s = bob.address().with_native_space();
+        let none = Address::zero().with_native_space();
+        let zero = U256::zero();
+
+        for tx in [
+            new_test_tx_with_read_info(
+                &alice, 0, 50, 0, false, /* packed */
+            ),
+            new_test_tx_with_read_info(
+                &alice, 1, 10, 0, false, /* packed */
+            ),
+            new_test_tx_with_read_info(&bob, 0, 40, 0, false /* packed */),
+        ] {
+            deferred_pool.insert(tx, false /* force */);
+        }
+        assert_eq!(
+            deferred_pool.lowest_tip_sender(Space::Native, &zero, &none),
+            Some((alice_addr_s, 10.into()))
+        );
+        assert_eq!(
+            deferred_pool.lowest_tip_sender(Space::Ethereum, &zero, &none),
+            None
+        );
+
+        // The packed transactions are not indexed.
+        deferred_pool.mark_packed(alice_addr_s, &1.into(), true);
+        assert_eq!(
+            deferred_pool.lowest_tip_sender(Space::Native, &zero, &none),
+            Some((bob_addr_s, 40.into()))
+        );
+        deferred_pool.mark_packed(alice_addr_s, &1.into(), false);
+        assert_eq!(
+            deferred_pool.lowest_tip_sender(Space::Native, &zero, &none),
+            Some((alice_addr_s, 10.into()))
+        );
+
+        // The next transaction is indexed after the last one is removed.
+        deferred_pool.remove_highest_nonce(&alice_addr_s);
+        assert_eq!(
+            deferred_pool.lowest_tip_sender(Space::Native, &zero, &none),
+            Some((bob_addr_s, 40.into()))
+        );
+        deferred_pool.remove_lowest_nonce(&bob_addr_s);
+        assert_eq!(
+            deferred_pool.lowest_tip_sender(Space::Native, &zero, &none),
+            Some((alice_addr_s, 50.into()))
+        );
+        assert_eq!(
+            deferred_pool.lowest_tip_sender(
+                Space::Native,
+                &zero,
+                &alice_addr_s
+            ),
+            None
+        );
+
+        deferred_pool.clear();
+        assert_eq!(
+            deferred_pool.lowest_tip_sender(Space::Native, &zero, &none),
+            None
+        );
+    }
+
+    #[test]
+    fn test_replaced_transaction_dropped() {
+        let mut pool = TransactionPoolInner::new_for_test();
+        let alice = Random.generate().unwrap();
+
+        let alice_tx0 = new_test_tx(&alice, 0, 10, 21000, 0, Space::Ethereum);
+        assert_eq!(
+            pool.insert_transaction_for_test(alice_tx0.clone(), U256::zero()),
+            InsertResult::NewAdded
+        );
+        assert!(pool.take_dropped_transactions().is_empty());
+
+        let alice_tx0_new =
+            new_test_tx(&alice, 0, 20, 21000, 0, Space::Ethereum);
+        assert!(matches!(
+            pool.insert_transaction_for_test(
+                alice_tx0_new.clone(),
+                U256::zero()
+            ),
+            InsertResult::Updated(_)
+        ));
+        let dropped = pool.take_dropped_transactions();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].transaction.hash(), alice_tx0.hash());
+        assert_eq!(
+            dropped[0].reason,
+            DropReason::Replaced {
+                by: alice_tx0_new.hash()
+            }
+        );
+        assert!(pool.take_dropped_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_drop_reason_serialization() {
+        let by = H256::from_low_u64_be(1);
+        assert_eq!(
+            serde_json::to_value(DropReason::Replaced { by }).unwrap(),
+            serde_json::json!({"reason": "replaced", "by": by})
+        );
+        assert_eq!(
+            serde_json::to_value(DropReason::Evicted { by }).unwrap(),
+            serde_json::json!({"reason": "evicted", "by": by})
+        );
+        assert_eq!(
+            serde_json::to_value(DropReason::GarbageCollected).unwrap(),
+            serde_json::json!({"reason": "garbageCollected"})
+        );
+    }
+
+    #[test]
+    fn test_evict_lowest_tip_transaction() {
+        let mut pool = TransactionPoolInner::new(
+            3,
+            3_000_000,
+            50,
+            4,
+            Default::default(),
+            None,
+            true, /* evict_lowest_tip */
+        );
+        let alice = Random.generate().unwrap();
+        let bob = Random.generate().unwrap();
+        let carol = Random.generate().unwrap();
+
+        let alice_tx0 = new_test_tx(&alice, 0, 50, 21000, 0, Space::Native);
+        let alice_tx1 = new_test_tx(&alice, 1, 10, 21000, 0, Space::Native);
+        let bob_tx0 = new_test_tx(&bob, 0, 40, 21000, 0, Space::Native);
+        for tx in [alice_tx0, alice_tx1.clone(), bob_tx0] {
+            assert_eq!(
+                pool.insert_transaction_for_test(tx, U256::zero()),
+                InsertResult::NewAdded
+            );
+        }
+
+        // The tip of the new transaction is not higher than any tail
+        // transaction of the others.
+        let carol_tx0 = new_test_tx(&carol, 0, 5, 21000, 0, Space::Native);
+        assert_eq!(
+            pool.insert_transaction_for_test(carol_tx0, U256::zero()),
+            InsertResult::Failed("txpool is full".into())
+        );
+        assert!(pool.take_dropped_transactions().is_empty());
+
+        // The last transaction of alice has the lowest tip and is evicted.
+        let carol_tx0 = new_test_tx(&carol, 0, 20, 21000, 0, Space::Native);
+        assert_eq!(
+            pool.insert_transaction_for_test(carol_tx0.clone(), U256::zero()),
+            InsertResult::NewAdded
+        );
+        assert!(pool.get(&alice_tx1.hash()).is_none());
+        assert_eq!(pool.total_deferred(Some(Space::Native)), 3);
+        let dropped = pool.take_dropped_transactions();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].transaction.hash(), alice_tx1.hash());
+        assert_eq!(
+            dropped[0].reason,
+            DropReason::Evicted {
+                by: carol_tx0.hash()
+            }
+        );
+    }
+
+    #[test]
+    fn test_delegated_sender_slots() {
+        let mut pool = TransactionPoolInner::new_for_test();
+        let alice = Random.generate().unwrap();
+
+        let alice_tx0 = new_test_tx(&alice, 0, 10, 21000, 0, Space::Ethereum);
+        assert_eq!(
+            pool.insert_transaction_for_test(alice_tx0, U256::zero()),
+            InsertResult::NewAdded
+        );
+
+        let alice_tx1 = new_test_tx(&alice, 1, 10, 21000, 0, Space::Ethereum);
+        assert!(pool
+            .check_delegation_slots(&alice_tx1, false, &U256::zero())
+            .is_ok());
+        assert!(matches!(
+            pool.check_delegation_slots(&alice_tx1, true, &U256::zero()),
+            Err(TransactionPoolError::SenderSlotsFull { max: 1 })
+        ));
+        // the queued transaction can still be replaced
+        let alice_tx0 = new_test_tx(&alice, 0, 20, 21000, 0, Space::Ethereum);
+        assert!(pool
+            .check_delegation_slots(&alice_tx0, true, &U256::zero())
+            .is_ok());
+        // the packed transactions don't count
+        assert!(pool
+            .check_delegation_slots(&alice_tx1, true, &U256::one())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_authority_reserved() {
+        let mut pool = TransactionPoolInner::new_for_test();
+        let alice = Random.generate().unwrap();
+        let bob = Random.generate().unwrap();
+        let carol = Random.generate().unwrap();
+
+        let bob_tx0 = new_test_tx(&bob, 0, 10, 21000, 0, Space::Ethereum);
+        assert_eq!(
+            pool.insert_transaction_for_test(bob_tx0, U256::zero()),
+            InsertResult::NewAdded
+        );
+
+        let tx = new_test_set_code_tx(&alice, 0, &bob);
+        assert!(matches!(
+            pool.check_delegation_slots(&tx, false, &U256::zero()),
+            Err(TransactionPoolError::AuthorityReserved { authority })
+                if authority == public_to_address(bob.public(), false)
+        ));
+
+        let tx = new_test_set_code_tx(&alice, 0, &carol);
+        assert!(pool
+            .check_delegation_slots(&tx, false, &U256::zero())
+            .is_ok());
+        // the sender may authorize itself
+        let tx = new_test_set_code_tx(&bob, 1, &bob);
+        assert!(pool
+            .check_delegation_slots(&tx, false, &U256::zero())
+            .is_ok());
+    }
 }
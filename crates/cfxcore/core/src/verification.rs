@@ -674,6 +674,7 @@ impl VerificationConfig {
     ) -> PackingCheckResult {
         let cip90a = height >= transitions.cip90a;
         let cip1559 = height >= transitions.cip1559;
+        let cip7702 = height >= transitions.cip7702;
 
         let (can_pack, later_pack) =
             Self::fast_recheck_inner(spec, |mode: &VerifyTxMode| {
@@ -681,6 +682,10 @@ impl VerificationConfig {
                     return false;
                 }
 
+                if !Self::check_eip7702_transaction(tx, cip7702, mode) {
+                    return false;
+                }
+
                 if let Transaction::Native(ref tx) = tx.unsigned {
                     Self::verify_transaction_epoch_height(
                         tx,
@@ -760,6 +765,7 @@ impl VerificationConfig {
         let cip90a = height >= transitions.cip90a;
         let cip130 = height >= transitions.cip130;
         let cip1559 = height >= transitions.cip1559;
+        let cip7702 = height >= transitions.cip7702;
 
         if let Transaction::Native(ref tx) = tx.unsigned {
             Self::verify_transaction_epoch_height(
@@ -778,6 +784,17 @@ impl VerificationConfig {
             bail!(TransactionError::FutureTransactionType)
         }
 
+        if !Self::check_eip7702_transaction(tx, cip7702, &mode) {
+            bail!(TransactionError::FutureTransactionType)
+        }
+
+        if tx
+            .authorization_list()
+            .map_or(false, |list| list.is_empty())
+        {
+            bail!(TransactionError::EmptyAuthorizationList)
+        }
+
         Self::check_gas_limit(tx, cip76, &mode)?;
         Self::check_gas_limit_with_calldata(tx, cip130)?;
         Ok(())
@@ -813,6 +830,21 @@ impl VerificationConfig {
         }
     }
 
+    fn check_eip7702_transaction(
+        tx: &TransactionWithSignature, cip7702: bool, mode: &VerifyTxMode,
+    ) -> bool {
+        if tx.authorization_list().is_none() {
+            return true;
+        }
+
+        use VerifyTxLocalMode::*;
+        match mode {
+            VerifyTxMode::Local(Full, _spec) => cip7702,
+            VerifyTxMode::Local(MaybeLater, _spec) => true,
+            VerifyTxMode::Remote => cip7702,
+        }
+    }
+
     /// Check transaction intrinsic gas. Influenced by CIP-76.
     fn check_gas_limit(
         tx: &TransactionWithSignature, cip76: bool, mode: &VerifyTxMode,
@@ -833,6 +865,7 @@ impl VerificationConfig {
                 *tx.action() == Action::Create,
                 &tx.data(),
                 tx.access_list(),
+                tx.authorization_list(),
                 &spec,
             );
             if *tx.gas() < (tx_intrinsic_gas as usize).into() {
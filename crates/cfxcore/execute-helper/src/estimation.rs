@@ -8,7 +8,8 @@ use cfx_executor::{
 };
 
 use super::observer::{
-    exec_tracer::ErrorUnwind, gasman::GasLimitEstimation, Observer,
+    access_list::AccessListTracer, exec_tracer::ErrorUnwind,
+    gasman::GasLimitEstimation, Observer,
 };
 use cfx_parameters::{consensus::ONE_CFX_IN_DRIP, staking::*};
 use cfx_statedb::Result as DbResult;
@@ -100,6 +101,17 @@ impl<'a> EstimationContext<'a> {
         Ok(())
     }
 
+    fn observer(
+        &self, tx: &SignedTransaction, request: &EstimateRequest,
+    ) -> Observer {
+        let mut observer = Observer::virtual_call();
+        if request.collect_access_list {
+            observer.access_list =
+                Some(AccessListTracer::new(tx, self.machine));
+        }
+        observer
+    }
+
     fn sponsored_contract_if_eligible_sender(
         &self, tx: &SignedTransaction, ty: SponsoredType,
     ) -> DbResult<Option<Address>> {
@@ -208,9 +220,10 @@ impl<'a> EstimationContext<'a> {
     ) -> DbResult<Result<(Executed, Option<u64>), ExecutionOutcome>> {
         // First pass
         self.state.checkpoint();
+        let observer = self.observer(tx, &request);
         let sender_pay_executed = match self
             .as_executive()
-            .transact(&tx, request.first_pass_options())?
+            .transact(&tx, request.first_pass_options(observer))?
         {
             ExecutionOutcome::Finished(executed) => executed,
             res => {
@@ -234,9 +247,10 @@ impl<'a> EstimationContext<'a> {
         let contract_pay_executed =
             if collateral_sponsored_contract_if_eligible_sender.is_some() {
                 self.state.checkpoint();
+                let observer = self.observer(tx, &request);
                 let res = self
                     .as_executive()
-                    .transact(&tx, request.second_pass_options())?;
+                    .transact(&tx, request.second_pass_options(observer))?;
                 self.state.revert_to_checkpoint();
 
                 contract_pay_executed = match res {
@@ -450,6 +464,9 @@ pub struct EstimateRequest {
     pub has_gas_price: bool,
     pub has_nonce: bool,
     pub has_storage_limit: bool,
+    /// Records the access list of the transaction into the `ext_result` of
+    /// the execution (see `AccessListKey`).
+    pub collect_access_list: bool,
 }
 
 impl EstimateRequest {
@@ -470,16 +487,20 @@ impl EstimateRequest {
         }
     }
 
-    fn first_pass_options(self) -> TransactOptions<Observer> {
+    fn first_pass_options(
+        self, observer: Observer,
+    ) -> TransactOptions<Observer> {
         TransactOptions {
-            observer: Observer::virtual_call(),
+            observer,
             settings: self.transact_settings(ChargeCollateral::EstimateSender),
         }
     }
 
-    pub fn second_pass_options(self) -> TransactOptions<Observer> {
+    pub fn second_pass_options(
+        self, observer: Observer,
+    ) -> TransactOptions<Observer> {
         TransactOptions {
-            observer: Observer::virtual_call(),
+            observer,
             settings: self.transact_settings(ChargeCollateral::EstimateSponsor),
         }
     }
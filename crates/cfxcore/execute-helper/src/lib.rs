@@ -6,6 +6,7 @@ extern crate error_chain;
 pub mod estimation;
 pub mod observer;
 pub mod phantom_tx;
+pub mod simulation;
 pub mod tx_outcome;
 
 pub use observer::exec_tracer;
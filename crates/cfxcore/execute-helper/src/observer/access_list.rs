@@ -0,0 +1,155 @@
+use cfx_executor::{
+    machine::Machine,
+    observer::{
+        CallTracer, CheckpointTracer, DrainTrace, InternalTransferTracer,
+        OpcodeTracer, StorageTracer,
+    },
+    stack::{FrameResult, FrameReturn},
+};
+use cfx_types::{Address, BigEndianHash, Space, H256};
+use cfx_vm_types::{ActionParams, InterpreterInfo};
+use primitives::{AccessList, AccessListItem, Action, SignedTransaction};
+use revm::interpreter::opcode;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use typemap::ShareDebugMap;
+
+/// Records the accounts and storage slots accessed by a transaction, which
+/// makes up the access list of `eth_createAccessList`.
+///
+/// Like geth, the sender, the recipient (or the created contract) and the
+/// precompiles are excluded, since they are always accessed.
+pub struct AccessListTracer {
+    excluded: HashSet<Address>,
+    access_list: BTreeMap<Address, BTreeSet<H256>>,
+    depth: usize,
+}
+
+impl AccessListTracer {
+    /// Creates a tracer seeded with the access list of `tx`, so that the
+    /// recorded list is a superset of the given one.
+    pub fn new(tx: &SignedTransaction, machine: &Machine) -> Self {
+        let builtins = match tx.space() {
+            Space::Native => machine.builtins(),
+            Space::Ethereum => machine.builtins_evm(),
+        };
+        let mut excluded: HashSet<Address> = builtins.keys().cloned().collect();
+        excluded.insert(tx.sender().address);
+        if let Action::Call(to) = tx.action() {
+            excluded.insert(*to);
+        }
+
+        let mut tracer = AccessListTracer {
+            excluded,
+            access_list: BTreeMap::new(),
+            depth: 0,
+        };
+        for item in tx.access_list().into_iter().flatten() {
+            tracer.record_address(item.address);
+            for key in &item.storage_keys {
+                tracer.record_slot(item.address, *key);
+            }
+        }
+        tracer
+    }
+
+    pub fn access_list(&self) -> AccessList {
+        self.access_list
+            .iter()
+            .map(|(address, keys)| AccessListItem {
+                address: *address,
+                storage_keys: keys.iter().cloned().collect(),
+            })
+            .collect()
+    }
+
+    fn record_address(&mut self, address: Address) {
+        if !self.excluded.contains(&address) {
+            self.access_list.entry(address).or_default();
+        }
+    }
+
+    fn record_slot(&mut self, address: Address, key: H256) {
+        if !self.excluded.contains(&address) {
+            self.access_list.entry(address).or_default().insert(key);
+        }
+    }
+}
+
+impl DrainTrace for AccessListTracer {
+    fn drain_trace(self, map: &mut ShareDebugMap) {
+        map.insert::<AccessListKey>(self.access_list());
+    }
+}
+
+pub struct AccessListKey;
+
+impl typemap::Key for AccessListKey {
+    type Value = AccessList;
+}
+
+impl CallTracer for AccessListTracer {
+    fn record_call(&mut self, _params: &ActionParams) { self.depth += 1; }
+
+    fn record_call_result(&mut self, _result: &FrameResult) { self.depth -= 1; }
+
+    fn record_create(&mut self, _params: &ActionParams) { self.depth += 1; }
+
+    fn record_create_result(&mut self, result: &FrameResult) {
+        self.depth -= 1;
+        // The contract created by the transaction is accessed anyway.
+        if self.depth == 0 {
+            if let Ok(FrameReturn {
+                create_address: Some(address),
+                ..
+            }) = result
+            {
+                self.excluded.insert(*address);
+                self.access_list.remove(address);
+            }
+        }
+    }
+}
+
+impl OpcodeTracer for AccessListTracer {
+    fn do_trace_opcode(&self, enabled: &mut bool) { *enabled = true; }
+
+    fn step(&mut self, interp: &dyn InterpreterInfo) {
+        let stack = interp.stack();
+        let peek = |n: usize| stack.len().checked_sub(n + 1).map(|i| stack[i]);
+
+        match interp.current_opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Some(key) = peek(0) {
+                    let address = interp.contract_address();
+                    self.record_slot(address, BigEndianHash::from_uint(&key));
+                }
+            }
+            opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::EXTCODESIZE
+            | opcode::BALANCE
+            | opcode::SELFDESTRUCT => {
+                if let Some(address) = peek(0) {
+                    self.record_address(to_address(&address));
+                }
+            }
+            opcode::DELEGATECALL
+            | opcode::CALL
+            | opcode::STATICCALL
+            | opcode::CALLCODE => {
+                if let Some(address) = peek(1) {
+                    self.record_address(to_address(&address));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl CheckpointTracer for AccessListTracer {}
+impl InternalTransferTracer for AccessListTracer {}
+impl StorageTracer for AccessListTracer {}
+
+fn to_address(word: &cfx_types::U256) -> Address {
+    Address::from(H256::from_uint(word))
+}
@@ -1,9 +1,13 @@
+pub mod access_list;
 pub mod exec_tracer;
 pub mod gasman;
+pub mod participants;
 mod utils;
 
+use access_list::AccessListTracer;
 use exec_tracer::ExecTracer;
 use gasman::GasMan;
+use participants::ParticipantsTracer;
 
 use cfx_executor::{
     executive_observer::{AsTracer, DrainTrace, TracerTrait},
@@ -20,6 +24,8 @@ pub struct Observer {
     pub tracer: Option<ExecTracer>,
     pub gas_man: Option<GasMan>,
     pub geth_tracer: Option<GethTracer>,
+    pub access_list: Option<AccessListTracer>,
+    pub participants: Option<ParticipantsTracer>,
 }
 
 impl Observer {
@@ -28,6 +34,8 @@ impl Observer {
             tracer: Some(ExecTracer::default()),
             gas_man: None,
             geth_tracer: None,
+            access_list: None,
+            participants: None,
         }
     }
 
@@ -36,6 +44,8 @@ impl Observer {
             tracer: None,
             gas_man: None,
             geth_tracer: None,
+            access_list: None,
+            participants: None,
         }
     }
 
@@ -44,6 +54,8 @@ impl Observer {
             tracer: Some(ExecTracer::default()),
             gas_man: Some(GasMan::default()),
             geth_tracer: None,
+            access_list: None,
+            participants: None,
         }
     }
 
@@ -55,6 +67,8 @@ impl Observer {
             tracer: None,
             gas_man: None,
             geth_tracer: Some(GethTracer::new(tx_exec_context, machine, opts)),
+            access_list: None,
+            participants: None,
         }
     }
 }
@@ -0,0 +1,80 @@
+use cfx_executor::{
+    observer::{
+        AddressPocket, CallTracer, CheckpointTracer, DrainTrace,
+        InternalTransferTracer, OpcodeTracer, StorageTracer,
+    },
+    stack::{FrameResult, FrameReturn},
+};
+use cfx_types::{AddressSpaceUtil, AddressWithSpace, Space, U256};
+use cfx_vm_types::ActionParams;
+use std::collections::BTreeSet;
+use typemap::ShareDebugMap;
+
+/// Records the accounts involved in a transaction besides its sender and
+/// recipient, i.e. the contracts it creates and the participants of its
+/// internal transfers, which make up the address transaction index.
+///
+/// Like the traces, the transfers and creations in the reverted frames are
+/// recorded as well.
+#[derive(Default)]
+pub struct ParticipantsTracer {
+    participants: BTreeSet<AddressWithSpace>,
+    create_spaces: Vec<Space>,
+}
+
+impl ParticipantsTracer {
+    fn record_pocket(&mut self, pocket: &AddressPocket) {
+        match pocket {
+            AddressPocket::Balance(address) => {
+                self.participants.insert(*address);
+            }
+            AddressPocket::MintBurn | AddressPocket::GasPayment => {}
+            _ => {
+                let address = pocket.inner_address_or_default();
+                self.participants.insert(address.with_native_space());
+            }
+        }
+    }
+}
+
+impl DrainTrace for ParticipantsTracer {
+    fn drain_trace(self, map: &mut ShareDebugMap) {
+        map.insert::<ParticipantsKey>(self.participants);
+    }
+}
+
+pub struct ParticipantsKey;
+
+impl typemap::Key for ParticipantsKey {
+    type Value = BTreeSet<AddressWithSpace>;
+}
+
+impl InternalTransferTracer for ParticipantsTracer {
+    fn trace_internal_transfer(
+        &mut self, from: AddressPocket, to: AddressPocket, _value: U256,
+    ) {
+        self.record_pocket(&from);
+        self.record_pocket(&to);
+    }
+}
+
+impl CallTracer for ParticipantsTracer {
+    fn record_create(&mut self, params: &ActionParams) {
+        self.create_spaces.push(params.space);
+    }
+
+    fn record_create_result(&mut self, result: &FrameResult) {
+        let space = self.create_spaces.pop().expect("create frame recorded");
+        if let Ok(FrameReturn {
+            create_address: Some(address),
+            ..
+        }) = result
+        {
+            self.participants.insert(address.with_space(space));
+        }
+    }
+}
+
+impl CheckpointTracer for ParticipantsTracer {}
+impl OpcodeTracer for ParticipantsTracer {}
+impl StorageTracer for ParticipantsTracer {}
@@ -0,0 +1,270 @@
+use cfx_executor::{
+    executive::{
+        ChargeCollateral, ExecutionOutcome, ExecutiveContext, TransactOptions,
+        TransactSettings,
+    },
+    machine::Machine,
+    state::{State, StateOverride},
+};
+
+use super::observer::Observer;
+use cfx_parameters::rpc::{
+    MAX_SIMULATE_BLOCKS, MAX_SIMULATE_CALLS_PER_BLOCK, MAX_SIMULATE_GAS,
+};
+use cfx_statedb::Result as DbResult;
+use cfx_types::{Address, H256, U256};
+use cfx_vm_types::{Env, Spec};
+use primitives::SignedTransaction;
+
+/// Overrides of the environment of a simulated block. The unset fields are
+/// derived from the previous block.
+#[derive(Debug, Clone, Default)]
+pub struct BlockOverrides {
+    /// The block number, i.e., `env.number`.
+    pub number: Option<u64>,
+    /// The epoch height, which is also the block number of the eSpace.
+    pub epoch_height: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub gas_limit: Option<U256>,
+    /// The base gas price of the space of the simulated transactions.
+    pub base_gas_price: Option<U256>,
+    pub author: Option<Address>,
+}
+
+/// A block of transactions to be simulated on top of the previous blocks.
+#[derive(Debug, Default)]
+pub struct SimulateBlock {
+    pub block_overrides: BlockOverrides,
+    pub state_override: Option<StateOverride>,
+    pub calls: Vec<SimulateCall>,
+}
+
+#[derive(Debug)]
+pub struct SimulateCall {
+    pub tx: SignedTransaction,
+    /// If not set, the nonce of the transaction is filled by the state.
+    pub has_nonce: bool,
+}
+
+/// The environment and the outcomes of a simulated block.
+#[derive(Debug)]
+pub struct SimulatedBlock {
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub number: u64,
+    pub epoch_height: u64,
+    pub timestamp: u64,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    pub author: Address,
+    pub base_gas_price: U256,
+    pub calls: Vec<SimulatedCall>,
+}
+
+#[derive(Debug)]
+pub struct SimulatedCall {
+    /// The executed transaction, with its nonce filled.
+    pub tx: SignedTransaction,
+    pub outcome: ExecutionOutcome,
+}
+
+/// Checks the number of blocks and the number of calls in each block of a
+/// simulation request, which is known before the calls are converted.
+pub fn check_simulate_counts<I>(calls_per_block: I) -> Result<(), String>
+where I: ExactSizeIterator<Item = usize> {
+    if calls_per_block.len() > MAX_SIMULATE_BLOCKS {
+        return Err(format!(
+            "too many blocks, at most {}",
+            MAX_SIMULATE_BLOCKS
+        ));
+    }
+    for calls in calls_per_block {
+        if calls > MAX_SIMULATE_CALLS_PER_BLOCK {
+            return Err(format!(
+                "too many calls in a block, at most {}",
+                MAX_SIMULATE_CALLS_PER_BLOCK
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the size of a simulation request before executing it, so that one
+/// request can't keep the node busy for long.
+pub fn check_simulate_limits(blocks: &[SimulateBlock]) -> Result<(), String> {
+    check_simulate_counts(blocks.iter().map(|block| block.calls.len()))?;
+
+    let mut total_gas = U256::zero();
+    for call in blocks.iter().flat_map(|block| &block.calls) {
+        total_gas = total_gas.saturating_add(*call.tx.gas());
+    }
+    if total_gas > U256::from(MAX_SIMULATE_GAS) {
+        return Err(format!(
+            "the total gas of the calls exceeds {}",
+            MAX_SIMULATE_GAS
+        ));
+    }
+    Ok(())
+}
+
+/// Executes the transactions of the simulated blocks one by one on the same
+/// state, so that each transaction sees the changes of the previous ones.
+pub struct SimulationContext<'a> {
+    state: &'a mut State,
+    machine: &'a Machine,
+    validation: bool,
+}
+
+impl<'a> SimulationContext<'a> {
+    pub fn new(
+        state: &'a mut State, machine: &'a Machine, validation: bool,
+    ) -> Self {
+        SimulationContext {
+            state,
+            machine,
+            validation,
+        }
+    }
+
+    pub fn apply_override(
+        &mut self, state_override: &StateOverride,
+    ) -> DbResult<()> {
+        self.state.apply_override(state_override)
+    }
+
+    pub fn burnt_gas_price(&self, base_gas_price: U256) -> U256 {
+        self.state.burnt_gas_price(base_gas_price)
+    }
+
+    /// Without validation, the gas fee and the storage collateral are not
+    /// charged and the nonce and base price are not checked, as in a virtual
+    /// call.
+    fn transact_settings(&self) -> TransactSettings {
+        if self.validation {
+            TransactSettings {
+                check_epoch_bound: false,
+                ..TransactSettings::all_checks()
+            }
+        } else {
+            TransactSettings {
+                charge_collateral: ChargeCollateral::EstimateSender,
+                charge_gas: false,
+                check_base_price: false,
+                check_epoch_bound: false,
+            }
+        }
+    }
+
+    pub fn transact(
+        &mut self, call: SimulateCall, env: &Env, spec: &Spec,
+    ) -> DbResult<SimulatedCall> {
+        let SimulateCall { mut tx, has_nonce } = call;
+
+        let sender = tx.sender();
+        if !has_nonce {
+            *tx.nonce_mut() = self.state.nonce(&sender)?;
+        } else if !self.validation {
+            self.state.set_nonce(&sender, tx.nonce())?;
+        }
+
+        let options = TransactOptions {
+            observer: Observer::with_tracing(),
+            settings: self.transact_settings(),
+        };
+        let outcome =
+            ExecutiveContext::new(self.state, env, self.machine, spec)
+                .transact(&tx, options)?;
+
+        if let Some(burnt_fee) =
+            outcome.try_as_executed().and_then(|e| e.burnt_fee)
+        {
+            self.state.burn_by_cip1559(burnt_fee);
+        }
+
+        Ok(SimulatedCall { tx, outcome })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_simulate_counts, check_simulate_limits, SimulateBlock,
+        SimulateCall,
+    };
+    use cfx_parameters::rpc::{
+        MAX_SIMULATE_BLOCKS, MAX_SIMULATE_CALLS_PER_BLOCK, MAX_SIMULATE_GAS,
+    };
+    use cfx_types::{Address, AddressSpaceUtil};
+    use primitives::transaction::native_transaction::NativeTransaction;
+
+    fn call(gas: u64) -> SimulateCall {
+        let tx = NativeTransaction {
+            gas: gas.into(),
+            ..Default::default()
+        }
+        .fake_sign(Address::zero().with_native_space());
+        SimulateCall {
+            tx,
+            has_nonce: false,
+        }
+    }
+
+    fn block(calls: usize, gas: u64) -> SimulateBlock {
+        SimulateBlock {
+            calls: (0..calls).map(|_| call(gas)).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_simulate_block_limit() {
+        let blocks: Vec<_> = (0..MAX_SIMULATE_BLOCKS)
+            .map(|_| SimulateBlock::default())
+            .collect();
+        assert!(check_simulate_limits(&blocks).is_ok());
+
+        let blocks: Vec<_> = (0..MAX_SIMULATE_BLOCKS + 1)
+            .map(|_| SimulateBlock::default())
+            .collect();
+        assert!(check_simulate_limits(&blocks).is_err());
+    }
+
+    #[test]
+    fn test_simulate_call_limit() {
+        assert!(check_simulate_limits(&[block(
+            MAX_SIMULATE_CALLS_PER_BLOCK,
+            21_000
+        )])
+        .is_ok());
+        assert!(check_simulate_limits(&[block(
+            MAX_SIMULATE_CALLS_PER_BLOCK + 1,
+            21_000
+        )])
+        .is_err());
+    }
+
+    #[test]
+    fn test_simulate_counts() {
+        let counts = vec![MAX_SIMULATE_CALLS_PER_BLOCK; MAX_SIMULATE_BLOCKS];
+        assert!(check_simulate_counts(counts.into_iter()).is_ok());
+        let counts = vec![0; MAX_SIMULATE_BLOCKS + 1];
+        assert!(check_simulate_counts(counts.into_iter()).is_err());
+        let counts = vec![0, MAX_SIMULATE_CALLS_PER_BLOCK + 1];
+        assert!(check_simulate_counts(counts.into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_simulate_gas_limit() {
+        // the gas is counted across the blocks
+        let half = MAX_SIMULATE_GAS / 2;
+        assert!(
+            check_simulate_limits(&[block(1, half), block(1, half)]).is_ok()
+        );
+        assert!(check_simulate_limits(&[
+            block(1, half),
+            block(1, half),
+            block(1, 1)
+        ])
+        .is_err());
+    }
+}
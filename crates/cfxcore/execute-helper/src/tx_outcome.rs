@@ -1,16 +1,20 @@
 use cfx_executor::{
     executive::ExecutionOutcome, internal_contract::make_staking_events,
 };
-use cfx_types::{H256, U256};
+use cfx_types::{AddressWithSpace, H256, U256};
 use cfx_vm_types::Spec;
 use pow_types::StakingEvent;
 use primitives::Receipt;
+use std::collections::BTreeSet;
 
 use alloy_rpc_types_trace::geth::GethTrace;
 use geth_tracer::GethTraceKey;
 
 use super::{
-    observer::exec_tracer::{ExecTrace, ExecTraceKey},
+    observer::{
+        exec_tracer::{ExecTrace, ExecTraceKey},
+        participants::ParticipantsKey,
+    },
     phantom_tx::{recover_phantom, PhantomTransaction},
 };
 
@@ -22,6 +26,7 @@ pub struct ProcessTxOutcome {
     pub tx_exec_error_msg: String,
     pub consider_repacked: bool,
     pub geth_trace: Option<GethTrace>,
+    pub tx_participants: BTreeSet<AddressWithSpace>,
 }
 
 fn tx_traces(outcome: &ExecutionOutcome) -> Vec<ExecTrace> {
@@ -37,12 +42,22 @@ fn geth_traces(outcome: &ExecutionOutcome) -> Option<GethTrace> {
         .and_then(|executed| executed.ext_result.get::<GethTraceKey>().cloned())
 }
 
+fn tx_participants(outcome: &ExecutionOutcome) -> BTreeSet<AddressWithSpace> {
+    outcome
+        .try_as_executed()
+        .and_then(|executed| {
+            executed.ext_result.get::<ParticipantsKey>().cloned()
+        })
+        .unwrap_or_default()
+}
+
 pub fn make_process_tx_outcome(
     outcome: ExecutionOutcome, accumulated_gas_used: &mut U256, tx_hash: H256,
     spec: &Spec,
 ) -> ProcessTxOutcome {
     let tx_traces = tx_traces(&outcome);
     let geth_trace = geth_traces(&outcome);
+    let tx_participants = tx_participants(&outcome);
     let tx_exec_error_msg = outcome.error_message();
     let consider_repacked = outcome.consider_repacked();
     let receipt = outcome.make_receipt(accumulated_gas_used, spec);
@@ -59,5 +74,6 @@ pub fn make_process_tx_outcome(
         tx_exec_error_msg,
         consider_repacked,
         geth_trace,
+        tx_participants,
     }
 }
@@ -0,0 +1,120 @@
+// Based on source code from the revm project (https://github.com/bluealloy/revm) under the MIT License.
+
+use blst::{
+    blst_map_to_g1, blst_p1, blst_p1_add_or_double_affine, blst_p1_affine,
+    blst_p1_affine_in_g1, blst_p1_affine_on_curve, blst_p1_from_affine,
+    blst_p1_to_affine, p1_affines,
+};
+
+use super::{
+    utils::{
+        extract_scalar_input, fp_from_bendian, fp_to_bytes, remove_padding,
+    },
+    G1_ADD_INPUT_LENGTH, G1_INPUT_ITEM_LENGTH, G1_MSM_INPUT_LENGTH,
+    G1_OUTPUT_LENGTH, PADDED_FP_LENGTH, SCALAR_BITS,
+};
+use crate::builtin::Error;
+
+/// Encodes a G1 point as `x || y`, each coordinate padded to 64 bytes.
+pub(super) fn encode_g1_point(input: &blst_p1_affine) -> Vec<u8> {
+    let mut out = vec![0u8; G1_OUTPUT_LENGTH];
+    fp_to_bytes(&mut out[..PADDED_FP_LENGTH], &input.x);
+    fp_to_bytes(&mut out[PADDED_FP_LENGTH..], &input.y);
+    out
+}
+
+/// Decodes a G1 point, which must be on the curve. The subgroup check is only
+/// performed if `subgroup_check` is set. The point at infinity is encoded as
+/// 128 zero bytes.
+pub(super) fn extract_g1_input(
+    input: &[u8], subgroup_check: bool,
+) -> Result<blst_p1_affine, Error> {
+    if input.len() != G1_INPUT_ITEM_LENGTH {
+        return Err(Error("BLS12-381 invalid G1 point length"));
+    }
+    let x = fp_from_bendian(remove_padding(&input[..PADDED_FP_LENGTH])?)?;
+    let y = fp_from_bendian(remove_padding(&input[PADDED_FP_LENGTH..])?)?;
+    let out = blst_p1_affine { x, y };
+
+    // SAFETY: `out` is a blst value.
+    if unsafe { !blst_p1_affine_on_curve(&out) } {
+        return Err(Error("BLS12-381 G1 point not on curve"));
+    }
+    if subgroup_check && unsafe { !blst_p1_affine_in_g1(&out) } {
+        return Err(Error("BLS12-381 G1 point not in subgroup"));
+    }
+    Ok(out)
+}
+
+fn to_affine(p: &blst_p1) -> blst_p1_affine {
+    let mut p_aff = blst_p1_affine::default();
+    // SAFETY: `p_aff` and `p` are blst values.
+    unsafe { blst_p1_to_affine(&mut p_aff, p) };
+    p_aff
+}
+
+/// BLS12_G1ADD: adds two G1 points, without the subgroup check.
+pub fn g1_add(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.len() != G1_ADD_INPUT_LENGTH {
+        return Err(Error("BLS12-381 G1ADD invalid input length"));
+    }
+    let a_aff = extract_g1_input(&input[..G1_INPUT_ITEM_LENGTH], false)?;
+    let b_aff = extract_g1_input(&input[G1_INPUT_ITEM_LENGTH..], false)?;
+
+    let mut b = blst_p1::default();
+    let mut p = blst_p1::default();
+    // SAFETY: `b`, `b_aff`, `p` and `a_aff` are blst values.
+    unsafe {
+        blst_p1_from_affine(&mut b, &b_aff);
+        blst_p1_add_or_double_affine(&mut p, &b, &a_aff);
+    }
+    Ok(encode_g1_point(&to_affine(&p)))
+}
+
+/// BLS12_G1MSM: the multi-scalar multiplication of `k` pairs of a G1 point
+/// and a scalar.
+pub fn g1_msm(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.is_empty() || input.len() % G1_MSM_INPUT_LENGTH != 0 {
+        return Err(Error("BLS12-381 G1MSM invalid input length"));
+    }
+
+    let mut points = Vec::new();
+    let mut scalars = Vec::new();
+    for item in input.chunks_exact(G1_MSM_INPUT_LENGTH) {
+        let (point, scalar) = item.split_at(G1_INPUT_ITEM_LENGTH);
+        let p_aff = extract_g1_input(point, true)?;
+        let scalar = extract_scalar_input(scalar)?;
+
+        // The batch API of blst doesn't accept the point at infinity, which
+        // contributes nothing to the sum anyway.
+        if point.iter().all(|x| *x == 0) {
+            continue;
+        }
+        let mut p = blst_p1::default();
+        // SAFETY: `p` and `p_aff` are blst values.
+        unsafe { blst_p1_from_affine(&mut p, &p_aff) };
+        points.push(p);
+        scalars.extend_from_slice(&scalar.b);
+    }
+
+    if points.is_empty() {
+        return Ok(vec![0u8; G1_OUTPUT_LENGTH]);
+    }
+    let multiexp =
+        p1_affines::from(points.as_slice()).mult(&scalars, SCALAR_BITS);
+    Ok(encode_g1_point(&to_affine(&multiexp)))
+}
+
+/// BLS12_MAP_FP_TO_G1: maps a base field element to a G1 point.
+pub fn map_fp_to_g1(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.len() != PADDED_FP_LENGTH {
+        return Err(Error("BLS12-381 MAP_FP_TO_G1 invalid input length"));
+    }
+    let fp = fp_from_bendian(remove_padding(input)?)?;
+
+    let mut p = blst_p1::default();
+    // SAFETY: `p` and `fp` are blst values. The third argument is unused if
+    // null.
+    unsafe { blst_map_to_g1(&mut p, &fp, std::ptr::null()) };
+    Ok(encode_g1_point(&to_affine(&p)))
+}
@@ -0,0 +1,137 @@
+// Based on source code from the revm project (https://github.com/bluealloy/revm) under the MIT License.
+
+use blst::{
+    blst_fp2, blst_map_to_g2, blst_p2, blst_p2_add_or_double_affine,
+    blst_p2_affine, blst_p2_affine_in_g2, blst_p2_affine_on_curve,
+    blst_p2_from_affine, blst_p2_to_affine, p2_affines,
+};
+
+use super::{
+    utils::{
+        extract_scalar_input, fp_from_bendian, fp_to_bytes, remove_padding,
+    },
+    G2_ADD_INPUT_LENGTH, G2_INPUT_ITEM_LENGTH, G2_MSM_INPUT_LENGTH,
+    G2_OUTPUT_LENGTH, PADDED_FP2_LENGTH, PADDED_FP_LENGTH, SCALAR_BITS,
+};
+use crate::builtin::Error;
+
+/// Encodes a G2 point as `x.c0 || x.c1 || y.c0 || y.c1`, each element padded
+/// to 64 bytes.
+pub(super) fn encode_g2_point(input: &blst_p2_affine) -> Vec<u8> {
+    let mut out = vec![0u8; G2_OUTPUT_LENGTH];
+    let coordinates = [
+        &input.x.fp[0],
+        &input.x.fp[1],
+        &input.y.fp[0],
+        &input.y.fp[1],
+    ];
+    for (chunk, fp) in out.chunks_exact_mut(PADDED_FP_LENGTH).zip(coordinates) {
+        fp_to_bytes(chunk, fp);
+    }
+    out
+}
+
+/// Decodes an element of the quadratic extension field as `c0 || c1`.
+fn extract_fp2(input: &[u8]) -> Result<blst_fp2, Error> {
+    debug_assert_eq!(input.len(), PADDED_FP2_LENGTH);
+    let c0 = fp_from_bendian(remove_padding(&input[..PADDED_FP_LENGTH])?)?;
+    let c1 = fp_from_bendian(remove_padding(&input[PADDED_FP_LENGTH..])?)?;
+    Ok(blst_fp2 { fp: [c0, c1] })
+}
+
+/// Decodes a G2 point, which must be on the curve. The subgroup check is only
+/// performed if `subgroup_check` is set. The point at infinity is encoded as
+/// 256 zero bytes.
+pub(super) fn extract_g2_input(
+    input: &[u8], subgroup_check: bool,
+) -> Result<blst_p2_affine, Error> {
+    if input.len() != G2_INPUT_ITEM_LENGTH {
+        return Err(Error("BLS12-381 invalid G2 point length"));
+    }
+    let x = extract_fp2(&input[..PADDED_FP2_LENGTH])?;
+    let y = extract_fp2(&input[PADDED_FP2_LENGTH..])?;
+    let out = blst_p2_affine { x, y };
+
+    // SAFETY: `out` is a blst value.
+    if unsafe { !blst_p2_affine_on_curve(&out) } {
+        return Err(Error("BLS12-381 G2 point not on curve"));
+    }
+    if subgroup_check && unsafe { !blst_p2_affine_in_g2(&out) } {
+        return Err(Error("BLS12-381 G2 point not in subgroup"));
+    }
+    Ok(out)
+}
+
+fn to_affine(p: &blst_p2) -> blst_p2_affine {
+    let mut p_aff = blst_p2_affine::default();
+    // SAFETY: `p_aff` and `p` are blst values.
+    unsafe { blst_p2_to_affine(&mut p_aff, p) };
+    p_aff
+}
+
+/// BLS12_G2ADD: adds two G2 points, without the subgroup check.
+pub fn g2_add(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.len() != G2_ADD_INPUT_LENGTH {
+        return Err(Error("BLS12-381 G2ADD invalid input length"));
+    }
+    let a_aff = extract_g2_input(&input[..G2_INPUT_ITEM_LENGTH], false)?;
+    let b_aff = extract_g2_input(&input[G2_INPUT_ITEM_LENGTH..], false)?;
+
+    let mut b = blst_p2::default();
+    let mut p = blst_p2::default();
+    // SAFETY: `b`, `b_aff`, `p` and `a_aff` are blst values.
+    unsafe {
+        blst_p2_from_affine(&mut b, &b_aff);
+        blst_p2_add_or_double_affine(&mut p, &b, &a_aff);
+    }
+    Ok(encode_g2_point(&to_affine(&p)))
+}
+
+/// BLS12_G2MSM: the multi-scalar multiplication of `k` pairs of a G2 point
+/// and a scalar.
+pub fn g2_msm(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.is_empty() || input.len() % G2_MSM_INPUT_LENGTH != 0 {
+        return Err(Error("BLS12-381 G2MSM invalid input length"));
+    }
+
+    let mut points = Vec::new();
+    let mut scalars = Vec::new();
+    for item in input.chunks_exact(G2_MSM_INPUT_LENGTH) {
+        let (point, scalar) = item.split_at(G2_INPUT_ITEM_LENGTH);
+        let p_aff = extract_g2_input(point, true)?;
+        let scalar = extract_scalar_input(scalar)?;
+
+        // The batch API of blst doesn't accept the point at infinity, which
+        // contributes nothing to the sum anyway.
+        if point.iter().all(|x| *x == 0) {
+            continue;
+        }
+        let mut p = blst_p2::default();
+        // SAFETY: `p` and `p_aff` are blst values.
+        unsafe { blst_p2_from_affine(&mut p, &p_aff) };
+        points.push(p);
+        scalars.extend_from_slice(&scalar.b);
+    }
+
+    if points.is_empty() {
+        return Ok(vec![0u8; G2_OUTPUT_LENGTH]);
+    }
+    let multiexp =
+        p2_affines::from(points.as_slice()).mult(&scalars, SCALAR_BITS);
+    Ok(encode_g2_point(&to_affine(&multiexp)))
+}
+
+/// BLS12_MAP_FP2_TO_G2: maps an element of the quadratic extension field to a
+/// G2 point.
+pub fn map_fp2_to_g2(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.len() != PADDED_FP2_LENGTH {
+        return Err(Error("BLS12-381 MAP_FP2_TO_G2 invalid input length"));
+    }
+    let fp2 = extract_fp2(input)?;
+
+    let mut p = blst_p2::default();
+    // SAFETY: `p` and `fp2` are blst values. The third argument is unused if
+    // null.
+    unsafe { blst_map_to_g2(&mut p, &fp2, std::ptr::null()) };
+    Ok(encode_g2_point(&to_affine(&p)))
+}
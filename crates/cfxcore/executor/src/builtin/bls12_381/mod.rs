@@ -0,0 +1,305 @@
+// Based on source code from the revm project (https://github.com/bluealloy/revm) under the MIT License.
+
+//! The BLS12-381 curve operations of EIP-2537.
+
+mod g1;
+mod g2;
+mod utils;
+
+pub use g1::{g1_add, g1_msm, map_fp_to_g1};
+pub use g2::{g2_add, g2_msm, map_fp2_to_g2};
+
+use blst::{
+    blst_final_exp, blst_fp12, blst_fp12_is_one, blst_fp12_mul,
+    blst_miller_loop, blst_p1_affine_is_inf, blst_p2_affine_is_inf,
+};
+
+use super::Error;
+use g1::extract_g1_input;
+use g2::extract_g2_input;
+
+/// The length of a base field element.
+const FP_LENGTH: usize = 48;
+/// The length of a base field element with the leading zero padding.
+const PADDED_FP_LENGTH: usize = 64;
+/// The length of an element of the quadratic extension field with the
+/// leading zero padding.
+const PADDED_FP2_LENGTH: usize = 128;
+/// The number of leading zero bytes of an encoded base field element.
+const PADDING_LENGTH: usize = 16;
+/// The length of a scalar.
+const SCALAR_LENGTH: usize = 32;
+/// The number of bits of a scalar.
+const SCALAR_BITS: usize = 256;
+
+const G1_INPUT_ITEM_LENGTH: usize = 128;
+const G1_OUTPUT_LENGTH: usize = 128;
+const G1_ADD_INPUT_LENGTH: usize = 2 * G1_INPUT_ITEM_LENGTH;
+pub const G1_MSM_INPUT_LENGTH: usize = G1_INPUT_ITEM_LENGTH + SCALAR_LENGTH;
+
+const G2_INPUT_ITEM_LENGTH: usize = 256;
+const G2_OUTPUT_LENGTH: usize = 256;
+const G2_ADD_INPUT_LENGTH: usize = 2 * G2_INPUT_ITEM_LENGTH;
+pub const G2_MSM_INPUT_LENGTH: usize = G2_INPUT_ITEM_LENGTH + SCALAR_LENGTH;
+
+pub const PAIRING_INPUT_LENGTH: usize =
+    G1_INPUT_ITEM_LENGTH + G2_INPUT_ITEM_LENGTH;
+
+/// The denominator of the MSM discounts.
+pub const MSM_MULTIPLIER: u64 = 1000;
+
+/// The discounts of BLS12_G1MSM for `k` pairs, indexed by `k - 1`.
+pub const G1_MSM_DISCOUNT_TABLE: [u16; 128] = [
+    1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682,
+    677, 673, 669, 665, 661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632,
+    630, 627, 625, 623, 621, 619, 617, 615, 613, 611, 609, 608, 606, 604, 603,
+    601, 599, 598, 596, 595, 593, 592, 591, 589, 588, 586, 585, 584, 582, 581,
+    580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564,
+    563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549,
+    548, 547, 547, 546, 545, 544, 543, 542, 541, 540, 540, 539, 538, 537, 536,
+    536, 535, 534, 533, 532, 532, 531, 530, 529, 528, 528, 527, 526, 525, 525,
+    524, 523, 522, 522, 521, 520, 520, 519,
+];
+
+/// The discounts of BLS12_G2MSM for `k` pairs, indexed by `k - 1`.
+pub const G2_MSM_DISCOUNT_TABLE: [u16; 128] = [
+    1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732,
+    724, 717, 711, 704, 699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655,
+    652, 649, 646, 643, 640, 637, 634, 632, 629, 627, 624, 622, 620, 618, 615,
+    613, 611, 609, 607, 606, 604, 602, 600, 598, 597, 595, 593, 592, 590, 589,
+    587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571, 570, 569,
+    568, 567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553,
+    552, 552, 551, 550, 549, 548, 547, 546, 545, 545, 544, 543, 542, 541, 541,
+    540, 539, 538, 537, 537, 536, 535, 535, 534, 533, 532, 532, 531, 530, 530,
+    529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+/// BLS12_PAIRING_CHECK: checks whether the product of the pairings of `k`
+/// pairs of a G1 point and a G2 point is the identity. Returns 32 bytes
+/// encoding 1 if it is, and 0 otherwise.
+pub fn pairing(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.is_empty() || input.len() % PAIRING_INPUT_LENGTH != 0 {
+        return Err(Error("BLS12-381 PAIRING invalid input length"));
+    }
+
+    // The accumulated product of the miller loops, or `None` if all the
+    // pairings so far involve the point at infinity.
+    let mut acc: Option<blst_fp12> = None;
+    for item in input.chunks_exact(PAIRING_INPUT_LENGTH) {
+        let (p1, p2) = item.split_at(G1_INPUT_ITEM_LENGTH);
+        let p1_aff = extract_g1_input(p1, true)?;
+        let p2_aff = extract_g2_input(p2, true)?;
+
+        // The pairing with the point at infinity is the identity.
+        // SAFETY: `p1_aff` and `p2_aff` are blst values.
+        if unsafe {
+            blst_p1_affine_is_inf(&p1_aff) || blst_p2_affine_is_inf(&p2_aff)
+        } {
+            continue;
+        }
+
+        let mut cur_ml = blst_fp12::default();
+        // SAFETY: `cur_ml`, `p2_aff` and `p1_aff` are blst values.
+        unsafe { blst_miller_loop(&mut cur_ml, &p2_aff, &p1_aff) };
+        acc = Some(match acc {
+            None => cur_ml,
+            Some(prev) => {
+                let mut res = blst_fp12::default();
+                // SAFETY: `res`, `prev` and `cur_ml` are blst values.
+                unsafe { blst_fp12_mul(&mut res, &prev, &cur_ml) };
+                res
+            }
+        });
+    }
+
+    let is_one = match acc {
+        None => true,
+        Some(acc) => {
+            let mut ret = blst_fp12::default();
+            // SAFETY: `ret` and `acc` are blst values.
+            unsafe {
+                blst_final_exp(&mut ret, &acc);
+                blst_fp12_is_one(&ret)
+            }
+        }
+    };
+
+    let mut output = vec![0u8; 32];
+    output[31] = is_one as u8;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hex::FromHex;
+
+    const G1: &str = "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1";
+    const G1_NEG: &str = "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb00000000000000000000000000000000114d1d6855d545a8aa7d76c8cf2e21f267816aef1db507c96655b9d5caac42364e6f38ba0ecb751bad54dcd6b939c2ca";
+    const G1_2: &str = "000000000000000000000000000000000572cbea904d67468808c8eb50a9450c9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e00000000000000000000000000000000166a9d8cabc673a322fda673779d8e3822ba3ecb8670e461f73bb9021d5fd76a4c56d9d4cd16bd1bba86881979749d28";
+    const G1_3: &str = "0000000000000000000000000000000009ece308f9d1f0131765212deca99697b112d61f9be9a5f1f3780a51335b3ff981747a0b2ca2179b96d2c0c9024e522400000000000000000000000000000000032b80d3a6f5b09f8a84623389c5f80ca69a0cddabc3097f9d9c27310fd43be6e745256c634af45ca3473b0590ae30d1";
+    const G1_8: &str = "00000000000000000000000000000000085ae765588126f5e860d019c0e26235f567a9c0c0b2d8ff30f3e8d436b1082596e5e7462d20f5be3764fd473e57f9cf0000000000000000000000000000000019e7dfab8a794b6abb9f84e57739de172a63415273f460d1607fa6a74f0acd97d9671b801dd1fd4f18232dd1259359a1";
+    const G2: &str = "00000000000000000000000000000000024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80000000000000000000000000000000013e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e000000000000000000000000000000000ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801000000000000000000000000000000000606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be";
+    const G2_2: &str = "000000000000000000000000000000001638533957d540a9d2370f17cc7ed5863bc0b995b8825e0ee1ea1e1e4d00dbae81f14b0bf3611b78c952aacab827a053000000000000000000000000000000000a4edef9c1ed7f729f520e47730a124fd70662a904ba1074728114d1031e1572c6c886f6b57ec72a6178288c47c33577000000000000000000000000000000000468fb440d82b0630aeb8dca2b5256789a66da69bf91009cbfe6bd221e47aa8ae88dece9764bf3bd999d95d71e4c9899000000000000000000000000000000000f6d4552fa65dd2638b361543f887136a43253d9c66c411697003f7a13c308f5422e1aa0a59c8967acdefd8b6e36ccf3";
+    const G2_2_NEG: &str = "000000000000000000000000000000001638533957d540a9d2370f17cc7ed5863bc0b995b8825e0ee1ea1e1e4d00dbae81f14b0bf3611b78c952aacab827a053000000000000000000000000000000000a4edef9c1ed7f729f520e47730a124fd70662a904ba1074728114d1031e1572c6c886f6b57ec72a6178288c47c3357700000000000000000000000000000000159816a62bfd3637403019ec17f9565eca10711b33f41222a74a157ed8694b99361e13153b080c4220616a28e1b31212000000000000000000000000000000000a93cc973f1a09741268466203c33ba0c044f7ab2d18d1a8d0309326e2eded2edc7de55e0bb776980d20027491c8ddb8";
+    const G2_5: &str = "000000000000000000000000000000000411a5de6730ffece671a9f21d65028cc0f1102378de124562cb1ff49db6f004fcd14d683024b0548eff3d1468df26880000000000000000000000000000000000fb837804dba8213329db46608b6c121d973363c1234a86dd183baff112709cf97096c5e9a1a770ee9d7dc641a894d60000000000000000000000000000000019b5e8f5d4a72f2b75811ac084a7f814317360bac52f6aab15eed416b4ef9938e0bdc4865cc2c4d0fd947e7c6925fd1400000000000000000000000000000000093567b4228be17ee62d11a254edd041ee4b953bffb8b8c7f925bd6662b4298bac2822b446f5b5de3b893e1be5aa4986";
+    /// The order of the subgroup.
+    const R: &str =
+        "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+
+    fn hex(parts: &[&str]) -> Vec<u8> { parts.concat().from_hex().unwrap() }
+
+    fn scalar(n: u8) -> String { format!("{:064x}", n) }
+
+    #[test]
+    fn g1_add_vectors() {
+        assert_eq!(g1_add(&hex(&[G1, G1_2])).unwrap(), hex(&[G1_3]));
+        assert_eq!(g1_add(&hex(&[G1, G1])).unwrap(), hex(&[G1_2]));
+        assert_eq!(g1_add(&hex(&[G1, G1_NEG])).unwrap(), vec![0u8; 128]);
+        let inf = "00".repeat(128);
+        assert_eq!(g1_add(&hex(&[G1, &inf])).unwrap(), hex(&[G1]));
+        assert!(g1_add(&hex(&[G1])).is_err());
+    }
+
+    #[test]
+    fn g1_msm_vectors() {
+        // 2 * G + 3 * (2 * G) = 8 * G
+        let input = hex(&[G1, &scalar(2), G1_2, &scalar(3)]);
+        assert_eq!(g1_msm(&input).unwrap(), hex(&[G1_8]));
+        assert_eq!(g1_msm(&hex(&[G1_2, &scalar(1)])).unwrap(), hex(&[G1_2]));
+        assert_eq!(g1_msm(&hex(&[G1, R])).unwrap(), vec![0u8; 128]);
+        let inf = "00".repeat(128);
+        assert_eq!(g1_msm(&hex(&[&inf, &scalar(5)])).unwrap(), vec![0u8; 128]);
+        assert!(g1_msm(&[]).is_err());
+    }
+
+    #[test]
+    fn g2_add_and_msm_vectors() {
+        assert_eq!(g2_add(&hex(&[G2, G2])).unwrap(), hex(&[G2_2]));
+        assert_eq!(g2_add(&hex(&[G2_2, G2_2_NEG])).unwrap(), vec![0u8; 256]);
+        assert_eq!(g2_msm(&hex(&[G2, &scalar(5)])).unwrap(), hex(&[G2_5]));
+        assert_eq!(
+            g2_msm(&hex(&[G2, &scalar(3), G2_2, &scalar(1)])).unwrap(),
+            hex(&[G2_5])
+        );
+        assert!(g2_msm(&hex(&[G2])).is_err());
+    }
+
+    #[test]
+    fn pairing_vectors() {
+        let zero = vec![0u8; 32];
+        let mut one = zero.clone();
+        one[31] = 1;
+        // e(G1, G2) * e(-G1, G2) = 1
+        assert_eq!(pairing(&hex(&[G1, G2, G1_NEG, G2])).unwrap(), one);
+        // e(2 * G1, G2) * e(G1, -2 * G2) = 1
+        assert_eq!(pairing(&hex(&[G1_2, G2, G1, G2_2_NEG])).unwrap(), one);
+        assert_eq!(pairing(&hex(&[G1, G2])).unwrap(), zero);
+        let inf = "00".repeat(128);
+        assert_eq!(pairing(&hex(&[&inf, G2])).unwrap(), one);
+        assert!(pairing(&[]).is_err());
+    }
+
+    #[test]
+    fn map_to_curve() {
+        let out = map_fp_to_g1(&hex(&[&"00".repeat(63), "01"])).unwrap();
+        extract_g1_input(&out, true).unwrap();
+        let out = map_fp2_to_g2(&hex(&[&"00".repeat(127), "01"])).unwrap();
+        extract_g2_input(&out, true).unwrap();
+        assert!(map_fp_to_g1(&hex(&[&"00".repeat(64)])).is_ok());
+        assert!(map_fp_to_g1(&hex(&[&"00".repeat(65)])).is_err());
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        // The base field modulus is not a canonical field element.
+        let modulus = "000000000000000000000000000000001a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+        assert!(map_fp_to_g1(&hex(&[modulus])).is_err());
+        // Non-zero padding.
+        let padded = format!("01{}", &G1[2..]);
+        assert!(g1_add(&hex(&[&padded, G1])).is_err());
+        // A point not on the curve.
+        let off_curve = format!("{}{}", &G1[..255], "0");
+        assert!(g1_add(&hex(&[&off_curve, G1])).is_err());
+        assert!(pairing(&hex(&[&off_curve, G2])).is_err());
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct TestVector {
+        input: String,
+        expected: Option<String>,
+        name: String,
+        expected_error: Option<String>,
+    }
+
+    /// Runs the test vectors of EIP-2537, vendored from revm. The gas in the
+    /// vectors predates the final gas schedule, so only the outputs are
+    /// checked. The scalar multiplications are run as MSMs of one pair.
+    fn run_test_vectors(
+        operation: fn(&[u8]) -> Result<Vec<u8>, Error>, file_name: &str,
+        vectors: &str,
+    ) {
+        let vectors: Vec<TestVector> = serde_json::from_str(vectors).unwrap();
+        assert!(!vectors.is_empty());
+        for vector in vectors {
+            let name = format!("{}/{}", file_name, vector.name);
+            let input = vector.input.from_hex::<Vec<u8>>().unwrap();
+            let result = operation(&input);
+            if let Some(error) = vector.expected_error {
+                assert!(
+                    result.is_err(),
+                    "expected error {} in {}, got {:?}",
+                    error,
+                    name,
+                    result
+                );
+            } else {
+                let expected = vector.expected.unwrap().from_hex::<Vec<u8>>();
+                assert_eq!(
+                    result
+                        .unwrap_or_else(|e| panic!("{} failed: {:?}", name, e)),
+                    expected.unwrap(),
+                    "{}",
+                    name
+                );
+            }
+        }
+    }
+
+    macro_rules! test_vectors {
+        ($($test:ident: $operation:expr, $file:literal;)*) => {$(
+            #[test]
+            fn $test() {
+                run_test_vectors(
+                    $operation,
+                    $file,
+                    include_str!(concat!("test-vectors/", $file)),
+                );
+            }
+        )*};
+    }
+
+    test_vectors! {
+        g1_add_test_vectors: g1_add, "add_G1_bls.json";
+        g1_add_fail_test_vectors: g1_add, "fail-add_G1_bls.json";
+        g1_mul_test_vectors: g1_msm, "mul_G1_bls.json";
+        g1_mul_fail_test_vectors: g1_msm, "fail-mul_G1_bls.json";
+        g1_msm_test_vectors: g1_msm, "multiexp_G1_bls.json";
+        g1_msm_fail_test_vectors: g1_msm, "fail-multiexp_G1_bls.json";
+        g2_add_test_vectors: g2_add, "add_G2_bls.json";
+        g2_add_fail_test_vectors: g2_add, "fail-add_G2_bls.json";
+        g2_mul_test_vectors: g2_msm, "mul_G2_bls.json";
+        g2_mul_fail_test_vectors: g2_msm, "fail-mul_G2_bls.json";
+        g2_msm_test_vectors: g2_msm, "multiexp_G2_bls.json";
+        g2_msm_fail_test_vectors: g2_msm, "fail-multiexp_G2_bls.json";
+        pairing_test_vectors: pairing, "pairing_check_bls.json";
+        pairing_fail_test_vectors: pairing, "fail-pairing_check_bls.json";
+        map_fp_to_g1_test_vectors: map_fp_to_g1, "map_fp_to_G1_bls.json";
+        map_fp_to_g1_fail_test_vectors:
+            map_fp_to_g1, "fail-map_fp_to_G1_bls.json";
+        map_fp2_to_g2_test_vectors: map_fp2_to_g2, "map_fp2_to_G2_bls.json";
+        map_fp2_to_g2_fail_test_vectors:
+            map_fp2_to_g2, "fail-map_fp2_to_G2_bls.json";
+    }
+}
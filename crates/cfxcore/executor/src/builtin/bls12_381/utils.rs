@@ -0,0 +1,73 @@
+// Based on source code from the revm project (https://github.com/bluealloy/revm) under the MIT License.
+
+use blst::{
+    blst_bendian_from_fp, blst_fp, blst_fp_from_bendian, blst_scalar,
+    blst_scalar_from_bendian,
+};
+use std::{cmp::Ordering, convert::TryInto};
+
+use super::{FP_LENGTH, PADDED_FP_LENGTH, PADDING_LENGTH, SCALAR_LENGTH};
+use crate::builtin::Error;
+
+/// The big endian representation of the base field modulus of BLS12-381.
+const MODULUS_REPR: [u8; FP_LENGTH] = hex_literal::hex!(
+    "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab"
+);
+
+/// Encodes a field element into 64 bytes, with 16 leading zero bytes.
+pub(super) fn fp_to_bytes(out: &mut [u8], input: &blst_fp) {
+    debug_assert_eq!(out.len(), PADDED_FP_LENGTH);
+    let (padding, rest) = out.split_at_mut(PADDING_LENGTH);
+    padding.fill(0);
+    // SAFETY: `rest` has exactly 48 bytes.
+    unsafe { blst_bendian_from_fp(rest.as_mut_ptr(), input) };
+}
+
+/// Strips the 16 leading zero bytes of an encoded field element.
+pub(super) fn remove_padding(input: &[u8]) -> Result<&[u8; FP_LENGTH], Error> {
+    if input.len() != PADDED_FP_LENGTH {
+        return Err(Error("BLS12-381 invalid field element length"));
+    }
+    let (padding, unpadded) = input.split_at(PADDING_LENGTH);
+    if !padding.iter().all(|&x| x == 0) {
+        return Err(Error("BLS12-381 field element with non-zero padding"));
+    }
+    Ok(unpadded.try_into().expect("length checked; qed"))
+}
+
+/// Decodes a big endian field element, which must be less than the modulus.
+pub(super) fn fp_from_bendian(
+    input: &[u8; FP_LENGTH],
+) -> Result<blst_fp, Error> {
+    if !is_valid_be(input) {
+        return Err(Error("BLS12-381 non-canonical field element"));
+    }
+    let mut fp = blst_fp::default();
+    // SAFETY: `input` has exactly 48 bytes.
+    unsafe { blst_fp_from_bendian(&mut fp, input.as_ptr()) };
+    Ok(fp)
+}
+
+/// Decodes a 32-byte big endian scalar. The scalar is not required to be less
+/// than the order of the subgroup.
+pub(super) fn extract_scalar_input(input: &[u8]) -> Result<blst_scalar, Error> {
+    if input.len() != SCALAR_LENGTH {
+        return Err(Error("BLS12-381 invalid scalar length"));
+    }
+    let mut out = blst_scalar::default();
+    // SAFETY: `input` has exactly 32 bytes.
+    unsafe { blst_scalar_from_bendian(&mut out, input.as_ptr()) };
+    Ok(out)
+}
+
+fn is_valid_be(input: &[u8; FP_LENGTH]) -> bool {
+    for (i, modulo) in input.iter().zip(MODULUS_REPR.iter()) {
+        match i.cmp(modulo) {
+            Ordering::Greater => return false,
+            Ordering::Less => return true,
+            Ordering::Equal => continue,
+        }
+    }
+    // The input is equal to the modulus.
+    false
+}
@@ -21,9 +21,11 @@
 //! Standard built-in contracts.
 
 mod blake2f;
+mod bls12_381;
 mod ethereum_trusted_setup_points;
 mod executable;
 mod kzg_point_evaluations;
+mod p256_verify;
 
 pub use executable::BuiltinExec;
 
@@ -225,6 +227,67 @@ impl Pricer for Blake2FPricer {
     }
 }
 
+/// A pricing model for the BLS12-381 multi-scalar multiplications (EIP-2537).
+/// The cost per pair is discounted as the number of pairs grows.
+pub(crate) struct Bls12MsmPricer {
+    base: u64,
+    item_len: usize,
+    discount_table: &'static [u16],
+}
+
+impl Bls12MsmPricer {
+    pub(crate) fn g1() -> Self {
+        Bls12MsmPricer {
+            base: 12000,
+            item_len: bls12_381::G1_MSM_INPUT_LENGTH,
+            discount_table: &bls12_381::G1_MSM_DISCOUNT_TABLE,
+        }
+    }
+
+    pub(crate) fn g2() -> Self {
+        Bls12MsmPricer {
+            base: 22500,
+            item_len: bls12_381::G2_MSM_INPUT_LENGTH,
+            discount_table: &bls12_381::G2_MSM_DISCOUNT_TABLE,
+        }
+    }
+}
+
+impl Pricer for Bls12MsmPricer {
+    fn cost(&self, input: &[u8]) -> U256 {
+        let k = input.len() / self.item_len;
+        // An empty input is rejected by `execute()`.
+        if k == 0 {
+            return U256::zero();
+        }
+        let discount =
+            self.discount_table[min(k, self.discount_table.len()) - 1];
+        U256::from(k) * U256::from(discount) * U256::from(self.base)
+            / U256::from(bls12_381::MSM_MULTIPLIER)
+    }
+}
+
+/// A BLS12-381 pairing pricing model. This computes a price using a base cost
+/// and a cost per pair.
+pub(crate) struct Bls12PairingPricer {
+    base: usize,
+    pair: usize,
+}
+
+impl Bls12PairingPricer {
+    pub(crate) fn new(base: usize, pair: usize) -> Self {
+        Bls12PairingPricer { base, pair }
+    }
+}
+
+impl Pricer for Bls12PairingPricer {
+    fn cost(&self, input: &[u8]) -> U256 {
+        U256::from(self.base)
+            + U256::from(self.pair)
+                * U256::from(input.len() / bls12_381::PAIRING_INPUT_LENGTH)
+    }
+}
+
 /// Pricing scheme, execution definition, and activation block for a built-in
 /// contract.
 ///
@@ -280,6 +343,14 @@ pub fn builtin_factory(name: &str) -> Box<dyn Impl> {
         "alt_bn128_pairing" => Box::new(Bn128PairingImpl) as Box<dyn Impl>,
         "blake2_f" => Box::new(Blake2FImpl) as Box<dyn Impl>,
         "kzg_point_eval" => Box::new(KzgPointEval) as Box<dyn Impl>,
+        "bls12_g1add" => Box::new(Bls12G1Add) as Box<dyn Impl>,
+        "bls12_g1msm" => Box::new(Bls12G1Msm) as Box<dyn Impl>,
+        "bls12_g2add" => Box::new(Bls12G2Add) as Box<dyn Impl>,
+        "bls12_g2msm" => Box::new(Bls12G2Msm) as Box<dyn Impl>,
+        "bls12_pairing_check" => Box::new(Bls12Pairing) as Box<dyn Impl>,
+        "bls12_map_fp_to_g1" => Box::new(Bls12MapFpToG1) as Box<dyn Impl>,
+        "bls12_map_fp2_to_g2" => Box::new(Bls12MapFp2ToG2) as Box<dyn Impl>,
+        "p256_verify" => Box::new(P256Verify) as Box<dyn Impl>,
         _ => panic!("invalid builtin name: {}", name),
     }
 }
@@ -332,6 +403,30 @@ struct Blake2FImpl;
 #[allow(dead_code)]
 struct KzgPointEval;
 
+#[derive(Debug)]
+struct Bls12G1Add;
+
+#[derive(Debug)]
+struct Bls12G1Msm;
+
+#[derive(Debug)]
+struct Bls12G2Add;
+
+#[derive(Debug)]
+struct Bls12G2Msm;
+
+#[derive(Debug)]
+struct Bls12Pairing;
+
+#[derive(Debug)]
+struct Bls12MapFpToG1;
+
+#[derive(Debug)]
+struct Bls12MapFp2ToG2;
+
+#[derive(Debug)]
+struct P256Verify;
+
 impl Impl for Identity {
     fn execute(
         &self, input: &[u8], output: &mut BytesRef,
@@ -787,11 +882,89 @@ impl Impl for KzgPointEval {
         Ok(())
     }
 }
+
+impl Impl for Bls12G1Add {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        output.write(0, &bls12_381::g1_add(input)?);
+        Ok(())
+    }
+}
+
+impl Impl for Bls12G1Msm {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        output.write(0, &bls12_381::g1_msm(input)?);
+        Ok(())
+    }
+}
+
+impl Impl for Bls12G2Add {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        output.write(0, &bls12_381::g2_add(input)?);
+        Ok(())
+    }
+}
+
+impl Impl for Bls12G2Msm {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        output.write(0, &bls12_381::g2_msm(input)?);
+        Ok(())
+    }
+}
+
+impl Impl for Bls12Pairing {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        output.write(0, &bls12_381::pairing(input)?);
+        Ok(())
+    }
+}
+
+impl Impl for Bls12MapFpToG1 {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        output.write(0, &bls12_381::map_fp_to_g1(input)?);
+        Ok(())
+    }
+}
+
+impl Impl for Bls12MapFp2ToG2 {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        output.write(0, &bls12_381::map_fp2_to_g2(input)?);
+        Ok(())
+    }
+}
+
+impl Impl for P256Verify {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        // An invalid signature is not an error, but returns empty data.
+        if p256_verify::verify(input) {
+            let mut result = [0u8; 32];
+            result[31] = 1;
+            output.write(0, &result);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        builtin_factory, modexp as me, Blake2FPricer, Builtin, Linear,
-        ModexpPricer, Pricer,
+        builtin_factory, modexp as me, Blake2FPricer, Bls12MsmPricer,
+        Bls12PairingPricer, Builtin, Linear, ModexpPricer, Pricer,
     };
     use cfx_bytes::BytesRef;
     use cfx_types::U256;
@@ -1485,4 +1658,21 @@ mod tests {
             .unwrap();
         assert_eq!(&out[..], &expected[..]);
     }
+
+    #[test]
+    fn bls12_pricers() {
+        let g1_msm = Bls12MsmPricer::g1();
+        assert_eq!(g1_msm.cost(&[]), U256::zero());
+        assert_eq!(g1_msm.cost(&[0u8; 160]), U256::from(12000));
+        assert_eq!(g1_msm.cost(&[0u8; 320]), U256::from(22776));
+        // The discount is capped after 128 pairs.
+        assert_eq!(g1_msm.cost(&[0u8; 160 * 200]), U256::from(1_245_600));
+
+        let g2_msm = Bls12MsmPricer::g2();
+        assert_eq!(g2_msm.cost(&[0u8; 288]), U256::from(22500));
+        assert_eq!(g2_msm.cost(&[0u8; 576]), U256::from(45000));
+
+        let pairing = Bls12PairingPricer::new(37700, 32600);
+        assert_eq!(pairing.cost(&[0u8; 768]), U256::from(102_900));
+    }
 }
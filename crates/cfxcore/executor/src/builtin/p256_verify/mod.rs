@@ -0,0 +1,82 @@
+// Based on source code from the revm project (https://github.com/bluealloy/revm) under the MIT License.
+
+//! The secp256r1 signature verification of RIP-7212.
+
+use p256::{
+    ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey},
+    EncodedPoint,
+};
+
+/// The input is encoded as follows:
+/// | hash |  r  |  s  |  x  |  y  |
+/// |  32  | 32  | 32  | 32  | 32  |
+pub const INPUT_LENGTH: usize = 160;
+
+/// Returns whether `input` contains a valid signature of the hash, signed by
+/// the public key `(x, y)`. Any malformed input is treated as an invalid
+/// signature.
+pub fn verify(input: &[u8]) -> bool {
+    if input.len() != INPUT_LENGTH {
+        return false;
+    }
+    let (hash, rest) = input.split_at(32);
+    let (sig, pk) = rest.split_at(64);
+
+    // Fails if r or s is zero or not less than the order of the curve.
+    let signature = match Signature::from_slice(sig) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let encoded_point = EncodedPoint::from_untagged_bytes(pk.into());
+    // Fails if the public key is not on the curve.
+    let public_key = match VerifyingKey::from_encoded_point(&encoded_point) {
+        Ok(public_key) => public_key,
+        Err(_) => return false,
+    };
+    public_key.verify_prehash(hash, &signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verify;
+    use crate::builtin::builtin_factory;
+    use cfx_bytes::BytesRef;
+    use rustc_hex::FromHex;
+
+    #[derive(serde_derive::Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct TestVector {
+        input: String,
+        expected: String,
+        name: String,
+    }
+
+    /// Runs the test vectors through `verify` and the precompile at 0x100,
+    /// which returns 1 for a valid signature and empty data otherwise. The
+    /// vectors follow the cases of the Wycheproof `ecdsa_secp256r1_sha256`
+    /// vectors: the valid vectors of the RIP-7212 test suite, the malleable
+    /// high-s signatures, the message hashes not less than the curve order,
+    /// r and s out of range, modified signatures, the public keys at infinity
+    /// or not on the curve, and the malformed inputs. Except the RIP-7212
+    /// ones, they are signed and checked with OpenSSL.
+    #[test]
+    fn test_vectors() {
+        let vectors: Vec<TestVector> =
+            serde_json::from_str(include_str!("test-vectors/p256_verify.json"))
+                .unwrap();
+        assert!(!vectors.is_empty());
+
+        let precompile = builtin_factory("p256_verify");
+        for vector in vectors {
+            let input = vector.input.from_hex::<Vec<u8>>().unwrap();
+            let expected = vector.expected.from_hex::<Vec<u8>>().unwrap();
+            assert_eq!(verify(&input), !expected.is_empty(), "{}", vector.name);
+
+            let mut output = vec![];
+            precompile
+                .execute(&input, &mut BytesRef::Flexible(&mut output))
+                .unwrap_or_else(|e| panic!("{} failed: {:?}", vector.name, e));
+            assert_eq!(output, expected, "{}", vector.name);
+        }
+    }
+}
@@ -331,10 +331,8 @@ impl<'a> ContextTrait for Context<'a> {
         {
             (Some(contract.code()), contract.code_hash())
         } else {
-            (
-                self.state.code(&code_address_with_space)?,
-                self.state.code_hash(&code_address_with_space)?,
-            )
+            self.state
+                .code_for_call(&code_address_with_space, self.spec)?
         };
 
         let mut params = ActionParams {
@@ -459,6 +457,13 @@ impl<'a> ContextTrait for Context<'a> {
             );
         }
 
+        if self.space == Space::Ethereum
+            && self.spec.cip7702
+            && data.first() == Some(&0xef)
+        {
+            return Err(vm::Error::CreateContractStartingWithEF);
+        }
+
         let owner = if self.space == Space::Native {
             self.origin.storage_owner
         } else {
@@ -526,6 +531,12 @@ impl<'a> ContextTrait for Context<'a> {
         self.tracer.step(interpreter);
     }
 
+    fn trace_step_cost(
+        &mut self, interpreter: &dyn vm::InterpreterInfo, gas_cost: U256,
+    ) {
+        self.tracer.step_cost(interpreter, gas_cost, self.state);
+    }
+
     fn trace_step_end(&mut self, interpreter: &dyn vm::InterpreterInfo) {
         self.tracer.step_end(interpreter);
     }
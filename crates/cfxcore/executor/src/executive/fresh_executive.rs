@@ -58,6 +58,14 @@ pub(super) struct CostInfo {
     pub storage_sponsor_eligible: bool,
 }
 
+/// An EIP-7702 authorization with a valid signature and chain id. Whether it
+/// applies depends on the state of the authority at execution.
+pub(super) struct Authorization {
+    pub authority: Address,
+    pub address: Address,
+    pub nonce: u64,
+}
+
 impl<'a, O: ExecutiveObserver> FreshExecutive<'a, O> {
     pub fn new(
         context: ExecutiveContext<'a>, tx: &'a SignedTransaction,
@@ -68,6 +76,7 @@ impl<'a, O: ExecutiveObserver> FreshExecutive<'a, O> {
             tx.action() == &Action::Create,
             &tx.data(),
             tx.access_list(),
+            tx.authorization_list(),
             context.spec,
         );
         FreshExecutive {
@@ -95,16 +104,21 @@ impl<'a, O: ExecutiveObserver> FreshExecutive<'a, O> {
 
         early_return_on_err!(self.check_sender_exist(&cost)?);
 
-        Ok(Ok(self.into_pre_checked(cost)))
+        let authorizations = self.recover_authorizations();
+
+        Ok(Ok(self.into_pre_checked(cost, authorizations)))
     }
 
-    fn into_pre_checked(self, cost: CostInfo) -> PreCheckedExecutive<'a, O> {
+    fn into_pre_checked(
+        self, cost: CostInfo, authorizations: Vec<Authorization>,
+    ) -> PreCheckedExecutive<'a, O> {
         PreCheckedExecutive {
             context: self.context,
             tx: self.tx,
             observer: self.observer,
             settings: self.settings,
             cost,
+            authorizations,
             substate: Substate::new(),
         }
     }
@@ -175,6 +189,30 @@ impl<'a, O: ExecutiveObserver> FreshExecutive<'a, O> {
         }
     }
 
+    /// Recovers the authorities of the EIP-7702 authorization list. The
+    /// authorizations for other chains, with an overflowing nonce or an
+    /// invalid signature are skipped, as they don't invalidate the
+    /// transaction.
+    fn recover_authorizations(&self) -> Vec<Authorization> {
+        let list = match self.tx.authorization_list() {
+            Some(list) => list,
+            None => return vec![],
+        };
+        let chain_id = U256::from(self.context.env.chain_id[&Space::Ethereum]);
+
+        list.iter()
+            .filter(|auth| auth.chain_id.is_zero() || auth.chain_id == chain_id)
+            .filter(|auth| auth.nonce < u64::MAX)
+            .filter_map(|auth| {
+                Some(Authorization {
+                    authority: auth.authority()?,
+                    address: auth.address,
+                    nonce: auth.nonce,
+                })
+            })
+            .collect()
+    }
+
     fn check_sender_exist(
         &self, cost: &CostInfo,
     ) -> DbResult<Result<(), ExecutionOutcome>> {
@@ -15,7 +15,7 @@ use cfx_types::{
     U256,
 };
 use cfx_vm_types::{CreateContractAddress, Env, Spec};
-use primitives::{AccessList, SignedTransaction};
+use primitives::{AccessList, AuthorizationList, SignedTransaction};
 
 use fresh_executive::FreshExecutive;
 use pre_checked_executive::PreCheckedExecutive;
@@ -64,7 +64,8 @@ impl<'a> ExecutiveContext<'a> {
 }
 
 pub fn gas_required_for(
-    is_create: bool, data: &[u8], access_list: Option<&AccessList>, spec: &Spec,
+    is_create: bool, data: &[u8], access_list: Option<&AccessList>,
+    authorization_list: Option<&AuthorizationList>, spec: &Spec,
 ) -> u64 {
     let init_gas = (if is_create {
         spec.tx_create_gas
@@ -94,7 +95,13 @@ pub fn gas_required_for(
         0
     };
 
-    init_gas + data_gas + access_gas
+    // Each authorization is charged as creating a new account, the cost for
+    // existing accounts is refunded at the end of the transaction.
+    let authorization_gas = authorization_list.map_or(0, |list| {
+        list.len() as u64 * spec.per_empty_account_cost as u64
+    });
+
+    init_gas + data_gas + access_gas + authorization_gas
 }
 
 pub fn contract_address(
@@ -1,7 +1,7 @@
 use super::{
     contract_address,
     executed::make_ext_result,
-    fresh_executive::CostInfo,
+    fresh_executive::{Authorization, CostInfo},
     transact_options::{ChargeCollateral, TransactSettings},
     Executed, ExecutionError, ExecutiveContext,
 };
@@ -18,8 +18,8 @@ use crate::{
 };
 use cfx_parameters::staking::code_collateral_units;
 use cfx_vm_types::{
-    self as vm, ActionParams, ActionValue, CallType, CreateContractAddress,
-    CreateType,
+    self as vm, extract_delegation, ActionParams, ActionValue, CallType,
+    CreateContractAddress, CreateType,
 };
 
 use cfx_statedb::Result as DbResult;
@@ -33,6 +33,7 @@ pub(super) struct PreCheckedExecutive<'a, O: ExecutiveObserver> {
     pub observer: O,
     pub settings: TransactSettings,
     pub cost: CostInfo,
+    pub authorizations: Vec<Authorization>,
     pub substate: Substate,
 }
 
@@ -50,6 +51,8 @@ impl<'a, O: ExecutiveObserver> PreCheckedExecutive<'a, O> {
             return self.finalize_on_insufficient_balance(actual_gas_cost);
         }
 
+        self.apply_authorizations()?;
+
         let params = self.make_action_params()?;
         if self.tx.space() == Space::Native
             && !self.check_create_address(&params)?
@@ -144,6 +147,39 @@ impl<'a, O: ExecutiveObserver> PreCheckedExecutive<'a, O> {
         Ok((actual_gas_cost, insufficient_sender_balance))
     }
 
+    /// Sets the delegations of EIP-7702 before the execution, which are kept
+    /// even if the execution fails. An authorization is skipped if the
+    /// authority is a contract or its nonce mismatches.
+    fn apply_authorizations(&mut self) -> DbResult<()> {
+        let spec = self.context.spec;
+        let state = &mut *self.context.state;
+
+        for auth in &self.authorizations {
+            let authority = auth.authority.with_evm_space();
+            if let Some(code) = state.code(&authority)? {
+                if extract_delegation(&code).is_none() {
+                    continue;
+                }
+            }
+            if state.nonce(&authority)? != U256::from(auth.nonce) {
+                continue;
+            }
+
+            // The intrinsic gas charges each authorization as creating a new
+            // account.
+            if state.exists(&authority)? {
+                self.substate.refund += (spec.per_empty_account_cost
+                    - spec.per_auth_base_cost)
+                    as u64;
+            }
+
+            state.set_delegation(&authority, &auth.address)?;
+            state.inc_nonce(&authority)?;
+        }
+
+        Ok(())
+    }
+
     fn make_action_params(&self) -> DbResult<ActionParams> {
         let tx = self.tx;
         let cost = &self.cost;
@@ -190,6 +226,8 @@ impl<'a, O: ExecutiveObserver> PreCheckedExecutive<'a, O> {
             }
             Action::Call(ref receipient) => {
                 let receipient = receipient.with_space(sender.space);
+                let (code, code_hash) =
+                    state.code_for_call(&receipient, self.context.spec)?;
                 let storage_owner = if cost.storage_sponsored {
                     receipient.address
                 } else {
@@ -205,8 +243,8 @@ impl<'a, O: ExecutiveObserver> PreCheckedExecutive<'a, O> {
                     gas: init_gas,
                     gas_price: cost.gas_price,
                     value: ActionValue::Transfer(*tx.value()),
-                    code: state.code(&receipient)?,
-                    code_hash: state.code_hash(&receipient)?,
+                    code,
+                    code_hash,
                     data: Some(tx.data().clone()),
                     call_type: CallType::Call,
                     create_type: CreateType::None,
@@ -476,15 +514,25 @@ impl<'a, O: ExecutiveObserver> PreCheckedExecutive<'a, O> {
         };
         // gas_used is only used to estimate gas needed
         let gas_used = tx.gas() - gas_left;
+        // After EIP-7702, the refund counter is capped at 1/5 of the gas used
+        // (EIP-3529) and returned before the minimum charge below.
+        let refund = if spec.cip7702 {
+            U256::min(self.substate.refund.into(), gas_used / 5)
+        } else {
+            U256::zero()
+        };
+        let gas_left = gas_left + refund;
+        let gas_used_after_refund = gas_used - refund;
         // gas_left should be smaller than 1/4 of gas_limit, otherwise
         // 3/4 of gas_limit is charged.
-        let charge_all = (gas_left + gas_left + gas_left) >= gas_used;
+        let charge_all =
+            (gas_left + gas_left + gas_left) >= gas_used_after_refund;
         let (gas_charged, gas_refunded) = if charge_all {
             let gas_refunded = tx.gas() >> 2;
             let gas_charged = tx.gas() - gas_refunded;
             (gas_charged, gas_refunded)
         } else {
-            (gas_used, gas_left)
+            (gas_used_after_refund, gas_left)
         };
 
         let fees_value = gas_charged.saturating_mul(cost.gas_price);
@@ -574,8 +622,10 @@ impl<'a, O: ExecutiveObserver> PreCheckedExecutive<'a, O> {
     }
 
     fn finalize_on_executed(
-        self, result: ExecutiveResult, refund_info: RefundInfo,
+        mut self, result: ExecutiveResult, refund_info: RefundInfo,
     ) -> DbResult<ExecutionOutcome> {
+        self.observer.as_tracer().record_tx_end(self.context.state);
+
         let tx = self.tx;
         let cost = self.cost;
         let ext_result = make_ext_result(self.observer);
@@ -19,18 +19,21 @@ use cfx_storage::{
     StateIndex,
 };
 use cfx_types::{
-    address_util::AddressUtil, Address, AddressSpaceUtil, BigEndianHash, U256,
-    U512,
+    address_util::AddressUtil, Address, AddressSpaceUtil, BigEndianHash, Space,
+    U256, U512,
 };
 use cfx_vm_interpreter::{FinalizationResult, GasPriceTier};
 use cfx_vm_types::{
-    self as vm, ActionParams, ActionValue, CallType, CreateContractAddress,
-    CreateType, Env,
+    self as vm, delegation_code, ActionParams, ActionValue, CallType,
+    CreateContractAddress, CreateType, Env,
 };
-use cfxkey::{Generator, Random};
+use cfxkey::{public_to_address, Generator, Random};
 use primitives::{
     storage::STORAGE_LAYOUT_REGULAR_V0,
-    transaction::{native_transaction::NativeTransaction, Action},
+    transaction::{
+        native_transaction::NativeTransaction, Action, AuthorizationListItem,
+        Eip7702Transaction, EthereumTransaction,
+    },
     EpochId, Transaction,
 };
 use rustc_hex::FromHex;
@@ -2278,3 +2281,264 @@ fn test_push0() {
         assert!(matches!(error, vm::Error::BadInstruction { .. }));
     }
 }
+
+fn sign_authorization(
+    authority_key: &cfxkey::KeyPair, address: Address, nonce: u64,
+) -> AuthorizationListItem {
+    // Valid on all chains.
+    let mut auth = AuthorizationListItem {
+        chain_id: U256::zero(),
+        address,
+        nonce,
+        y_parity: 0,
+        r: U256::zero(),
+        s: U256::zero(),
+    };
+    let sig =
+        cfxkey::sign(authority_key.secret(), &auth.signature_hash()).unwrap();
+    auth.y_parity = sig.v();
+    auth.r = U256::from_big_endian(sig.r());
+    auth.s = U256::from_big_endian(sig.s());
+    auth
+}
+
+fn set_code_transaction(
+    sender_key: &cfxkey::KeyPair, to: Address, gas: u64,
+    authorization_list: Vec<AuthorizationListItem>,
+) -> SignedTransaction {
+    Transaction::Ethereum(EthereumTransaction::Eip7702(Eip7702Transaction {
+        chain_id: 1,
+        nonce: U256::zero(),
+        max_priority_fee_per_gas: U256::one(),
+        max_fee_per_gas: U256::one(),
+        gas: U256::from(gas),
+        action: Action::Call(to),
+        value: U256::zero(),
+        data: vec![],
+        access_list: vec![],
+        authorization_list,
+    }))
+    .sign(sender_key.secret())
+}
+
+fn set_code_env() -> (Env, Machine, Spec) {
+    let mut env = Env::default();
+    env.chain_id.insert(Space::Ethereum, 1);
+    env.gas_limit = U256::from(1_000_000);
+    let machine = make_byzantium_machine(5);
+    let mut spec = machine.spec_for_test(env.number);
+    spec.cip7702 = true;
+    (env, machine, spec)
+}
+
+#[test]
+fn test_set_code_transaction() {
+    let sender_key = Random.generate().unwrap();
+    let authority_key = Random.generate().unwrap();
+    let authority =
+        public_to_address(authority_key.public(), /* type_nibble */ false);
+    let delegated = Address::from_low_u64_be(0x7702);
+
+    let auth = sign_authorization(&authority_key, delegated, 0);
+    assert_eq!(auth.authority(), Some(authority));
+    let t = set_code_transaction(&sender_key, authority, 200_000, vec![auth]);
+
+    let (env, machine, spec) = set_code_env();
+    let storage_manager = new_state_manager_for_unit_test();
+    let mut state = get_state_for_genesis_write(&storage_manager);
+    state
+        .add_balance(&t.sender(), &U256::from(1_000_000), CleanupMode::NoEmpty)
+        .unwrap();
+    // code:
+    //
+    // 60 01 - push 1
+    // 60 00 - push 0
+    // 55 - sstore
+    let delegated_with_space = delegated.with_evm_space();
+    state
+        .new_contract_with_code(&delegated_with_space, U256::zero())
+        .unwrap();
+    state
+        .init_code(
+            &delegated_with_space,
+            "6001600055".from_hex().unwrap(),
+            Address::zero(),
+        )
+        .unwrap();
+
+    let res = {
+        let ex = ExecutiveContext::new(&mut state, &env, &machine, &spec);
+        ex.transact(&t, TransactOptions::default()).unwrap()
+    };
+    assert!(
+        matches!(res, ExecutionOutcome::Finished(_)),
+        "Expected success. {:?}",
+        res
+    );
+
+    // The code of the delegated account runs on the storage of the
+    // authority.
+    let authority_with_space = authority.with_evm_space();
+    assert_eq!(
+        state.code(&authority_with_space).unwrap().as_deref(),
+        Some(&delegation_code(&delegated))
+    );
+    assert_eq!(state.nonce(&authority_with_space).unwrap(), U256::one());
+    assert_eq!(
+        state
+            .storage_at(&authority_with_space, &vec![0; 32])
+            .unwrap(),
+        U256::one()
+    );
+    assert_eq!(
+        state
+            .storage_at(&delegated_with_space, &vec![0; 32])
+            .unwrap(),
+        U256::zero()
+    );
+}
+
+fn set_code_gas_charged(
+    spec: &Spec, gas_limit: u64, authority_exists: bool,
+) -> U256 {
+    let (env, machine, _) = set_code_env();
+    let sender_key = Random.generate().unwrap();
+    let authority_key = Random.generate().unwrap();
+    let authority =
+        public_to_address(authority_key.public(), false).with_evm_space();
+    let auth = sign_authorization(&authority_key, Address::random(), 0);
+    let t = set_code_transaction(
+        &sender_key,
+        Address::random(),
+        gas_limit,
+        vec![auth],
+    );
+
+    let storage_manager = new_state_manager_for_unit_test();
+    let mut state = get_state_for_genesis_write(&storage_manager);
+    state
+        .add_balance(&t.sender(), &U256::from(1_000_000), CleanupMode::NoEmpty)
+        .unwrap();
+    if authority_exists {
+        state
+            .add_balance(&authority, &U256::one(), CleanupMode::NoEmpty)
+            .unwrap();
+    }
+
+    let ex = ExecutiveContext::new(&mut state, &env, &machine, spec);
+    match ex.transact(&t, TransactOptions::default()).unwrap() {
+        ExecutionOutcome::Finished(executed) => {
+            // 21000 for the transaction and 25000 for the authorization.
+            assert_eq!(executed.gas_used, U256::from(46_000));
+            executed.gas_charged
+        }
+        res => panic!("Expected success. {:?}", res),
+    }
+}
+
+#[test]
+fn test_set_code_refund() {
+    let (_, _, spec) = set_code_env();
+    let gas = 46_000;
+
+    assert_eq!(set_code_gas_charged(&spec, gas, false), U256::from(gas));
+    // The refund of 12500 for the existing authority is capped at 1/5 of the
+    // gas used.
+    assert_eq!(
+        set_code_gas_charged(&spec, gas, true),
+        U256::from(gas - gas / 5)
+    );
+}
+
+#[test]
+fn test_set_code_refund_activation() {
+    let (_, _, spec) = set_code_env();
+    let mut spec_before = spec.clone();
+    spec_before.cip7702 = false;
+    let gas = 60_000;
+
+    // Before the activation, no refund is given.
+    assert_eq!(
+        set_code_gas_charged(&spec_before, gas, true),
+        U256::from(46_000)
+    );
+    // After the activation, the refund still keeps the charge above 3/4 of
+    // the gas limit.
+    assert_eq!(
+        set_code_gas_charged(&spec, gas, true),
+        U256::from(gas - gas / 4)
+    );
+    assert_eq!(set_code_gas_charged(&spec, gas, false), U256::from(46_000));
+}
+
+#[test]
+fn test_set_code_invalid_authorizations() {
+    let sender_key = Random.generate().unwrap();
+    let authority_key = Random.generate().unwrap();
+    let authority =
+        public_to_address(authority_key.public(), false).with_evm_space();
+    let delegated = Address::from_low_u64_be(0x7702);
+
+    // A mismatched nonce and a malformed signature are skipped, while the
+    // transaction still succeeds.
+    let mut malformed = sign_authorization(&authority_key, delegated, 0);
+    malformed.y_parity = 2;
+    let t = set_code_transaction(
+        &sender_key,
+        Address::random(),
+        200_000,
+        vec![sign_authorization(&authority_key, delegated, 1), malformed],
+    );
+
+    let (env, machine, spec) = set_code_env();
+    let storage_manager = new_state_manager_for_unit_test();
+    let mut state = get_state_for_genesis_write(&storage_manager);
+    state
+        .add_balance(&t.sender(), &U256::from(1_000_000), CleanupMode::NoEmpty)
+        .unwrap();
+
+    let res = {
+        let ex = ExecutiveContext::new(&mut state, &env, &machine, &spec);
+        ex.transact(&t, TransactOptions::default()).unwrap()
+    };
+    assert!(matches!(res, ExecutionOutcome::Finished(_)), "{:?}", res);
+    assert_eq!(state.code(&authority).unwrap(), None);
+    assert_eq!(state.nonce(&authority).unwrap(), U256::zero());
+}
+
+#[test]
+fn test_set_code_clear_delegation() {
+    let sender_key = Random.generate().unwrap();
+    let authority_key = Random.generate().unwrap();
+    let authority =
+        public_to_address(authority_key.public(), false).with_evm_space();
+
+    let (env, machine, spec) = set_code_env();
+    let storage_manager = new_state_manager_for_unit_test();
+    let mut state = get_state_for_genesis_write(&storage_manager);
+    state
+        .add_balance(
+            &public_to_address(sender_key.public(), false).with_evm_space(),
+            &U256::from(1_000_000),
+            CleanupMode::NoEmpty,
+        )
+        .unwrap();
+    state
+        .set_delegation(&authority, &Address::from_low_u64_be(0x7702))
+        .unwrap();
+
+    // A delegation to the zero address clears the code.
+    let t = set_code_transaction(
+        &sender_key,
+        Address::random(),
+        200_000,
+        vec![sign_authorization(&authority_key, Address::zero(), 0)],
+    );
+    let res = {
+        let ex = ExecutiveContext::new(&mut state, &env, &machine, &spec);
+        ex.transact(&t, TransactOptions::default()).unwrap()
+    };
+    assert!(matches!(res, ExecutionOutcome::Finished(_)), "{:?}", res);
+    assert_eq!(state.code(&authority).unwrap(), None);
+    assert_eq!(state.nonce(&authority).unwrap(), U256::one());
+}
@@ -7,8 +7,8 @@ mod vm_factory;
 use super::builtin::Builtin;
 use crate::{
     builtin::{
-        builtin_factory, AltBn128PairingPricer, Blake2FPricer, Linear,
-        ModexpPricer,
+        builtin_factory, AltBn128PairingPricer, Blake2FPricer, Bls12MsmPricer,
+        Bls12PairingPricer, Linear, ModexpPricer,
     },
     internal_contract::InternalContractMap,
     spec::CommonParams,
@@ -185,6 +185,70 @@ fn new_builtin_map(
             params.transition_numbers.cip144,
         ),
     );
+    btree.insert(
+        Address::from(H256::from_low_u64_be(11)),
+        Builtin::new(
+            Box::new(Linear::new(375, 0)),
+            builtin_factory("bls12_g1add"),
+            params.transition_numbers.cip2537,
+        ),
+    );
+    btree.insert(
+        Address::from(H256::from_low_u64_be(12)),
+        Builtin::new(
+            Box::new(Bls12MsmPricer::g1()),
+            builtin_factory("bls12_g1msm"),
+            params.transition_numbers.cip2537,
+        ),
+    );
+    btree.insert(
+        Address::from(H256::from_low_u64_be(13)),
+        Builtin::new(
+            Box::new(Linear::new(600, 0)),
+            builtin_factory("bls12_g2add"),
+            params.transition_numbers.cip2537,
+        ),
+    );
+    btree.insert(
+        Address::from(H256::from_low_u64_be(14)),
+        Builtin::new(
+            Box::new(Bls12MsmPricer::g2()),
+            builtin_factory("bls12_g2msm"),
+            params.transition_numbers.cip2537,
+        ),
+    );
+    btree.insert(
+        Address::from(H256::from_low_u64_be(15)),
+        Builtin::new(
+            Box::new(Bls12PairingPricer::new(37700, 32600)),
+            builtin_factory("bls12_pairing_check"),
+            params.transition_numbers.cip2537,
+        ),
+    );
+    btree.insert(
+        Address::from(H256::from_low_u64_be(16)),
+        Builtin::new(
+            Box::new(Linear::new(5500, 0)),
+            builtin_factory("bls12_map_fp_to_g1"),
+            params.transition_numbers.cip2537,
+        ),
+    );
+    btree.insert(
+        Address::from(H256::from_low_u64_be(17)),
+        Builtin::new(
+            Box::new(Linear::new(23800, 0)),
+            builtin_factory("bls12_map_fp2_to_g2"),
+            params.transition_numbers.cip2537,
+        ),
+    );
+    btree.insert(
+        Address::from(H256::from_low_u64_be(0x100)),
+        Builtin::new(
+            Box::new(Linear::new(3450, 0)),
+            builtin_factory("p256_verify"),
+            params.transition_numbers.cip7212,
+        ),
+    );
     btree
 }
 
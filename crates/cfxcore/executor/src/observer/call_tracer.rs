@@ -1,10 +1,10 @@
-use crate::stack::FrameResult;
-use cfx_vm_types::ActionParams;
+use crate::{stack::FrameResult, state::State};
+use cfx_vm_types::{ActionParams, Error as VmError};
 
 use impl_tools::autoimpl;
 use impl_trait_for_tuples::impl_for_tuples;
 
-#[impl_for_tuples(3)]
+#[impl_for_tuples(5)]
 #[autoimpl(for<T: trait + ?Sized> &mut T)]
 #[allow(unused_variables)]
 pub trait CallTracer {
@@ -19,4 +19,14 @@ pub trait CallTracer {
 
     /// Prepares create result trace
     fn record_create_result(&mut self, result: &FrameResult) {}
+
+    /// Called when a frame fails, before its changes are reverted and before
+    /// its result is recorded. The state is exposed for the tracers
+    /// inspecting the accounts at the failure (e.g., the `db` object of the
+    /// JS tracer).
+    fn record_frame_error(&mut self, error: &VmError, state: &State) {}
+
+    /// Called with the final state once the transaction is executed and the
+    /// unused gas is refunded.
+    fn record_tx_end(&mut self, state: &State) {}
 }
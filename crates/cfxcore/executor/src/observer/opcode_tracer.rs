@@ -1,10 +1,11 @@
+use crate::state::State;
 use cfx_types::{Address, H256, U256};
 use cfx_vm_types::InterpreterInfo;
 
 use impl_tools::autoimpl;
 use impl_trait_for_tuples::impl_for_tuples;
 
-#[impl_for_tuples(3)]
+#[impl_for_tuples(5)]
 #[autoimpl(for<T: trait + ?Sized> &mut T)]
 pub trait OpcodeTracer {
     fn do_trace_opcode(&self, _enabled: &mut bool) {}
@@ -19,6 +20,19 @@ pub trait OpcodeTracer {
     /// more is available on `interp` (see [Interpreter]).
     fn step(&mut self, interp: &dyn InterpreterInfo) { let _ = interp; }
 
+    /// Called after `step` once the gas cost of the instruction is known,
+    /// right before the instruction is executed.
+    ///
+    /// The state is exposed for the tracers inspecting accounts during the
+    /// execution (e.g., the `db` object of the JS tracer).
+    fn step_cost(
+        &mut self, interp: &dyn InterpreterInfo, gas_cost: U256, state: &State,
+    ) {
+        let _ = interp;
+        let _ = gas_cost;
+        let _ = state;
+    }
+
     /// Called after `step` when the instruction has been executed.
     fn step_end(&mut self, interp: &dyn InterpreterInfo) { let _ = interp; }
 
@@ -115,6 +115,10 @@ pub struct TransitionsBlockNumber {
     pub cip144: BlockNumber,
     /// CIP-145: Fix Receipts upon `NotEnoughBalance` Error
     pub cip145: BlockNumber,
+    /// EIP-2537: Precompile for BLS12-381 curve operations
+    pub cip2537: BlockNumber,
+    /// RIP-7212: Precompile for secp256r1 Curve Support
+    pub cip7212: BlockNumber,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -136,6 +140,8 @@ pub struct TransitionsEpochHeight {
     /// CIP-133: Enhanced Block Hash Query
     pub cip133e: BlockHeight,
     pub cip1559: BlockHeight,
+    /// EIP-7702: Set EOA account code in eSpace
+    pub cip7702: BlockHeight,
 }
 
 impl Default for CommonParams {
@@ -194,6 +200,7 @@ impl CommonParams {
         spec.cip144 = number >= self.transition_numbers.cip144;
         spec.cip145 = number >= self.transition_numbers.cip145;
         spec.cip1559 = height >= self.transition_heights.cip1559;
+        spec.cip7702 = height >= self.transition_heights.cip7702;
         spec.cancun_opcodes = number >= self.transition_numbers.cancun_opcodes;
         if spec.cancun_opcodes {
             spec.sload_gas = 800;
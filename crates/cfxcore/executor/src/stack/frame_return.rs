@@ -15,6 +15,10 @@ pub(super) fn process_return<'a>(
     let frame_result =
         result.map(|result| FrameReturn::new(frame_local, result));
 
+    if let Err(e) = &frame_result {
+        resources.tracer.record_frame_error(e, resources.state);
+    }
+
     let apply_state = frame_result.as_ref().map_or(false, |r| r.apply_state);
 
     if apply_state {
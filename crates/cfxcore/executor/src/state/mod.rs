@@ -23,8 +23,8 @@ pub use state_object::get_state_for_genesis_write;
 pub use state_object::{
     distribute_pos_interest, initialize_cip107, initialize_cip137,
     initialize_or_update_dao_voted_params, settle_collateral_for_all,
-    update_pos_status, State, StateCommitResult,
-    COMMISSION_PRIVILEGE_SPECIAL_KEY,
+    update_pos_status, AccountOverride, State, StateCommitResult,
+    StateOverride, COMMISSION_PRIVILEGE_SPECIAL_KEY,
 };
 
 use cfx_types::AddressWithSpace;
@@ -17,6 +17,8 @@ impl OverlayAccount {
 
     pub fn balance(&self) -> &U256 { &self.balance }
 
+    pub fn set_balance(&mut self, balance: &U256) { self.balance = *balance; }
+
     pub fn add_balance(&mut self, by: &U256) {
         self.balance = self.balance + *by;
     }
@@ -44,6 +46,11 @@ impl OverlayAccount {
         });
     }
 
+    pub fn clear_code(&mut self) {
+        self.code_hash = KECCAK_EMPTY;
+        self.code = None;
+    }
+
     pub(super) fn is_code_loaded(&self) -> bool {
         self.code.is_some() || self.code_hash == KECCAK_EMPTY
     }
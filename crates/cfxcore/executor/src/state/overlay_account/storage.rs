@@ -50,6 +50,28 @@ impl OverlayAccount {
         Ok(())
     }
 
+    /// Overwrites a storage entry without settling the storage collateral.
+    /// Only used by virtual executions with state overrides, which are never
+    /// committed.
+    pub fn override_storage(&mut self, key: Vec<u8>, value: U256) {
+        let owner = if self.should_have_owner(&key) && !value.is_zero() {
+            Some(self.address.address)
+        } else {
+            None
+        };
+        Arc::make_mut(&mut self.storage_write_cache)
+            .insert(key, StorageValue { value, owner });
+    }
+
+    /// Regards all the storage entries of this account as empty, so the
+    /// storage can be replaced entirely by `override_storage`. Only used by
+    /// virtual executions with state overrides, which are never committed.
+    pub fn clear_storage_for_override(&mut self) {
+        Arc::make_mut(&mut self.storage_write_cache).clear();
+        self.storage_read_cache.write().clear();
+        self.pending_db_clear = true;
+    }
+
     #[cfg(test)]
     pub fn set_storage_simple(&mut self, key: Vec<u8>, value: U256) {
         Arc::make_mut(&mut self.storage_write_cache)
@@ -3,8 +3,10 @@ use crate::{state::CleanupMode, try_loaded};
 use cfx_bytes::Bytes;
 use cfx_statedb::Result as DbResult;
 use cfx_types::{
-    address_util::AddressUtil, Address, AddressWithSpace, Space, H256, U256,
+    address_util::AddressUtil, Address, AddressSpaceUtil, AddressWithSpace,
+    Space, H256, U256,
 };
+use cfx_vm_types::{delegation_code, extract_delegation, Spec};
 use keccak_hash::KECCAK_EMPTY;
 #[cfg(test)]
 use primitives::StorageLayout;
@@ -141,6 +143,48 @@ impl State {
         Ok(())
     }
 
+    /// Returns the delegated address if the account is delegated by EIP-7702.
+    pub fn delegation(
+        &self, address: &AddressWithSpace,
+    ) -> DbResult<Option<Address>> {
+        if address.space != Space::Ethereum {
+            return Ok(None);
+        }
+        Ok(self
+            .code(address)?
+            .and_then(|code| extract_delegation(&code)))
+    }
+
+    /// Returns the code and the code hash executed on a call to `address`.
+    /// After EIP-7702, a call to a delegated account executes the code of the
+    /// delegated address. The delegation is not followed recursively.
+    pub fn code_for_call(
+        &self, address: &AddressWithSpace, spec: &Spec,
+    ) -> DbResult<(Option<Arc<Vec<u8>>>, H256)> {
+        let address = match self.delegation(address)? {
+            Some(delegated) if spec.cip7702 => {
+                delegated.with_space(address.space)
+            }
+            _ => *address,
+        };
+        Ok((self.code(&address)?, self.code_hash(&address)?))
+    }
+
+    /// Sets the code of an eSpace account to the delegation designator of
+    /// `delegated`, or clears it if `delegated` is the zero address.
+    pub fn set_delegation(
+        &mut self, address: &AddressWithSpace, delegated: &Address,
+    ) -> DbResult<()> {
+        debug_assert_eq!(address.space, Space::Ethereum);
+        let mut account = self.write_account_or_new_lock(address)?;
+        if delegated.is_zero() {
+            account.clear_code();
+        } else {
+            account.init_code(delegation_code(delegated), Address::zero());
+        }
+        Ok(())
+    }
+
     pub fn admin(&self, address: &Address) -> DbResult<Address> {
         let acc = try_loaded!(self.read_native_account_lock(address));
         Ok(*acc.admin())
@@ -39,6 +39,10 @@ mod staking;
 /// Implements access functions for the account storage entries of `State`.
 mod storage_entry;
 
+/// Implements functions for overriding account fields of `State` before a
+/// virtual execution.
+mod state_override;
+
 mod reward;
 
 #[cfg(test)]
@@ -51,6 +55,7 @@ pub use self::{
     reward::initialize_cip137,
     sponsor::COMMISSION_PRIVILEGE_SPECIAL_KEY,
     staking::initialize_or_update_dao_voted_params,
+    state_override::{AccountOverride, StateOverride},
 };
 #[cfg(test)]
 pub use tests::get_state_for_genesis_write;
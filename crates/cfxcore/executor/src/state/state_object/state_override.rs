@@ -0,0 +1,83 @@
+use super::State;
+use cfx_bytes::Bytes;
+use cfx_statedb::Result as DbResult;
+use cfx_types::{Address, AddressWithSpace, H256, U256};
+use std::collections::HashMap;
+
+/// Overrides of the fields of an account, applied to the state before a
+/// virtual execution (e.g., the `stateOverrides` of `debug_traceCall`).
+#[derive(Debug, Clone, Default)]
+pub struct AccountOverride {
+    /// Replaces the balance of the account.
+    pub balance: Option<U256>,
+    /// Replaces the nonce of the account.
+    pub nonce: Option<U256>,
+    /// Replaces the code of the account.
+    pub code: Option<Bytes>,
+    /// Replaces the whole storage of the account. All the slots not listed
+    /// here are regarded as empty.
+    pub state: Option<HashMap<H256, H256>>,
+    /// Replaces the listed storage slots of the account and leaves the others
+    /// unchanged.
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+/// A collection of account overrides, keyed by the account address.
+pub type StateOverride = HashMap<AddressWithSpace, AccountOverride>;
+
+impl State {
+    /// Applies the overrides to the cached accounts. The changes bypass the
+    /// storage collateral and must never be committed, so this should only be
+    /// used for virtual executions.
+    pub fn apply_override(
+        &mut self, state_override: &StateOverride,
+    ) -> DbResult<()> {
+        for (address, account_override) in state_override {
+            self.apply_account_override(address, account_override)?;
+        }
+        Ok(())
+    }
+
+    fn apply_account_override(
+        &mut self, address: &AddressWithSpace,
+        account_override: &AccountOverride,
+    ) -> DbResult<()> {
+        if account_override.state.is_some()
+            && account_override.state_diff.is_some()
+        {
+            bail!(format!(
+                "Both state and stateDiff are set in the override of account {:?}",
+                address.address
+            ));
+        }
+
+        let mut account = self.write_account_or_new_lock(address)?;
+
+        if let Some(balance) = &account_override.balance {
+            account.set_balance(balance);
+        }
+        if let Some(nonce) = &account_override.nonce {
+            account.set_nonce(nonce);
+        }
+        if let Some(code) = &account_override.code {
+            account.init_code(code.clone(), Address::zero());
+        }
+
+        let storage =
+            match (&account_override.state, &account_override.state_diff) {
+                (Some(state), _) => {
+                    account.clear_storage_for_override();
+                    state
+                }
+                (None, Some(state_diff)) => state_diff,
+                (None, None) => return Ok(()),
+            };
+        for (key, value) in storage {
+            account.override_storage(
+                key.as_bytes().to_vec(),
+                U256::from_big_endian(value.as_bytes()),
+            );
+        }
+        Ok(())
+    }
+}
@@ -1269,6 +1269,83 @@ fn test_automatic_collateral_normal_account() {
     );
 }
 
+#[test]
+fn apply_state_override() {
+    use super::{AccountOverride, StateOverride};
+    use cfx_types::H256;
+    use std::collections::HashMap;
+
+    let storage_manager = new_state_manager_for_unit_test();
+    let mut state = get_state_for_genesis_write(&storage_manager);
+    let address = Address::from_low_u64_be(1).with_evm_space();
+    let k1 = u256_to_vec(&U256::from(1));
+    let k2 = u256_to_vec(&U256::from(2));
+
+    state
+        .new_contract_with_code(&address, U256::zero())
+        .unwrap();
+    state
+        .set_storage(
+            &address,
+            k1.clone(),
+            U256::one(),
+            Address::zero(),
+            &mut Substate::new(),
+        )
+        .unwrap();
+
+    // `stateDiff` only touches the listed slots.
+    let mut state_diff = HashMap::new();
+    state_diff.insert(H256::from_low_u64_be(2), H256::from_low_u64_be(5));
+    let mut state_override = StateOverride::new();
+    state_override.insert(
+        address,
+        AccountOverride {
+            balance: Some(U256::from(100)),
+            nonce: Some(U256::from(7)),
+            code: Some(vec![0x60, 0x00]),
+            state_diff: Some(state_diff),
+            ..Default::default()
+        },
+    );
+    state.apply_override(&state_override).unwrap();
+    assert_eq!(state.balance(&address).unwrap(), U256::from(100));
+    assert_eq!(state.nonce(&address).unwrap(), U256::from(7));
+    assert_eq!(
+        state.code(&address).unwrap().unwrap().as_ref(),
+        &vec![0x60, 0x00]
+    );
+    assert_eq!(state.storage_at(&address, &k1).unwrap(), U256::one());
+    assert_eq!(state.storage_at(&address, &k2).unwrap(), U256::from(5));
+
+    // `state` replaces the whole storage.
+    let mut full_state = HashMap::new();
+    full_state.insert(H256::from_low_u64_be(2), H256::from_low_u64_be(6));
+    let mut state_override = StateOverride::new();
+    state_override.insert(
+        address,
+        AccountOverride {
+            state: Some(full_state),
+            ..Default::default()
+        },
+    );
+    state.apply_override(&state_override).unwrap();
+    assert_eq!(state.storage_at(&address, &k1).unwrap(), U256::zero());
+    assert_eq!(state.storage_at(&address, &k2).unwrap(), U256::from(6));
+
+    // Setting both `state` and `stateDiff` is rejected.
+    let mut state_override = StateOverride::new();
+    state_override.insert(
+        address,
+        AccountOverride {
+            state: Some(HashMap::new()),
+            state_diff: Some(HashMap::new()),
+            ..Default::default()
+        },
+    );
+    assert!(state.apply_override(&state_override).is_err());
+}
+
 // #[test]
 // fn test_automatic_collateral_contract_account() {
 //     let storage_manager = new_state_manager_for_unit_test();
@@ -29,6 +29,9 @@ pub struct Substate {
     pub logs: Vec<LogEntry>,
     /// Created contracts.
     pub contracts_created: Vec<AddressWithSpace>,
+    /// The gas refunded at the end of the transaction, e.g., for the existing
+    /// authorities of EIP-7702.
+    pub refund: u64,
 }
 
 impl Substate {
@@ -37,6 +40,7 @@ impl Substate {
         self.touched.extend(s.touched);
         self.logs.extend(s.logs);
         self.contracts_created.extend(s.contracts_created);
+        self.refund += s.refund;
         for (address, amount) in s.storage_collateralized {
             *self.storage_collateralized.entry(address).or_insert(0) += amount;
         }
@@ -1,6 +1,7 @@
 use crate::{
     config::TracingInspectorConfig,
     fourbyte::FourByteInspector,
+    js_tracer::JsTracer,
     tracing_inspector::TracingInspector,
     types::{LogCallOrder, TxExecContext},
     utils::{to_alloy_address, to_alloy_h256, to_alloy_u256},
@@ -8,8 +9,8 @@ use crate::{
 use alloy_primitives::{Address, Bytes, LogData};
 use alloy_rpc_types_trace::geth::{
     CallConfig, GethDebugBuiltInTracerType, GethDebugBuiltInTracerType::*,
-    GethDebugTracerType, GethDebugTracingOptions, GethTrace, NoopFrame,
-    PreStateConfig,
+    GethDebugTracerType, GethDebugTracingOptions, GethTrace, MuxFrame,
+    NoopFrame, PreStateConfig,
 };
 use cfx_executor::{
     machine::Machine,
@@ -18,6 +19,7 @@ use cfx_executor::{
         OpcodeTracer, StorageTracer,
     },
     stack::{FrameResult, FrameReturn},
+    state::State as ExecutorState,
 };
 use cfx_types::H160;
 use cfx_vm_types::{ActionParams, CallType, Error, InterpreterInfo};
@@ -26,13 +28,17 @@ use revm::{
     interpreter::{Gas, InstructionResult, InterpreterResult},
     primitives::State,
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 pub struct GethTracer {
     inner: TracingInspector,
     //
     fourbyte_inspector: FourByteInspector,
     //
+    js_tracer: Option<JsTracer>,
+    // inner tracers of the muxTracer
+    mux_tracers: Vec<(GethDebugBuiltInTracerType, GethTracer)>,
+    //
     tx_gas_limit: u64, // tx level gas limit
     //
     gas_left: u64, // update in call_result/create_result
@@ -50,6 +56,43 @@ impl GethTracer {
         opts: GethDebugTracingOptions,
     ) -> Self {
         let TxExecContext { tx_gas_limit, .. } = tx_exec_context;
+        let js_tracer = match &opts.tracer {
+            Some(GethDebugTracerType::JsTracer(code)) => Some(JsTracer::new(
+                code,
+                &opts,
+                &tx_exec_context,
+                machine.clone(),
+            )),
+            _ => None,
+        };
+        let mux_tracers = match &opts.tracer {
+            Some(GethDebugTracerType::BuiltInTracer(MuxTracer)) => opts
+                .tracer_config
+                .clone()
+                .into_mux_config()
+                // the config is validated by the RPC layer, a malformed one
+                // traces nothing instead of panicking
+                .map(|config| config.0)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(tracer, config)| {
+                    let opts = GethDebugTracingOptions {
+                        tracer: Some(GethDebugTracerType::BuiltInTracer(
+                            tracer,
+                        )),
+                        tracer_config: config.unwrap_or_default(),
+                        ..opts.clone()
+                    };
+                    let inner = GethTracer::new(
+                        tx_exec_context.clone(),
+                        machine.clone(),
+                        opts,
+                    );
+                    (tracer, inner)
+                })
+                .collect(),
+            _ => vec![],
+        };
         let config = match opts.tracer {
             Some(GethDebugTracerType::BuiltInTracer(builtin_tracer)) => {
                 match builtin_tracer {
@@ -83,6 +126,8 @@ impl GethTracer {
         Self {
             inner: TracingInspector::new(config, machine, tx_exec_context),
             fourbyte_inspector: FourByteInspector::new(),
+            js_tracer,
+            mux_tracers,
             tx_gas_limit,
             depth: 0,
             gas_left: tx_gas_limit,
@@ -118,9 +163,25 @@ impl GethTracer {
         self.tracer_type() == Some(FourByteTracer)
     }
 
+    fn is_mux_tracer(&self) -> bool { self.tracer_type() == Some(MuxTracer) }
+
     pub fn gas_used(&self) -> u64 { self.tx_gas_limit - self.gas_left }
 
     pub fn drain(self) -> GethTrace {
+        if let Some(js_tracer) = self.js_tracer {
+            let gas_used = self.tx_gas_limit - self.gas_left;
+            return GethTrace::JS(js_tracer.drain(gas_used));
+        }
+
+        if self.is_mux_tracer() {
+            let frames = self
+                .mux_tracers
+                .into_iter()
+                .map(|(tracer, inner)| (tracer, inner.drain()))
+                .collect::<HashMap<_, _>>();
+            return GethTrace::MuxTracer(MuxFrame(frames));
+        }
+
         let trace = match self.tracer_type() {
             Some(t) => match t {
                 FourByteTracer => self.fourbyte_inspector.drain(),
@@ -191,6 +252,18 @@ impl StorageTracer for GethTracer {}
 
 impl CallTracer for GethTracer {
     fn record_call(&mut self, params: &ActionParams) {
+        if let Some(js_tracer) = &mut self.js_tracer {
+            js_tracer.record_call(params);
+        }
+
+        for (_, inner) in &mut self.mux_tracers {
+            inner.record_call(params);
+        }
+
+        if self.is_mux_tracer() {
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             self.fourbyte_inspector.record_call(params);
             return;
@@ -242,7 +315,15 @@ impl CallTracer for GethTracer {
     }
 
     fn record_call_result(&mut self, result: &FrameResult) {
-        if self.is_fourbyte_tracer() {
+        if let Some(js_tracer) = &mut self.js_tracer {
+            js_tracer.record_call_result(result);
+        }
+
+        for (_, inner) in &mut self.mux_tracers {
+            inner.record_call_result(result);
+        }
+
+        if self.is_fourbyte_tracer() || self.is_mux_tracer() {
             return;
         }
 
@@ -275,7 +356,15 @@ impl CallTracer for GethTracer {
     }
 
     fn record_create(&mut self, params: &ActionParams) {
-        if self.is_fourbyte_tracer() {
+        if let Some(js_tracer) = &mut self.js_tracer {
+            js_tracer.record_create(params);
+        }
+
+        for (_, inner) in &mut self.mux_tracers {
+            inner.record_create(params);
+        }
+
+        if self.is_fourbyte_tracer() || self.is_mux_tracer() {
             return;
         }
 
@@ -309,7 +398,15 @@ impl CallTracer for GethTracer {
     }
 
     fn record_create_result(&mut self, result: &FrameResult) {
-        if self.is_fourbyte_tracer() {
+        if let Some(js_tracer) = &mut self.js_tracer {
+            js_tracer.record_create_result(result);
+        }
+
+        for (_, inner) in &mut self.mux_tracers {
+            inner.record_create_result(result);
+        }
+
+        if self.is_fourbyte_tracer() || self.is_mux_tracer() {
             return;
         }
 
@@ -348,6 +445,19 @@ impl CallTracer for GethTracer {
         self.inner
             .fill_trace_on_call_end(outcome, create_address, gas_spent);
     }
+
+    fn record_frame_error(&mut self, error: &Error, state: &ExecutorState) {
+        if let Some(js_tracer) = &mut self.js_tracer {
+            js_tracer.record_frame_error(error, state);
+        }
+    }
+
+    fn record_tx_end(&mut self, state: &ExecutorState) {
+        let gas_used = self.gas_used();
+        if let Some(js_tracer) = &mut self.js_tracer {
+            js_tracer.record_tx_end(gas_used, state);
+        }
+    }
 }
 
 impl OpcodeTracer for GethTracer {
@@ -355,15 +465,29 @@ impl OpcodeTracer for GethTracer {
         if self.inner.config.record_steps {
             *enabled |= true;
         }
+        if let Some(js_tracer) = &self.js_tracer {
+            *enabled |= js_tracer.traces_opcode();
+        }
+        for (_, inner) in &self.mux_tracers {
+            inner.do_trace_opcode(enabled);
+        }
     }
 
     fn initialize_interp(&mut self, gas_limit: cfx_types::U256) {
+        for (_, inner) in &mut self.mux_tracers {
+            inner.initialize_interp(gas_limit);
+        }
+
         self.inner
             .gas_inspector
             .set_gas_remainning(gas_limit.as_u64());
     }
 
     fn step(&mut self, interp: &dyn InterpreterInfo) {
+        for (_, inner) in &mut self.mux_tracers {
+            inner.step(interp);
+        }
+
         self.inner
             .gas_inspector
             .set_gas_remainning(interp.gas_remainning().as_u64());
@@ -371,9 +495,30 @@ impl OpcodeTracer for GethTracer {
         if self.inner.config.record_steps {
             self.inner.start_step(interp, self.depth as u64);
         }
+
+        if let Some(js_tracer) = &mut self.js_tracer {
+            js_tracer.step(interp);
+        }
+    }
+
+    fn step_cost(
+        &mut self, interp: &dyn InterpreterInfo, gas_cost: cfx_types::U256,
+        state: &ExecutorState,
+    ) {
+        for (_, inner) in &mut self.mux_tracers {
+            inner.step_cost(interp, gas_cost, state);
+        }
+
+        if let Some(js_tracer) = &mut self.js_tracer {
+            js_tracer.step_cost(interp, gas_cost, state);
+        }
     }
 
     fn step_end(&mut self, interp: &dyn InterpreterInfo) {
+        for (_, inner) in &mut self.mux_tracers {
+            inner.step_end(interp);
+        }
+
         let remainning = interp.gas_remainning().as_u64();
         let last_gas_cost = self
             .inner
@@ -390,9 +535,13 @@ impl OpcodeTracer for GethTracer {
     }
 
     fn log(
-        &mut self, _address: &cfx_types::Address,
-        topics: &Vec<cfx_types::H256>, data: &[u8],
+        &mut self, address: &cfx_types::Address, topics: &Vec<cfx_types::H256>,
+        data: &[u8],
     ) {
+        for (_, inner) in &mut self.mux_tracers {
+            inner.log(address, topics, data);
+        }
+
         if self.inner.config.record_logs {
             let trace_idx = self.inner.last_trace_idx();
             let trace = &mut self.inner.traces.arena[trace_idx];
@@ -405,10 +554,14 @@ impl OpcodeTracer for GethTracer {
     }
 
     fn selfdestruct(
-        &mut self, _contract: &cfx_types::Address, target: &cfx_types::Address,
-        _value: cfx_types::U256,
+        &mut self, contract: &cfx_types::Address, target: &cfx_types::Address,
+        value: cfx_types::U256,
     ) {
-        if self.is_fourbyte_tracer() {
+        for (_, inner) in &mut self.mux_tracers {
+            inner.selfdestruct(contract, target, value);
+        }
+
+        if self.is_fourbyte_tracer() || self.is_mux_tracer() {
             return;
         }
 
@@ -452,6 +605,9 @@ pub fn to_instruction_result(frame_result: &FrameResult) -> InstructionResult {
             Error::InvalidAddress(_) => todo!(), /* when selfdestruct refund */
             // address is invalid will emit this error
             Error::ConflictAddress(_) => InstructionResult::CreateCollision,
+            Error::CreateContractStartingWithEF => {
+                InstructionResult::CreateContractStartingWithEF
+            }
         },
     };
     result
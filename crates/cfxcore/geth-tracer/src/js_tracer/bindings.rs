@@ -0,0 +1,549 @@
+//! The objects exposed to the JS tracer scripts: `log`, `db`, the call frames
+//! and the global helper functions of geth.
+
+use super::Shared;
+use alloy_primitives::hex;
+use boa_engine::{
+    js_string,
+    native_function::NativeFunction,
+    object::{builtins::JsUint8Array, ObjectInitializer},
+    property::Attribute,
+    Context, JsArgs, JsBigInt, JsError, JsNativeError, JsObject, JsResult,
+    JsString, JsValue,
+};
+use cfx_types::{Address, AddressSpaceUtil, H256, U256};
+use cfx_vm_types::{contract_address, CreateContractAddress};
+use revm::interpreter::OpCode;
+use std::rc::Rc;
+
+/// geth pads the memory slices up to this size.
+const MEMORY_PADDING_LIMIT: usize = 1024 * 1024;
+
+/// The longest byte array read from the script, far beyond the memory an
+/// execution can afford within the block gas limit.
+const MAX_BYTES_LENGTH: u64 = 16 * 1024 * 1024;
+
+/// Wraps a closure over the shared tracer data as a JS function.
+fn method<F>(shared: &Rc<Shared>, f: F) -> NativeFunction
+where F: Fn(&Shared, &[JsValue], &mut Context) -> JsResult<JsValue> + 'static {
+    let shared = shared.clone();
+    // SAFETY: the closure only captures an `Rc` of plain Rust data, which
+    // holds no garbage collected value and needs no tracing.
+    unsafe {
+        NativeFunction::from_closure(move |_this, args, ctx| {
+            f(&shared, args, ctx)
+        })
+    }
+}
+
+pub(super) fn error(message: impl Into<String>) -> JsError {
+    JsNativeError::error().with_message(message.into()).into()
+}
+
+fn type_error(message: impl Into<String>) -> JsError {
+    JsNativeError::typ().with_message(message.into()).into()
+}
+
+pub(super) fn to_buf(bytes: &[u8], ctx: &mut Context) -> JsResult<JsValue> {
+    Ok(JsUint8Array::from_iter(bytes.iter().copied(), ctx)?.into())
+}
+
+pub(super) fn to_bigint(value: U256) -> JsValue {
+    JsValue::BigInt(
+        JsBigInt::from_string(&value.to_string())
+            .expect("U256 is displayed in decimal"),
+    )
+}
+
+pub(super) fn to_number(value: u64) -> JsValue { JsValue::from(value as f64) }
+
+/// Reads a byte array, a `Uint8Array` or a hex string.
+fn bytes_from_js(value: &JsValue, ctx: &mut Context) -> JsResult<Vec<u8>> {
+    if let Some(s) = value.as_string() {
+        let s = s.to_std_string_escaped();
+        return hex::decode(s.trim_start_matches("0x"))
+            .map_err(|e| type_error(format!("invalid hex string: {}", e)));
+    }
+
+    let obj = value
+        .as_object()
+        .ok_or_else(|| type_error("expected a byte array or a hex string"))?;
+    let len = obj.get(js_string!("length"), ctx)?.to_length(ctx)?;
+    if len > MAX_BYTES_LENGTH {
+        return Err(type_error(format!(
+            "byte array too long: {} > {}",
+            len, MAX_BYTES_LENGTH
+        )));
+    }
+    let len = len as usize;
+    let mut bytes = Vec::with_capacity(len);
+    for i in 0..len {
+        bytes.push(obj.get(i, ctx)?.to_uint8(ctx)?);
+    }
+    Ok(bytes)
+}
+
+/// Reads an address, keeping the last 20 bytes of longer inputs as geth.
+fn address_from_js(value: &JsValue, ctx: &mut Context) -> JsResult<Address> {
+    let bytes = bytes_from_js(value, ctx)?;
+    let mut address = Address::zero();
+    let len = bytes.len().min(20);
+    address.0[20 - len..].copy_from_slice(&bytes[bytes.len() - len..]);
+    Ok(address)
+}
+
+fn word_from_js(value: &JsValue, ctx: &mut Context) -> JsResult<H256> {
+    let bytes = bytes_from_js(value, ctx)?;
+    let mut word = H256::zero();
+    let len = bytes.len().min(32);
+    word.0[32 - len..].copy_from_slice(&bytes[bytes.len() - len..]);
+    Ok(word)
+}
+
+fn op_name(op: u8) -> String {
+    match OpCode::new(op) {
+        Some(op) => op.as_str().to_owned(),
+        None => format!("opcode {:#x} not defined", op),
+    }
+}
+
+/// Copies `memory[offset..offset + len]`, padded with zeros.
+fn memory_slice(memory: &[u8], offset: usize, len: usize) -> JsResult<Vec<u8>> {
+    let end = offset
+        .checked_add(len)
+        .filter(|end| *end <= memory.len() + MEMORY_PADDING_LIMIT)
+        .ok_or_else(|| {
+            error(format!(
+                "tracer accessed out of bound memory: offset {}, len {}",
+                offset, len
+            ))
+        })?;
+
+    let mut slice = vec![0u8; len];
+    if offset < memory.len() {
+        let available = end.min(memory.len()) - offset;
+        slice[..available].copy_from_slice(&memory[offset..offset + available]);
+    }
+    Ok(slice)
+}
+
+/// Builds the `log` object passed to `step` and `fault`. The same object is
+/// reused during the whole execution and reads the current step.
+pub(super) fn log_object(shared: &Rc<Shared>, ctx: &mut Context) -> JsObject {
+    let op = ObjectInitializer::new(ctx)
+        .function(
+            method(shared, |s, _, _| {
+                let op = s.step.borrow().op;
+                Ok(JsValue::from((0x60..=0x7f).contains(&op)))
+            }),
+            js_string!("isPush"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, _| {
+                Ok(JsString::from(op_name(s.step.borrow().op)).into())
+            }),
+            js_string!("getName"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, _| {
+                Ok(JsString::from(op_name(s.step.borrow().op)).into())
+            }),
+            js_string!("toString"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, _| Ok(to_number(s.step.borrow().op as u64))),
+            js_string!("toNumber"),
+            0,
+        )
+        .build();
+
+    let stack = ObjectInitializer::new(ctx)
+        .function(
+            method(shared, |s, args, ctx| {
+                let index = args.get_or_undefined(0).to_index(ctx)? as usize;
+                s.interp.with("stack", |interp| {
+                    let stack = interp.stack();
+                    if index >= stack.len() {
+                        return Err(error(format!(
+                            "tracer accessed out of bound stack: size {}, index {}",
+                            stack.len(),
+                            index
+                        )));
+                    }
+                    Ok(to_bigint(stack[stack.len() - 1 - index]))
+                })
+            }),
+            js_string!("peek"),
+            1,
+        )
+        .function(
+            method(shared, |s, _, _| {
+                s.interp.with("stack", |interp| {
+                    Ok(to_number(interp.stack().len() as u64))
+                })
+            }),
+            js_string!("length"),
+            0,
+        )
+        .build();
+
+    let memory = ObjectInitializer::new(ctx)
+        .function(
+            method(shared, |s, args, ctx| {
+                let begin = args.get_or_undefined(0).to_index(ctx)? as usize;
+                let end = args.get_or_undefined(1).to_index(ctx)? as usize;
+                if end < begin {
+                    return Err(error(format!(
+                        "tracer accessed out of bound memory: begin {}, end {}",
+                        begin, end
+                    )));
+                }
+                let slice = s.interp.with("memory", |interp| {
+                    memory_slice(interp.mem(), begin, end - begin)
+                })?;
+                to_buf(&slice, ctx)
+            }),
+            js_string!("slice"),
+            2,
+        )
+        .function(
+            method(shared, |s, args, ctx| {
+                let offset = args.get_or_undefined(0).to_index(ctx)? as usize;
+                s.interp.with("memory", |interp| {
+                    let word = memory_slice(interp.mem(), offset, 32)?;
+                    Ok(to_bigint(U256::from_big_endian(&word)))
+                })
+            }),
+            js_string!("getUint"),
+            1,
+        )
+        .function(
+            method(shared, |s, _, _| {
+                s.interp.with("memory", |interp| {
+                    Ok(to_number(interp.mem().len() as u64))
+                })
+            }),
+            js_string!("length"),
+            0,
+        )
+        .build();
+
+    let contract = ObjectInitializer::new(ctx)
+        .function(
+            method(shared, |s, _, ctx| {
+                to_buf(s.current_frame()?.caller.as_bytes(), ctx)
+            }),
+            js_string!("getCaller"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, ctx| {
+                to_buf(s.current_frame()?.address.as_bytes(), ctx)
+            }),
+            js_string!("getAddress"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, _| Ok(to_bigint(s.current_frame()?.value))),
+            js_string!("getValue"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, ctx| {
+                let input = s.current_frame()?.input.clone();
+                to_buf(&input, ctx)
+            }),
+            js_string!("getInput"),
+            0,
+        )
+        .build();
+
+    ObjectInitializer::new(ctx)
+        .property(js_string!("op"), op, Attribute::all())
+        .property(js_string!("stack"), stack, Attribute::all())
+        .property(js_string!("memory"), memory, Attribute::all())
+        .property(js_string!("contract"), contract, Attribute::all())
+        .function(
+            method(shared, |s, _, _| Ok(to_number(s.step.borrow().pc))),
+            js_string!("getPC"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, _| Ok(to_number(s.step.borrow().gas))),
+            js_string!("getGas"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, _| Ok(to_number(s.step.borrow().cost))),
+            js_string!("getCost"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, _| Ok(to_number(s.step.borrow().depth))),
+            js_string!("getDepth"),
+            0,
+        )
+        .function(
+            method(shared, |s, _, _| {
+                Ok(match &s.step.borrow().error {
+                    Some(err) => JsString::from(err.as_str()).into(),
+                    None => JsValue::undefined(),
+                })
+            }),
+            js_string!("getError"),
+            0,
+        )
+        .build()
+}
+
+/// Builds the `db` object, which reads the state of the current space. It is
+/// only accessible in `step`, while the instruction is not executed yet.
+pub(super) fn db_object(shared: &Rc<Shared>, ctx: &mut Context) -> JsObject {
+    ObjectInitializer::new(ctx)
+        .function(
+            method(shared, |s, args, ctx| {
+                let address = address_from_js(args.get_or_undefined(0), ctx)?
+                    .with_space(s.space());
+                s.state.with("db", |state| {
+                    state
+                        .balance(&address)
+                        .map(to_bigint)
+                        .map_err(|e| error(e.to_string()))
+                })
+            }),
+            js_string!("getBalance"),
+            1,
+        )
+        .function(
+            method(shared, |s, args, ctx| {
+                let address = address_from_js(args.get_or_undefined(0), ctx)?
+                    .with_space(s.space());
+                s.state.with("db", |state| {
+                    state
+                        .nonce(&address)
+                        .map(|nonce| to_number(nonce.low_u64()))
+                        .map_err(|e| error(e.to_string()))
+                })
+            }),
+            js_string!("getNonce"),
+            1,
+        )
+        .function(
+            method(shared, |s, args, ctx| {
+                let address = address_from_js(args.get_or_undefined(0), ctx)?
+                    .with_space(s.space());
+                let code = s.state.with("db", |state| {
+                    state.code(&address).map_err(|e| error(e.to_string()))
+                })?;
+                to_buf(code.as_ref().map_or(&[][..], |code| &code[..]), ctx)
+            }),
+            js_string!("getCode"),
+            1,
+        )
+        .function(
+            method(shared, |s, args, ctx| {
+                let address = address_from_js(args.get_or_undefined(0), ctx)?
+                    .with_space(s.space());
+                let key = word_from_js(args.get_or_undefined(1), ctx)?;
+                let value = s.state.with("db", |state| {
+                    state
+                        .storage_at(&address, key.as_bytes())
+                        .map_err(|e| error(e.to_string()))
+                })?;
+                let mut word = [0u8; 32];
+                value.to_big_endian(&mut word);
+                to_buf(&word, ctx)
+            }),
+            js_string!("getState"),
+            2,
+        )
+        .function(
+            method(shared, |s, args, ctx| {
+                let address = address_from_js(args.get_or_undefined(0), ctx)?
+                    .with_space(s.space());
+                s.state.with("db", |state| {
+                    state
+                        .exists(&address)
+                        .map(JsValue::from)
+                        .map_err(|e| error(e.to_string()))
+                })
+            }),
+            js_string!("exists"),
+            1,
+        )
+        .build()
+}
+
+/// The call frame passed to `enter`.
+pub(super) struct FrameData {
+    pub typ: &'static str,
+    pub from: Address,
+    pub to: Address,
+    pub input: Vec<u8>,
+    pub gas: u64,
+    pub value: Option<U256>,
+}
+
+pub(super) fn frame_object(
+    frame: FrameData, ctx: &mut Context,
+) -> JsResult<JsObject> {
+    let from = to_buf(frame.from.as_bytes(), ctx)?;
+    let to = to_buf(frame.to.as_bytes(), ctx)?;
+    let input = to_buf(&frame.input, ctx)?;
+    let value = frame.value.map_or(JsValue::undefined(), to_bigint);
+    let typ = JsValue::from(JsString::from(frame.typ));
+    let gas = to_number(frame.gas);
+
+    Ok(ObjectInitializer::new(ctx)
+        .function(constant(typ), js_string!("getType"), 0)
+        .function(constant(from), js_string!("getFrom"), 0)
+        .function(constant(to), js_string!("getTo"), 0)
+        .function(constant(input), js_string!("getInput"), 0)
+        .function(constant(gas), js_string!("getGas"), 0)
+        .function(constant(value), js_string!("getValue"), 0)
+        .build())
+}
+
+/// The call frame result passed to `exit`.
+pub(super) struct FrameResultData {
+    pub gas_used: u64,
+    pub output: Vec<u8>,
+    pub error: Option<String>,
+}
+
+pub(super) fn frame_result_object(
+    result: FrameResultData, ctx: &mut Context,
+) -> JsResult<JsObject> {
+    let gas_used = to_number(result.gas_used);
+    let output = to_buf(&result.output, ctx)?;
+    let error = result.error.map_or(JsValue::undefined(), |err| {
+        JsString::from(err.as_str()).into()
+    });
+
+    Ok(ObjectInitializer::new(ctx)
+        .function(constant(gas_used), js_string!("getGasUsed"), 0)
+        .function(constant(output), js_string!("getOutput"), 0)
+        .function(constant(error), js_string!("getError"), 0)
+        .build())
+}
+
+/// A function returning `value`.
+fn constant(value: JsValue) -> NativeFunction {
+    NativeFunction::from_copy_closure_with_captures(
+        |_this, _args, value, _ctx| Ok(value.clone()),
+        value,
+    )
+}
+
+/// Registers the global helper functions of geth: `toHex`, `toWord`,
+/// `toAddress`, `toContract`, `toContract2`, `isPrecompiled` and `slice`.
+pub(super) fn register_builtins(
+    shared: &Rc<Shared>, ctx: &mut Context,
+) -> JsResult<()> {
+    ctx.register_global_callable(
+        js_string!("toHex"),
+        1,
+        NativeFunction::from_fn_ptr(|_this, args, ctx| {
+            let bytes = bytes_from_js(args.get_or_undefined(0), ctx)?;
+            Ok(JsString::from(format!("0x{}", hex::encode(bytes))).into())
+        }),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toWord"),
+        1,
+        NativeFunction::from_fn_ptr(|_this, args, ctx| {
+            let word = word_from_js(args.get_or_undefined(0), ctx)?;
+            to_buf(word.as_bytes(), ctx)
+        }),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toAddress"),
+        1,
+        NativeFunction::from_fn_ptr(|_this, args, ctx| {
+            let address = address_from_js(args.get_or_undefined(0), ctx)?;
+            to_buf(address.as_bytes(), ctx)
+        }),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toContract"),
+        2,
+        NativeFunction::from_fn_ptr(|_this, args, ctx| {
+            let from = address_from_js(args.get_or_undefined(0), ctx)?;
+            let nonce = args.get_or_undefined(1).to_index(ctx)?;
+            let (address, _) = contract_address(
+                CreateContractAddress::FromSenderNonce,
+                0,
+                &from,
+                &U256::from(nonce),
+                &[],
+            );
+            to_buf(address.as_bytes(), ctx)
+        }),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toContract2"),
+        3,
+        NativeFunction::from_fn_ptr(|_this, args, ctx| {
+            let from = address_from_js(args.get_or_undefined(0), ctx)?;
+            let salt = word_from_js(args.get_or_undefined(1), ctx)?;
+            let code = bytes_from_js(args.get_or_undefined(2), ctx)?;
+            let (address, _) = contract_address(
+                CreateContractAddress::FromSenderSaltAndCodeHash(salt),
+                0,
+                &from,
+                &U256::zero(),
+                &code,
+            );
+            to_buf(address.as_bytes(), ctx)
+        }),
+    )?;
+    ctx.register_global_callable(
+        js_string!("isPrecompiled"),
+        1,
+        method(shared, |s, args, ctx| {
+            let address = address_from_js(args.get_or_undefined(0), ctx)?;
+            Ok(JsValue::from(s.is_precompile(&address)))
+        }),
+    )?;
+    ctx.register_global_callable(
+        js_string!("slice"),
+        3,
+        NativeFunction::from_fn_ptr(|_this, args, ctx| {
+            let bytes = bytes_from_js(args.get_or_undefined(0), ctx)?;
+            let start = args.get_or_undefined(1).to_index(ctx)? as usize;
+            let end = args.get_or_undefined(2).to_index(ctx)? as usize;
+            if start > end || end > bytes.len() {
+                return Err(error(format!(
+                    "tracer accessed out of bound slice: size {}, start {}, end {}",
+                    bytes.len(),
+                    start,
+                    end
+                )));
+            }
+            to_buf(&bytes[start..end], ctx)
+        }),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boa_engine::Source;
+
+    #[test]
+    fn test_bytes_from_js() {
+        let mut ctx = Context::default();
+        let bytes = ctx.eval(Source::from_bytes("[1, 2, 255]")).unwrap();
+        assert_eq!(bytes_from_js(&bytes, &mut ctx).unwrap(), vec![1, 2, 255]);
+        let hex = JsValue::from(js_string!("0x0102"));
+        assert_eq!(bytes_from_js(&hex, &mut ctx).unwrap(), vec![1, 2]);
+
+        // the length is checked before allocating
+        let huge = ctx.eval(Source::from_bytes("({length: 1e15})")).unwrap();
+        assert!(bytes_from_js(&huge, &mut ctx).is_err());
+    }
+}
@@ -0,0 +1,882 @@
+//! A geth compatible JavaScript tracer, see
+//! <https://geth.ethereum.org/docs/developers/evm-tracing/custom-tracer#custom-javascript-tracing>.
+//!
+//! The script is an object with the `result` and `fault` functions, and
+//! optionally `setup`, `step`, `enter` and `exit`:
+//! - `setup(config)` receives the `tracerConfig` of the request.
+//! - `step(log, db)` is called before each instruction.
+//! - `fault(log, db)` is called when an instruction fails.
+//! - `enter(frame)` and `exit(frameResult)` are called around the inner calls.
+//! - `result(ctx, db)` returns the JSON result of the tracer.
+//!
+//! Unlike geth, big numbers are native JS `BigInt`s. `db` reads the state
+//! before the traced instruction in `step`, at the failure in `fault`, and
+//! after the refund of the unused gas in `result`.
+
+mod bindings;
+
+use crate::types::TxExecContext;
+use alloy_rpc_types_trace::geth::GethDebugTracingOptions;
+use bindings::{
+    db_object, error, frame_object, frame_result_object, log_object,
+    register_builtins, to_bigint, to_buf, to_number, FrameData,
+    FrameResultData,
+};
+use boa_engine::{
+    js_string,
+    object::{builtins::JsArray, ObjectInitializer},
+    property::Attribute,
+    Context, JsObject, JsResult, JsString, JsValue, Script, Source,
+};
+use cfx_executor::{
+    machine::Machine,
+    stack::{FrameResult, FrameReturn},
+    state::State,
+};
+use cfx_types::{Address, AddressSpaceUtil, Space, U256};
+use cfx_vm_types::{
+    ActionParams, CallType, CreateType, Error as VmError, InterpreterInfo,
+};
+use serde_json::Value;
+use std::{
+    cell::{Cell, Ref, RefCell},
+    future::Future,
+    pin::pin,
+    rc::Rc,
+    sync::Arc,
+    task::{self, Poll, RawWaker, RawWakerVTable, Waker},
+    time::{Duration, Instant},
+};
+
+/// The timeout of the tracer if the request doesn't set one, the same as
+/// geth.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The loops in a single call of the script are bounded, since the timeout
+/// is not checked while a native function (e.g., `Array.prototype.map`) runs
+/// its callback.
+const LOOP_ITERATION_LIMIT: u64 = 1 << 24;
+
+/// The "clock cycles" of the JS engine between two checks of the timeout
+/// while a function of the script is running.
+const TIMEOUT_CHECK_BUDGET: u32 = 1 << 12;
+
+/// Calls the function of the global `__hook` on `__tracer` with the arguments
+/// of `__args`, as a script which can be suspended to check the timeout.
+const INVOKE_HOOK: &str = "__hook.apply(__tracer, __args)";
+
+/// A reference lent to the JS bindings while a function of the script is
+/// running, so that the script reads the interpreter and the state without
+/// copying them at each step.
+struct Scoped<T: ?Sized + 'static>(Cell<Option<*const T>>);
+
+impl<T: ?Sized + 'static> Scoped<T> {
+    fn new() -> Self { Scoped(Cell::new(None)) }
+
+    /// Lends `value` to the bindings during `f`.
+    ///
+    /// # Safety
+    ///
+    /// `value` must stay valid until `f` returns.
+    unsafe fn lend<R>(&self, value: *const T, f: impl FnOnce() -> R) -> R {
+        self.0.set(Some(value));
+        let result = f();
+        self.0.set(None);
+        result
+    }
+
+    fn with<R>(
+        &self, name: &str, f: impl FnOnce(&T) -> JsResult<R>,
+    ) -> JsResult<R> {
+        match self.0.get() {
+            // SAFETY: the pointer is only set while it is lent.
+            Some(value) => f(unsafe { &*value }),
+            None => Err(error(format!("{} is not accessible here", name))),
+        }
+    }
+}
+
+/// The scalar fields of the traced instruction.
+#[derive(Default)]
+struct StepLog {
+    pc: u64,
+    op: u8,
+    gas: u64,
+    cost: u64,
+    depth: u64,
+    error: Option<String>,
+}
+
+/// The contract executed by a call frame.
+struct ContractInfo {
+    space: Space,
+    caller: Address,
+    address: Address,
+    value: U256,
+    input: Vec<u8>,
+    gas: u64,
+}
+
+/// The data shared between the tracer and the JS bindings.
+struct Shared {
+    interp: Scoped<dyn InterpreterInfo>,
+    state: Scoped<State>,
+    step: RefCell<StepLog>,
+    frames: RefCell<Vec<ContractInfo>>,
+    machine: Arc<Machine>,
+    block_number: u64,
+}
+
+impl Shared {
+    fn current_frame(&self) -> JsResult<Ref<'_, ContractInfo>> {
+        let frames = self.frames.borrow();
+        if frames.is_empty() {
+            return Err(error("no contract is executing"));
+        }
+        Ok(Ref::map(frames, |frames| frames.last().unwrap()))
+    }
+
+    fn space(&self) -> Space {
+        self.frames
+            .borrow()
+            .last()
+            .map_or(Space::Ethereum, |frame| frame.space)
+    }
+
+    fn is_precompile(&self, address: &Address) -> bool {
+        self.machine
+            .builtin(&address.with_space(self.space()), self.block_number)
+            .is_some()
+    }
+}
+
+/// The functions of the script.
+struct Hooks {
+    this: JsValue,
+    invoke: Script,
+    result: JsObject,
+    fault: JsObject,
+    step: Option<JsObject>,
+    enter: Option<JsObject>,
+    exit: Option<JsObject>,
+}
+
+impl Hooks {
+    /// Evaluates the tracer object and calls its `setup`, failing once
+    /// `deadline` is passed as the other functions of the script.
+    fn compile(
+        code: &str, config: &Value, deadline: Instant, ctx: &mut Context,
+    ) -> Result<Self, String> {
+        let source = format!("({})", code);
+        let script =
+            Script::parse(Source::from_bytes(source.as_bytes()), None, ctx)
+                .map_err(|e| e.to_string())?;
+        let obj =
+            evaluate(&script, deadline, ctx).map_err(|e| e.to_string())?;
+        let obj = obj
+            .as_object()
+            .cloned()
+            .ok_or("the tracer must be a JS object")?;
+
+        let result = hook(&obj, "result", ctx)?
+            .ok_or("trace object must expose a function result()")?;
+        let fault = hook(&obj, "fault", ctx)?
+            .ok_or("trace object must expose a function fault()")?;
+        let step = hook(&obj, "step", ctx)?;
+        let enter = hook(&obj, "enter", ctx)?;
+        let exit = hook(&obj, "exit", ctx)?;
+        if enter.is_some() != exit.is_some() {
+            return Err("trace object must expose either both or none of \
+                        enter() and exit()"
+                .into());
+        }
+
+        let this = JsValue::from(obj.clone());
+        let invoke = Script::parse(Source::from_bytes(INVOKE_HOOK), None, ctx)
+            .map_err(|e| e.to_string())?;
+        if let Some(setup) = hook(&obj, "setup", ctx)? {
+            let config =
+                JsValue::from_json(config, ctx).map_err(|e| e.to_string())?;
+            call_hook(&invoke, &setup, &this, &[config], deadline, ctx)
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(Hooks {
+            this,
+            invoke,
+            result,
+            fault,
+            step,
+            enter,
+            exit,
+        })
+    }
+
+    /// Calls `func` on the tracer object, failing once `deadline` is passed.
+    fn call(
+        &self, func: &JsObject, args: &[JsValue], deadline: Instant,
+        ctx: &mut Context,
+    ) -> JsResult<JsValue> {
+        call_hook(&self.invoke, func, &self.this, args, deadline, ctx)
+    }
+}
+
+/// Calls `func` on `this` through the `invoke` script of `INVOKE_HOOK`,
+/// failing once `deadline` is passed.
+fn call_hook(
+    invoke: &Script, func: &JsObject, this: &JsValue, args: &[JsValue],
+    deadline: Instant, ctx: &mut Context,
+) -> JsResult<JsValue> {
+    let global = ctx.global_object();
+    let args = JsArray::from_iter(args.iter().cloned(), ctx);
+    global.set(js_string!("__hook"), func.clone(), false, ctx)?;
+    global.set(js_string!("__tracer"), this.clone(), false, ctx)?;
+    global.set(js_string!("__args"), args, false, ctx)?;
+    evaluate(invoke, deadline, ctx)
+}
+
+/// Evaluates `script`, failing once `deadline` is passed.
+fn evaluate(
+    script: &Script, deadline: Instant, ctx: &mut Context,
+) -> JsResult<JsValue> {
+    let waker = noop_waker();
+    let mut cx = task::Context::from_waker(&waker);
+    let mut future =
+        pin!(script.evaluate_async_with_budget(ctx, TIMEOUT_CHECK_BUDGET));
+    // The script yields each time the budget is spent. A timed out script is
+    // dropped in the middle of its execution, which leaves the context
+    // unusable, but the tracing is aborted anyway.
+    loop {
+        if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+            return result;
+        }
+        if Instant::now() > deadline {
+            return Err(error("execution timeout"));
+        }
+    }
+}
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker { RawWaker::new(&(), &VTABLE) }
+    fn noop(_: *const ()) {}
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    // SAFETY: the waker does nothing and never reads its data.
+    unsafe { Waker::from_raw(clone(&())) }
+}
+
+/// Converts a gas amount, saturating at `u64::MAX` (e.g., for the cost of an
+/// out of gas memory expansion).
+fn saturating_u64(value: U256) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+fn hook(
+    obj: &JsObject, name: &str, ctx: &mut Context,
+) -> Result<Option<JsObject>, String> {
+    let value = obj
+        .get(JsString::from(name), ctx)
+        .map_err(|e| e.to_string())?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    match value.as_callable() {
+        Some(func) => Ok(Some(func.clone())),
+        None => Err(format!("{} of the tracer is not a function", name)),
+    }
+}
+
+fn new_context() -> Context {
+    let mut ctx = Context::default();
+    ctx.runtime_limits_mut()
+        .set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+    ctx
+}
+
+/// Checks that the JS tracer of the options compiles and that its timeout is
+/// valid, so that the errors are reported before tracing.
+pub fn validate_js_tracer(
+    code: &str, opts: &GethDebugTracingOptions,
+) -> Result<(), String> {
+    let timeout = parse_timeout(opts.timeout.as_deref())?;
+    Hooks::compile(
+        code,
+        &opts.tracer_config.0,
+        Instant::now() + timeout,
+        &mut new_context(),
+    )?;
+    Ok(())
+}
+
+/// Parses a go duration (e.g., `"300ms"` or `"1m30s"`) as geth.
+fn parse_timeout(timeout: Option<&str>) -> Result<Duration, String> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return Ok(DEFAULT_TIMEOUT),
+    };
+    let invalid = || format!("invalid timeout {:?}", timeout);
+
+    let mut rest = timeout;
+    let mut nanos = 0f64;
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(invalid)?;
+        let number: f64 = rest[..number_len].parse().map_err(|_| invalid())?;
+        rest = &rest[number_len..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let unit = match &rest[..unit_len] {
+            "ns" => 1.0,
+            "us" | "µs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[unit_len..];
+        nanos += number * unit;
+    }
+    Ok(Duration::from_nanos(nanos.round() as u64))
+}
+
+/// The fields of the `ctx` object passed to `result`.
+#[derive(Default)]
+struct TxContext {
+    typ: &'static str,
+    from: Address,
+    to: Address,
+    input: Vec<u8>,
+    gas: u64,
+    gas_price: U256,
+    value: U256,
+    output: Vec<u8>,
+    error: Option<String>,
+}
+
+pub struct JsTracer {
+    ctx: Context,
+    /// `None` if the script failed to compile.
+    hooks: Option<Hooks>,
+    shared: Rc<Shared>,
+    log: JsObject,
+    db: JsObject,
+    tx: TxContext,
+    started: Instant,
+    timeout: Duration,
+    /// The first error of the script, which aborts the tracing.
+    error: Option<String>,
+    /// The result computed with the final state, see `record_tx_end`.
+    outcome: Option<Result<Value, String>>,
+}
+
+impl JsTracer {
+    pub fn new(
+        code: &str, opts: &GethDebugTracingOptions,
+        tx_exec_context: &TxExecContext, machine: Arc<Machine>,
+    ) -> Self {
+        let started = Instant::now();
+        let mut ctx = new_context();
+        let shared = Rc::new(Shared {
+            interp: Scoped::new(),
+            state: Scoped::new(),
+            step: RefCell::new(StepLog::default()),
+            frames: RefCell::new(Vec::new()),
+            machine,
+            block_number: tx_exec_context.block_number,
+        });
+        let log = log_object(&shared, &mut ctx);
+        let db = db_object(&shared, &mut ctx);
+
+        let setup =
+            parse_timeout(opts.timeout.as_deref()).and_then(|timeout| {
+                register_builtins(&shared, &mut ctx)
+                    .map_err(|e| e.to_string())?;
+                let hooks = Hooks::compile(
+                    code,
+                    &opts.tracer_config.0,
+                    started + timeout,
+                    &mut ctx,
+                )?;
+                Ok((timeout, hooks))
+            });
+        let (timeout, hooks, error) = match setup {
+            Ok((timeout, hooks)) => (timeout, Some(hooks), None),
+            Err(e) => (DEFAULT_TIMEOUT, None, Some(e)),
+        };
+
+        JsTracer {
+            ctx,
+            hooks,
+            shared,
+            log,
+            db,
+            tx: TxContext::default(),
+            started,
+            timeout,
+            error,
+            outcome: None,
+        }
+    }
+
+    /// Calls a function of the script, unless the tracing is aborted.
+    fn call(&mut self, func: &JsObject, args: &[JsValue]) -> Option<JsValue> {
+        if self.error.is_some() {
+            return None;
+        }
+        let deadline = self.started + self.timeout;
+        if Instant::now() > deadline {
+            self.error = Some("execution timeout".into());
+            return None;
+        }
+
+        let hooks = self.hooks.as_ref()?;
+        match hooks.call(func, args, deadline, &mut self.ctx) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error = Some(e.to_string());
+                None
+            }
+        }
+    }
+
+    pub fn traces_opcode(&self) -> bool {
+        self.hooks
+            .as_ref()
+            .map_or(false, |hooks| hooks.step.is_some())
+    }
+
+    fn depth(&self) -> usize { self.shared.frames.borrow().len() }
+
+    pub fn record_call(&mut self, params: &ActionParams) {
+        let typ = match params.call_type {
+            CallType::CallCode => "CALLCODE",
+            CallType::DelegateCall => "DELEGATECALL",
+            CallType::StaticCall => "STATICCALL",
+            _ => "CALL",
+        };
+        self.enter_frame(typ, params);
+    }
+
+    pub fn record_create(&mut self, params: &ActionParams) {
+        let typ = match params.create_type {
+            CreateType::CREATE2 => "CREATE2",
+            _ => "CREATE",
+        };
+        self.enter_frame(typ, params);
+    }
+
+    fn enter_frame(&mut self, typ: &'static str, params: &ActionParams) {
+        let input = params
+            .data
+            .clone()
+            .or_else(|| params.code.as_ref().map(|code| code.to_vec()))
+            .unwrap_or_default();
+        let value = params.value.value();
+        let gas = saturating_u64(params.gas);
+
+        if self.depth() == 0 {
+            self.tx = TxContext {
+                typ: if typ.starts_with("CREATE") {
+                    "CREATE"
+                } else {
+                    "CALL"
+                },
+                from: params.sender,
+                to: params.address,
+                input: input.clone(),
+                gas,
+                gas_price: params.gas_price,
+                value,
+                ..Default::default()
+            };
+        } else if let Some(enter) =
+            self.hooks.as_ref().and_then(|hooks| hooks.enter.clone())
+        {
+            let has_value = !matches!(
+                params.call_type,
+                CallType::DelegateCall | CallType::StaticCall
+            );
+            let (from, to) = match params.call_type {
+                CallType::DelegateCall | CallType::CallCode => {
+                    (params.address, params.code_address)
+                }
+                _ => (params.sender, params.address),
+            };
+            let frame = FrameData {
+                typ,
+                from,
+                to,
+                input: input.clone(),
+                gas,
+                value: has_value.then_some(value),
+            };
+            match frame_object(frame, &mut self.ctx) {
+                Ok(frame) => {
+                    self.call(&enter, &[frame.into()]);
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+
+        self.shared.frames.borrow_mut().push(ContractInfo {
+            space: params.space,
+            caller: params.sender,
+            address: params.address,
+            value,
+            input,
+            gas,
+        });
+    }
+
+    pub fn record_call_result(&mut self, result: &FrameResult) {
+        self.exit_frame(result);
+    }
+
+    pub fn record_create_result(&mut self, result: &FrameResult) {
+        self.exit_frame(result);
+    }
+
+    fn exit_frame(&mut self, result: &FrameResult) {
+        let depth = self.depth() as u64;
+        let gas = match self.shared.frames.borrow_mut().pop() {
+            Some(frame) => frame.gas,
+            None => return,
+        };
+
+        let (gas_used, output, error) = match result {
+            Ok(FrameReturn {
+                gas_left,
+                apply_state,
+                return_data,
+                ..
+            }) => (
+                gas.saturating_sub(saturating_u64(*gas_left)),
+                return_data.to_vec(),
+                (!apply_state).then(|| "execution reverted".to_string()),
+            ),
+            Err(e) => (gas, Vec::new(), Some(e.to_string())),
+        };
+
+        if depth == 1 {
+            self.tx.output = output;
+            self.tx.error = error;
+        } else if let Some(exit) =
+            self.hooks.as_ref().and_then(|hooks| hooks.exit.clone())
+        {
+            let frame_result = FrameResultData {
+                gas_used,
+                output,
+                error,
+            };
+            match frame_result_object(frame_result, &mut self.ctx) {
+                Ok(frame_result) => {
+                    self.call(&exit, &[frame_result.into()]);
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+    }
+
+    /// Calls `fault` if the failed frame is the one executing the last traced
+    /// instruction.
+    pub fn record_frame_error(&mut self, error: &VmError, state: &State) {
+        let fault = match self.hooks.as_ref() {
+            Some(hooks) => hooks.fault.clone(),
+            None => return,
+        };
+        if self.shared.step.borrow().depth != self.depth() as u64 {
+            return;
+        }
+        self.shared.step.borrow_mut().error = Some(error.to_string());
+
+        let args = [self.log.clone().into(), self.db.clone().into()];
+        let shared = self.shared.clone();
+        // SAFETY: the state outlives the call.
+        unsafe { shared.state.lend(state, || self.call(&fault, &args)) };
+    }
+
+    pub fn step(&mut self, interp: &dyn InterpreterInfo) {
+        *self.shared.step.borrow_mut() = StepLog {
+            pc: interp.program_counter(),
+            op: interp.current_opcode(),
+            gas: saturating_u64(interp.gas_remainning()),
+            cost: 0,
+            depth: self.depth() as u64,
+            error: None,
+        };
+    }
+
+    pub fn step_cost(
+        &mut self, interp: &dyn InterpreterInfo, gas_cost: U256, state: &State,
+    ) {
+        let step = match self.hooks.as_ref().and_then(|h| h.step.clone()) {
+            Some(step) => step,
+            None => return,
+        };
+        self.shared.step.borrow_mut().cost = saturating_u64(gas_cost);
+
+        let args = [self.log.clone().into(), self.db.clone().into()];
+        let shared = self.shared.clone();
+        // SAFETY: the interpreter and the state outlive the call, and the
+        // erased lifetime of the interpreter is never observed after it.
+        unsafe {
+            let interp: *const (dyn InterpreterInfo + '_) = interp;
+            let interp: *const (dyn InterpreterInfo + 'static) =
+                std::mem::transmute(interp);
+            shared.interp.lend(interp, || {
+                shared.state.lend(state, || self.call(&step, &args))
+            });
+        }
+    }
+
+    /// Calls `result` with the final state of the transaction.
+    pub fn record_tx_end(&mut self, gas_used: u64, state: &State) {
+        let shared = self.shared.clone();
+        // SAFETY: the state outlives the call.
+        let outcome =
+            unsafe { shared.state.lend(state, || self.result(gas_used)) };
+        self.outcome = Some(outcome);
+    }
+
+    /// Returns the result of the script, or the error aborting it. `db` is
+    /// not accessible in `result` if the transaction was not executed.
+    pub fn drain(mut self, gas_used: u64) -> Value {
+        let outcome = match self.outcome.take() {
+            Some(outcome) => outcome,
+            None => self.result(gas_used),
+        };
+        match outcome {
+            Ok(value) => value,
+            Err(e) => serde_json::json!({ "error": e }),
+        }
+    }
+
+    fn result(&mut self, gas_used: u64) -> Result<Value, String> {
+        let result = match &self.hooks {
+            Some(hooks) => hooks.result.clone(),
+            None => return Err(self.error.take().unwrap_or_default()),
+        };
+        let ctx = self.ctx_object(gas_used).map_err(|e| e.to_string())?;
+        let value = self.call(&result, &[ctx.into(), self.db.clone().into()]);
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        match value {
+            Some(value) if !value.is_undefined() => {
+                value.to_json(&mut self.ctx).map_err(|e| e.to_string())
+            }
+            _ => Ok(Value::Null),
+        }
+    }
+
+    fn ctx_object(&mut self, gas_used: u64) -> JsResult<JsObject> {
+        let ctx = &mut self.ctx;
+        let tx = &self.tx;
+        let typ = JsValue::from(JsString::from(tx.typ));
+        let from = to_buf(tx.from.as_bytes(), ctx)?;
+        let to = to_buf(tx.to.as_bytes(), ctx)?;
+        let input = to_buf(&tx.input, ctx)?;
+        let output = to_buf(&tx.output, ctx)?;
+        let time = JsValue::from(JsString::from(format!(
+            "{:?}",
+            self.started.elapsed()
+        )));
+
+        let mut obj = ObjectInitializer::new(ctx);
+        obj.property(js_string!("type"), typ, Attribute::all())
+            .property(js_string!("from"), from, Attribute::all())
+            .property(js_string!("to"), to, Attribute::all())
+            .property(js_string!("input"), input, Attribute::all())
+            .property(js_string!("gas"), to_number(tx.gas), Attribute::all())
+            .property(
+                js_string!("gasUsed"),
+                to_number(gas_used),
+                Attribute::all(),
+            )
+            .property(
+                js_string!("gasPrice"),
+                to_bigint(tx.gas_price),
+                Attribute::all(),
+            )
+            .property(
+                js_string!("value"),
+                to_bigint(tx.value),
+                Attribute::all(),
+            )
+            .property(
+                js_string!("block"),
+                to_number(self.shared.block_number),
+                Attribute::all(),
+            )
+            .property(js_string!("output"), output, Attribute::all())
+            .property(js_string!("time"), time, Attribute::all());
+        if let Some(error) = &tx.error {
+            obj.property(
+                js_string!("error"),
+                JsString::from(error.as_str()),
+                Attribute::all(),
+            );
+        }
+        Ok(obj.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout(None), Ok(DEFAULT_TIMEOUT));
+        assert_eq!(
+            parse_timeout(Some("300ms")),
+            Ok(Duration::from_millis(300))
+        );
+        assert_eq!(parse_timeout(Some("1m30s")), Ok(Duration::from_secs(90)));
+        assert_eq!(
+            parse_timeout(Some("1.5s")),
+            Ok(Duration::from_millis(1500))
+        );
+        assert!(parse_timeout(Some("")).is_err());
+        assert!(parse_timeout(Some("10")).is_err());
+        assert!(parse_timeout(Some("10d")).is_err());
+    }
+
+    fn compile(code: &str, config: &Value, ctx: &mut Context) -> Hooks {
+        Hooks::compile(code, config, Instant::now() + DEFAULT_TIMEOUT, ctx)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_compile_hooks() {
+        let config = Value::Null;
+        let mut ctx = new_context();
+        let deadline = Instant::now() + DEFAULT_TIMEOUT;
+
+        assert!(Hooks::compile(
+            "{data: [], fault: function() {}, result: function() { return this.data; }}",
+            &config,
+            deadline,
+            &mut ctx,
+        )
+        .is_ok());
+        // result and fault are required
+        assert!(Hooks::compile(
+            "{fault: function() {}}",
+            &config,
+            deadline,
+            &mut ctx
+        )
+        .is_err());
+        // enter and exit come together
+        assert!(Hooks::compile(
+            "{fault: function() {}, result: function() {}, enter: function() {}}",
+            &config,
+            deadline,
+            &mut ctx,
+        )
+        .is_err());
+        // syntax error
+        assert!(Hooks::compile(
+            "{result: function() {",
+            &config,
+            deadline,
+            &mut ctx
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_compile_timeout() {
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(100);
+
+        // in setup
+        let err = Hooks::compile(
+            "{fault: function() {}, result: function() {}, \
+             setup: function() { while (true) {} }}",
+            &Value::Null,
+            deadline,
+            &mut new_context(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("execution timeout"));
+
+        // in the tracer object itself
+        let err = Hooks::compile(
+            "{fault: function() {}, result: function() {}, \
+             data: (function() { while (true) {} })()}",
+            &Value::Null,
+            deadline,
+            &mut new_context(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("execution timeout"));
+        assert!(started.elapsed() < DEFAULT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_setup_config() {
+        let config = serde_json::json!({ "limit": 3 });
+        let mut ctx = new_context();
+        let hooks = compile(
+            "{fault: function() {}, result: function() { return this.limit; }, \
+             setup: function(cfg) { this.limit = cfg.limit; }}",
+            &config,
+            &mut ctx,
+        );
+
+        let value = hooks.result.call(&hooks.this, &[], &mut ctx).unwrap();
+        assert_eq!(value.to_json(&mut ctx).unwrap(), serde_json::json!(3));
+    }
+
+    #[test]
+    fn test_hook_timeout() {
+        let mut ctx = new_context();
+        let hooks = compile(
+            "{fault: function() {}, result: function() { while (true) {} }}",
+            &Value::Null,
+            &mut ctx,
+        );
+
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(100);
+        let err = hooks
+            .call(&hooks.result, &[], deadline, &mut ctx)
+            .unwrap_err();
+        assert!(err.to_string().contains("execution timeout"));
+        // interrupted long before the loop iteration limit
+        assert!(started.elapsed() < DEFAULT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_hook_arguments() {
+        let mut ctx = new_context();
+        let hooks = compile(
+            "{sum: 0, fault: function() {}, \
+             result: function(a, b) { this.sum += a + b; return this.sum; }}",
+            &Value::Null,
+            &mut ctx,
+        );
+
+        let deadline = Instant::now() + DEFAULT_TIMEOUT;
+        let args = [JsValue::from(1), JsValue::from(2)];
+        hooks
+            .call(&hooks.result, &args, deadline, &mut ctx)
+            .unwrap();
+        let value = hooks
+            .call(&hooks.result, &args, deadline, &mut ctx)
+            .unwrap();
+        assert_eq!(value.to_json(&mut ctx).unwrap(), serde_json::json!(6));
+    }
+}
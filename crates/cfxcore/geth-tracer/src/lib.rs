@@ -5,6 +5,7 @@ mod fourbyte;
 mod gas;
 mod geth_builder;
 mod geth_tracer;
+mod js_tracer;
 mod tracing_inspector;
 mod types;
 mod utils;
@@ -14,7 +15,9 @@ use config::TracingInspectorConfig;
 use geth_builder::GethTraceBuilder;
 
 pub use geth_tracer::{GethTraceKey, GethTracer};
+pub use js_tracer::validate_js_tracer;
 pub use types::{GethTraceWithHash, TxExecContext};
 pub use utils::{
-    from_alloy_address, to_alloy_address, to_alloy_h256, to_alloy_u256,
+    from_alloy_address, from_alloy_u256, to_alloy_address, to_alloy_h256,
+    to_alloy_u256,
 };
@@ -71,6 +71,11 @@ pub fn to_alloy_u256(u: U256) -> RU256 {
     RU256::from_be_bytes(be_bytes)
 }
 
+// convert from alloy U256 to cfx U256
+pub fn from_alloy_u256(u: RU256) -> U256 {
+    U256::from_big_endian(&u.to_be_bytes::<32>())
+}
+
 pub fn to_alloy_address(h: H160) -> RAddress {
     RAddress::from_slice(h.as_bytes())
 }
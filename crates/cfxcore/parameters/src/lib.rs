@@ -138,6 +138,26 @@ pub mod rpc {
     pub const TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_LOW: usize = 100;
     pub const TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_MEDIUM: usize = 600;
     pub const GAS_PRICE_DEFAULT_VALUE: usize = 1_000_000_000;
+    /// The max number of blocks simulated in one `eth_simulateV1` or
+    /// `cfx_simulate` request.
+    pub const MAX_SIMULATE_BLOCKS: usize = 256;
+    /// The max number of calls in one simulated block.
+    pub const MAX_SIMULATE_CALLS_PER_BLOCK: usize = 1_000;
+    /// The max sum of the gas limits of all the calls in one simulation
+    /// request. The calls without a gas limit are charged the default gas
+    /// limit of a virtual call.
+    pub const MAX_SIMULATE_GAS: u64 = 500_000_000;
+    /// The number of epochs in a section of the bloom-bits log index.
+    pub const BLOOM_BITS_SECTION_SIZE: u64 = 4096;
+    /// A section of the bloom-bits log index is built once the executed
+    /// epochs are this many epochs past its end, so that it is seldom
+    /// rebuilt because of pivot chain reorgs.
+    pub const BLOOM_BITS_CONFIRMATIONS: u64 = 256;
+    /// The default and the max number of entries of the address transaction
+    /// index read in one `cfx_getTransactionsByAddress` or
+    /// `eth_getTransactionsByAddress` request.
+    pub const ADDRESS_TX_PAGE_SIZE: u64 = 100;
+    pub const MAX_ADDRESS_TX_PAGE_SIZE: u64 = 1000;
 }
 
 pub mod sync {
@@ -337,6 +357,8 @@ pub mod light {
         pub static ref TX_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
         pub static ref TX_INFO_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
         pub static ref STORAGE_ROOT_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+        pub static ref ETH_RECEIPT_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+        pub static ref ETH_STATE_ENTRY_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
 
         /// Maximum time period we wait for a response for an on-demand query.
         /// After this timeout has been reached, we try another peer or give up.
@@ -344,6 +366,14 @@ pub mod light {
 
         /// Items not accessed for this amount of time are removed from the cache.
         pub static ref CACHE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+        /// Frequency of rebroadcasting the locally submitted transactions that
+        /// are not yet included.
+        pub static ref LOCAL_TX_REBROADCAST_PERIOD: Duration = Duration::from_secs(60);
+
+        /// Locally submitted transactions not included for this amount of time
+        /// are no longer tracked.
+        pub static ref LOCAL_TX_TIMEOUT: Duration = Duration::from_secs(3 * 60 * 60);
     }
 
     /// The threshold controlling whether a node is in catch-up mode.
@@ -364,6 +394,8 @@ pub mod light {
     pub const TX_REQUEST_BATCH_SIZE: usize = 30;
     pub const TX_INFO_REQUEST_BATCH_SIZE: usize = 30;
     pub const STORAGE_ROOT_REQUEST_BATCH_SIZE: usize = 30;
+    pub const ETH_RECEIPT_REQUEST_BATCH_SIZE: usize = 30;
+    pub const ETH_STATE_ENTRY_REQUEST_BATCH_SIZE: usize = 30;
 
     /// Maximum number of in-flight items at any given time.
     /// If we reach this limit, we will not request any more.
@@ -377,6 +409,8 @@ pub mod light {
     pub const MAX_TXS_IN_FLIGHT: usize = 100;
     pub const MAX_TX_INFOS_IN_FLIGHT: usize = 100;
     pub const MAX_STORAGE_ROOTS_IN_FLIGHT: usize = 100;
+    pub const MAX_ETH_RECEIPTS_IN_FLIGHT: usize = 100;
+    pub const MAX_ETH_STATE_ENTRIES_IN_FLIGHT: usize = 100;
 
     /// Maximum number of in-flight epoch requests at any given time.
     /// Similar to `MAX_HEADERS_IN_FLIGHT`. However, it is hard to match
@@ -420,6 +454,28 @@ pub mod light {
 
     // Number of blocks we retrieve in parallel for the gas price sample.
     pub const GAS_PRICE_BATCH_SIZE: usize = 30;
+
+    /// Maximum number of rounds to execute a virtual call on a light node.
+    /// The state entries read in each round are retrieved from the peers
+    /// before the next round, until the execution reads no missing entry.
+    ///
+    /// All the entries missing in a round are retrieved together, so a round
+    /// is only added by an entry whose key depends on an entry retrieved in
+    /// the previous round, e.g. a proxy account, its code, the implementation
+    /// address in its storage, then the code and storage of the
+    /// implementation. This bounds the depth of such dependencies rather than
+    /// the number of entries, and bounds a call to about
+    /// `MAX_VIRTUAL_CALL_ROUNDS * MAX_POLL_TIME` (64s) of retrieval.
+    pub const MAX_VIRTUAL_CALL_ROUNDS: usize = 16;
+
+    /// Maximum number of locally submitted transactions tracked at any given
+    /// time.
+    pub const MAX_LOCAL_TXS: usize = 4096;
+
+    /// Maximum number of locally submitted transactions of a sender tracked at
+    /// any given time, so that a single sender can not evict the transactions
+    /// of all the others.
+    pub const MAX_LOCAL_TXS_PER_SENDER: usize = 64;
 }
 
 pub const WORKER_COMPUTATION_PARALLELISM: usize = 8;
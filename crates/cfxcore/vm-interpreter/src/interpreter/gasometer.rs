@@ -18,8 +18,8 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use cfx_types::{Space, U256};
-use cfx_vm_types::{self as vm, Spec};
+use cfx_types::{Address, Space, U256};
+use cfx_vm_types::{self as vm, extract_delegation, Spec};
 use std::cmp;
 use vm::BlockHashSource;
 
@@ -273,12 +273,18 @@ impl<Gas: CostType> Gasometer<Gas> {
                             .overflow_add(spec.call_value_transfer_gas.into()));
                 }
 
+                gas = overflowing!(
+                    gas.overflow_add(delegation_gas(context, &address)?.into())
+                );
+
                 let requested = *stack.peek(0);
 
                 Request::GasMemProvide(gas, mem, Some(requested))
             }
             instructions::DELEGATECALL | instructions::STATICCALL => {
-                let gas = Gas::from(spec.call_gas);
+                let address = u256_to_address(stack.peek(1));
+                let gas = overflowing!(Gas::from(spec.call_gas)
+                    .overflow_add(delegation_gas(context, &address)?.into()));
                 let mem = cmp::max(
                     mem_needed(stack.peek(4), stack.peek(5))?,
                     mem_needed(stack.peek(2), stack.peek(3))?,
@@ -401,6 +407,21 @@ impl<Gas: CostType> Gasometer<Gas> {
     }
 }
 
+/// Calling an account delegated by EIP-7702 loads the code of the delegated
+/// account, which costs another `call_gas`.
+fn delegation_gas(
+    context: &dyn vm::Context, address: &Address,
+) -> vm::Result<usize> {
+    let spec = context.spec();
+    if !spec.cip7702 || context.space() != Space::Ethereum {
+        return Ok(0);
+    }
+    let delegated = context
+        .extcode(address)?
+        .map_or(false, |code| extract_delegation(&code).is_some());
+    Ok(if delegated { spec.call_gas } else { 0 })
+}
+
 #[inline]
 fn mem_needed_const<Gas: CostType>(mem: &U256, add: usize) -> vm::Result<Gas> {
     Gas::from_u256(overflowing!(mem.overflowing_add(U256::from(add))))
@@ -631,6 +631,11 @@ impl<Cost: CostType, const CANCUN: bool> Interpreter<Cost, CANCUN> {
         {
             return Err(InterpreterResult::Done(Err(e)));
         }
+
+        if self.do_trace {
+            context.trace_step_cost(self, requirements.gas_cost.as_u256());
+        }
+
         self.mem.expand(requirements.memory_required_size);
         self.gasometer
             .as_mut()
@@ -273,6 +273,15 @@ pub trait Context {
         let _ = interpreter;
     }
 
+    /// Trace the gas cost of an instruction, which is known after
+    /// `trace_step` and right before the instruction is executed.
+    fn trace_step_cost(
+        &mut self, interpreter: &dyn InterpreterInfo, gas_cost: U256,
+    ) {
+        let _ = interpreter;
+        let _ = gas_cost;
+    }
+
     fn trace_step_end(&mut self, interpreter: &dyn InterpreterInfo) {
         let _ = interpreter;
     }
@@ -0,0 +1,33 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Delegation designators of EIP-7702.
+
+use cfx_bytes::Bytes;
+use cfx_types::Address;
+
+/// The code of a delegated account is `DELEGATION_PREFIX || address`.
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// The length of a delegation designator.
+pub const DELEGATION_CODE_LENGTH: usize = 23;
+
+/// Builds the delegation designator pointing to `address`.
+pub fn delegation_code(address: &Address) -> Bytes {
+    let mut code = Vec::with_capacity(DELEGATION_CODE_LENGTH);
+    code.extend_from_slice(&DELEGATION_PREFIX);
+    code.extend_from_slice(address.as_bytes());
+    code
+}
+
+/// Returns the delegated address if `code` is a delegation designator.
+pub fn extract_delegation(code: &[u8]) -> Option<Address> {
+    if code.len() == DELEGATION_CODE_LENGTH
+        && code.starts_with(&DELEGATION_PREFIX)
+    {
+        Some(Address::from_slice(&code[DELEGATION_PREFIX.len()..]))
+    } else {
+        None
+    }
+}
@@ -120,6 +120,9 @@ pub enum Error {
     InvalidAddress(Address),
     /// Create a contract on an address with existing contract
     ConflictAddress(Address),
+    /// Deploy a contract whose code starts with the 0xEF byte, which is
+    /// reserved for the delegation designators of EIP-7702
+    CreateContractStartingWithEF,
 }
 
 #[derive(Debug)]
@@ -196,6 +199,9 @@ impl fmt::Display for Error {
             ConflictAddress(ref addr) => {
                 write!(f, "Contract creation on an existing address: {}", addr)
             }
+            CreateContractStartingWithEF => {
+                write!(f, "Contract code starting with the 0xEF byte")
+            }
         }
     }
 }
@@ -5,6 +5,7 @@
 mod action_params;
 mod call_create_type;
 mod context;
+mod delegation;
 mod env;
 mod error;
 mod instruction_result;
@@ -22,6 +23,10 @@ pub use self::{
         contract_address, BlockHashSource, Context, ContractCreateResult,
         CreateContractAddress, MessageCallResult,
     },
+    delegation::{
+        delegation_code, extract_delegation, DELEGATION_CODE_LENGTH,
+        DELEGATION_PREFIX,
+    },
     env::Env,
     error::{
         separate_out_db_error, Error, ExecTrapError, ExecTrapResult, Result,
@@ -107,6 +107,11 @@ pub struct Spec {
     pub eip1820_gas: usize,
     pub access_list_storage_key_gas: usize,
     pub access_list_address_gas: usize,
+    /// Intrinsic gas of each authorization in an EIP-7702 transaction
+    pub per_empty_account_cost: usize,
+    /// Gas cost of an authorization to an existing account, the rest of
+    /// `per_empty_account_cost` is refunded
+    pub per_auth_base_cost: usize,
     /// Amount of additional gas to pay when SUICIDE credits a non-existant
     /// account
     pub suicide_to_new_account_cost: usize,
@@ -185,6 +190,8 @@ pub struct Spec {
     pub cip144: bool,
     /// CIP-145: Fix Receipts upon `NotEnoughBalance` Error
     pub cip145: bool,
+    /// EIP-7702: Set EOA account code in eSpace
+    pub cip7702: bool,
 }
 
 /// Wasm cost table
@@ -303,6 +310,8 @@ impl Spec {
             eip1820_gas: 1_500_000,
             access_list_storage_key_gas: 1900,
             access_list_address_gas: 2400,
+            per_empty_account_cost: 25000,
+            per_auth_base_cost: 12500,
             suicide_to_new_account_cost: 25000,
             sub_gas_cap_divisor: Some(64),
             no_empty: true,
@@ -340,6 +349,7 @@ impl Spec {
             cip1559: false,
             cancun_opcodes: false,
             cip144: false,
+            cip7702: false,
         }
     }
 
@@ -13,6 +13,7 @@ use std::{
 };
 
 use jsonrpc_http_server::Server as HttpServer;
+use jsonrpc_ipc_server::Server as IpcServer;
 use jsonrpc_tcp_server::Server as TcpServer;
 use jsonrpc_ws_server::Server as WSServer;
 use parking_lot::{Condvar, Mutex};
@@ -63,7 +64,8 @@ use crate::{
             cfx::RpcImpl, common::RpcImpl as CommonRpcImpl,
             eth_pubsub::PubSubClient as EthPubSubClient, pubsub::PubSubClient,
         },
-        setup_debug_rpc_apis, setup_public_eth_rpc_apis, setup_public_rpc_apis,
+        setup_admin_rpc_apis, setup_debug_rpc_apis, setup_public_eth_rpc_apis,
+        setup_public_rpc_apis,
     },
     GENESIS_VERSION,
 };
@@ -284,7 +286,8 @@ pub fn initialize_common_modules(
         }
     };
 
-    metrics::initialize(conf.metrics_config());
+    metrics::initialize(conf.metrics_config())
+        .map_err(|e| format!("Failed to initialize metrics: {}", e))?;
 
     let worker_thread_pool = Arc::new(Mutex::new(ThreadPool::with_name(
         "Tx Recover".into(),
@@ -343,6 +346,16 @@ pub fn initialize_common_modules(
         }
     };
 
+    let genesis_alloc = match conf.raw_conf.genesis_alloc {
+        Some(ref file) => genesis::load_alloc_file(file, |addr_str| {
+            parse_config_address_string(
+                addr_str,
+                network_config.get_network_type(),
+            )
+        })?,
+        None => Default::default(),
+    };
+
     // Only try to setup PoW genesis block if pos is enabled from genesis.
     let initial_nodes = if conf.raw_conf.pos_reference_enable_height == 0 {
         Some(
@@ -362,6 +375,7 @@ pub fn initialize_common_modules(
     let genesis_block = genesis_block(
         &storage_manager,
         genesis_accounts.clone(),
+        &genesis_alloc,
         Address::from_str(GENESIS_VERSION).unwrap(),
         U256::zero(),
         machine.clone(),
@@ -394,6 +408,11 @@ pub fn initialize_common_modules(
         pow.clone(),
     ));
 
+    // light nodes do not execute the epochs
+    if node_type != NodeType::Light {
+        data_man.start_bloom_bits_indexer();
+    }
+
     let network = {
         let mut rng = StdRng::from_rng(OsRng).unwrap();
         let private_key = ConsensusPrivateKey::generate(&mut rng);
@@ -499,6 +518,7 @@ pub fn initialize_common_modules(
     let pubsub = PubSubClient::new(
         runtime.executor(),
         consensus.clone(),
+        txpool.clone(),
         notifications.clone(),
         *network.get_network_type(),
     );
@@ -506,6 +526,7 @@ pub fn initialize_common_modules(
     let eth_pubsub = EthPubSubClient::new(
         runtime.executor(),
         consensus.clone(),
+        txpool.clone(),
         notifications.clone(),
     );
 
@@ -542,10 +563,12 @@ pub fn initialize_not_light_node_modules(
         Arc<BlockGenerator>,
         Option<HttpServer>,
         Option<HttpServer>,
+        Option<HttpServer>,
         Option<TcpServer>,
         Option<TcpServer>,
         Option<WSServer>,
         Option<WSServer>,
+        Option<IpcServer>,
         Arc<PosVerifier>,
         Runtime,
         Option<HttpServer>,
@@ -697,7 +720,7 @@ pub fn initialize_not_light_node_modules(
         txpool.clone(),
         maybe_txgen.clone(),
         maybe_direct_txgen,
-        conf.rpc_impl_config(),
+        conf.rpc_impl_config()?,
         accounts,
     ));
 
@@ -712,6 +735,21 @@ pub fn initialize_not_light_node_modules(
         ),
     )?;
 
+    let admin_rpc_http_server = match conf.admin_jwt_secret()? {
+        Some(jwt_secret) => super::rpc::start_http(
+            conf.admin_http_config(),
+            setup_admin_rpc_apis(
+                common_impl.clone(),
+                rpc_impl.clone(),
+                pubsub.clone(),
+                eth_pubsub.clone(),
+                jwt_secret,
+                &conf,
+            ),
+        )?,
+        None => None,
+    };
+
     let debug_rpc_tcp_server = super::rpc::start_tcp(
         conf.local_tcp_config(),
         setup_debug_rpc_apis(
@@ -748,6 +786,18 @@ pub fn initialize_not_light_node_modules(
         RpcExtractor,
     )?;
 
+    let debug_rpc_ipc_server = super::rpc::start_ipc(
+        conf.ipc_config(),
+        setup_public_rpc_apis(
+            common_impl.clone(),
+            rpc_impl.clone(),
+            pubsub.clone(),
+            eth_pubsub.clone(),
+            &conf,
+        ),
+        RpcExtractor,
+    )?;
+
     let rpc_ws_server = super::rpc::start_ws(
         conf.ws_config(),
         setup_public_rpc_apis(
@@ -804,11 +854,13 @@ pub fn initialize_not_light_node_modules(
         sync,
         blockgen,
         debug_rpc_http_server,
+        admin_rpc_http_server,
         rpc_http_server,
         debug_rpc_tcp_server,
         rpc_tcp_server,
         debug_rpc_ws_server,
         rpc_ws_server,
+        debug_rpc_ipc_server,
         pos_verifier,
         runtime,
         eth_rpc_http_server,
@@ -894,6 +946,10 @@ pub mod delegate_convert {
         fn into(x: Self) -> BoxFuture<T> { x }
     }
 
+    impl<T: Send + Sync + 'static> Into<BoxFuture<T>> for JsonRpcResult<T> {
+        fn into(x: Self) -> BoxFuture<T> { x.into_future().boxed() }
+    }
+
     impl<T: Send + Sync + 'static> Into<BoxFuture<T>> for RpcBoxFuture<T> {
         fn into(x: Self) -> BoxFuture<T> {
             Box::new(x.map_err(|rpc_error| Into::into(rpc_error)))
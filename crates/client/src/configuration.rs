@@ -2,7 +2,12 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use std::{collections::BTreeMap, convert::TryInto, path::PathBuf, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    convert::TryInto,
+    path::PathBuf,
+    sync::Arc,
+};
 
 use lazy_static::*;
 use parking_lot::RwLock;
@@ -51,8 +56,11 @@ use primitives::block_header::CIP112_TRANSITION_HEIGHT;
 use txgen::TransactionGeneratorConfig;
 
 use crate::rpc::{
-    impls::RpcImplConfiguration, rpc_apis::ApiSet, HttpConfiguration,
-    TcpConfiguration, WsConfiguration,
+    client_throttle::{ClientThrottle, ClientThrottleConfig},
+    impls::RpcImplConfiguration,
+    jwt::JwtSecret,
+    rpc_apis::ApiSet,
+    HttpConfiguration, IpcConfiguration, TcpConfiguration, WsConfiguration,
 };
 
 lazy_static! {
@@ -127,6 +135,7 @@ build_config! {
         (metrics_influxdb_password, (Option<String>), None)
         (metrics_influxdb_node, (Option<String>), None)
         (metrics_output_file, (Option<String>), None)
+        (metrics_prometheus_listen_addr, (Option<String>), None)
         (metrics_report_interval_ms, (u64), 3_000)
         (rocksdb_disable_wal, (bool), false)
         (txgen_account_count, (usize), 10)
@@ -144,6 +153,7 @@ build_config! {
         (era_epoch_count, (u64), ERA_DEFAULT_EPOCH_COUNT)
         (heavy_block_difficulty_ratio, (u64), HEAVY_BLOCK_DEFAULT_DIFFICULTY_RATIO)
         (genesis_accounts, (Option<String>), None)
+        (genesis_alloc, (Option<String>), None)
         (genesis_secrets, (Option<String>), None)
         (initial_difficulty, (Option<u64>), None)
         (tanzanite_transition_height, (u64), TANZANITE_HEIGHT)
@@ -165,6 +175,9 @@ build_config! {
         (next_hardfork_transition_height, (Option<u64>), None)
         (cip1559_transition_height, (Option<u64>), None)
         (cancun_opcodes_transition_number, (Option<u64>), None)
+        (cip7702_transition_height, (Option<u64>), None)
+        (cip2537_transition_number, (Option<u64>), None)
+        (cip7212_transition_number, (Option<u64>), None)
         (referee_bound, (usize), REFEREE_DEFAULT_BOUND)
         (params_dao_vote_period, (u64), DAO_PARAMETER_VOTE_PERIOD)
         (timer_chain_beta, (u64), TIMER_CHAIN_DEFAULT_BETA)
@@ -187,6 +200,9 @@ build_config! {
         (jsonrpc_local_tcp_port, (Option<u16>), None)
         (jsonrpc_local_http_port, (Option<u16>), None)
         (jsonrpc_local_ws_port, (Option<u16>), None)
+        (jsonrpc_ipc_path, (Option<String>), None)
+        (jsonrpc_admin_http_port, (Option<u16>), None)
+        (jsonrpc_admin_jwt_secret_path, (Option<String>), None)
         (jsonrpc_ws_port, (Option<u16>), None)
         (jsonrpc_tcp_port, (Option<u16>), None)
         (jsonrpc_http_port, (Option<u16>), None)
@@ -202,6 +218,9 @@ build_config! {
         // but disconnect from the public network.
         (network_id, (Option<u64>), None)
         (rpc_enable_metrics, (bool), false)
+        (rpc_client_throttling_bucket, (Option<String>), None)
+        (rpc_client_throttling_cache_size, (usize), 10_000)
+        (rpc_trust_forwarded_for, (bool), false)
         (tcp_port, (u16), 32323)
         (public_tcp_port, (Option<u16>), None)
         (public_address, (Option<String>), None)
@@ -274,6 +293,12 @@ build_config! {
         (tx_pool_min_native_tx_gas_price, (Option<u64>), None)
         (tx_pool_min_eth_tx_gas_price, (Option<u64>), None)
         (tx_pool_nonce_bits, (usize), TXPOOL_DEFAULT_NONCE_BITS)
+        (tx_pool_journal_path, (Option<String>), None)
+        (tx_pool_journal_rotate_interval_s, (u64), 3600)
+        (tx_pool_price_bump_percent, (u64), 2)
+        (tx_pool_priority_fee_bump_percent, (u64), 0)
+        (tx_pool_max_txs_per_sender, (Option<usize>), None)
+        (tx_pool_evict_lowest_tip, (bool), false)
         (max_packing_batch_gas_limit, (u64), 3_000_000)
         (max_packing_batch_size, (usize), 50)
         (packing_pool_degree, (u8), 4)
@@ -324,6 +349,8 @@ build_config! {
         (max_trans_count_received_in_catch_up, (u64), 60_000)
         (persist_tx_index, (bool), false)
         (persist_block_number_index, (bool), true)
+        (persist_bloom_bits_index, (bool), false)
+        (persist_address_tx_index, (bool), false)
         (print_memory_usage_period_s, (Option<u64>), None)
         (target_block_gas_limit, (u64), DEFAULT_TARGET_BLOCK_GAS_LIMIT)
         (executive_trace, (bool), false)
@@ -412,6 +439,13 @@ build_config! {
         (node_type, (Option<NodeType>), None, NodeType::from_str)
         (public_rpc_apis, (ApiSet), ApiSet::Safe, ApiSet::from_str)
         (public_evm_rpc_apis, (ApiSet), ApiSet::Evm, ApiSet::from_str)
+        (jsonrpc_ipc_permissions, (u32), 0o600, parse_ipc_permissions)
+        (rpc_client_api_keys, (HashSet<String>), HashSet::new(), |s: &str| {
+            Ok::<_, String>(s.split(',')
+                .map(|key| key.trim().to_owned())
+                .filter(|key| !key.is_empty())
+                .collect())
+        })
         (single_mpt_space, (Option<Space>), None, |s| match s {
             "native" => Ok(Space::Native),
             "evm" => Ok(Space::Ethereum),
@@ -968,6 +1002,8 @@ impl Configuration {
                 * self.raw_conf.era_epoch_count as f64)
                 as usize,
             strict_tx_index_gc: self.raw_conf.strict_tx_index_gc,
+            persist_bloom_bits_index: self.raw_conf.persist_bloom_bits_index,
+            persist_address_tx_index: self.raw_conf.persist_address_tx_index,
         };
 
         // By default, we do not keep the block data for additional period,
@@ -997,7 +1033,11 @@ impl Configuration {
                 conf.additional_maintained_trace_epoch_count = Some(0);
             }
         }
-        if conf.additional_maintained_transaction_index_epoch_count != Some(0) {
+        if conf.additional_maintained_transaction_index_epoch_count != Some(0)
+            || conf.persist_address_tx_index
+        {
+            // The address transaction index is checked against the
+            // transaction index.
             conf.persist_tx_index = true;
         }
         conf
@@ -1031,6 +1071,10 @@ impl Configuration {
                 .metrics_influxdb_password
                 .clone(),
             influxdb_report_node: self.raw_conf.metrics_influxdb_node.clone(),
+            prometheus_listen_addr: self
+                .raw_conf
+                .metrics_prometheus_listen_addr
+                .clone(),
         }
     }
 
@@ -1063,18 +1107,65 @@ impl Configuration {
                 .max_packing_batch_gas_limit,
             max_packing_batch_size: self.raw_conf.max_packing_batch_size,
             packing_pool_degree: self.raw_conf.packing_pool_degree,
+            journal_path: self
+                .raw_conf
+                .tx_pool_journal_path
+                .as_ref()
+                .map(PathBuf::from),
+            journal_rotate_interval: Duration::from_secs(
+                self.raw_conf.tx_pool_journal_rotate_interval_s,
+            ),
+            price_bump_percent: self.raw_conf.tx_pool_price_bump_percent,
+            priority_fee_bump_percent: self
+                .raw_conf
+                .tx_pool_priority_fee_bump_percent,
+            max_txs_per_sender: self.raw_conf.tx_pool_max_txs_per_sender,
+            evict_lowest_tip: self.raw_conf.tx_pool_evict_lowest_tip,
         }
     }
 
-    pub fn rpc_impl_config(&self) -> RpcImplConfiguration {
-        RpcImplConfiguration {
+    pub fn rpc_impl_config(&self) -> Result<RpcImplConfiguration, String> {
+        Ok(RpcImplConfiguration {
             get_logs_filter_max_limit: self.raw_conf.get_logs_filter_max_limit,
             dev_pack_tx_immediately: self.is_dev_mode()
                 && self.raw_conf.dev_block_interval_ms.is_none(),
             max_payload_bytes: self.raw_conf.jsonrpc_ws_max_payload_bytes,
             enable_metrics: self.raw_conf.rpc_enable_metrics,
             poll_lifetime_in_seconds: self.raw_conf.poll_lifetime_in_seconds,
+            client_throttle: self.client_throttle()?.map(Arc::new),
+        })
+    }
+
+    /// Creates the per-client throttling of the public RPC endpoints, or
+    /// returns `None` if it is disabled.
+    pub fn client_throttle(&self) -> Result<Option<ClientThrottle>, String> {
+        let bucket = match &self.raw_conf.rpc_client_throttling_bucket {
+            Some(bucket) => bucket.clone(),
+            None => return Ok(None),
+        };
+        let throttle = ClientThrottle::new(ClientThrottleConfig {
+            bucket,
+            cache_size: self.raw_conf.rpc_client_throttling_cache_size,
+            api_keys: self.raw_conf.rpc_client_api_keys.clone(),
+            trust_forwarded_for: self.raw_conf.rpc_trust_forwarded_for,
+            throttling_conf: self.raw_conf.throttling_conf.clone(),
+        })
+        .map_err(|e| format!("Invalid client throttling: {}", e))?;
+        info!(
+            "RPC client throttling enabled. HTTP clients are throttled per \
+             IP only behind a trusted proxy with rpc_trust_forwarded_for, \
+             and WebSocket clients are throttled per session."
+        );
+        if self.raw_conf.rpc_client_api_keys.is_empty()
+            && !self.raw_conf.rpc_trust_forwarded_for
+        {
+            warn!(
+                "rpc_client_throttling_bucket is set without \
+                 rpc_client_api_keys or rpc_trust_forwarded_for, so HTTP \
+                 clients are not throttled per client."
+            );
         }
+        Ok(Some(throttle))
     }
 
     pub fn local_http_config(&self) -> HttpConfiguration {
@@ -1097,6 +1188,30 @@ impl Configuration {
         )
     }
 
+    pub fn admin_http_config(&self) -> HttpConfiguration {
+        HttpConfiguration::new(
+            None,
+            self.raw_conf.jsonrpc_admin_http_port,
+            self.raw_conf.jsonrpc_cors.clone(),
+            self.raw_conf.jsonrpc_http_keep_alive,
+            self.raw_conf.jsonrpc_http_threads,
+        )
+    }
+
+    /// Loads the secret authenticating the admin RPC endpoint, or returns
+    /// `None` if the endpoint is disabled.
+    pub fn admin_jwt_secret(&self) -> Result<Option<JwtSecret>, String> {
+        if self.raw_conf.jsonrpc_admin_http_port.is_none() {
+            return Ok(None);
+        }
+        match &self.raw_conf.jsonrpc_admin_jwt_secret_path {
+            Some(path) => JwtSecret::from_file(Path::new(path)).map(Some),
+            None => Err("jsonrpc_admin_jwt_secret_path must be set to start \
+                         the admin RPC endpoint"
+                .into()),
+        }
+    }
+
     pub fn eth_http_config(&self) -> HttpConfiguration {
         HttpConfiguration::new(
             None,
@@ -1134,6 +1249,13 @@ impl Configuration {
         )
     }
 
+    pub fn ipc_config(&self) -> IpcConfiguration {
+        IpcConfiguration::new(
+            self.raw_conf.jsonrpc_ipc_path.clone(),
+            self.raw_conf.jsonrpc_ipc_permissions,
+        )
+    }
+
     pub fn ws_config(&self) -> WsConfiguration {
         WsConfiguration::new(
             None,
@@ -1435,6 +1557,30 @@ impl Configuration {
             .or(self.raw_conf.next_hardfork_transition_number)
             .unwrap_or(default_transition_time);
 
+        //
+        // EIP-7702 in eSpace
+        //
+        params.transition_heights.cip7702 = self
+            .raw_conf
+            .cip7702_transition_height
+            .unwrap_or(default_transition_time);
+
+        //
+        // EIP-2537 BLS12-381 precompiles
+        //
+        params.transition_numbers.cip2537 = self
+            .raw_conf
+            .cip2537_transition_number
+            .unwrap_or(default_transition_time);
+
+        //
+        // RIP-7212 secp256r1 precompile
+        //
+        params.transition_numbers.cip7212 = self
+            .raw_conf
+            .cip7212_transition_number
+            .unwrap_or(default_transition_time);
+
         if params.transition_heights.cip1559
             < self.raw_conf.pos_reference_enable_height
         {
@@ -1471,6 +1617,20 @@ pub fn parse_hex_string<F: FromStr>(hex_str: &str) -> Result<F, F::Err> {
     hex_str.strip_prefix("0x").unwrap_or(hex_str).parse()
 }
 
+/// Parses the file mode of the IPC socket, e.g. "600". Only the permission
+/// bits are allowed.
+pub fn parse_ipc_permissions(s: &str) -> Result<u32, String> {
+    let mode = u32::from_str_radix(s, 8)
+        .map_err(|e| format!("Invalid jsonrpc_ipc_permissions: {}", e))?;
+    if mode > 0o777 {
+        return Err(format!(
+            "Invalid jsonrpc_ipc_permissions: {} is not within 777",
+            s
+        ));
+    }
+    Ok(mode)
+}
+
 pub fn parse_config_address_string(
     addr: &str, network: &Network,
 ) -> Result<Address, String> {
@@ -1506,7 +1666,9 @@ pub fn parse_config_address_string(
 mod tests {
     use cfx_addr::Network;
 
-    use crate::configuration::parse_config_address_string;
+    use crate::configuration::{
+        parse_config_address_string, parse_ipc_permissions,
+    };
 
     #[test]
     fn test_config_address_string() {
@@ -1543,4 +1705,16 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn test_ipc_permissions() {
+        assert_eq!(parse_ipc_permissions("600"), Ok(0o600));
+        assert_eq!(parse_ipc_permissions("0660"), Ok(0o660));
+        assert_eq!(parse_ipc_permissions("777"), Ok(0o777));
+        // Neither the special bits nor the modes beyond `u16` are allowed.
+        assert!(parse_ipc_permissions("1777").is_err());
+        assert!(parse_ipc_permissions("200600").is_err());
+        assert!(parse_ipc_permissions("680").is_err());
+        assert!(parse_ipc_permissions("").is_err());
+    }
 }
@@ -3,6 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 use jsonrpc_http_server::Server as HttpServer;
+use jsonrpc_ipc_server::Server as IpcServer;
 use jsonrpc_tcp_server::Server as TcpServer;
 use jsonrpc_ws_server::Server as WsServer;
 
@@ -23,11 +24,13 @@ use std::sync::Arc;
 pub struct FullClientExtraComponents {
     pub consensus: Arc<ConsensusGraph>,
     pub debug_rpc_http_server: Option<HttpServer>,
+    pub admin_rpc_http_server: Option<HttpServer>,
     pub rpc_http_server: Option<HttpServer>,
     pub debug_rpc_tcp_server: Option<TcpServer>,
     pub rpc_tcp_server: Option<TcpServer>,
     pub debug_rpc_ws_server: Option<WsServer>,
     pub rpc_ws_server: Option<WsServer>,
+    pub debug_rpc_ipc_server: Option<IpcServer>,
     pub runtime: Runtime,
     pub sync: Arc<SynchronizationService>,
     pub txpool: Arc<TransactionPool>,
@@ -58,11 +61,13 @@ impl FullClient {
             sync,
             blockgen,
             debug_rpc_http_server,
+            admin_rpc_http_server,
             rpc_http_server,
             debug_rpc_tcp_server,
             rpc_tcp_server,
             debug_rpc_ws_server,
             rpc_ws_server,
+            debug_rpc_ipc_server,
             pos_handler,
             runtime,
             eth_rpc_http_server,
@@ -75,11 +80,13 @@ impl FullClient {
             other_components: FullClientExtraComponents {
                 consensus,
                 debug_rpc_http_server,
+                admin_rpc_http_server,
                 rpc_http_server,
                 debug_rpc_tcp_server,
                 rpc_tcp_server,
                 debug_rpc_ws_server,
                 rpc_ws_server,
+                debug_rpc_ipc_server,
                 runtime,
                 sync,
                 txpool,
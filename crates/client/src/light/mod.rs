@@ -8,6 +8,7 @@ use parking_lot::{Condvar, Mutex};
 use secret_store::SecretStore;
 
 use jsonrpc_http_server::Server as HttpServer;
+use jsonrpc_ipc_server::Server as IpcServer;
 use jsonrpc_tcp_server::Server as TcpServer;
 use jsonrpc_ws_server::Server as WsServer;
 
@@ -16,7 +17,8 @@ use crate::{
     configuration::Configuration,
     rpc::{
         extractor::RpcExtractor, impls::light::RpcImpl,
-        setup_debug_rpc_apis_light, setup_public_rpc_apis_light,
+        setup_admin_rpc_apis_light, setup_debug_rpc_apis_light,
+        setup_public_rpc_apis_light,
     },
 };
 use blockgen::BlockGenerator;
@@ -30,8 +32,10 @@ use runtime::Runtime;
 pub struct LightClientExtraComponents {
     pub consensus: Arc<ConsensusGraph>,
     pub debug_rpc_http_server: Option<HttpServer>,
+    pub admin_rpc_http_server: Option<HttpServer>,
     pub debug_rpc_tcp_server: Option<TcpServer>,
     pub debug_rpc_ws_server: Option<WsServer>,
+    pub debug_rpc_ipc_server: Option<IpcServer>,
     pub light: Arc<LightQueryService>,
     pub rpc_http_server: Option<HttpServer>,
     pub rpc_tcp_server: Option<TcpServer>,
@@ -97,6 +101,7 @@ impl LightClient {
             consensus.clone(),
             data_man.clone(),
         ));
+        let client_throttle = conf.client_throttle()?.map(Arc::new);
 
         let debug_rpc_http_server = super::rpc::start_http(
             conf.local_http_config(),
@@ -109,6 +114,21 @@ impl LightClient {
             ),
         )?;
 
+        let admin_rpc_http_server = match conf.admin_jwt_secret()? {
+            Some(jwt_secret) => super::rpc::start_http(
+                conf.admin_http_config(),
+                setup_admin_rpc_apis_light(
+                    common_impl.clone(),
+                    rpc_impl.clone(),
+                    pubsub.clone(),
+                    eth_pubsub.clone(),
+                    jwt_secret,
+                    &conf,
+                ),
+            )?,
+            None => None,
+        };
+
         let debug_rpc_tcp_server = super::rpc::start_tcp(
             conf.local_tcp_config(),
             setup_debug_rpc_apis_light(
@@ -128,6 +148,7 @@ impl LightClient {
                 rpc_impl.clone(),
                 pubsub.clone(),
                 eth_pubsub.clone(),
+                client_throttle.clone(),
                 &conf,
             ),
             RpcExtractor,
@@ -140,6 +161,20 @@ impl LightClient {
                 rpc_impl.clone(),
                 pubsub.clone(),
                 eth_pubsub.clone(),
+                client_throttle.clone(),
+                &conf,
+            ),
+            RpcExtractor,
+        )?;
+
+        let debug_rpc_ipc_server = super::rpc::start_ipc(
+            conf.ipc_config(),
+            setup_public_rpc_apis_light(
+                common_impl.clone(),
+                rpc_impl.clone(),
+                pubsub.clone(),
+                eth_pubsub.clone(),
+                client_throttle.clone(),
                 &conf,
             ),
             RpcExtractor,
@@ -152,6 +187,7 @@ impl LightClient {
                 rpc_impl.clone(),
                 pubsub.clone(),
                 eth_pubsub.clone(),
+                client_throttle.clone(),
                 &conf,
             ),
             RpcExtractor,
@@ -164,6 +200,7 @@ impl LightClient {
                 rpc_impl,
                 pubsub.clone(),
                 eth_pubsub.clone(),
+                client_throttle,
                 &conf,
             ),
         )?;
@@ -177,8 +214,10 @@ impl LightClient {
             other_components: LightClientExtraComponents {
                 consensus,
                 debug_rpc_http_server,
+                admin_rpc_http_server,
                 debug_rpc_tcp_server,
                 debug_rpc_ws_server,
+                debug_rpc_ipc_server,
                 light,
                 rpc_http_server,
                 rpc_tcp_server,
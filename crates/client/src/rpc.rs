@@ -9,6 +9,10 @@ use jsonrpc_http_server::{
     AccessControlAllowOrigin, DomainsValidation, Server as HttpServer,
     ServerBuilder as HttpServerBuilder,
 };
+use jsonrpc_ipc_server::{
+    MetaExtractor as IpcMetaExtractor, SecurityAttributes, Server as IpcServer,
+    ServerBuilder as IpcServerBuilder,
+};
 use jsonrpc_tcp_server::{
     MetaExtractor as TpcMetaExtractor, Server as TcpServer,
     ServerBuilder as TcpServerBuilder,
@@ -23,6 +27,7 @@ use std::{
 };
 
 mod authcodes;
+pub mod client_throttle;
 pub mod errors;
 pub mod extractor;
 mod helpers;
@@ -30,6 +35,7 @@ mod http_common;
 pub mod impls;
 pub mod informant;
 mod interceptor;
+pub mod jwt;
 pub mod metadata;
 pub mod rpc_apis;
 mod traits;
@@ -47,10 +53,12 @@ use self::{
         cfx::{CfxHandler, LocalRpcImpl, RpcImpl, TestRpcImpl},
         cfx_filter::CfxFilterClient,
         common::RpcImpl as CommonImpl,
+        eth::light::EthHandler as LightEthHandler,
         eth_pubsub::PubSubClient as EthPubSubClient,
         light::{
             CfxHandler as LightCfxHandler, DebugRpcImpl as LightDebugRpcImpl,
             RpcImpl as LightImpl, TestRpcImpl as LightTestRpcImpl,
+            TransactionPoolHandler as LightTransactionPoolHandler,
         },
         pool::TransactionPoolHandler,
         pos::{PoSInterceptor, PosHandler},
@@ -77,14 +85,17 @@ pub use self::types::{Block as RpcBlock, Origin};
 use crate::{
     configuration::Configuration,
     rpc::{
+        client_throttle::ClientThrottle,
         errors::request_rejected_too_many_request_error,
+        extractor::RpcExtractor,
         impls::{
             eth::{EthHandler, GethDebugHandler},
             eth_filter::EthFilterClient,
             trace::EthTraceHandler,
             RpcImplConfiguration,
         },
-        interceptor::{RpcInterceptor, RpcProxy},
+        interceptor::{Caller, RpcInterceptor, RpcProxy},
+        jwt::{JwtInterceptor, JwtSecret},
         rpc_apis::{Api, ApiSet},
         traits::eth_space::debug::Debug,
     },
@@ -95,7 +106,10 @@ use lazy_static::lazy_static;
 pub use metadata::Metadata;
 use metrics::{register_timer_with_group, ScopeTimer, Timer};
 use parking_lot::Mutex;
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 use throttling::token_bucket::{ThrottleResult, TokenBucketManager};
 
 lazy_static! {
@@ -187,15 +201,35 @@ impl WsConfiguration {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct IpcConfiguration {
+    pub enabled: bool,
+    pub path: String,
+    // The file mode of the socket file, only applied on unix platforms.
+    pub permissions: u32,
+}
+
+impl IpcConfiguration {
+    pub fn new(path: Option<String>, permissions: u32) -> Self {
+        IpcConfiguration {
+            enabled: path.is_some(),
+            path: path.unwrap_or_default(),
+            permissions,
+        }
+    }
+}
+
 pub fn setup_public_rpc_apis(
     common: Arc<CommonImpl>, rpc: Arc<RpcImpl>, pubsub: PubSubClient,
     eth_pubsub: EthPubSubClient, conf: &Configuration,
 ) -> MetaIoHandler<Metadata> {
+    let client_throttle = rpc.config.client_throttle.clone();
     setup_rpc_apis(
         common,
         rpc,
         pubsub,
         eth_pubsub,
+        client_throttle,
         &conf.raw_conf.throttling_conf,
         "rpc",
         conf.raw_conf.public_rpc_apis.list_apis(),
@@ -206,11 +240,13 @@ pub fn setup_public_eth_rpc_apis(
     common: Arc<CommonImpl>, rpc: Arc<RpcImpl>, pubsub: PubSubClient,
     eth_pubsub: EthPubSubClient, conf: &Configuration,
 ) -> MetaIoHandler<Metadata> {
+    let client_throttle = rpc.config.client_throttle.clone();
     setup_rpc_apis(
         common,
         rpc,
         pubsub,
         eth_pubsub,
+        client_throttle,
         &conf.raw_conf.throttling_conf,
         "rpc",
         conf.raw_conf.public_evm_rpc_apis.list_apis(),
@@ -226,16 +262,28 @@ pub fn setup_debug_rpc_apis(
         rpc,
         pubsub,
         eth_pubsub,
+        None,
         &conf.raw_conf.throttling_conf,
         "rpc_local",
         ApiSet::All.list_apis(),
     )
 }
 
+pub fn setup_admin_rpc_apis(
+    common: Arc<CommonImpl>, rpc: Arc<RpcImpl>, pubsub: PubSubClient,
+    eth_pubsub: EthPubSubClient, jwt_secret: JwtSecret, conf: &Configuration,
+) -> MetaIoHandler<Metadata> {
+    with_jwt_authentication(
+        setup_debug_rpc_apis(common, rpc, pubsub, eth_pubsub, conf),
+        jwt_secret,
+    )
+}
+
 fn setup_rpc_apis(
     common: Arc<CommonImpl>, rpc: Arc<RpcImpl>, pubsub: PubSubClient,
-    eth_pubsub: EthPubSubClient, throttling_conf: &Option<String>,
-    throttling_section: &str, apis: HashSet<Api>,
+    eth_pubsub: EthPubSubClient, client_throttle: Option<Arc<ClientThrottle>>,
+    throttling_conf: &Option<String>, throttling_section: &str,
+    apis: HashSet<Api>,
 ) -> MetaIoHandler<Metadata> {
     let mut handler = MetaIoHandler::default();
     for api in &apis {
@@ -247,6 +295,7 @@ fn setup_rpc_apis(
                     &mut handler,
                     &rpc.config,
                     cfx,
+                    client_throttle.clone(),
                     throttling_conf,
                     throttling_section,
                 );
@@ -269,6 +318,7 @@ fn setup_rpc_apis(
                             &mut handler,
                             &rpc.config,
                             filter_client,
+                            client_throttle.clone(),
                             throttling_conf,
                             throttling_section,
                         );
@@ -296,6 +346,7 @@ fn setup_rpc_apis(
                     &mut handler,
                     &rpc.config,
                     evm,
+                    client_throttle.clone(),
                     throttling_conf,
                     throttling_section,
                 );
@@ -318,6 +369,7 @@ fn setup_rpc_apis(
                             &mut handler,
                             &rpc.config,
                             filter_client,
+                            client_throttle.clone(),
                             throttling_conf,
                             throttling_section,
                         );
@@ -335,6 +387,7 @@ fn setup_rpc_apis(
                     &mut handler,
                     &rpc.config,
                     pubsub.clone().to_delegate(),
+                    client_throttle.clone(),
                     throttling_conf,
                     throttling_section,
                 );
@@ -345,6 +398,7 @@ fn setup_rpc_apis(
                     &mut handler,
                     &rpc.config,
                     eth_pubsub.clone().to_delegate(),
+                    client_throttle.clone(),
                     throttling_conf,
                     throttling_section,
                 );
@@ -370,6 +424,7 @@ fn setup_rpc_apis(
                     &mut handler,
                     &rpc.config,
                     trace,
+                    client_throttle.clone(),
                     throttling_conf,
                     throttling_section,
                 );
@@ -381,6 +436,7 @@ fn setup_rpc_apis(
                     &mut handler,
                     &rpc.config,
                     txpool,
+                    client_throttle.clone(),
                     throttling_conf,
                     throttling_section,
                 );
@@ -407,10 +463,14 @@ pub fn extend_with_interceptor<
     T: IntoIterator<Item = (String, RemoteProcedure<Metadata>)>,
 >(
     handler: &mut MetaIoHandler<Metadata>, rpc_conf: &RpcImplConfiguration,
-    rpc_impl: T, throttling_conf: &Option<String>, throttling_section: &str,
+    rpc_impl: T, client_throttle: Option<Arc<ClientThrottle>>,
+    throttling_conf: &Option<String>, throttling_section: &str,
 ) {
-    let interceptor =
-        ThrottleInterceptor::new(throttling_conf, throttling_section);
+    let interceptor = ThrottleInterceptor::new(
+        throttling_conf,
+        throttling_section,
+        client_throttle,
+    );
     if rpc_conf.enable_metrics {
         handler.extend_with(RpcProxy::new(
             rpc_impl,
@@ -421,6 +481,17 @@ pub fn extend_with_interceptor<
     }
 }
 
+/// Requires every call to the methods of `handler` to carry a bearer token
+/// signed with `jwt_secret`.
+fn with_jwt_authentication(
+    handler: MetaIoHandler<Metadata>, jwt_secret: JwtSecret,
+) -> MetaIoHandler<Metadata> {
+    let mut authenticated = MetaIoHandler::default();
+    authenticated
+        .extend_with(RpcProxy::new(handler, JwtInterceptor::new(jwt_secret)));
+    authenticated
+}
+
 fn add_meta_rpc_methods(
     mut handler: MetaIoHandler<Metadata>, apis: HashSet<Api>,
 ) -> MetaIoHandler<Metadata> {
@@ -453,13 +524,15 @@ fn add_meta_rpc_methods(
 
 pub fn setup_public_rpc_apis_light(
     common: Arc<CommonImpl>, rpc: Arc<LightImpl>, pubsub: PubSubClient,
-    eth_pubsub: EthPubSubClient, conf: &Configuration,
+    eth_pubsub: EthPubSubClient, client_throttle: Option<Arc<ClientThrottle>>,
+    conf: &Configuration,
 ) -> MetaIoHandler<Metadata> {
     setup_rpc_apis_light(
         common,
         rpc,
         pubsub,
         eth_pubsub,
+        client_throttle,
         &conf.raw_conf.throttling_conf,
         "rpc",
         conf.raw_conf.public_rpc_apis.list_apis(),
@@ -477,16 +550,28 @@ pub fn setup_debug_rpc_apis_light(
         rpc,
         pubsub,
         eth_pubsub,
+        None,
         &conf.raw_conf.throttling_conf,
         "rpc_local",
         light_debug_apis,
     )
 }
 
+pub fn setup_admin_rpc_apis_light(
+    common: Arc<CommonImpl>, rpc: Arc<LightImpl>, pubsub: PubSubClient,
+    eth_pubsub: EthPubSubClient, jwt_secret: JwtSecret, conf: &Configuration,
+) -> MetaIoHandler<Metadata> {
+    with_jwt_authentication(
+        setup_debug_rpc_apis_light(common, rpc, pubsub, eth_pubsub, conf),
+        jwt_secret,
+    )
+}
+
 fn setup_rpc_apis_light(
     common: Arc<CommonImpl>, rpc: Arc<LightImpl>, pubsub: PubSubClient,
-    eth_pubsub: EthPubSubClient, throttling_conf: &Option<String>,
-    throttling_section: &str, apis: HashSet<Api>,
+    eth_pubsub: EthPubSubClient, client_throttle: Option<Arc<ClientThrottle>>,
+    throttling_conf: &Option<String>, throttling_section: &str,
+    apis: HashSet<Api>,
 ) -> MetaIoHandler<Metadata> {
     let mut handler = MetaIoHandler::default();
     for api in apis {
@@ -497,11 +582,22 @@ fn setup_rpc_apis_light(
                 let interceptor = ThrottleInterceptor::new(
                     throttling_conf,
                     throttling_section,
+                    client_throttle.clone(),
                 );
                 handler.extend_with(RpcProxy::new(cfx, interceptor));
             }
             Api::Eth => {
-                warn!("Light nodes do not support evm ports.");
+                let evm = LightEthHandler::new(
+                    rpc.consensus.clone(),
+                    rpc.light.clone(),
+                )
+                .to_delegate();
+                let interceptor = ThrottleInterceptor::new(
+                    throttling_conf,
+                    throttling_section,
+                    client_throttle.clone(),
+                );
+                handler.extend_with(RpcProxy::new(evm, interceptor));
             }
             Api::EthDebug => {
                 warn!("Light nodes do not support evm ports.");
@@ -526,7 +622,14 @@ fn setup_rpc_apis_light(
                 warn!("Light nodes do not support trace RPC");
             }
             Api::TxPool => {
-                warn!("Light nodes do not support txpool RPC");
+                let txpool =
+                    LightTransactionPoolHandler::new(rpc.clone()).to_delegate();
+                let interceptor = ThrottleInterceptor::new(
+                    throttling_conf,
+                    throttling_section,
+                    client_throttle.clone(),
+                );
+                handler.extend_with(RpcProxy::new(txpool, interceptor));
             }
             Api::Pos => {
                 warn!("Light nodes do not support PoS RPC");
@@ -563,7 +666,8 @@ pub fn start_http(
     if !conf.enabled {
         return Ok(None);
     }
-    let mut builder = HttpServerBuilder::new(handler);
+    let mut builder =
+        HttpServerBuilder::with_meta_extractor(handler, RpcExtractor);
     if let Some(threads) = conf.threads {
         builder = builder.threads(threads);
     }
@@ -603,47 +707,149 @@ where
     }
 }
 
+pub fn start_ipc<H, T>(
+    conf: IpcConfiguration, handler: H, extractor: T,
+) -> Result<Option<IpcServer>, String>
+where
+    H: Into<MetaIoHandler<Metadata>>,
+    T: IpcMetaExtractor<Metadata> + 'static,
+{
+    if !conf.enabled {
+        return Ok(None);
+    }
+
+    if conf.permissions > 0o777 {
+        return Err(format!(
+            "IPC error: invalid permissions {:o} (path = {})",
+            conf.permissions, conf.path
+        ));
+    }
+
+    let security_attributes = SecurityAttributes::empty()
+        .set_mode(conf.permissions as _)
+        .map_err(|io_error| {
+            format!("IPC error: {} (path = {})", io_error, conf.path)
+        })?;
+
+    let server = IpcServerBuilder::with_meta_extractor(handler, extractor)
+        .set_security_attributes(security_attributes)
+        .start(&conf.path)
+        .map_err(|io_error| {
+            format!("IPC error: {} (path = {})", io_error, conf.path)
+        })?;
+
+    // The server sets the mode after binding the socket, so the mode is
+    // checked before the server is handed out.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(&conf.path)
+            .map(|metadata| metadata.permissions().mode() & 0o777);
+        if mode.as_ref().ok() != Some(&conf.permissions) {
+            server.close();
+            let _ = std::fs::remove_file(&conf.path);
+            return Err(match mode {
+                Ok(mode) => format!(
+                    "IPC error: the socket has permissions {:o} instead of {:o} (path = {})",
+                    mode, conf.permissions, conf.path
+                ),
+                Err(io_error) => {
+                    format!("IPC error: {} (path = {})", io_error, conf.path)
+                }
+            });
+        }
+    }
+
+    Ok(Some(server))
+}
+
 struct ThrottleInterceptor {
     manager: TokenBucketManager,
+    client_throttle: Option<Arc<ClientThrottle>>,
 }
 
 impl ThrottleInterceptor {
-    fn new(file: &Option<String>, section: &str) -> Self {
+    fn new(
+        file: &Option<String>, section: &str,
+        client_throttle: Option<Arc<ClientThrottle>>,
+    ) -> Self {
         let manager = match file {
             Some(file) => TokenBucketManager::load(file, Some(section))
                 .expect("invalid throttling configuration file"),
             None => TokenBucketManager::default(),
         };
 
-        ThrottleInterceptor { manager }
+        ThrottleInterceptor {
+            manager,
+            client_throttle,
+        }
+    }
+
+    fn throttle_client(
+        &self, name: &String, caller: &Caller,
+    ) -> JsonRpcResult<()> {
+        let client_throttle = match &self.client_throttle {
+            Some(client_throttle) => client_throttle,
+            None => return Ok(()),
+        };
+        let client = match client_throttle.client_id(caller) {
+            Some(client) => client,
+            None => return Ok(()),
+        };
+
+        let (result, throttled_for) = client_throttle.throttle(&client, name);
+        if result != ThrottleResult::Success {
+            debug!("RPC {} throttled for client {:?}", name, client);
+        }
+        throttle_result(result, throttled_for, "client throttled")
     }
 }
 
+/// Converts the result of throttling into the error returned to the caller,
+/// which tells when to retry.
+fn throttle_result(
+    result: ThrottleResult, throttled_for: Option<Duration>, reason: &str,
+) -> JsonRpcResult<()> {
+    let wait_time = match result {
+        ThrottleResult::Success => return Ok(()),
+        ThrottleResult::Throttled(wait_time) => Some(wait_time),
+        ThrottleResult::AlreadyThrottled => throttled_for,
+    };
+    let details = match wait_time {
+        Some(wait_time) => format!(
+            "{}, please retry after {} ms",
+            reason,
+            wait_time.as_millis().max(1)
+        ),
+        None => format!("{}, please try again later", reason),
+    };
+    bail!(request_rejected_too_many_request_error(Some(details)))
+}
+
 impl RpcInterceptor for ThrottleInterceptor {
-    fn before(&self, name: &String) -> JsonRpcResult<()> {
+    fn before(&self, name: &String, caller: &Caller) -> JsonRpcResult<()> {
+        self.throttle_client(name, caller)?;
+
         let bucket = match self.manager.get(name) {
             Some(bucket) => bucket,
             None => return Ok(()),
         };
 
-        let result = bucket.lock().throttle_default();
-
-        match result {
-            ThrottleResult::Success => Ok(()),
+        let (result, throttled_for) = {
+            let mut bucket = bucket.lock();
+            (bucket.throttle_default(), bucket.throttled_for())
+        };
+        match &result {
+            ThrottleResult::Success => {}
             ThrottleResult::Throttled(wait_time) => {
-                debug!("RPC {} throttled in {:?}", name, wait_time);
-                bail!(request_rejected_too_many_request_error(Some(format!(
-                    "throttled in {:?}",
-                    wait_time
-                ))))
+                debug!("RPC {} throttled in {:?}", name, wait_time)
             }
             ThrottleResult::AlreadyThrottled => {
-                debug!("RPC {} already throttled", name);
-                bail!(request_rejected_too_many_request_error(Some(
-                    "already throttled, please try again later".into()
-                )))
+                debug!("RPC {} already throttled", name)
             }
         }
+        throttle_result(result, throttled_for, "throttled")
     }
 }
 
@@ -661,8 +867,8 @@ impl MetricsInterceptor {
 }
 
 impl RpcInterceptor for MetricsInterceptor {
-    fn before(&self, name: &String) -> JsonRpcResult<()> {
-        self.throttle_interceptor.before(name)?;
+    fn before(&self, name: &String, caller: &Caller) -> JsonRpcResult<()> {
+        self.throttle_interceptor.before(name, caller)?;
         // Use a global variable here because `http` and `web3` setup different
         // interceptors for the same RPC API.
         let mut timers = METRICS_INTERCEPTOR_TIMERS.lock();
@@ -693,3 +899,91 @@ impl RpcInterceptor for MetricsInterceptor {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{start_ipc, IpcConfiguration, Metadata};
+    use crate::rpc::extractor::RpcExtractor;
+    use jsonrpc_core::{MetaIoHandler, Value};
+    use tempdir::TempDir;
+
+    fn new_handler() -> MetaIoHandler<Metadata> {
+        let mut handler = MetaIoHandler::default();
+        handler.add_method("test_ping", |_| Ok(Value::String("pong".into())));
+        handler
+    }
+
+    fn socket_path(dir: &TempDir) -> String {
+        dir.path().join("cfx.ipc").to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn test_ipc_disabled() {
+        let conf = IpcConfiguration::new(None, 0o600);
+        assert!(start_ipc(conf, new_handler(), RpcExtractor)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_ipc_invalid_permissions() {
+        let dir = TempDir::new("ipc_invalid_permissions").unwrap();
+        let path = socket_path(&dir);
+        let conf = IpcConfiguration::new(Some(path.clone()), 0o1777);
+        assert!(start_ipc(conf, new_handler(), RpcExtractor).is_err());
+        assert!(!dir.path().join("cfx.ipc").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ipc_permissions() {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let dir = TempDir::new("ipc_permissions").unwrap();
+        for permissions in [0o600, 0o660] {
+            let path = socket_path(&dir);
+            let conf = IpcConfiguration::new(Some(path.clone()), permissions);
+            let server = start_ipc(conf, new_handler(), RpcExtractor)
+                .unwrap()
+                .unwrap();
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, permissions);
+            server.close();
+            let _ = fs::remove_file(&path);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ipc_request() {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            os::unix::net::UnixStream,
+            time::Duration,
+        };
+
+        let dir = TempDir::new("ipc_request").unwrap();
+        let path = socket_path(&dir);
+        let conf = IpcConfiguration::new(Some(path.clone()), 0o600);
+        let _server = start_ipc(conf, new_handler(), RpcExtractor)
+            .unwrap()
+            .unwrap();
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        stream
+            .write_all(
+                b"{\"jsonrpc\":\"2.0\",\"method\":\"test_ping\",\"params\":[],\"id\":1}\n",
+            )
+            .unwrap();
+
+        let mut response = String::new();
+        BufReader::new(stream).read_line(&mut response).unwrap();
+        assert_eq!(
+            response.trim(),
+            r#"{"jsonrpc":"2.0","result":"pong","id":1}"#
+        );
+    }
+}
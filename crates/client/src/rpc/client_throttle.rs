@@ -0,0 +1,380 @@
+// Copyright 2023 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Per-client throttling of the public RPC endpoints. Every client, identified
+//! by its API key, its IP address or its WebSocket session, gets its own token
+//! bucket, and every call costs the weight of its method, so that one client
+//! cannot exhaust the shared per-method buckets for everyone.
+
+use crate::rpc::interceptor::Caller;
+use keccak_hash::keccak;
+use lazy_static::lazy_static;
+use lru_cache::LruCache;
+use metrics::{Counter, CounterUsize};
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::read_to_string,
+    net::IpAddr,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use throttling::token_bucket::{ThrottleResult, TokenBucket};
+
+/// The section of the throttling configuration file with the method weights.
+pub const CLIENT_WEIGHTS_SECTION: &str = "rpc_client_weights";
+
+/// At most this many clients are reported individually through the metrics,
+/// the others are accounted together.
+const MAX_CLIENT_METRICS: usize = 1000;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ClientId {
+    ApiKey(String),
+    Ip(IpAddr),
+    WsSession(u64),
+}
+
+impl ClientId {
+    /// The name of the client in the metrics, which does not reveal the API
+    /// keys.
+    fn metrics_name(&self) -> String {
+        match self {
+            ClientId::ApiKey(key) => {
+                format!("key_{:x}", keccak(key.as_bytes()))[..20].to_string()
+            }
+            ClientId::Ip(ip) => {
+                format!("ip_{}", ip).replace(|c| c == '.' || c == ':', "_")
+            }
+            ClientId::WsSession(session) => format!("ws_{}", session),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientThrottleConfig {
+    /// The token bucket of each client, in the format of the throttling
+    /// configuration file.
+    pub bucket: String,
+    /// The maximum number of client buckets kept, the least recently used
+    /// ones are dropped beyond it.
+    pub cache_size: usize,
+    /// The API keys identifying the clients, others are ignored.
+    pub api_keys: HashSet<String>,
+    /// Whether the HTTP requests are attributed to the last address of their
+    /// `X-Forwarded-For` header, which is only safe if the endpoint is only
+    /// reachable through a reverse proxy appending it.
+    pub trust_forwarded_for: bool,
+    /// The throttling configuration file with the method weights.
+    pub throttling_conf: Option<String>,
+}
+
+lazy_static! {
+    static ref CLIENT_COUNTERS: Mutex<HashMap<ClientId, Arc<ClientCounters>>> =
+        Default::default();
+    static ref OTHER_CLIENT_COUNTERS: Arc<ClientCounters> =
+        Arc::new(ClientCounters::register("others"));
+}
+
+struct ClientCounters {
+    requests: Arc<dyn Counter<usize>>,
+    throttled: Arc<dyn Counter<usize>>,
+}
+
+impl ClientCounters {
+    fn register(name: &str) -> Self {
+        ClientCounters {
+            requests: CounterUsize::register_with_group(
+                "rpc_client",
+                &format!("{}_requests", name),
+            ),
+            throttled: CounterUsize::register_with_group(
+                "rpc_client",
+                &format!("{}_throttled", name),
+            ),
+        }
+    }
+}
+
+pub struct ClientThrottle {
+    bucket: String,
+    weights: HashMap<String, u64>,
+    api_keys: HashSet<String>,
+    trust_forwarded_for: bool,
+    buckets: Mutex<LruCache<ClientId, TokenBucket>>,
+}
+
+impl ClientThrottle {
+    pub fn new(config: ClientThrottleConfig) -> Result<Self, String> {
+        // The buckets would be dropped as soon as they are created.
+        if config.cache_size == 0 {
+            return Err("client throttling cache size must be positive".into());
+        }
+        // Check the bucket format once here as the buckets are created lazily.
+        let bucket = TokenBucket::from_str(&config.bucket)
+            .map_err(|e| format!("invalid client token bucket: {}", e))?;
+        let weights = match &config.throttling_conf {
+            Some(file) => load_weights(file)?,
+            None => HashMap::new(),
+        };
+        // A call costing more than the capacity would always be throttled.
+        for (method, weight) in &weights {
+            if *weight > bucket.max_tokens() {
+                return Err(format!(
+                    "weight {} of {} exceeds the client bucket capacity {}",
+                    weight,
+                    method,
+                    bucket.max_tokens()
+                ));
+            }
+        }
+
+        Ok(ClientThrottle {
+            bucket: config.bucket,
+            weights,
+            api_keys: config.api_keys,
+            trust_forwarded_for: config.trust_forwarded_for,
+            buckets: Mutex::new(LruCache::new(config.cache_size)),
+        })
+    }
+
+    /// Identifies the caller, or returns `None` for the local callers and
+    /// those that cannot be identified.
+    pub fn client_id(&self, caller: &Caller) -> Option<ClientId> {
+        if let Some(key) = &caller.api_key {
+            if self.api_keys.contains(key) {
+                return Some(ClientId::ApiKey(key.clone()));
+            }
+        }
+        if let Some(session) = caller.ws_session {
+            return Some(ClientId::WsSession(session));
+        }
+        let ip = caller.remote_ip.or(if self.trust_forwarded_for {
+            caller.forwarded_ip
+        } else {
+            None
+        })?;
+        (!ip.is_loopback()).then(|| ClientId::Ip(ip))
+    }
+
+    /// Acquires the weight of `method` from the bucket of `client`.
+    pub fn throttle(
+        &self, client: &ClientId, method: &str,
+    ) -> (ThrottleResult, Option<Duration>) {
+        let (result, throttled_for) = {
+            let mut buckets = self.buckets.lock();
+            // Taking the bucket out and putting it back also marks it as the
+            // most recently used one.
+            let mut bucket = match buckets.remove(client) {
+                Some(bucket) => bucket,
+                None => TokenBucket::from_str(&self.bucket)
+                    .expect("checked in constructor"),
+            };
+            let result = match self.weights.get(method) {
+                Some(weight) => bucket.throttle(*weight, 0),
+                None => bucket.throttle_default(),
+            };
+            let throttled_for = bucket.throttled_for();
+            buckets.insert(client.clone(), bucket);
+            (result, throttled_for)
+        };
+
+        let counters = client_counters(client);
+        counters.requests.inc(1);
+        if result != ThrottleResult::Success {
+            counters.throttled.inc(1);
+        }
+        (result, throttled_for)
+    }
+}
+
+fn client_counters(client: &ClientId) -> Arc<ClientCounters> {
+    let mut counters = CLIENT_COUNTERS.lock();
+    if let Some(c) = counters.get(client) {
+        return c.clone();
+    }
+    if counters.len() >= MAX_CLIENT_METRICS {
+        return OTHER_CLIENT_COUNTERS.clone();
+    }
+    let c = Arc::new(ClientCounters::register(&client.metrics_name()));
+    counters.insert(client.clone(), c.clone());
+    c
+}
+
+/// Loads the method weights from the `[rpc_client_weights]` section of the
+/// throttling configuration file, where each method is mapped to the number
+/// of tokens a call costs.
+fn load_weights(file: &str) -> Result<HashMap<String, u64>, String> {
+    let content = read_to_string(file)
+        .map_err(|e| format!("failed to read toml file: {:?}", e))?;
+    let toml_val = content
+        .parse::<toml::Value>()
+        .map_err(|e| format!("failed to parse toml file: {:?}", e))?;
+
+    let table = match toml_val.get(CLIENT_WEIGHTS_SECTION) {
+        Some(val) => val.as_table().ok_or_else(|| {
+            format!("section [{}] is not a table", CLIENT_WEIGHTS_SECTION)
+        })?,
+        None => return Ok(HashMap::new()),
+    };
+
+    let mut weights = HashMap::new();
+    for (method, weight) in table {
+        let weight = weight
+            .as_integer()
+            .filter(|w| *w > 0)
+            .ok_or_else(|| format!("invalid weight of {}", method))?;
+        weights.insert(method.clone(), weight as u64);
+    }
+    Ok(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientId, ClientThrottle, ClientThrottleConfig};
+    use crate::rpc::interceptor::Caller;
+    use std::fs;
+    use tempdir::TempDir;
+    use throttling::token_bucket::ThrottleResult;
+
+    fn new_config(
+        api_keys: &[&str], trust_forwarded_for: bool,
+    ) -> ClientThrottleConfig {
+        ClientThrottleConfig {
+            bucket: "2,2,1,1,0".into(),
+            cache_size: 2,
+            api_keys: api_keys.iter().map(|k| k.to_string()).collect(),
+            trust_forwarded_for,
+            throttling_conf: None,
+        }
+    }
+
+    fn new_throttle(
+        api_keys: &[&str], trust_forwarded_for: bool,
+    ) -> ClientThrottle {
+        ClientThrottle::new(new_config(api_keys, trust_forwarded_for)).unwrap()
+    }
+
+    #[test]
+    fn test_client_id() {
+        let throttle = new_throttle(&["key"], false);
+        let caller = Caller {
+            api_key: Some("key".into()),
+            remote_ip: Some("1.2.3.4".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(
+            throttle.client_id(&caller),
+            Some(ClientId::ApiKey("key".into()))
+        );
+
+        // unknown API keys are ignored
+        let caller = Caller {
+            api_key: Some("unknown".into()),
+            ..caller
+        };
+        assert_eq!(
+            throttle.client_id(&caller),
+            Some(ClientId::Ip("1.2.3.4".parse().unwrap()))
+        );
+
+        // local callers are not throttled
+        let caller = Caller {
+            remote_ip: Some("127.0.0.1".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(throttle.client_id(&caller), None);
+
+        // WebSocket clients are identified by their sessions
+        let caller = Caller {
+            ws_session: Some(7),
+            ..Default::default()
+        };
+        assert_eq!(throttle.client_id(&caller), Some(ClientId::WsSession(7)));
+
+        // forwarded addresses are only used if trusted
+        let caller = Caller {
+            forwarded_ip: Some("1.2.3.4".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(throttle.client_id(&caller), None);
+        assert_eq!(
+            new_throttle(&[], true).client_id(&caller),
+            Some(ClientId::Ip("1.2.3.4".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_weights() {
+        let tempdir = TempDir::new("").unwrap();
+        let file = tempdir.path().join("throttling.toml");
+        let mut config = new_config(&[], false);
+        config.throttling_conf = Some(file.to_str().unwrap().into());
+
+        fs::write(&file, "[rpc_client_weights]\ncfx_getLogs=2\n").unwrap();
+        let throttle = ClientThrottle::new(config.clone()).unwrap();
+        let client = ClientId::Ip("1.1.1.1".parse().unwrap());
+        assert_eq!(
+            throttle.throttle(&client, "cfx_getLogs").0,
+            ThrottleResult::Success
+        );
+        assert!(matches!(
+            throttle.throttle(&client, "cfx_getLogs").0,
+            ThrottleResult::Throttled(_)
+        ));
+
+        // weights above the bucket capacity are rejected
+        fs::write(&file, "[rpc_client_weights]\ncfx_getLogs=3\n").unwrap();
+        assert!(ClientThrottle::new(config.clone()).is_err());
+        fs::write(&file, "[rpc_client_weights]\ncfx_getLogs=0\n").unwrap();
+        assert!(ClientThrottle::new(config).is_err());
+    }
+
+    #[test]
+    fn test_cache_size() {
+        let mut config = new_config(&[], false);
+        config.cache_size = 0;
+        assert!(ClientThrottle::new(config).is_err());
+
+        // the least recently used bucket is dropped and starts over
+        let throttle = new_throttle(&[], false);
+        let a = ClientId::Ip("1.1.1.1".parse().unwrap());
+        let b = ClientId::Ip("2.2.2.2".parse().unwrap());
+        let c = ClientId::Ip("3.3.3.3".parse().unwrap());
+        throttle.throttle(&a, "cfx_getLogs");
+        throttle.throttle(&a, "cfx_getLogs");
+        throttle.throttle(&b, "cfx_getLogs");
+        throttle.throttle(&c, "cfx_getLogs");
+        assert_eq!(
+            throttle.throttle(&a, "cfx_getLogs").0,
+            ThrottleResult::Success
+        );
+    }
+
+    #[test]
+    fn test_throttle_per_client() {
+        let throttle = new_throttle(&[], false);
+        let a = ClientId::Ip("1.1.1.1".parse().unwrap());
+        let b = ClientId::Ip("2.2.2.2".parse().unwrap());
+
+        assert_eq!(
+            throttle.throttle(&a, "cfx_getLogs").0,
+            ThrottleResult::Success
+        );
+        assert_eq!(
+            throttle.throttle(&a, "cfx_getLogs").0,
+            ThrottleResult::Success
+        );
+        let (result, throttled_for) = throttle.throttle(&a, "cfx_getLogs");
+        assert!(matches!(result, ThrottleResult::Throttled(_)));
+        assert!(throttled_for.is_some());
+
+        // other clients are not affected
+        assert_eq!(
+            throttle.throttle(&b, "cfx_getLogs").0,
+            ThrottleResult::Success
+        );
+    }
+}
@@ -35,7 +35,7 @@
 /// by 1.
 ///
 /// Do not recycle deprecated error codes.
-const NEXT_SERVER_ERROR_CODE: i64 = -32078;
+const NEXT_SERVER_ERROR_CODE: i64 = -32080;
 /// When the above number is equal to -32100, take the number below on the
 /// right for new error code, then increase it by 1.
 const CFX_EXTRA_SERVER_ERROR_CODE: i64 = -31999;
@@ -67,6 +67,9 @@ pub const REQUEST_REJECTED_TOO_MANY_REQUESTS: i64 = -32072;
 /// This is mostly an application error but it's generic enough to define it
 /// here.
 pub const REQUEST_REJECTED_LIMIT_DATA: i64 = -32041;
+/// When the request to an authenticated endpoint does not carry valid
+/// credentials.
+pub const REQUEST_REJECTED_UNAUTHORIZED: i64 = -32079;
 
 /* Conflux node status related error codes
  *
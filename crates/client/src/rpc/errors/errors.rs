@@ -195,6 +195,7 @@ impl From<TransactionPoolError> for EthApiError {
                 TransactionError::FutureTransactionType => Self::InvalidTransaction(RpcInvalidTransactionError::TxTypeNotSupported),
                 TransactionError::InvalidReceiver => Self::Other("Invalid receiver".to_string()),
                 TransactionError::TooLargeNonce => Self::InvalidTransaction(RpcInvalidTransactionError::NonceMaxValue),
+                TransactionError::EmptyAuthorizationList => Self::InvalidTransaction(RpcInvalidTransactionError::EmptyAuthorizationList),
             },
             TransactionPoolError::GasLimitExceeded { .. } => Self::PoolError(RpcPoolError::ExceedsGasLimit),
             TransactionPoolError::GasPriceLessThanMinimum { .. } => Self::PoolError(RpcPoolError::Underpriced),
@@ -203,6 +204,8 @@ impl From<TransactionPoolError> for EthApiError {
             TransactionPoolError::NonceTooStale { .. } => Self::InvalidTransaction(RpcInvalidTransactionError::NonceTooLow),
             TransactionPoolError::OutOfBalance { .. } => Self::InvalidTransaction(RpcInvalidTransactionError::InsufficientFundsForTransfer),
             TransactionPoolError::TxPoolFull => Self::PoolError(RpcPoolError::TxPoolOverflow),
+            TransactionPoolError::SenderSlotsFull { .. } => Self::PoolError(RpcPoolError::AccountLimitExceeded),
+            TransactionPoolError::AuthorityReserved { .. } => Self::PoolError(RpcPoolError::AuthorityReserved),
             TransactionPoolError::HigherGasPriceNeeded => Self::PoolError(RpcPoolError::ReplaceUnderpriced),
             TransactionPoolError::Other(msg) => Self::Other(msg),
         }
@@ -345,6 +348,9 @@ pub enum RpcInvalidTransactionError {
     /// Blob transaction is a create transaction
     #[error("blob transaction is a create transaction")]
     BlobTransactionIsCreate,
+    /// Set-code transaction has no authorization
+    #[error("empty EIP-7702 authorization list")]
+    EmptyAuthorizationList,
 }
 
 impl RpcInvalidTransactionError {
@@ -476,6 +482,9 @@ pub enum RpcPoolError {
     /// When the transaction pool is full
     #[error("txpool is full")]
     TxPoolOverflow,
+    /// When the sender has too many transactions in the pool
+    #[error("account limit exceeded")]
+    AccountLimitExceeded,
     /// When the replacement transaction is underpriced
     #[error("replacement transaction underpriced")]
     ReplaceUnderpriced,
@@ -491,6 +500,9 @@ pub enum RpcPoolError {
     /// When the max initcode size is exceeded
     #[error("max initcode size exceeded")]
     ExceedsMaxInitCodeSize,
+    /// When an authority of EIP-7702 has transactions in the pool
+    #[error("authority already reserved")]
+    AuthorityReserved,
     /// Errors related to invalid transactions
     #[error(transparent)]
     Invalid(#[from] RpcInvalidTransactionError),
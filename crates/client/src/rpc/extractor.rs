@@ -16,12 +16,16 @@
 
 //! Parity-specific metadata extractors.
 
-use crate::rpc::{http_common::HttpMetaExtractor, Metadata, Origin};
+use crate::rpc::{
+    http_common::HttpMetaExtractor, interceptor::Caller, Metadata, Origin,
+};
 use cfx_types::H256;
+use jsonrpc_http_server::{self as http, hyper};
+use jsonrpc_ipc_server as ipc;
 use jsonrpc_pubsub::Session;
 use jsonrpc_tcp_server as tcp;
 use jsonrpc_ws_server as ws;
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc};
 //use ws;
 
 /// Common HTTP & IPC metadata extractor.
@@ -40,15 +44,61 @@ impl HttpMetaExtractor for RpcExtractor {
                 user_agent.unwrap_or_else(|| "unknown agent".to_string())
             )),
             session: None,
+            caller: Default::default(),
         }
     }
 }
 
+impl http::MetaExtractor<Metadata> for RpcExtractor {
+    fn read_metadata(&self, req: &hyper::Request<hyper::Body>) -> Metadata {
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|val| val.to_str().ok().map(ToOwned::to_owned))
+        };
+
+        let mut metadata = HttpMetaExtractor::read_metadata(
+            self,
+            header("origin"),
+            header("user-agent"),
+        );
+        metadata.caller = Caller {
+            bearer_token: header("authorization").and_then(|authorization| {
+                let (scheme, token) = authorization.split_once(' ')?;
+                scheme
+                    .eq_ignore_ascii_case("bearer")
+                    .then(|| token.trim().to_owned())
+            }),
+            api_key: header("x-api-key"),
+            // The HTTP server does not tell the address of the peer.
+            remote_ip: None,
+            forwarded_ip: header("x-forwarded-for")
+                .and_then(|forwarded| forwarded_ip(&forwarded)),
+            ws_session: None,
+        };
+        metadata
+    }
+}
+
 impl tcp::MetaExtractor<Metadata> for RpcExtractor {
     fn extract(&self, req: &tcp::RequestContext) -> Metadata {
         Metadata {
             origin: Origin::Tcp(req.peer_addr),
             session: Some(Arc::new(Session::new(req.sender.clone()))),
+            caller: Caller {
+                remote_ip: Some(req.peer_addr.ip()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl ipc::MetaExtractor<Metadata> for RpcExtractor {
+    fn extract(&self, req: &ipc::RequestContext) -> Metadata {
+        Metadata {
+            origin: Origin::Ipc(H256::from_low_u64_be(req.session_id)),
+            session: Some(Arc::new(Session::new(req.sender.clone()))),
+            caller: Default::default(),
         }
     }
 }
@@ -60,10 +110,23 @@ impl ws::MetaExtractor<Metadata> for RpcExtractor {
                 session: H256::from_low_u64_be(req.session_id),
             },
             session: Some(Arc::new(Session::new(req.sender()))),
+            // The WebSocket server does not tell the address of the peer, so
+            // the session stands for the client.
+            caller: Caller {
+                ws_session: Some(req.session_id),
+                ..Default::default()
+            },
         }
     }
 }
 
+/// Returns the last address of an `X-Forwarded-For` header, i.e. the one
+/// appended by the proxy which the request comes from. The preceding
+/// addresses are supplied by the client and cannot be trusted.
+fn forwarded_ip(forwarded: &str) -> Option<IpAddr> {
+    forwarded.rsplit(',').next()?.trim().parse().ok()
+}
+
 ///// WebSockets server metadata extractor and request middleware.
 //pub struct WsExtractor {
 //    authcodes_path: Option<PathBuf>,
@@ -255,7 +318,7 @@ impl ws::MetaExtractor<Metadata> for RpcExtractor {
 
 #[cfg(test)]
 mod tests {
-    use super::{HttpMetaExtractor, Origin, RpcExtractor};
+    use super::{forwarded_ip, HttpMetaExtractor, Origin, RpcExtractor};
 
     #[test]
     fn should_extract_rpc_origin() {
@@ -283,4 +346,15 @@ mod tests {
             Origin::Rpc("unknown origin / https://conflux-chain.org".into())
         );
     }
+
+    #[test]
+    fn should_take_last_forwarded_ip() {
+        assert_eq!(
+            forwarded_ip("1.1.1.1, 2.2.2.2 ,3.3.3.3"),
+            Some("3.3.3.3".parse().unwrap())
+        );
+        assert_eq!(forwarded_ip("::1"), Some("::1".parse().unwrap()));
+        assert_eq!(forwarded_ip("1.1.1.1, unknown"), None);
+        assert_eq!(forwarded_ip(""), None);
+    }
 }
@@ -0,0 +1,60 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_types::{Space, H256};
+use cfxcore::transaction_pool::{DropReason, DroppedTransaction};
+
+/// The notification of the `droppedTransactions` subscription of `space`,
+/// which only publishes the transactions of its own space. `result` builds
+/// the notification from the transaction hash and the drop reason.
+pub fn dropped_transaction_result<R>(
+    dropped: &DroppedTransaction, space: Space,
+    result: impl FnOnce(H256, DropReason) -> R,
+) -> Option<R> {
+    if dropped.transaction.space() != space {
+        return None;
+    }
+    Some(result(dropped.transaction.hash(), dropped.reason.clone()))
+}
+
+/// A signed transaction of `space` dropped for `reason`.
+#[cfg(test)]
+pub fn dropped(space: Space, reason: DropReason) -> DroppedTransaction {
+    use cfx_types::{Address, U256};
+    use cfxkey::{Generator, Random};
+    use primitives::{
+        transaction::{Eip155Transaction, NativeTransaction},
+        Action, Transaction,
+    };
+    use std::sync::Arc;
+
+    let tx: Transaction = match space {
+        Space::Native => NativeTransaction {
+            nonce: U256::zero(),
+            gas_price: U256::one(),
+            gas: U256::from(21000),
+            action: Action::Call(Address::random()),
+            value: U256::zero(),
+            storage_limit: 0,
+            epoch_height: 0,
+            chain_id: 1,
+            data: vec![],
+        }
+        .into(),
+        Space::Ethereum => Eip155Transaction {
+            nonce: U256::zero(),
+            gas_price: U256::one(),
+            gas: U256::from(21000),
+            action: Action::Call(Address::random()),
+            value: U256::zero(),
+            chain_id: Some(1),
+            data: vec![],
+        }
+        .into(),
+    };
+    DroppedTransaction {
+        transaction: Arc::new(tx.sign(Random.generate().unwrap().secret())),
+        reason,
+    }
+}
@@ -2,6 +2,7 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+mod dropped_transaction;
 mod epoch_queue;
 mod poll_filter;
 mod poll_manager;
@@ -14,6 +15,9 @@ pub use self::{
     },
     poll_manager::PollManager,
 };
+#[cfg(test)]
+pub use dropped_transaction::dropped;
+pub use dropped_transaction::dropped_transaction_result;
 pub use epoch_queue::EpochQueue;
 pub use subscribers::{Id as SubscriberId, Subscribers};
 pub use variadic_value::{maybe_vec_into, VariadicValue};
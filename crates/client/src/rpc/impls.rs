@@ -2,6 +2,9 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use crate::rpc::client_throttle::ClientThrottle;
+use std::sync::Arc;
+
 #[derive(Clone, Default)]
 pub struct RpcImplConfiguration {
     pub get_logs_filter_max_limit: Option<usize>,
@@ -18,6 +21,9 @@ pub struct RpcImplConfiguration {
     pub enable_metrics: bool,
 
     pub poll_lifetime_in_seconds: Option<u32>,
+
+    /// The per-client throttling of the public RPC methods, if enabled.
+    pub client_throttle: Option<Arc<ClientThrottle>>,
 }
 
 pub mod cfx;
@@ -70,32 +70,41 @@ use crate::{
         },
         impls::{
             common::{self, RpcImpl as CommonImpl},
+            eth::debug::convert_account_override,
             RpcImplConfiguration,
         },
         traits::{cfx::Cfx, debug::LocalRpc, test::TestRpc},
         types::{
             eth::Transaction as EthTransaction, pos::Block as PosBlock,
             sign_call, Account as RpcAccount, AccountPendingInfo,
-            AccountPendingTransactions, BlameInfo, Block as RpcBlock,
+            AccountPendingTransactions, AddressTransaction,
+            AddressTransactions, BlameInfo, Block as RpcBlock,
             BlockHashOrEpochNumber, Bytes, CallRequest, CfxRpcLogFilter,
             CheckBalanceAgainstTransactionResponse, ConsensusGraphStates,
             EpochNumber, EstimateGasAndCollateralResponse, Log as RpcLog,
             PackedOrExecuted, Receipt as RpcReceipt,
-            RewardInfo as RpcRewardInfo, SendTxRequest, Status as RpcStatus,
-            StorageCollateralInfo, SyncGraphStates,
-            Transaction as RpcTransaction,
+            RewardInfo as RpcRewardInfo, SendTxRequest, SimulatePayload,
+            SimulatedBlock, Status as RpcStatus, StorageCollateralInfo,
+            SyncGraphStates, Transaction as RpcTransaction,
         },
         RpcResult,
     },
 };
 use cfx_addr::Network;
-use cfx_execute_helper::estimation::EstimateRequest;
-use cfx_executor::state::State;
+use cfx_execute_helper::{
+    estimation::EstimateRequest,
+    simulation::{
+        check_simulate_counts, check_simulate_limits, BlockOverrides,
+        SimulateBlock, SimulateCall,
+    },
+};
+use cfx_executor::state::{State, StateOverride};
 use cfx_parameters::{
     consensus_internal::REWARD_EPOCH_COUNT,
     genesis::{
         genesis_contract_address_four_year, genesis_contract_address_two_year,
     },
+    rpc::{ADDRESS_TX_PAGE_SIZE, MAX_ADDRESS_TX_PAGE_SIZE},
     staking::{BLOCKS_PER_YEAR, DRIPS_PER_STORAGE_COLLATERAL_UNIT},
 };
 use cfx_storage::state::StateDbGetOriginalMethods;
@@ -545,7 +554,7 @@ impl RpcImpl {
             bail!(request_rejected_in_catch_up_mode(None));
         }
         let (signed_trans, failed_trans) =
-            self.tx_pool.insert_new_transactions(vec![tx]);
+            self.tx_pool.insert_new_local_transactions(vec![tx]);
         // FIXME: how is it possible?
         if signed_trans.len() + failed_trans.len() > 1 {
             // This should never happen
@@ -751,6 +760,63 @@ impl RpcImpl {
         Ok(None)
     }
 
+    fn transactions_by_address(
+        &self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>,
+    ) -> RpcResult<AddressTransactions> {
+        info!(
+            "RPC Request: cfx_getTransactionsByAddress(address={:?}, cursor={:?}, limit={:?})",
+            address, cursor, limit
+        );
+        invalid_params_check(
+            "address",
+            check_rpc_address_network(
+                Some(address.network),
+                self.sync.network.get_network_type(),
+            ),
+        )?;
+
+        let data_man = self.consensus.get_data_manager();
+        if !data_man.address_tx_index_enabled() {
+            bail!(internal_error_msg(
+                "the address transaction index is not enabled"
+            ));
+        }
+
+        let limit = limit.map_or(ADDRESS_TX_PAGE_SIZE, |limit| limit.as_u64());
+        if limit == 0 {
+            bail!(invalid_params("limit", "limit should be positive"));
+        }
+        if limit > MAX_ADDRESS_TX_PAGE_SIZE {
+            bail!(invalid_params(
+                "limit",
+                format!("limit should be at most {}", MAX_ADDRESS_TX_PAGE_SIZE)
+            ));
+        }
+
+        let (txs, next_cursor) = data_man.transactions_by_address(
+            &address.hex_address.with_native_space(),
+            cursor.map(|cursor| cursor.as_u64()),
+            limit,
+        );
+
+        Ok(AddressTransactions {
+            transactions: txs
+                .into_iter()
+                .map(|(entry, tx_index)| AddressTransaction {
+                    transaction_hash: entry.tx_hash,
+                    block_hash: tx_index.block_hash,
+                    epoch_number: entry.epoch_number.into(),
+                    transaction_index: (tx_index
+                        .rpc_index
+                        .unwrap_or(tx_index.real_index)
+                        as u64)
+                        .into(),
+                })
+                .collect(),
+            next_cursor: next_cursor.map(Into::into),
+        })
+    }
+
     fn get_block_execution_info(
         &self, block_hash: &H256,
     ) -> RpcResult<Option<BlockExecInfo>> {
@@ -1124,6 +1190,11 @@ impl RpcImpl {
                 )) if tx_data_len > 0 => {
                     unsigned.data = vec![0; tx_data_len];
                 }
+                Transaction::Ethereum(EthereumTransaction::Eip7702(
+                    ref mut unsigned,
+                )) if tx_data_len > 0 => {
+                    unsigned.data = vec![0; tx_data_len];
+                }
                 _ => {}
             };
 
@@ -1261,47 +1332,7 @@ impl RpcImpl {
         );
         let (execution_outcome, _estimation) =
             self.exec_transaction(request, epoch)?;
-        match execution_outcome {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
-                expected,
-                got,
-            )) => bail!(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"nonce is too old expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::InvalidRecipientAddress(recipient),
-            ) => bail!(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"invalid recipient address {:?}", recipient}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::NotEnoughGasLimit { expected, got },
-            ) => bail!(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                bail!(call_execution_error(
-                    "Transaction can not be executed".into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(VmError::Reverted),
-                executed,
-            ) => bail!(call_execution_error(
-                "Transaction reverted".into(),
-                format!("0x{}", executed.output.to_hex::<String>())
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                bail!(call_execution_error(
-                    "Transaction execution failed".into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
-        }
+        call_output(execution_outcome)
     }
 
     fn estimate_gas_and_collateral(
@@ -1312,72 +1343,111 @@ impl RpcImpl {
         );
         let (execution_outcome, estimation) =
             self.exec_transaction(request, epoch)?;
-        match execution_outcome {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
-                expected,
-                got,
-            )) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"nonce is too old expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::InvalidRecipientAddress(recipient),
-            ) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"invalid recipient address {:?}", recipient}
-            )),
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                bail!(call_execution_error(
-                    "Can not estimate: transaction can not be executed".into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::NotEnoughGasLimit { expected, got },
-            ) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(VmError::Reverted),
-                executed,
-            ) => {
-                let network_type = *self.sync.network.get_network_type();
-                let (revert_error, innermost_error, errors) =
-                    decode_error(&executed, |addr| {
-                        RpcAddress::try_from_h160(addr.clone(), network_type)
-                            .unwrap()
-                            .base32_address
-                    });
-
-                bail!(call_execution_error(
-                    format!("Estimation isn't accurate: transaction is reverted{}{}",
-                        revert_error, innermost_error),
-                    errors.join("\n"),
-                ))
+        let network_type = *self.sync.network.get_network_type();
+        estimate_response(execution_outcome, estimation, network_type)
+    }
+
+    fn simulate(
+        &self, payload: SimulatePayload,
+        block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
+    ) -> RpcResult<Vec<SimulatedBlock>> {
+        info!(
+            "RPC Request: cfx_simulate payload={:?}, block_hash_or_epoch_number={:?}",
+            payload, block_hash_or_epoch_number
+        );
+
+        // the counts are checked before the calls are converted and signed
+        check_simulate_counts(
+            payload
+                .block_state_calls
+                .iter()
+                .map(|block| block.calls.len()),
+        )
+        .map_err(|e| invalid_params("blockStateCalls", e))?;
+
+        let consensus_graph = self.consensus_graph();
+        let epoch =
+            self.get_epoch_number_with_pivot_check(block_hash_or_epoch_number)?;
+        let epoch_height = consensus_graph
+            .get_height_from_epoch_number(epoch.clone().into())?;
+        let chain_id = consensus_graph.best_chain_id().in_native_space();
+
+        let mut blocks = Vec::with_capacity(payload.block_state_calls.len());
+        for block in payload.block_state_calls {
+            let overrides = block.block_overrides.unwrap_or_default();
+            if let Some(miner) = &overrides.miner {
+                self.check_address_network(miner.network)?;
             }
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                bail!(call_execution_error(
-                    format! {"Can not estimate: transaction execution failed, \
-                    all gas will be charged (execution error: {:?})", e}
-                    .into(),
-                    format! {"{:?}", e}
-                ))
+            let block_overrides = BlockOverrides {
+                number: overrides.block_number.map(|n| n.as_u64()),
+                epoch_height: overrides.epoch_number.map(|n| n.as_u64()),
+                timestamp: overrides.timestamp.map(|t| t.as_u64()),
+                gas_limit: overrides.gas_limit,
+                base_gas_price: overrides.base_fee_per_gas,
+                author: overrides.miner.map(|miner| miner.hex_address),
+            };
+
+            let state_override = match block.state_overrides {
+                Some(state_overrides) => {
+                    let mut state_override = StateOverride::new();
+                    for (address, account) in state_overrides {
+                        self.check_address_network(address.network)?;
+                        state_override.insert(
+                            address.hex_address.with_native_space(),
+                            convert_account_override(account)?,
+                        );
+                    }
+                    Some(state_override)
+                }
+                None => None,
+            };
+
+            let mut calls = Vec::with_capacity(block.calls.len());
+            for request in block.calls {
+                let rpc_request_network = invalid_params_check(
+                    "request",
+                    rpc_call_request_network(
+                        request.from.as_ref(),
+                        request.to.as_ref(),
+                    ),
+                )?;
+                invalid_params_check(
+                    "request",
+                    check_rpc_address_network(
+                        rpc_request_network,
+                        self.sync.network.get_network_type(),
+                    ),
+                )?;
+
+                let has_nonce = request.nonce.is_some();
+                let tx = sign_call(epoch_height, chain_id, request)?;
+                calls.push(SimulateCall { tx, has_nonce });
             }
-            ExecutionOutcome::Finished(executed) => executed,
-        };
-        let storage_collateralized =
-            U64::from(estimation.estimated_storage_limit);
-        let estimated_gas_used = estimation.estimated_gas_limit;
-        let response = EstimateGasAndCollateralResponse {
-            gas_limit: estimated_gas_used, /* gas_limit used to be 4/3 of
-                                            * gas_used due to inaccuracy,
-                                            * currently it's the same as gas
-                                            * used as it's more accurate */
-            gas_used: estimated_gas_used,
-            storage_collateralized,
-        };
-        Ok(response)
+
+            blocks.push(SimulateBlock {
+                block_overrides,
+                state_override,
+                calls,
+            });
+        }
+
+        check_simulate_limits(&blocks)
+            .map_err(|e| invalid_params("blockStateCalls", e))?;
+
+        let simulated_blocks = consensus_graph.simulate_virtual(
+            blocks,
+            epoch.into(),
+            Space::Native,
+            payload.validation,
+        )?;
+        let network = *self.sync.network.get_network_type();
+        simulated_blocks
+            .into_iter()
+            .map(|block| {
+                SimulatedBlock::try_from(block, network)
+                    .map_err(|e| invalid_params("blockStateCalls", e).into())
+            })
+            .collect()
     }
 
     fn check_balance_against_transaction(
@@ -1446,20 +1516,14 @@ impl RpcImpl {
         let consensus_graph = self.consensus_graph();
         let epoch = epoch.unwrap_or(EpochNumber::LatestState);
 
-        let estimate_request = EstimateRequest {
-            has_sender: request.from.is_some(),
-            has_gas_limit: request.gas.is_some(),
-            has_gas_price: request.gas_price.is_some()
-                || request.max_priority_fee_per_gas.is_some(),
-            has_nonce: request.nonce.is_some(),
-            has_storage_limit: request.storage_limit.is_some(),
-        };
-
         let epoch_height = consensus_graph
             .get_height_from_epoch_number(epoch.clone().into())?;
         let chain_id = consensus_graph.best_chain_id();
-        let signed_tx =
-            sign_call(epoch_height, chain_id.in_native_space(), request)?;
+        let (signed_tx, estimate_request) = prepare_call_request(
+            request,
+            epoch_height,
+            chain_id.in_native_space(),
+        )?;
         trace!("call tx {:?}", signed_tx);
 
         consensus_graph.call_virtual(&signed_tx, epoch.into(), estimate_request)
@@ -2245,6 +2309,145 @@ impl RpcImpl {
     }
 }
 
+/// Builds the virtual transaction of a `cfx_call` or
+/// `cfx_estimateGasAndCollateral` request on the native space.
+pub(crate) fn prepare_call_request(
+    request: CallRequest, epoch_height: u64, chain_id: u32,
+) -> RpcResult<(SignedTransaction, EstimateRequest)> {
+    let estimate_request = EstimateRequest {
+        has_sender: request.from.is_some(),
+        has_gas_limit: request.gas.is_some(),
+        has_gas_price: request.gas_price.is_some()
+            || request.max_priority_fee_per_gas.is_some(),
+        has_nonce: request.nonce.is_some(),
+        has_storage_limit: request.storage_limit.is_some(),
+        collect_access_list: false,
+    };
+
+    let signed_tx = sign_call(epoch_height, chain_id, request)?;
+    Ok((signed_tx, estimate_request))
+}
+
+pub(crate) fn call_output(
+    execution_outcome: ExecutionOutcome,
+) -> RpcResult<Bytes> {
+    match execution_outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => bail!(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"nonce is too old expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => bail!(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"invalid recipient address {:?}", recipient}
+        )),
+        ExecutionOutcome::NotExecutedDrop(TxDropError::NotEnoughGasLimit {
+            expected,
+            got,
+        }) => bail!(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            bail!(call_execution_error(
+                "Transaction can not be executed".into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(VmError::Reverted),
+            executed,
+        ) => bail!(call_execution_error(
+            "Transaction reverted".into(),
+            format!("0x{}", executed.output.to_hex::<String>())
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            bail!(call_execution_error(
+                "Transaction execution failed".into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
+    }
+}
+
+pub(crate) fn estimate_response(
+    execution_outcome: ExecutionOutcome, estimation: EstimateExt,
+    network_type: Network,
+) -> RpcResult<EstimateGasAndCollateralResponse> {
+    match execution_outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"nonce is too old expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"invalid recipient address {:?}", recipient}
+        )),
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            bail!(call_execution_error(
+                "Can not estimate: transaction can not be executed".into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::NotExecutedDrop(TxDropError::NotEnoughGasLimit {
+            expected,
+            got,
+        }) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(VmError::Reverted),
+            executed,
+        ) => {
+            let (revert_error, innermost_error, errors) =
+                decode_error(&executed, |addr| {
+                    RpcAddress::try_from_h160(addr.clone(), network_type)
+                        .unwrap()
+                        .base32_address
+                });
+
+            bail!(call_execution_error(
+                format!(
+                    "Estimation isn't accurate: transaction is reverted{}{}",
+                    revert_error, innermost_error
+                ),
+                errors.join("\n"),
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            bail!(call_execution_error(
+                format! {"Can not estimate: transaction execution failed, \
+                all gas will be charged (execution error: {:?})", e}
+                .into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::Finished(executed) => executed,
+    };
+    let storage_collateralized = U64::from(estimation.estimated_storage_limit);
+    let estimated_gas_used = estimation.estimated_gas_limit;
+    let response = EstimateGasAndCollateralResponse {
+        gas_limit: estimated_gas_used, /* gas_limit used to be 4/3 of
+                                        * gas_used due to inaccuracy,
+                                        * currently it's the same as gas
+                                        * used as it's more accurate */
+        gas_used: estimated_gas_used,
+        storage_collateralized,
+    };
+    Ok(response)
+}
+
 #[allow(dead_code)]
 pub struct CfxHandler {
     common: Arc<CommonImpl>,
@@ -2303,10 +2506,13 @@ impl Cfx for CfxHandler {
             fn collateral_for_storage(&self, address: RpcAddress, num: Option<EpochNumber>)
                 -> BoxFuture<U256>;
             fn call(&self, request: CallRequest, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>)
-                -> JsonRpcResult<Bytes>;
+                -> BoxFuture<Bytes>;
             fn estimate_gas_and_collateral(
                 &self, request: CallRequest, epoch_number: Option<EpochNumber>)
-                -> JsonRpcResult<EstimateGasAndCollateralResponse>;
+                -> BoxFuture<EstimateGasAndCollateralResponse>;
+            fn simulate(
+                &self, payload: SimulatePayload, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>)
+                -> JsonRpcResult<Vec<SimulatedBlock>>;
             fn check_balance_against_transaction(
                 &self, account_addr: RpcAddress, contract_addr: RpcAddress, gas_limit: U256, gas_price: U256, storage_limit: U256, epoch: Option<EpochNumber>,
             ) -> BoxFuture<CheckBalanceAgainstTransactionResponse>;
@@ -2316,6 +2522,7 @@ impl Cfx for CfxHandler {
             fn storage_at(&self, addr: RpcAddress, pos: U256, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>)
                 -> BoxFuture<Option<H256>>;
             fn transaction_by_hash(&self, hash: H256) -> BoxFuture<Option<RpcTransaction>>;
+            fn transactions_by_address(&self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>) -> BoxFuture<AddressTransactions>;
             fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<Option<RpcReceipt>>;
             fn storage_root(&self, address: RpcAddress, epoch_num: Option<EpochNumber>) -> BoxFuture<Option<StorageRoot>>;
             fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<TokenSupplyInfo>;
@@ -2449,3 +2656,80 @@ impl LocalRpc for LocalRpcImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{call_output, estimate_response, prepare_call_request};
+    use crate::rpc::types::{CallRequest, RpcAddress};
+    use cfx_addr::Network;
+    use cfx_execute_helper::estimation::EstimateExt;
+    use cfx_executor::executive::{ExecutionOutcome, TxDropError};
+    use cfx_types::{Address, AddressSpaceUtil, U256};
+    use primitives::{
+        transaction::{CIP1559_TYPE, LEGACY_TX_TYPE},
+        Action,
+    };
+
+    #[test]
+    fn test_prepare_call_request() {
+        let (tx, request) =
+            prepare_call_request(CallRequest::default(), 10, 1029).unwrap();
+        assert!(!request.has_sender);
+        assert!(!request.has_gas_limit);
+        assert!(!request.has_gas_price);
+        assert!(!request.has_nonce);
+        assert!(!request.has_storage_limit);
+        assert!(!request.collect_access_list);
+        assert_eq!(tx.sender(), Address::zero().with_native_space());
+        assert_eq!(tx.action(), &Action::Create);
+        assert_eq!(tx.chain_id(), Some(1029));
+        assert_eq!(tx.type_id(), LEGACY_TX_TYPE);
+
+        let from: Address = "0x1000000000000000000000000000000000000001"
+            .parse()
+            .unwrap();
+        let to: Address = "0x8000000000000000000000000000000000000002"
+            .parse()
+            .unwrap();
+        let call_request = CallRequest {
+            from: Some(RpcAddress::try_from_h160(from, Network::Main).unwrap()),
+            to: Some(RpcAddress::try_from_h160(to, Network::Main).unwrap()),
+            gas: Some(50000.into()),
+            max_priority_fee_per_gas: Some(2.into()),
+            nonce: Some(3.into()),
+            storage_limit: Some(64.into()),
+            ..Default::default()
+        };
+
+        let (tx, request) =
+            prepare_call_request(call_request, 10, 1029).unwrap();
+        assert!(request.has_sender);
+        assert!(request.has_gas_limit);
+        assert!(request.has_gas_price);
+        assert!(request.has_nonce);
+        assert!(request.has_storage_limit);
+        assert_eq!(tx.sender(), from.with_native_space());
+        assert_eq!(tx.action(), &Action::Call(to));
+        assert_eq!(tx.gas(), &U256::from(50000));
+        assert_eq!(tx.nonce(), &U256::from(3));
+        assert_eq!(tx.type_id(), CIP1559_TYPE);
+    }
+
+    #[test]
+    fn test_not_executed_outcome() {
+        let outcome = || {
+            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+                3.into(),
+                1.into(),
+            ))
+        };
+
+        assert!(call_output(outcome()).is_err());
+        assert!(estimate_response(
+            outcome(),
+            EstimateExt::default(),
+            Network::Main
+        )
+        .is_err());
+    }
+}
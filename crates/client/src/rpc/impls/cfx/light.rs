@@ -12,6 +12,7 @@ use cfxcore::{
         self, query_service::TxInfo, Error as LightError, ErrorKind,
     },
     rpc_errors::{account_result_to_rpc_result, invalid_params_check},
+    transaction_pool::{PendingReason, TransactionStatus},
     verification::EpochReceiptProof,
     ConsensusGraph, ConsensusGraphTrait, LightQueryService, PeerInfo,
     SharedConsensusGraph,
@@ -35,27 +36,39 @@ use crate::{
     common::delegate_convert,
     rpc::{
         errors,
-        impls::common::{self, RpcImpl as CommonImpl},
-        traits::{cfx::Cfx, debug::LocalRpc, test::TestRpc},
+        impls::{
+            cfx::cfx_handler::{
+                call_output, estimate_response, prepare_call_request,
+            },
+            common::{self, RpcImpl as CommonImpl},
+        },
+        traits::{
+            cfx::Cfx, debug::LocalRpc, pool::TransactionPool, test::TestRpc,
+        },
         types::{
+            call_request::rpc_call_request_network,
             cfx::check_rpc_address_network,
             pos::{Block as PosBlock, PoSEpochReward},
             Account as RpcAccount, AccountPendingInfo,
-            AccountPendingTransactions, BlameInfo, Block as RpcBlock,
-            BlockHashOrEpochNumber, Bytes, CallRequest, CfxFeeHistory,
-            CfxRpcLogFilter, CheckBalanceAgainstTransactionResponse,
-            ConsensusGraphStates, EpochNumber,
-            EstimateGasAndCollateralResponse, FeeHistory, Log as RpcLog,
-            PoSEconomics, Receipt as RpcReceipt, RewardInfo as RpcRewardInfo,
-            RpcAddress, SendTxRequest, SponsorInfo, StatOnGasLoad,
+            AccountPendingTransactions, AddressTransactions, BlameInfo,
+            Block as RpcBlock, BlockHashOrEpochNumber, Bytes, CallRequest,
+            CfxFeeHistory, CfxRpcLogFilter,
+            CheckBalanceAgainstTransactionResponse, ConsensusGraphStates,
+            EpochNumber, EstimateGasAndCollateralResponse, FeeHistory,
+            Log as RpcLog, PoSEconomics, Receipt as RpcReceipt,
+            RewardInfo as RpcRewardInfo, RpcAddress, SendTxRequest,
+            SimulatePayload, SimulatedBlock, SponsorInfo, StatOnGasLoad,
             Status as RpcStatus, StorageCollateralInfo, SyncGraphStates,
-            TokenSupplyInfo, Transaction as RpcTransaction, VoteParamsInfo,
-            WrapTransaction, U64 as HexU64,
+            TokenSupplyInfo, Transaction as RpcTransaction,
+            TxPoolPendingNonceRange, TxPoolStatus, TxWithPoolInfo,
+            VoteParamsInfo, WrapTransaction, U64 as HexU64,
         },
         RpcBoxFuture, RpcResult,
     },
 };
 use cfx_addr::Network;
+use cfx_execute_helper::estimation::EstimateExt;
+use cfx_executor::executive::ExecutionOutcome;
 use cfx_parameters::rpc::GAS_PRICE_DEFAULT_VALUE;
 use cfxcore::{
     light_protocol::QueryService, rpc_errors::ErrorKind::LightProtocol,
@@ -91,13 +104,13 @@ pub struct RpcImpl {
     accounts: Arc<AccountProvider>,
 
     // consensus graph
-    consensus: SharedConsensusGraph,
+    pub consensus: SharedConsensusGraph,
 
     // block data manager
     data_man: Arc<BlockDataManager>,
 
     // helper API for retrieving verified information from peers
-    light: Arc<LightQueryService>,
+    pub light: Arc<LightQueryService>,
 }
 
 impl RpcImpl {
@@ -347,18 +360,6 @@ impl RpcImpl {
         Box::new(fut.boxed().compat())
     }
 
-    pub fn account_pending_info(
-        &self, address: RpcAddress,
-    ) -> RpcBoxFuture<Option<AccountPendingInfo>> {
-        info!("RPC Request: cfx_getAccountPendingInfo({:?})", address);
-
-        let fut = async move {
-            // TODO impl light node rpc
-            Ok(None)
-        };
-        Box::new(fut.boxed().compat())
-    }
-
     fn vote_list(
         &self, address: RpcAddress, num: Option<EpochNumber>,
     ) -> RpcBoxFuture<Vec<VoteStakeInfo>> {
@@ -458,6 +459,88 @@ impl RpcImpl {
         Box::new(fut.boxed().compat())
     }
 
+    /// Executes `request` locally on the verified state of the epoch, which
+    /// is retrieved from the peers on demand.
+    async fn exec_transaction(
+        light: Arc<LightQueryService>, request: CallRequest,
+        epoch: EpochNumber, chain_id: u32,
+    ) -> RpcResult<(ExecutionOutcome, EstimateExt)> {
+        let rpc_request_network = invalid_params_check(
+            "request",
+            rpc_call_request_network(
+                request.from.as_ref(),
+                request.to.as_ref(),
+            ),
+        )?;
+        invalid_params_check(
+            "request",
+            check_rpc_address_network(
+                rpc_request_network,
+                light.get_network_type(),
+            ),
+        )?;
+
+        let epoch = epoch.into();
+        let epoch_height = light.get_height_from_epoch_number(epoch.clone())?;
+        let (signed_tx, estimate_request) =
+            prepare_call_request(request, epoch_height, chain_id)?;
+        trace!("call tx {:?}", signed_tx);
+
+        light.call_virtual(signed_tx, epoch, estimate_request).await
+    }
+
+    fn call(
+        &self, request: CallRequest,
+        block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
+    ) -> RpcBoxFuture<Bytes> {
+        info!(
+            "RPC Request: cfx_call request={:?} epoch={:?}",
+            request, block_hash_or_epoch_number
+        );
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+        let consensus_graph = self.consensus.clone();
+        let chain_id = self.consensus.best_chain_id().in_native_space();
+
+        let fut = async move {
+            let epoch = Self::get_epoch_number_with_pivot_check(
+                consensus_graph,
+                block_hash_or_epoch_number,
+            )?;
+
+            let (execution_outcome, _estimation) =
+                Self::exec_transaction(light, request, epoch, chain_id).await?;
+            call_output(execution_outcome)
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn estimate_gas_and_collateral(
+        &self, request: CallRequest, epoch: Option<EpochNumber>,
+    ) -> RpcBoxFuture<EstimateGasAndCollateralResponse> {
+        info!(
+            "RPC Request: cfx_estimateGasAndCollateral request={:?}, epoch={:?}",
+            request, epoch
+        );
+
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState);
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+        let chain_id = self.consensus.best_chain_id().in_native_space();
+
+        let fut = async move {
+            let network_type = *light.get_network_type();
+            let (execution_outcome, estimation) =
+                Self::exec_transaction(light, request, epoch, chain_id).await?;
+            estimate_response(execution_outcome, estimation, network_type)
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
     fn get_logs(&self, filter: CfxRpcLogFilter) -> RpcBoxFuture<Vec<RpcLog>> {
         info!("RPC Request: cfx_getLogs filter={:?}", filter);
 
@@ -511,14 +594,25 @@ impl RpcImpl {
 
         debug!("Deserialized tx: {:?}", tx);
 
-        // TODO(thegaram): consider adding a light node specific tx pool;
-        // light nodes would track those txs and maintain their statuses
-        // for future queries
+        let hash = tx.hash();
 
-        match /* success = */ light.send_raw_tx(raw) {
-            true => Ok(tx.hash().into()),
-            false => bail!(LightProtocol(light_protocol::ErrorKind::InternalError("Unable to relay tx".into()).into())),
+        if !light.send_raw_tx(raw) {
+            bail!(LightProtocol(
+                light_protocol::ErrorKind::InternalError(
+                    "Unable to relay tx".into()
+                )
+                .into()
+            ))
+        }
+
+        // track the tx until its receipt is verified, so that it is
+        // rebroadcast and accounted for in the nonces of its sender.
+        // note: the tx is relayed already, so this is best-effort.
+        if let Err(e) = light.track_local_tx(tx) {
+            warn!("Unable to track local tx {:?}: {}", hash, e);
         }
+
+        Ok(hash.into())
     }
 
     fn send_raw_transaction(&self, raw: Bytes) -> RpcResult<H256> {
@@ -539,20 +633,12 @@ impl RpcImpl {
             tx.check_rpc_address_network("tx", light.get_network_type())?;
 
             if tx.nonce.is_none() {
-                // TODO(thegaram): consider adding a light node specific tx pool
-                // to track the nonce
-
-                let address = tx.from.clone().into();
-                let epoch = EpochNumber::LatestState.into_primitive();
-
-                let nonce = light
-                    .get_account(epoch, address)
-                    .await?
-                    .map(|a| a.nonce)
-                    .unwrap_or(U256::zero());
+                let address: H160 = tx.from.clone().into();
+                let nonce =
+                    light.get_next_nonce(address.with_native_space()).await?;
 
                 tx.nonce.replace(nonce.into());
-                debug!("after loading nonce in latest state, tx = {:?}", tx);
+                debug!("after loading the next nonce, tx = {:?}", tx);
             }
 
             let epoch_height = light.get_latest_verifiable_epoch_number().map_err(|_| {
@@ -761,6 +847,15 @@ impl RpcImpl {
         let fut = async move {
             Self::check_address_network(address.network, &light)?;
 
+            // without an epoch, the pending local txs are taken into account
+            if num.is_none() {
+                let address: H160 = address.into();
+                return Ok(invalid_params_check(
+                    "address",
+                    light.get_next_nonce(address.with_native_space()).await,
+                )?);
+            }
+
             let epoch =
                 Self::get_epoch_number_with_pivot_check(consensus_graph, num)?
                     .into();
@@ -1176,6 +1271,162 @@ impl RpcImpl {
 
         Box::new(fut.boxed().compat())
     }
+
+    fn account_pending_info(
+        &self, address: RpcAddress,
+    ) -> RpcBoxFuture<Option<AccountPendingInfo>> {
+        info!("RPC Request: cfx_getAccountPendingInfo({:?})", address);
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            Self::check_address_network(address.network, &light)?;
+            let address: H160 = address.into();
+
+            let (state_nonce, txs) = invalid_params_check(
+                "address",
+                light
+                    .get_pending_local_txs(address.with_native_space())
+                    .await,
+            )?;
+
+            Ok(txs.first().map(|first_tx| AccountPendingInfo {
+                local_nonce: state_nonce.into(),
+                pending_count: U256::from(txs.len()),
+                pending_nonce: (*first_tx.nonce()).into(),
+                next_pending_tx: first_tx.hash().into(),
+            }))
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn account_pending_transactions(
+        &self, address: RpcAddress, maybe_start_nonce: Option<U256>,
+        maybe_limit: Option<U64>,
+    ) -> RpcBoxFuture<AccountPendingTransactions> {
+        info!("RPC Request: cfx_getAccountPendingTransactions(addr={:?}, start_nonce={:?}, limit={:?})",
+              address, maybe_start_nonce, maybe_limit);
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            Self::check_address_network(address.network, &light)?;
+            let network = address.network;
+            let address: H160 = address.into();
+
+            let (state_nonce, txs) = invalid_params_check(
+                "address",
+                light
+                    .get_pending_local_txs(address.with_native_space())
+                    .await,
+            )?;
+
+            // the first tx is ready if no nonce before it is missing
+            let first_tx_status = txs.first().map(|first_tx| {
+                if *first_tx.nonce() == state_nonce {
+                    TransactionStatus::Ready
+                } else {
+                    TransactionStatus::Pending(PendingReason::FutureNonce)
+                }
+            });
+
+            let start_nonce = maybe_start_nonce.unwrap_or_default();
+            let limit = maybe_limit.map_or(usize::MAX, |l| l.as_usize());
+
+            Ok(AccountPendingTransactions {
+                pending_transactions: txs
+                    .iter()
+                    .filter(|tx| *tx.nonce() >= start_nonce)
+                    .take(limit)
+                    .map(|tx| RpcTransaction::from_signed(tx, None, network))
+                    .collect::<Result<_, _>>()?,
+                first_tx_status,
+                pending_count: U64::from(txs.len()),
+            })
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn txpool_status(&self) -> RpcResult<TxPoolStatus> {
+        info!("RPC Request: txpool_status");
+
+        // all local txs are ready to be packed from the light node's view
+        let count = U64::from(self.light.local_txs().len());
+
+        Ok(TxPoolStatus {
+            deferred: U64::zero(),
+            ready: count,
+            received: count,
+            unexecuted: count,
+        })
+    }
+
+    fn txpool_next_nonce(&self, address: RpcAddress) -> RpcBoxFuture<U256> {
+        info!("RPC Request: txpool_nextNonce({:?})", address);
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            Self::check_address_network(address.network, &light)?;
+            let address: H160 = address.into();
+
+            Ok(invalid_params_check(
+                "address",
+                light.get_next_nonce(address.with_native_space()).await,
+            )?)
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn txpool_transaction_by_address_and_nonce(
+        &self, address: RpcAddress, nonce: U256,
+    ) -> RpcResult<Option<RpcTransaction>> {
+        info!(
+            "RPC Request: txpool_transactionByAddressAndNonce({:?}, {:?})",
+            address, nonce
+        );
+        Self::check_address_network(address.network, &self.light)?;
+        let network = address.network;
+        let address: H160 = address.into();
+
+        Ok(self
+            .light
+            .local_txs()
+            .get_by_nonce(&address.with_native_space(), &nonce)
+            .map(|tx| RpcTransaction::from_signed(&tx, None, network))
+            .transpose()?)
+    }
+
+    fn txpool_pending_nonce_range(
+        &self, address: RpcAddress,
+    ) -> RpcResult<TxPoolPendingNonceRange> {
+        info!("RPC Request: txpool_pendingNonceRange({:?})", address);
+        Self::check_address_network(address.network, &self.light)?;
+        let address: H160 = address.into();
+
+        let txs = self
+            .light
+            .local_txs()
+            .pending_transactions(&address.with_native_space(), U256::zero());
+
+        // follow the full nodes, which report `[U256::MAX, 0]` without
+        // pending txs
+        let mut ret = TxPoolPendingNonceRange {
+            min_nonce: U256::max_value(),
+            max_nonce: U256::zero(),
+        };
+        if let (Some(first), Some(last)) = (txs.first(), txs.last()) {
+            ret.min_nonce = *first.nonce();
+            ret.max_nonce = *last.nonce();
+        }
+        Ok(ret)
+    }
 }
 
 async fn fetch_block_for_fee_history(
@@ -1218,11 +1469,12 @@ impl Cfx for CfxHandler {
             fn get_client_version(&self) -> JsonRpcResult<String>;
             fn get_status(&self) -> JsonRpcResult<RpcStatus>;
             fn skipped_blocks_by_epoch(&self, num: EpochNumber) -> JsonRpcResult<Vec<H256>>;
-            fn account_pending_info(&self, addr: RpcAddress) -> BoxFuture<Option<AccountPendingInfo>>;
         }
 
         to self.rpc_impl {
             fn account(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<RpcAccount>;
+            fn account_pending_info(&self, addr: RpcAddress) -> BoxFuture<Option<AccountPendingInfo>>;
+            fn account_pending_transactions(&self, address: RpcAddress, maybe_start_nonce: Option<U256>, maybe_limit: Option<U64>) -> BoxFuture<AccountPendingTransactions>;
             fn accumulate_interest_rate(&self, num: Option<EpochNumber>) -> BoxFuture<U256>;
             fn admin(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<Option<RpcAddress>>;
             fn balance(&self, address: RpcAddress, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>) -> BoxFuture<U256>;
@@ -1230,11 +1482,13 @@ impl Cfx for CfxHandler {
             fn block_by_hash_with_pivot_assumption(&self, block_hash: H256, pivot_hash: H256, epoch_number: U64) -> BoxFuture<RpcBlock>;
             fn block_by_hash(&self, hash: H256, include_txs: bool) -> BoxFuture<Option<RpcBlock>>;
             fn blocks_by_epoch(&self, num: EpochNumber) -> JsonRpcResult<Vec<H256>>;
+            fn call(&self, request: CallRequest, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>) -> BoxFuture<Bytes>;
             fn check_balance_against_transaction(&self, account_addr: RpcAddress, contract_addr: RpcAddress, gas_limit: U256, gas_price: U256, storage_limit: U256, epoch: Option<EpochNumber>) -> BoxFuture<CheckBalanceAgainstTransactionResponse>;
             fn code(&self, address: RpcAddress, block_hash_or_epoch_num: Option<BlockHashOrEpochNumber>) -> BoxFuture<Bytes>;
             fn collateral_for_storage(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<U256>;
             fn deposit_list(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<Vec<DepositInfo>>;
             fn epoch_number(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<U256>;
+            fn estimate_gas_and_collateral(&self, request: CallRequest, epoch_num: Option<EpochNumber>) -> BoxFuture<EstimateGasAndCollateralResponse>;
             fn gas_price(&self) -> BoxFuture<U256>;
             fn get_logs(&self, filter: CfxRpcLogFilter) -> BoxFuture<Vec<RpcLog>>;
             fn interest_rate(&self, num: Option<EpochNumber>) -> BoxFuture<U256>;
@@ -1254,10 +1508,8 @@ impl Cfx for CfxHandler {
 
     // TODO(thegaram): add support for these
     not_supported! {
-        fn account_pending_transactions(&self, address: RpcAddress, maybe_start_nonce: Option<U256>, maybe_limit: Option<U64>) -> BoxFuture<AccountPendingTransactions>;
         fn block_by_block_number(&self, block_number: U64, include_txs: bool) -> BoxFuture<Option<RpcBlock>>;
-        fn call(&self, request: CallRequest, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>) -> JsonRpcResult<Bytes>;
-        fn estimate_gas_and_collateral(&self, request: CallRequest, epoch_num: Option<EpochNumber>) -> JsonRpcResult<EstimateGasAndCollateralResponse>;
+        fn simulate(&self, payload: SimulatePayload, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>) -> JsonRpcResult<Vec<SimulatedBlock>>;
         fn get_block_reward_info(&self, num: EpochNumber) -> JsonRpcResult<Vec<RpcRewardInfo>>;
         fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<TokenSupplyInfo>;
         fn get_collateral_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<StorageCollateralInfo>;
@@ -1265,6 +1517,34 @@ impl Cfx for CfxHandler {
         fn get_pos_reward_by_epoch(&self, epoch: EpochNumber) -> JsonRpcResult<Option<PoSEpochReward>>;
         fn get_fee_burnt(&self, epoch: Option<EpochNumber>) -> JsonRpcResult<U256>;
         fn max_priority_fee_per_gas(&self) -> BoxFuture<U256>;
+        fn transactions_by_address(&self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>) -> BoxFuture<AddressTransactions>;
+    }
+}
+
+pub struct TransactionPoolHandler {
+    rpc_impl: Arc<RpcImpl>,
+}
+
+impl TransactionPoolHandler {
+    pub fn new(rpc_impl: Arc<RpcImpl>) -> Self {
+        TransactionPoolHandler { rpc_impl }
+    }
+}
+
+impl TransactionPool for TransactionPoolHandler {
+    delegate! {
+        to self.rpc_impl {
+            fn txpool_status(&self) -> JsonRpcResult<TxPoolStatus>;
+            fn txpool_next_nonce(&self, address: RpcAddress) -> BoxFuture<U256>;
+            fn txpool_pending_nonce_range(&self, address: RpcAddress) -> JsonRpcResult<TxPoolPendingNonceRange>;
+            fn txpool_transaction_by_address_and_nonce(&self, address: RpcAddress, nonce: U256) -> JsonRpcResult<Option<RpcTransaction>>;
+            fn account_pending_info(&self, addr: RpcAddress) -> BoxFuture<Option<AccountPendingInfo>>;
+            fn account_pending_transactions(&self, address: RpcAddress, maybe_start_nonce: Option<U256>, maybe_limit: Option<U64>) -> BoxFuture<AccountPendingTransactions>;
+        }
+    }
+
+    not_supported! {
+        fn txpool_tx_with_pool_info(&self, hash: H256) -> JsonRpcResult<TxWithPoolInfo>;
     }
 }
 
@@ -33,7 +33,7 @@ impl TransactionPool for TransactionPoolHandler {
     delegate! {
         to self.common {
             fn txpool_status(&self) -> JsonRpcResult<TxPoolStatus>;
-            fn txpool_next_nonce(&self, address: RpcAddress) -> JsonRpcResult<U256>;
+            fn txpool_next_nonce(&self, address: RpcAddress) -> BoxFuture<U256>;
             fn txpool_pending_nonce_range(&self, address: RpcAddress) -> JsonRpcResult<TxPoolPendingNonceRange>;
             fn txpool_tx_with_pool_info(&self, hash: H256) -> JsonRpcResult<TxWithPoolInfo>;
             fn txpool_transaction_by_address_and_nonce(&self, address: RpcAddress, nonce: U256) -> JsonRpcResult<Option<RpcTransaction>>;
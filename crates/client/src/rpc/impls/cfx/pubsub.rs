@@ -4,12 +4,14 @@
 
 use crate::rpc::{
     errors,
-    helpers::{EpochQueue, SubscriberId, Subscribers},
+    helpers::{
+        dropped_transaction_result, EpochQueue, SubscriberId, Subscribers,
+    },
     metadata::Metadata,
     traits::pubsub::PubSub,
     types::{
         pubsub::{self, SubscriptionEpoch},
-        Header as RpcHeader, Log as RpcLog,
+        Header as RpcHeader, Log as RpcLog, Transaction as RpcTransaction,
     },
 };
 use cfx_addr::Network;
@@ -19,7 +21,9 @@ use cfx_parameters::{
 };
 use cfx_types::{Space, H256};
 use cfxcore::{
-    channel::Channel, BlockDataManager, Notifications, SharedConsensusGraph,
+    channel::Channel, sync::SyncPhaseType,
+    transaction_pool::DroppedTransaction, BlockDataManager, Notifications,
+    SharedConsensusGraph, SharedTransactionPool,
 };
 use futures::{
     compat::Future01CompatExt,
@@ -31,9 +35,10 @@ use jsonrpc_pubsub::{
     typed::{Sink, Subscriber},
     SubscriptionId,
 };
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use primitives::{
     filter::LogFilter, log_entry::LocalizedLogEntry, BlockReceipts,
+    SignedTransaction,
 };
 use runtime::Executor;
 use std::{
@@ -51,24 +56,43 @@ pub struct PubSubClient {
     heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
     epochs_subscribers: Arc<RwLock<Subscribers<Client>>>,
     logs_subscribers: Arc<RwLock<Subscribers<(Client, LogFilter)>>>,
+    pending_transactions_subscribers: Arc<RwLock<Subscribers<(Client, bool)>>>,
+    dropped_transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    syncing_subscribers: Arc<RwLock<Subscribers<Client>>>,
     epochs_ordered: Arc<Channel<(u64, Vec<H256>)>>,
+    txpool: SharedTransactionPool,
+    // the id of the subscription to the new pending transactions of the pool,
+    // which is only kept while there are `newPendingTransactions` subscribers
+    pending_transactions_receiver: Arc<Mutex<Option<u64>>>,
 }
 
 impl PubSubClient {
     /// Creates new `PubSubClient`.
     pub fn new(
         executor: Executor, consensus: SharedConsensusGraph,
-        notifications: Arc<Notifications>, network: Network,
+        txpool: SharedTransactionPool, notifications: Arc<Notifications>,
+        network: Network,
     ) -> Self {
         let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
         let epochs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
         let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+        let pending_transactions_subscribers =
+            Arc::new(RwLock::new(Subscribers::default()));
+        let dropped_transactions_subscribers =
+            Arc::new(RwLock::new(Subscribers::default()));
+        let syncing_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 
         let handler = Arc::new(ChainNotificationHandler {
             executor,
             consensus: consensus.clone(),
             data_man: consensus.get_data_manager().clone(),
             heads_subscribers: heads_subscribers.clone(),
+            pending_transactions_subscribers: pending_transactions_subscribers
+                .clone(),
+            dropped_transactions_subscribers: dropped_transactions_subscribers
+                .clone(),
+            syncing_subscribers: syncing_subscribers.clone(),
+            last_syncing: Mutex::new(None),
             network,
         });
 
@@ -86,12 +110,33 @@ impl PubSubClient {
         // run futures@0.3 future on tokio@0.1 executor
         handler.executor.spawn(fut.unit_error().boxed().compat());
 
+        // --------- droppedTransactions ---------
+        let receiver = txpool.dropped_transactions().subscribe();
+        let handler_clone = handler.clone();
+        let fut = receiver.for_each(move |dropped| {
+            handler_clone.notify_dropped_transaction(&dropped);
+        });
+        handler.executor.spawn(fut.unit_error().boxed().compat());
+
+        // --------- syncing ---------
+        let receiver = notifications.sync_phase_changes.subscribe();
+        let handler_clone = handler.clone();
+        let fut = receiver.for_each(move |phase| {
+            handler_clone.notify_sync_phase(phase);
+        });
+        handler.executor.spawn(fut.unit_error().boxed().compat());
+
         PubSubClient {
             handler,
             heads_subscribers,
             epochs_subscribers,
             logs_subscribers,
+            pending_transactions_subscribers,
+            dropped_transactions_subscribers,
+            syncing_subscribers,
             epochs_ordered: notifications.epochs_ordered.clone(),
+            txpool,
+            pending_transactions_receiver: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -100,6 +145,43 @@ impl PubSubClient {
         Arc::downgrade(&self.handler)
     }
 
+    // Subscribe to the new pending transactions of the pool when the first
+    // `newPendingTransactions` subscriber is added, so that the pool does not
+    // queue every inserted transaction while nobody has subscribed.
+    fn start_pending_transactions_loop(&self) {
+        let mut receiver_id = self.pending_transactions_receiver.lock();
+        if receiver_id.is_some() {
+            return;
+        }
+
+        debug!("start_pending_transactions_loop");
+        let receiver = self.txpool.new_pending_transactions().subscribe();
+        *receiver_id = Some(receiver.id);
+
+        let handler_clone = self.handler.clone();
+        let fut = receiver.for_each(move |tx| {
+            handler_clone.notify_pending_transaction(&tx);
+        });
+        // run futures@0.3 future on tokio@0.1 executor
+        self.handler
+            .executor
+            .spawn(fut.unit_error().boxed().compat());
+    }
+
+    // Unsubscribe from the pool after the last `newPendingTransactions`
+    // subscriber is removed, which also terminates the loop above.
+    fn stop_pending_transactions_loop(&self) {
+        let mut receiver_id = self.pending_transactions_receiver.lock();
+        if !self.pending_transactions_subscribers.read().is_empty() {
+            return;
+        }
+
+        if let Some(id) = receiver_id.take() {
+            debug!("stop_pending_transactions_loop");
+            self.txpool.new_pending_transactions().unsubscribe(id);
+        }
+    }
+
     // Start an async loop that continuously receives epoch notifications and
     // publishes the corresponding epochs to subscriber `id`, keeping their
     // original order. The loop terminates when subscriber `id` unsubscribes.
@@ -228,6 +310,12 @@ pub struct ChainNotificationHandler {
     consensus: SharedConsensusGraph,
     data_man: Arc<BlockDataManager>,
     heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    pending_transactions_subscribers: Arc<RwLock<Subscribers<(Client, bool)>>>,
+    dropped_transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    syncing_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    // the sync status last published, as only the transitions between
+    // catch-up and normal phases are published
+    last_syncing: Mutex<Option<bool>>,
     pub network: Network,
 }
 
@@ -291,6 +379,87 @@ impl ChainNotificationHandler {
         }
     }
 
+    // notify each subscriber about the new pending transaction `tx`, either
+    // with its hash or with the full transaction
+    fn notify_pending_transaction(&self, tx: &SignedTransaction) {
+        trace!("notify_pending_transaction({:?})", tx.hash());
+
+        let subscribers = self.pending_transactions_subscribers.read();
+
+        if subscribers.is_empty() || tx.space() != Space::Native {
+            return;
+        }
+
+        let full_tx = if subscribers.values().any(|(_, full)| *full) {
+            match RpcTransaction::from_signed(tx, None, self.network) {
+                Ok(t) => Some(t),
+                Err(e) => {
+                    error!(
+                        "Unexpected error while constructing RpcTransaction: {:?}",
+                        e
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        for (subscriber, full) in subscribers.values() {
+            let result = match &full_tx {
+                Some(t) if *full => pubsub::Result::Transaction(t.clone()),
+                _ => pubsub::Result::TransactionHash(tx.hash()),
+            };
+            Self::notify(&self.executor, subscriber, result);
+        }
+    }
+
+    fn notify_dropped_transaction(&self, dropped: &DroppedTransaction) {
+        trace!("notify_dropped_transaction({:?})", dropped);
+
+        let subscribers = self.dropped_transactions_subscribers.read();
+
+        if subscribers.is_empty() {
+            return;
+        }
+        let result = match dropped_transaction_result(
+            dropped,
+            Space::Native,
+            |transaction_hash, reason| pubsub::Result::DroppedTransaction {
+                transaction_hash,
+                reason,
+            },
+        ) {
+            Some(result) => result,
+            None => return,
+        };
+
+        for subscriber in subscribers.values() {
+            Self::notify(&self.executor, subscriber, result.clone());
+        }
+    }
+
+    fn notify_sync_phase(&self, phase: SyncPhaseType) {
+        trace!("notify_sync_phase({:?})", phase);
+
+        let syncing = phase != SyncPhaseType::Normal;
+        {
+            let mut last_syncing = self.last_syncing.lock();
+            if *last_syncing == Some(syncing) {
+                return;
+            }
+            *last_syncing = Some(syncing);
+        }
+
+        for subscriber in self.syncing_subscribers.read().values() {
+            Self::notify(
+                &self.executor,
+                subscriber,
+                pubsub::Result::SyncState { syncing },
+            );
+        }
+    }
+
     async fn notify_epoch(&self, subscriber: Client, epoch: (u64, Vec<H256>)) {
         trace!("notify_epoch({:?})", epoch);
 
@@ -539,7 +708,49 @@ impl PubSub for PubSubClient {
             (pubsub::Kind::Logs, _) => {
                 errors::invalid_params("logs", "Expected filter parameter.")
             }
-            _ => errors::unimplemented(None),
+            // --------- newPendingTransactions ---------
+            (pubsub::Kind::NewPendingTransactions, None) => {
+                self.pending_transactions_subscribers
+                    .write()
+                    .push(subscriber, false);
+                self.start_pending_transactions_loop();
+                return;
+            }
+            (
+                pubsub::Kind::NewPendingTransactions,
+                Some(pubsub::Params::FullTransactions(full)),
+            ) => {
+                self.pending_transactions_subscribers
+                    .write()
+                    .push(subscriber, full);
+                self.start_pending_transactions_loop();
+                return;
+            }
+            (pubsub::Kind::NewPendingTransactions, _) => {
+                errors::invalid_params(
+                    "newPendingTransactions",
+                    "Expected boolean parameter.",
+                )
+            }
+            // --------- droppedTransactions ---------
+            (pubsub::Kind::DroppedTransactions, None) => {
+                self.dropped_transactions_subscribers
+                    .write()
+                    .push(subscriber);
+                return;
+            }
+            (pubsub::Kind::DroppedTransactions, _) => errors::invalid_params(
+                "droppedTransactions",
+                "Expected no parameters.",
+            ),
+            // --------- syncing ---------
+            (pubsub::Kind::Syncing, None) => {
+                self.syncing_subscribers.write().push(subscriber);
+                return;
+            }
+            (pubsub::Kind::Syncing, _) => {
+                errors::invalid_params("syncing", "Expected no parameters.")
+            }
         };
 
         let _ = subscriber.reject(error);
@@ -551,7 +762,78 @@ impl PubSub for PubSubClient {
         let res0 = self.heads_subscribers.write().remove(&id).is_some();
         let res1 = self.epochs_subscribers.write().remove(&id).is_some();
         let res2 = self.logs_subscribers.write().remove(&id).is_some();
+        let res3 = self
+            .pending_transactions_subscribers
+            .write()
+            .remove(&id)
+            .is_some();
+        if res3 {
+            self.stop_pending_transactions_loop();
+        }
+        let res4 = self
+            .dropped_transactions_subscribers
+            .write()
+            .remove(&id)
+            .is_some();
+        let res5 = self.syncing_subscribers.write().remove(&id).is_some();
+
+        Ok(res0 || res1 || res2 || res3 || res4 || res5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pubsub;
+    use crate::rpc::helpers::{dropped, dropped_transaction_result};
+    use cfx_types::{Space, H256};
+    use cfxcore::transaction_pool::{DropReason, DroppedTransaction};
+    use serde_json::json;
+
+    fn result(dropped: &DroppedTransaction) -> Option<pubsub::Result> {
+        dropped_transaction_result(
+            dropped,
+            Space::Native,
+            |transaction_hash, reason| pubsub::Result::DroppedTransaction {
+                transaction_hash,
+                reason,
+            },
+        )
+    }
+
+    #[test]
+    fn test_dropped_transaction_result() {
+        let by = H256::from_low_u64_be(1);
+        let replaced = dropped(Space::Native, DropReason::Replaced { by });
+        assert_eq!(
+            serde_json::to_value(result(&replaced).unwrap()).unwrap(),
+            json!({
+                "transactionHash": replaced.transaction.hash(),
+                "reason": "replaced",
+                "by": by,
+            })
+        );
+
+        let evicted = dropped(Space::Native, DropReason::Evicted { by });
+        assert_eq!(
+            serde_json::to_value(result(&evicted).unwrap()).unwrap(),
+            json!({
+                "transactionHash": evicted.transaction.hash(),
+                "reason": "evicted",
+                "by": by,
+            })
+        );
+
+        let collected = dropped(Space::Native, DropReason::GarbageCollected);
+        assert_eq!(
+            serde_json::to_value(result(&collected).unwrap()).unwrap(),
+            json!({
+                "transactionHash": collected.transaction.hash(),
+                "reason": "garbageCollected",
+            })
+        );
 
-        Ok(res0 || res1 || res2)
+        // The transactions of the other space are not published.
+        let other = dropped(Space::Ethereum, DropReason::Replaced { by });
+        assert!(result(&other).is_none());
     }
 }
@@ -1,21 +1,28 @@
-use std::{convert::TryInto, sync::Arc};
+use std::{collections::HashMap, convert::TryInto, sync::Arc};
 
 use crate::rpc::{
     errors::invalid_params_msg,
     traits::eth_space::debug::Debug,
     types::eth::{BlockNumber, CallRequest},
 };
+use alloy_primitives::B256;
+use alloy_rpc_types::{
+    state::AccountOverride as RpcAccountOverride, BlockOverrides,
+};
 use alloy_rpc_types_trace::geth::{
-    GethDebugBuiltInTracerType,
+    GethDebugBuiltInTracerType, GethDebugTracerConfig,
     GethDebugTracerType::{BuiltInTracer, JsTracer},
     GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame,
     TraceResult,
 };
-use cfx_types::{Space, H256};
+use cfx_executor::state::{AccountOverride, StateOverride};
+use cfx_types::{AddressSpaceUtil, Space, H256, U256};
 use cfxcore::{ConsensusGraph, ConsensusGraphTrait, SharedConsensusGraph};
-use geth_tracer::to_alloy_h256;
+use geth_tracer::{
+    from_alloy_address, from_alloy_u256, to_alloy_h256, validate_js_tracer,
+};
 use jsonrpc_core::Result as JsonRpcResult;
-use primitives::{Block, BlockHeaderBuilder, EpochNumber};
+use primitives::{Block, BlockHeader, BlockHeaderBuilder, EpochNumber};
 
 pub struct GethDebugHandler {
     consensus: SharedConsensusGraph,
@@ -59,6 +66,60 @@ impl GethDebugHandler {
     }
 }
 
+/// Pre checks the config of a builtin tracer, so that a malformed config is
+/// reported as invalid params instead of failing the trace.
+fn check_tracer_config(
+    tracer: GethDebugBuiltInTracerType, config: GethDebugTracerConfig,
+) -> JsonRpcResult<()> {
+    match tracer {
+        GethDebugBuiltInTracerType::CallTracer => {
+            let _ = config
+                .into_call_config()
+                .map_err(|e| invalid_params_msg(&e.to_string()))?;
+        }
+        GethDebugBuiltInTracerType::PreStateTracer => {
+            let _ = config
+                .into_pre_state_config()
+                .map_err(|e| invalid_params_msg(&e.to_string()))?;
+        }
+        GethDebugBuiltInTracerType::FourByteTracer
+        | GethDebugBuiltInTracerType::NoopTracer
+        | GethDebugBuiltInTracerType::MuxTracer => (),
+    }
+    Ok(())
+}
+
+/// Pre checks the tracer of the options, so that a malformed tracer or config
+/// is reported as invalid params by every `debug_trace*` method instead of
+/// failing the trace.
+fn check_tracing_options(opts: &GethDebugTracingOptions) -> JsonRpcResult<()> {
+    match &opts.tracer {
+        None | Some(BuiltInTracer(GethDebugBuiltInTracerType::NoopTracer)) => {}
+        Some(BuiltInTracer(GethDebugBuiltInTracerType::MuxTracer)) => {
+            // pre check the config of every inner tracer
+            let mux_config = opts
+                .tracer_config
+                .clone()
+                .into_mux_config()
+                .map_err(|e| invalid_params_msg(&e.to_string()))?;
+            for (tracer, config) in mux_config.0 {
+                if tracer == GethDebugBuiltInTracerType::MuxTracer {
+                    return Err(invalid_params_msg(
+                        "muxTracer can not be nested",
+                    ));
+                }
+                check_tracer_config(tracer, config.unwrap_or_default())?;
+            }
+        }
+        Some(BuiltInTracer(builtin_tracer)) => {
+            check_tracer_config(*builtin_tracer, opts.tracer_config.clone())?
+        }
+        Some(JsTracer(code)) => validate_js_tracer(code, opts)
+            .map_err(|e| invalid_params_msg(&e))?,
+    }
+    Ok(())
+}
+
 impl Debug for GethDebugHandler {
     fn db_get(&self, _key: String) -> JsonRpcResult<Option<String>> {
         Ok(Some("To be implemented!".into()))
@@ -69,40 +130,13 @@ impl Debug for GethDebugHandler {
     ) -> JsonRpcResult<GethTrace> {
         let opts = opts.unwrap_or_default();
 
-        // early return if tracer is not supported or NoopTracer is requested
-        if let Some(tracer_type) = &opts.tracer {
-            match tracer_type {
-                BuiltInTracer(builtin_tracer) => match builtin_tracer {
-                    GethDebugBuiltInTracerType::FourByteTracer => (),
-                    GethDebugBuiltInTracerType::CallTracer => {
-                        // pre check config
-                        let _ = opts
-                            .tracer_config
-                            .clone()
-                            .into_call_config()
-                            .map_err(|e| {
-                            invalid_params_msg(&e.to_string())
-                        })?;
-                        ()
-                    }
-                    GethDebugBuiltInTracerType::PreStateTracer => {
-                        // pre check config
-                        let _ = opts
-                            .tracer_config
-                            .clone()
-                            .into_pre_state_config()
-                            .map_err(|e| invalid_params_msg(&e.to_string()))?;
-                        ()
-                    }
-                    GethDebugBuiltInTracerType::NoopTracer => {
-                        return Ok(GethTrace::NoopTracer(NoopFrame::default()))
-                    }
-                    GethDebugBuiltInTracerType::MuxTracer => {
-                        return Err(invalid_params_msg("not supported"))
-                    }
-                },
-                JsTracer(_) => return Err(invalid_params_msg("not supported")),
-            }
+        check_tracing_options(&opts)?;
+
+        // early return if NoopTracer is requested
+        if let Some(BuiltInTracer(GethDebugBuiltInTracerType::NoopTracer)) =
+            &opts.tracer
+        {
+            return Ok(GethTrace::NoopTracer(NoopFrame::default()));
         }
 
         let tx_index = self
@@ -137,6 +171,7 @@ impl Debug for GethDebugHandler {
         &self, block_hash: H256, opts: Option<GethDebugTracingOptions>,
     ) -> JsonRpcResult<Vec<TraceResult>> {
         let opts = opts.unwrap_or_default();
+        check_tracing_options(&opts)?;
         let epoch_num = self
             .consensus_graph()
             .get_block_epoch_number_with_pivot_check(&block_hash, false)?;
@@ -163,6 +198,7 @@ impl Debug for GethDebugHandler {
         &self, block: BlockNumber, opts: Option<GethDebugTracingOptions>,
     ) -> JsonRpcResult<Vec<TraceResult>> {
         let opts = opts.unwrap_or_default();
+        check_tracing_options(&opts)?;
         let num = self
             .get_block_epoch_num(block)
             .map_err(|e| invalid_params_msg(&e))?;
@@ -184,12 +220,12 @@ impl Debug for GethDebugHandler {
         Ok(result)
     }
 
-    // TODO: implement state and block override
     fn debug_trace_call(
         &self, request: CallRequest, block_number: Option<BlockNumber>,
         opts: Option<GethDebugTracingCallOptions>,
     ) -> JsonRpcResult<GethTrace> {
         let opts = opts.unwrap_or_default();
+        check_tracing_options(&opts.tracing_options)?;
         let block_num = block_number.unwrap_or_default();
 
         let epoch_num = self
@@ -221,14 +257,19 @@ impl Debug for GethDebugHandler {
                 true, /* update_cache */
             )
             .expect("blocks exist");
-        let pivot_block = epoch_blocks.last().expect("should have block");
-        let header = BlockHeaderBuilder::new()
-            .with_base_price(pivot_block.block_header.base_price())
-            .with_parent_hash(pivot_block.block_header.hash())
-            .with_height(epoch_num + 1)
-            .with_timestamp(pivot_block.block_header.timestamp() + 1)
-            .with_gas_limit(*pivot_block.block_header.gas_limit())
-            .build();
+        let pivot_header =
+            &epoch_blocks.last().expect("should have block").block_header;
+
+        let state_override = opts
+            .state_overrides
+            .map(convert_state_override)
+            .transpose()?;
+
+        let header = override_block_header(
+            pivot_header,
+            epoch_num,
+            opts.block_overrides.unwrap_or_default(),
+        )?;
         let block = Block::new(header, vec![Arc::new(signed_tx)]);
         let blocks: Vec<Arc<Block>> = vec![Arc::new(block)];
 
@@ -238,6 +279,7 @@ impl Debug for GethDebugHandler {
             &blocks,
             opts.tracing_options,
             None,
+            state_override,
         )?;
 
         let res = traces.first().expect("should have trace");
@@ -245,3 +287,242 @@ impl Debug for GethDebugHandler {
         Ok(res.trace.clone())
     }
 }
+
+/// Builds the header of the synthetic block of `debug_traceCall` on top of
+/// `pivot_header`, applying the block overrides.
+fn override_block_header(
+    pivot_header: &BlockHeader, epoch_num: u64, block_overrides: BlockOverrides,
+) -> JsonRpcResult<BlockHeader> {
+    // the block number of the eSpace is the epoch height
+    let height = match block_overrides.number {
+        Some(number) => override_to_u64(number, "number")?,
+        None => epoch_num + 1,
+    };
+    let timestamp = match block_overrides.time {
+        Some(time) => override_to_u64(time, "time")?,
+        None => pivot_header.timestamp() + 1,
+    };
+    let gas_limit = match block_overrides.gas_limit {
+        Some(gas_limit) => override_to_u64(gas_limit, "gasLimit")?.into(),
+        None => *pivot_header.gas_limit(),
+    };
+    let author = block_overrides
+        .coinbase
+        .map(from_alloy_address)
+        .unwrap_or_default();
+    let mut base_price = pivot_header.base_price();
+    if let Some(base_fee) = block_overrides.base_fee {
+        base_price.get_or_insert_with(Default::default)[Space::Ethereum] =
+            from_alloy_u256(base_fee);
+    }
+
+    Ok(BlockHeaderBuilder::new()
+        .with_base_price(base_price)
+        .with_parent_hash(pivot_header.hash())
+        .with_height(height)
+        .with_timestamp(timestamp)
+        .with_gas_limit(gas_limit)
+        .with_author(author)
+        .build())
+}
+
+/// Converts the geth `stateOverrides` of espace accounts to the executor
+/// representation.
+pub(crate) fn convert_state_override(
+    state_overrides: impl IntoIterator<
+        Item = (alloy_primitives::Address, RpcAccountOverride),
+    >,
+) -> JsonRpcResult<StateOverride> {
+    let mut state_override = StateOverride::new();
+    for (address, account) in state_overrides {
+        state_override.insert(
+            from_alloy_address(address).with_evm_space(),
+            convert_account_override(account)?,
+        );
+    }
+    Ok(state_override)
+}
+
+/// Converts the override of a single account, which is shared by the core
+/// space and the espace.
+pub(crate) fn convert_account_override(
+    account: RpcAccountOverride,
+) -> JsonRpcResult<AccountOverride> {
+    let convert_storage = |storage: HashMap<B256, B256>| -> HashMap<_, _> {
+        storage
+            .into_iter()
+            .map(|(key, value)| (H256::from(key.0), H256::from(value.0)))
+            .collect()
+    };
+
+    if account.state.is_some() && account.state_diff.is_some() {
+        return Err(invalid_params_msg(
+            "state and stateDiff can't be set in the same account override",
+        ));
+    }
+
+    let nonce = account
+        .nonce
+        .map(|nonce| override_to_u64(nonce, "nonce").map(U256::from))
+        .transpose()?;
+    Ok(AccountOverride {
+        balance: account.balance.map(from_alloy_u256),
+        nonce,
+        code: account.code.map(|code| code.to_vec()),
+        state: account.state.map(convert_storage),
+        state_diff: account.state_diff.map(convert_storage),
+    })
+}
+
+pub(crate) fn override_to_u64<T: TryInto<u64>>(
+    value: T, field: &str,
+) -> JsonRpcResult<u64> {
+    value.try_into().map_err(|_| {
+        invalid_params_msg(&format!("{} override exceeds u64", field))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_tracing_options, convert_account_override, override_block_header,
+    };
+    use alloy_rpc_types::{
+        state::AccountOverride as RpcAccountOverride, BlockOverrides,
+    };
+    use alloy_rpc_types_trace::geth::GethDebugTracingOptions;
+    use cfx_types::{Address, Space, U256};
+    use primitives::{BlockHeader, BlockHeaderBuilder};
+    use serde_json::json;
+
+    fn pivot_header() -> BlockHeader {
+        BlockHeaderBuilder::new()
+            .with_height(100)
+            .with_timestamp(1000)
+            .with_gas_limit(30_000_000.into())
+            .build()
+    }
+
+    fn block_overrides(overrides: serde_json::Value) -> BlockOverrides {
+        serde_json::from_value(overrides).unwrap()
+    }
+
+    #[test]
+    fn test_block_header_without_overrides() {
+        let pivot = pivot_header();
+        let header =
+            override_block_header(&pivot, 10, Default::default()).unwrap();
+        assert_eq!(header.height(), 11);
+        assert_eq!(header.timestamp(), 1001);
+        assert_eq!(*header.gas_limit(), U256::from(30_000_000));
+        assert_eq!(*header.author(), Address::zero());
+        assert_eq!(*header.parent_hash(), pivot.hash());
+        assert!(header.base_price().is_none());
+    }
+
+    #[test]
+    fn test_block_header_with_overrides() {
+        let pivot = pivot_header();
+        let overrides = block_overrides(json!({
+            "number": "0x20",
+            "time": "0x7d0",
+            "gasLimit": "0x1000",
+            "coinbase": "0x1000000000000000000000000000000000000001",
+            "baseFee": "0x3b9aca00",
+        }));
+        let header = override_block_header(&pivot, 10, overrides).unwrap();
+        assert_eq!(header.height(), 0x20);
+        assert_eq!(header.timestamp(), 2000);
+        assert_eq!(*header.gas_limit(), U256::from(0x1000));
+        assert_eq!(
+            *header.author(),
+            "1000000000000000000000000000000000000001".parse().unwrap()
+        );
+        let base_price = header.base_price().unwrap();
+        assert_eq!(base_price[Space::Ethereum], U256::from(1_000_000_000));
+        assert_eq!(base_price[Space::Native], U256::zero());
+    }
+
+    #[test]
+    fn test_block_number_override_exceeding_u64() {
+        let overrides = block_overrides(json!({
+            "number": "0x10000000000000000",
+        }));
+        let err =
+            override_block_header(&pivot_header(), 10, overrides).unwrap_err();
+        assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
+    }
+
+    #[test]
+    fn test_account_override_with_state_and_state_diff() {
+        let account: RpcAccountOverride = serde_json::from_value(json!({
+            "state": {},
+            "stateDiff": {},
+        }))
+        .unwrap();
+        let err = convert_account_override(account).unwrap_err();
+        assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
+
+        let account: RpcAccountOverride = serde_json::from_value(json!({
+            "stateDiff": {},
+        }))
+        .unwrap();
+        assert!(convert_account_override(account).is_ok());
+    }
+
+    fn check_options(opts: serde_json::Value) -> jsonrpc_core::Result<()> {
+        let opts: GethDebugTracingOptions =
+            serde_json::from_value(opts).unwrap();
+        check_tracing_options(&opts)
+    }
+
+    #[test]
+    fn test_check_mux_tracer_options() {
+        assert!(check_options(json!({
+            "tracer": "muxTracer",
+            "tracerConfig": {
+                "callTracer": { "onlyTopCall": true },
+                "4byteTracer": null,
+            },
+        }))
+        .is_ok());
+
+        for tracer_config in [
+            // nested muxTracer
+            json!({ "muxTracer": { "callTracer": null } }),
+            // malformed config of an inner tracer
+            json!({ "callTracer": { "onlyTopCall": "yes" } }),
+            // not a map of tracers
+            json!(["callTracer"]),
+        ] {
+            let err = check_options(json!({
+                "tracer": "muxTracer",
+                "tracerConfig": tracer_config,
+            }))
+            .unwrap_err();
+            assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
+        }
+    }
+
+    #[test]
+    fn test_check_tracer_options() {
+        assert!(check_options(json!({})).is_ok());
+        assert!(check_options(json!({ "tracer": "noopTracer" })).is_ok());
+        assert!(check_options(json!({
+            "tracer": "prestateTracer",
+            "tracerConfig": { "diffMode": true },
+        }))
+        .is_ok());
+
+        let err = check_options(json!({
+            "tracer": "callTracer",
+            "tracerConfig": { "withLog": 1 },
+        }))
+        .unwrap_err();
+        assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
+
+        let err = check_options(json!({ "tracer": "{result: function() {" }))
+            .unwrap_err();
+        assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
+    }
+}
@@ -2,31 +2,49 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::rpc::{
-    errors::{
-        geth_call_execution_error, internal_error, invalid_input_rpc_err,
-        invalid_params, request_rejected_in_catch_up_mode, unknown_block,
-        EthApiError, RpcInvalidTransactionError, RpcPoolError,
-    },
-    impls::RpcImplConfiguration,
-    traits::eth_space::eth::Eth,
-    types::{
-        eth::{
-            AccountPendingTransactions, Block as RpcBlock, BlockNumber,
-            CallRequest, EthRpcLogFilter, Log, Receipt, SyncInfo, SyncStatus,
-            Transaction,
+// To convert from RpcResult to BoxFuture by delegate! macro automatically.
+use crate::{
+    common::delegate_convert,
+    rpc::{
+        errors::{
+            geth_call_execution_error, internal_error, internal_error_msg,
+            invalid_input_rpc_err, invalid_params,
+            request_rejected_in_catch_up_mode, unknown_block, EthApiError,
+            RpcInvalidTransactionError, RpcPoolError,
+        },
+        impls::{
+            eth::debug::{convert_state_override, override_to_u64},
+            RpcImplConfiguration,
+        },
+        traits::eth_space::eth::Eth,
+        types::{
+            eth::{
+                AccessListWithGasUsed, AccountPendingTransactions,
+                AccountProof, AddressTransaction, AddressTransactions,
+                Block as RpcBlock, BlockNumber, CallRequest, EthRpcLogFilter,
+                Log, Receipt, SimulatePayload, SimulatedBlock, StorageProof,
+                SyncInfo, SyncStatus, Transaction,
+            },
+            Bytes, FeeHistory, Index, MAX_GAS_CALL_REQUEST, U64 as HexU64,
         },
-        Bytes, FeeHistory, Index, MAX_GAS_CALL_REQUEST, U64 as HexU64,
     },
 };
-use cfx_execute_helper::estimation::{
-    decode_error, EstimateExt, EstimateRequest,
+use cfx_execute_helper::{
+    estimation::{decode_error, EstimateExt, EstimateRequest},
+    observer::access_list::AccessListKey,
+    simulation::{
+        check_simulate_counts, check_simulate_limits, BlockOverrides,
+        SimulateBlock, SimulateCall,
+    },
 };
 use cfx_executor::executive::{
     revert_reason_decode, ExecutionError, ExecutionOutcome, TxDropError,
 };
-use cfx_parameters::rpc::GAS_PRICE_DEFAULT_VALUE;
+use cfx_parameters::rpc::{
+    ADDRESS_TX_PAGE_SIZE, GAS_PRICE_DEFAULT_VALUE, MAX_ADDRESS_TX_PAGE_SIZE,
+};
 use cfx_statedb::StateDbExt;
+use cfx_storage::{state::StateDbGetOriginalMethods, StorageStateTrait};
 use cfx_types::{
     Address, AddressSpaceUtil, BigEndianHash, Space, H160, H256, U256, U64,
 };
@@ -38,12 +56,16 @@ use cfxcore::{
     SharedSynchronizationService, SharedTransactionPool,
 };
 use clap::crate_version;
-use jsonrpc_core::{Error as RpcError, Result as RpcResult};
+use delegate::delegate;
+use geth_tracer::{from_alloy_address, from_alloy_u256};
+use jsonrpc_core::{BoxFuture, Error as RpcError, Result as RpcResult};
+use keccak_hash::KECCAK_EMPTY;
 use primitives::{
-    filter::LogFilter, receipt::EVM_SPACE_SUCCESS, Action,
-    BlockHashOrEpochNumber, EpochNumber, StorageKey, StorageValue,
-    TransactionStatus, TransactionWithSignature,
+    filter::LogFilter, receipt::EVM_SPACE_SUCCESS, AccessList, Account, Action,
+    BlockHashOrEpochNumber, EpochNumber, SignedTransaction, StateRoot,
+    StorageKey, StorageValue, TransactionStatus, TransactionWithSignature,
 };
+use rlp::Rlp;
 use rustc_hex::ToHex;
 use std::convert::TryInto;
 
@@ -73,6 +95,34 @@ impl EthHandler {
             .downcast_ref::<ConsensusGraph>()
             .expect("downcast should succeed")
     }
+
+    /// Returns the state root one snapshot period before the state of epoch
+    /// `height`, which pads the intermediate MPT keys in the state proofs.
+    fn prev_snapshot_state_root(
+        &self, height: u64,
+    ) -> RpcResult<Option<StateRoot>> {
+        let data_man = self.consensus.get_data_manager();
+        let snapshot_epoch_count = data_man.get_snapshot_epoch_count() as u64;
+        if height <= snapshot_epoch_count {
+            return Ok(None);
+        }
+
+        let hash = self
+            .consensus
+            .get_hash_from_epoch_number(EpochNumber::Number(
+                height - snapshot_epoch_count,
+            ))
+            .map_err(RpcError::invalid_params)?;
+        let commitment = data_man
+            .get_epoch_execution_commitment_with_db(&hash)
+            .ok_or_else(|| {
+                internal_error_msg(
+                    "state root of the previous snapshot period not found",
+                )
+            })?;
+
+        Ok(Some(commitment.state_root_with_aux_info.state_root))
+    }
 }
 
 fn block_tx_by_index(
@@ -105,34 +155,11 @@ fn block_tx_by_index(
 }
 
 impl EthHandler {
-    fn exec_transaction(
-        &self, mut request: CallRequest,
-        block_number_or_hash: Option<BlockNumber>,
-    ) -> CfxRpcResult<(ExecutionOutcome, EstimateExt)> {
+    /// Resolves the epoch to execute virtual calls against.
+    fn call_epoch(
+        &self, block_number_or_hash: Option<BlockNumber>,
+    ) -> CfxRpcResult<EpochNumber> {
         let consensus_graph = self.consensus_graph();
-
-        if request.gas_price.is_some()
-            && request.max_priority_fee_per_gas.is_some()
-        {
-            return Err(RpcError::from(
-                EthApiError::ConflictingFeeFieldsInRequest,
-            )
-            .into());
-        }
-
-        if request.max_fee_per_gas.is_some()
-            && request.max_priority_fee_per_gas.is_some()
-        {
-            if request.max_fee_per_gas.unwrap()
-                < request.max_priority_fee_per_gas.unwrap()
-            {
-                return Err(RpcError::from(
-                    RpcInvalidTransactionError::TipAboveFeeCap,
-                )
-                .into());
-            }
-        }
-
         let epoch = match block_number_or_hash.unwrap_or_default() {
             BlockNumber::Hash { hash, .. } => {
                 match consensus_graph.get_block_epoch_number(&hash) {
@@ -154,20 +181,23 @@ impl EthHandler {
             }
             epoch => epoch.try_into()?,
         };
+        Ok(epoch)
+    }
 
-        // if gas_price is zero, it is considered as not set
-        request.unset_zero_gas_price();
+    fn exec_transaction(
+        &self, request: CallRequest, block_number_or_hash: Option<BlockNumber>,
+        collect_access_list: bool,
+    ) -> CfxRpcResult<(ExecutionOutcome, EstimateExt)> {
+        let consensus_graph = self.consensus_graph();
 
-        let estimate_request = EstimateRequest {
-            has_sender: request.from.is_some(),
-            has_gas_limit: request.gas.is_some(),
-            has_gas_price: request.gas_price.is_some(),
-            has_nonce: request.nonce.is_some(),
-            has_storage_limit: false,
-        };
+        let epoch = self.call_epoch(block_number_or_hash)?;
 
         let chain_id = self.consensus.best_chain_id();
-        let signed_tx = request.sign_call(chain_id.in_evm_space())?;
+        let (signed_tx, estimate_request) = prepare_call_request(
+            request,
+            chain_id.in_evm_space(),
+            collect_access_list,
+        )?;
 
         trace!("call tx {:?}, request {:?}", signed_tx, estimate_request);
         consensus_graph.call_virtual(&signed_tx, epoch, estimate_request)
@@ -181,7 +211,7 @@ impl EthHandler {
             bail!(request_rejected_in_catch_up_mode(None));
         }
         let (signed_trans, failed_trans) =
-            self.tx_pool.insert_new_transactions(vec![tx]);
+            self.tx_pool.insert_new_local_transactions(vec![tx]);
         if signed_trans.len() + failed_trans.len() > 1 {
             // This should never happen
             error!("insert_new_transactions failed, invalid length of returned result vector {}", signed_trans.len() + failed_trans.len());
@@ -202,140 +232,408 @@ impl EthHandler {
         }
     }
 
-    fn construct_rpc_receipt(
-        &self, b: &PhantomBlock, idx: usize, prior_log_index: &mut usize,
-    ) -> RpcResult<Receipt> {
-        if b.transactions.len() != b.receipts.len() {
-            return Err(internal_error(
-                "Inconsistent state: transactions and receipts length mismatch",
-            ));
+    fn get_tx_from_txpool(&self, hash: H256) -> Option<Transaction> {
+        let tx = self.tx_pool.get_transaction(&hash)?;
+
+        if tx.space() == Space::Ethereum {
+            Some(Transaction::from_signed(
+                &tx,
+                (None, None, None),
+                (None, None),
+            ))
+        } else {
+            None
         }
+    }
+}
 
-        if b.transactions.len() != b.errors.len() {
-            return Err(internal_error(
-                "Inconsistent state: transactions and errors length mismatch",
-            ));
+/// Validates the fee fields of `request` and signs it for a virtual call in
+/// eSpace.
+pub(crate) fn prepare_call_request(
+    mut request: CallRequest, evm_chain_id: u32, collect_access_list: bool,
+) -> CfxRpcResult<(SignedTransaction, EstimateRequest)> {
+    if request.gas_price.is_some() && request.max_priority_fee_per_gas.is_some()
+    {
+        return Err(
+            RpcError::from(EthApiError::ConflictingFeeFieldsInRequest).into()
+        );
+    }
+
+    if request.max_fee_per_gas.is_some()
+        && request.max_priority_fee_per_gas.is_some()
+    {
+        if request.max_fee_per_gas.unwrap()
+            < request.max_priority_fee_per_gas.unwrap()
+        {
+            return Err(RpcError::from(
+                RpcInvalidTransactionError::TipAboveFeeCap,
+            )
+            .into());
         }
+    }
 
-        if idx >= b.transactions.len() {
-            return Err(internal_error(
-                "Inconsistent state: tx index out of bound",
-            ));
+    // if gas_price is zero, it is considered as not set
+    request.unset_zero_gas_price();
+
+    let estimate_request = EstimateRequest {
+        has_sender: request.from.is_some(),
+        has_gas_limit: request.gas.is_some(),
+        has_gas_price: request.gas_price.is_some(),
+        has_nonce: request.nonce.is_some(),
+        has_storage_limit: false,
+        collect_access_list,
+    };
+
+    let signed_tx = request.sign_call(evm_chain_id)?;
+    Ok((signed_tx, estimate_request))
+}
+
+/// Returns the output of a virtual call, or the error of the execution in the
+/// format of geth.
+pub(crate) fn call_output(
+    execution_outcome: ExecutionOutcome,
+) -> RpcResult<Bytes> {
+    match execution_outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => bail!(invalid_input_rpc_err(
+            format! {"err: nonce is too old expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => bail!(invalid_input_rpc_err(
+            format! {"err: invalid recipient address {:?}", recipient}
+        )),
+        ExecutionOutcome::NotExecutedDrop(TxDropError::NotEnoughGasLimit {
+            expected,
+            got,
+        }) => bail!(invalid_input_rpc_err(
+            format! {"err: not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            bail!(invalid_input_rpc_err(format! {"err: {:?}", e}))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(VmError::Reverted),
+            executed,
+        ) => bail!(geth_call_execution_error(
+            format!(
+                "execution reverted: {}",
+                revert_reason_decode(&executed.output)
+            ),
+            format!("0x{}", executed.output.to_hex::<String>())
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(e),
+            executed,
+        ) => bail!(geth_call_execution_error(
+            format!("execution reverted: {}", e),
+            format!("0x{}", executed.output.to_hex::<String>())
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            bail!(geth_call_execution_error(
+                format! {"execution failed: {:?}", e},
+                "".into()
+            ))
         }
+        ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
+    }
+}
 
-        let tx = &b.transactions[idx];
-        let receipt = &b.receipts[idx];
+pub(crate) fn construct_rpc_receipt(
+    b: &PhantomBlock, idx: usize, prior_log_index: &mut usize,
+) -> RpcResult<Receipt> {
+    if b.transactions.len() != b.receipts.len() {
+        return Err(internal_error(
+            "Inconsistent state: transactions and receipts length mismatch",
+        ));
+    }
 
-        if receipt.logs.iter().any(|l| l.space != Space::Ethereum) {
-            return Err(internal_error(
-                "Inconsistent state: native tx in phantom block",
-            ));
+    if b.transactions.len() != b.errors.len() {
+        return Err(internal_error(
+            "Inconsistent state: transactions and errors length mismatch",
+        ));
+    }
+
+    if idx >= b.transactions.len() {
+        return Err(internal_error(
+            "Inconsistent state: tx index out of bound",
+        ));
+    }
+
+    let tx = &b.transactions[idx];
+    let receipt = &b.receipts[idx];
+
+    if receipt.logs.iter().any(|l| l.space != Space::Ethereum) {
+        return Err(internal_error(
+            "Inconsistent state: native tx in phantom block",
+        ));
+    }
+
+    let contract_address = match receipt.outcome_status {
+        TransactionStatus::Success => {
+            Transaction::deployed_contract_address(tx)
         }
+        _ => None,
+    };
+
+    let transaction_hash = tx.hash();
+    let transaction_index: U256 = idx.into();
+    let block_hash = b.pivot_header.hash();
+    let block_height: U256 = b.pivot_header.height().into();
+
+    let logs: Vec<_> = receipt
+        .logs
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(idx, log)| Log {
+            address: log.address,
+            topics: log.topics,
+            data: Bytes(log.data),
+            block_hash,
+            block_number: block_height,
+            transaction_hash,
+            transaction_index,
+            log_index: Some((*prior_log_index + idx).into()),
+            transaction_log_index: Some(idx.into()),
+            removed: false,
+        })
+        .collect();
+
+    *prior_log_index += logs.len();
 
-        let contract_address = match receipt.outcome_status {
-            TransactionStatus::Success => {
-                Transaction::deployed_contract_address(tx)
+    let gas_used = match idx {
+        0 => receipt.accumulated_gas_used,
+        idx => {
+            receipt.accumulated_gas_used
+                - b.receipts[idx - 1].accumulated_gas_used
+        }
+    };
+
+    let tx_exec_error_msg = if b.errors[idx].is_empty() {
+        None
+    } else {
+        Some(b.errors[idx].clone())
+    };
+
+    let effective_gas_price =
+        if let Some(base_price) = b.pivot_header.base_price() {
+            let base_price = base_price[tx.space()];
+            if *tx.gas_price() < base_price {
+                *tx.gas_price()
+            } else {
+                tx.effective_gas_price(&base_price)
             }
-            _ => None,
+        } else {
+            *tx.gas_price()
         };
 
-        let transaction_hash = tx.hash();
-        let transaction_index: U256 = idx.into();
-        let block_hash = b.pivot_header.hash();
-        let block_height: U256 = b.pivot_header.height().into();
-
-        let logs: Vec<_> = receipt
-            .logs
-            .iter()
-            .cloned()
-            .enumerate()
-            .map(|(idx, log)| Log {
-                address: log.address,
-                topics: log.topics,
-                data: Bytes(log.data),
-                block_hash,
-                block_number: block_height,
-                transaction_hash,
-                transaction_index,
-                log_index: Some((*prior_log_index + idx).into()),
-                transaction_log_index: Some(idx.into()),
-                removed: false,
-            })
-            .collect();
+    Ok(Receipt {
+        transaction_hash,
+        transaction_index,
+        block_hash,
+        from: tx.sender().address,
+        to: match tx.action() {
+            Action::Create => None,
+            Action::Call(addr) => Some(*addr),
+        },
+        block_number: block_height,
+        cumulative_gas_used: receipt.accumulated_gas_used,
+        gas_used,
+        contract_address,
+        logs,
+        logs_bloom: receipt.log_bloom,
+        status_code: receipt.outcome_status.in_space(Space::Ethereum).into(),
+        effective_gas_price,
+        tx_exec_error_msg,
+        transaction_type: receipt
+            .burnt_gas_fee
+            .is_some()
+            .then_some(U64::from(tx.type_id())),
+        burnt_gas_fee: receipt.burnt_gas_fee,
+    })
+}
 
-        *prior_log_index += logs.len();
+/// Returns the receipt of transaction `tx_hash` in `phantom_block`.
+pub(crate) fn phantom_block_receipt(
+    phantom_block: &PhantomBlock, tx_hash: H256,
+) -> RpcResult<Option<Receipt>> {
+    let mut prior_log_index = 0;
 
-        let gas_used = match idx {
-            0 => receipt.accumulated_gas_used,
-            idx => {
-                receipt.accumulated_gas_used
-                    - b.receipts[idx - 1].accumulated_gas_used
+    for (idx, tx) in phantom_block.transactions.iter().enumerate() {
+        if tx.hash() == tx_hash {
+            let receipt = construct_rpc_receipt(
+                phantom_block,
+                idx,
+                &mut prior_log_index,
+            )?;
+            // A skipped transaction is not available to clients if accessed
+            // by its hash.
+            if receipt.status_code
+                == TransactionStatus::Skipped.in_space(Space::Ethereum).into()
+            {
+                return Ok(None);
             }
-        };
 
-        let tx_exec_error_msg = if b.errors[idx].is_empty() {
-            None
-        } else {
-            Some(b.errors[idx].clone())
-        };
+            return Ok(Some(receipt));
+        }
 
-        let effective_gas_price =
-            if let Some(base_price) = b.pivot_header.base_price() {
-                let base_price = base_price[tx.space()];
-                if *tx.gas_price() < base_price {
-                    *tx.gas_price()
-                } else {
-                    tx.effective_gas_price(&base_price)
-                }
-            } else {
-                *tx.gas_price()
+        // if the if-branch was not entered, we do the bookeeping here
+        prior_log_index += phantom_block.receipts[idx].logs.len();
+    }
+
+    Ok(None)
+}
+
+// The methods served asynchronously on light nodes, which return futures in
+// `Eth`.
+impl EthHandler {
+    fn balance(
+        &self, address: H160, num: Option<BlockNumber>,
+    ) -> RpcResult<U256> {
+        let epoch_num = num.unwrap_or_default().try_into()?;
+
+        info!(
+            "RPC Request: eth_getBalance address={:?} epoch_num={:?}",
+            address, epoch_num
+        );
+
+        let state_db = self
+            .consensus
+            .get_eth_state_db_by_epoch_number(epoch_num, "num")?;
+        let acc = state_db
+            .get_account(&address.with_evm_space())
+            .map_err(|err| CfxRpcError::from(err))?;
+
+        Ok(acc.map_or(U256::zero(), |acc| acc.balance).into())
+    }
+
+    fn call(
+        &self, request: CallRequest, block_number_or_hash: Option<BlockNumber>,
+    ) -> RpcResult<Bytes> {
+        info!(
+            "RPC Request: eth_call request={:?}, block_num={:?}",
+            request, block_number_or_hash
+        );
+
+        let (execution_outcome, _estimation) =
+            self.exec_transaction(request, block_number_or_hash, false)?;
+        call_output(execution_outcome)
+    }
+
+    fn transaction_receipt(&self, tx_hash: H256) -> RpcResult<Option<Receipt>> {
+        info!(
+            "RPC Request: eth_getTransactionReceipt tx_hash={:?}",
+            tx_hash
+        );
+
+        let tx_index =
+            match self.consensus.get_data_manager().transaction_index_by_hash(
+                &tx_hash, false, /* update_cache */
+            ) {
+                None => return Ok(None),
+                Some(tx_index) => tx_index,
             };
 
-        Ok(Receipt {
-            transaction_hash,
-            transaction_index,
-            block_hash,
-            from: tx.sender().address,
-            to: match tx.action() {
-                Action::Create => None,
-                Action::Call(addr) => Some(*addr),
+        let epoch_num =
+            match self.consensus.get_block_epoch_number(&tx_index.block_hash) {
+                None => return Ok(None),
+                Some(n) => n,
+            };
+
+        if epoch_num > self.consensus_graph().best_executed_state_epoch_number()
+        {
+            // The receipt is only visible to optimistic execution.
+            return Ok(None);
+        }
+
+        let maybe_block = self
+            .consensus_graph()
+            .get_phantom_block_by_number(
+                EpochNumber::Number(epoch_num),
+                None,
+                false, /* include_traces */
+            )
+            .map_err(RpcError::invalid_params)?;
+
+        let phantom_block = match maybe_block {
+            None => return Ok(None),
+            Some(b) => b,
+        };
+
+        phantom_block_receipt(&phantom_block, tx_hash)
+    }
+
+    fn storage_at(
+        &self, address: H160, position: U256, block_num: Option<BlockNumber>,
+    ) -> RpcResult<H256> {
+        let epoch_num = block_num.unwrap_or_default().try_into()?;
+
+        info!(
+            "RPC Request: eth_getStorageAt address={:?}, position={:?}, block_num={:?})",
+            address, position, epoch_num
+        );
+
+        let state_db = self
+            .consensus
+            .get_eth_state_db_by_epoch_number(epoch_num, "epoch_number")?;
+
+        let position: H256 = H256::from_uint(&position);
+
+        let key = StorageKey::new_storage_key(&address, position.as_ref())
+            .with_evm_space();
+
+        Ok(
+            match state_db
+                .get::<StorageValue>(key)
+                .map_err(|err| CfxRpcError::from(err))?
+            {
+                Some(entry) => H256::from_uint(&entry.value).into(),
+                None => H256::zero(),
             },
-            block_number: block_height,
-            cumulative_gas_used: receipt.accumulated_gas_used,
-            gas_used,
-            contract_address,
-            logs,
-            logs_bloom: receipt.log_bloom,
-            status_code: receipt
-                .outcome_status
-                .in_space(Space::Ethereum)
-                .into(),
-            effective_gas_price,
-            tx_exec_error_msg,
-            transaction_type: receipt
-                .burnt_gas_fee
-                .is_some()
-                .then_some(U64::from(tx.type_id())),
-            burnt_gas_fee: receipt.burnt_gas_fee,
-        })
+        )
     }
 
-    fn get_tx_from_txpool(&self, hash: H256) -> Option<Transaction> {
-        let tx = self.tx_pool.get_transaction(&hash)?;
+    fn logs(&self, filter: EthRpcLogFilter) -> RpcResult<Vec<Log>> {
+        info!("RPC Request: eth_getLogs({:?})", filter);
 
-        if tx.space() == Space::Ethereum {
-            Some(Transaction::from_signed(
-                &tx,
-                (None, None, None),
-                (None, None),
-            ))
-        } else {
-            None
+        let filter: LogFilter =
+            filter.into_primitive(self.consensus.clone())?;
+
+        let logs = self
+            .consensus_graph()
+            .logs(filter)
+            .map_err(|err| CfxRpcError::from(err))?;
+
+        // If the results does not fit into `max_limit`, report an error
+        if let Some(max_limit) = self.config.get_logs_filter_max_limit {
+            if logs.len() > max_limit {
+                bail!(invalid_params("filter", format!("This query results in too many logs, max limitation is {}, please use a smaller block range", max_limit)));
+            }
         }
+
+        Ok(logs
+            .iter()
+            .cloned()
+            .map(|l| Log::try_from_localized(l, self.consensus.clone(), false))
+            .collect::<Result<_, _>>()?)
     }
 }
 
 impl Eth for EthHandler {
+    // The inherent methods above take precedence over the trait methods.
+    delegate! {
+        to self {
+            fn balance(&self, address: H160, num: Option<BlockNumber>) -> BoxFuture<U256>;
+            fn storage_at(&self, address: H160, position: U256, block_num: Option<BlockNumber>) -> BoxFuture<H256>;
+            fn call(&self, request: CallRequest, block_number_or_hash: Option<BlockNumber>) -> BoxFuture<Bytes>;
+            fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<Option<Receipt>>;
+            fn logs(&self, filter: EthRpcLogFilter) -> BoxFuture<Vec<Log>>;
+        }
+    }
+
     fn client_version(&self) -> RpcResult<String> {
         info!("RPC Request: web3_clientVersion");
         Ok(parity_version::version(crate_version!()))
@@ -451,54 +749,83 @@ impl Eth for EthHandler {
         }
     }
 
-    fn balance(
-        &self, address: H160, num: Option<BlockNumber>,
-    ) -> RpcResult<U256> {
-        let epoch_num = num.unwrap_or_default().try_into()?;
+    fn proof(
+        &self, address: H160, storage_keys: Vec<H256>,
+        block_num: Option<BlockNumber>,
+    ) -> RpcResult<AccountProof> {
+        let epoch_num: EpochNumber =
+            block_num.unwrap_or_default().try_into()?;
 
         info!(
-            "RPC Request: eth_getBalance address={:?} epoch_num={:?}",
-            address, epoch_num
+            "RPC Request: eth_getProof address={:?}, storage_keys={:?}, block_num={:?}",
+            address, storage_keys, epoch_num
         );
 
-        let state_db = self
-            .consensus
-            .get_eth_state_db_by_epoch_number(epoch_num, "num")?;
-        let acc = state_db
-            .get_account(&address.with_evm_space())
-            .map_err(|err| CfxRpcError::from(err))?;
-
-        Ok(acc.map_or(U256::zero(), |acc| acc.balance).into())
-    }
-
-    fn storage_at(
-        &self, address: H160, position: U256, block_num: Option<BlockNumber>,
-    ) -> RpcResult<H256> {
-        let epoch_num = block_num.unwrap_or_default().try_into()?;
-
-        info!(
-            "RPC Request: eth_getStorageAt address={:?}, position={:?}, block_num={:?})",
-            address, position, epoch_num
-        );
+        let state = self.consensus.get_storage_state_by_epoch_number(
+            epoch_num.clone(),
+            "block_num",
+        )?;
+        let height = self
+            .consensus_graph()
+            .get_height_from_epoch_number(epoch_num)
+            .map_err(RpcError::invalid_params)?;
 
-        let state_db = self
-            .consensus
-            .get_eth_state_db_by_epoch_number(epoch_num, "epoch_number")?;
+        let state_root = state
+            .get_state_root()
+            .map_err(|err| CfxRpcError::from(err))?
+            .state_root;
+        let prev_snapshot_state_root = self.prev_snapshot_state_root(height)?;
 
-        let position: H256 = H256::from_uint(&position);
+        let (account_value, account_proof) = state
+            .get_original_raw_with_proof(
+                StorageKey::new_account_key(&address).with_evm_space(),
+            )
+            .map_err(|err| CfxRpcError::from(err))?;
+        let account = match &account_value {
+            Some(raw) => Some(
+                Account::new_from_rlp(address, &Rlp::new(raw))
+                    .map_err(internal_error)?,
+            ),
+            None => None,
+        };
 
-        let key = StorageKey::new_storage_key(&address, position.as_ref())
-            .with_evm_space();
+        let mut storage_proof = Vec::with_capacity(storage_keys.len());
+        for key in storage_keys {
+            let (value, proof) = state
+                .get_original_raw_with_proof(
+                    StorageKey::new_storage_key(&address, key.as_ref())
+                        .with_evm_space(),
+                )
+                .map_err(|err| CfxRpcError::from(err))?;
+            let value = match value {
+                Some(raw) => {
+                    rlp::decode::<StorageValue>(&raw)
+                        .map_err(|err| CfxRpcError::from(err))?
+                        .value
+                }
+                None => U256::zero(),
+            };
+            storage_proof.push(StorageProof {
+                key,
+                value,
+                proof: Bytes::new(rlp::encode(&proof)),
+            });
+        }
 
-        Ok(
-            match state_db
-                .get::<StorageValue>(key)
-                .map_err(|err| CfxRpcError::from(err))?
-            {
-                Some(entry) => H256::from_uint(&entry.value).into(),
-                None => H256::zero(),
-            },
-        )
+        Ok(AccountProof {
+            address,
+            balance: account.as_ref().map_or(U256::zero(), |acc| acc.balance),
+            nonce: account.as_ref().map_or(U256::zero(), |acc| acc.nonce),
+            code_hash: account
+                .as_ref()
+                .map_or(KECCAK_EMPTY, |acc| acc.code_hash),
+            account_value: account_value.map(|raw| Bytes::new(raw.into_vec())),
+            account_proof: Bytes::new(rlp::encode(&account_proof)),
+            storage_proof,
+            epoch_number: height.into(),
+            state_root,
+            prev_snapshot_state_root,
+        })
     }
 
     fn block_by_hash(
@@ -730,63 +1057,6 @@ impl Eth for EthHandler {
         self.send_raw_transaction(raw)
     }
 
-    fn call(
-        &self, request: CallRequest, block_number_or_hash: Option<BlockNumber>,
-    ) -> RpcResult<Bytes> {
-        info!(
-            "RPC Request: eth_call request={:?}, block_num={:?}",
-            request, block_number_or_hash
-        );
-
-        let (execution_outcome, _estimation) =
-            self.exec_transaction(request, block_number_or_hash)?;
-        match execution_outcome {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
-                expected,
-                got,
-            )) => bail!(invalid_input_rpc_err(
-                format! {"err: nonce is too old expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::InvalidRecipientAddress(recipient),
-            ) => bail!(invalid_input_rpc_err(
-                format! {"err: invalid recipient address {:?}", recipient}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::NotEnoughGasLimit { expected, got },
-            ) => bail!(invalid_input_rpc_err(
-                format! {"err: not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                bail!(invalid_input_rpc_err(format! {"err: {:?}", e}))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(VmError::Reverted),
-                executed,
-            ) => bail!(geth_call_execution_error(
-                format!(
-                    "execution reverted: {}",
-                    revert_reason_decode(&executed.output)
-                ),
-                format!("0x{}", executed.output.to_hex::<String>())
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(e),
-                executed,
-            ) => bail!(geth_call_execution_error(
-                format!("execution reverted: {}", e),
-                format!("0x{}", executed.output.to_hex::<String>())
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                bail!(geth_call_execution_error(
-                    format! {"execution failed: {:?}", e},
-                    "".into()
-                ))
-            }
-            ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
-        }
-    }
-
     fn estimate_gas(
         &self, request: CallRequest, block_number_or_hash: Option<BlockNumber>,
     ) -> RpcResult<U256> {
@@ -795,7 +1065,7 @@ impl Eth for EthHandler {
             request, block_number_or_hash
         );
         let (execution_outcome, estimation) =
-            self.exec_transaction(request, block_number_or_hash)?;
+            self.exec_transaction(request, block_number_or_hash, false)?;
         match execution_outcome {
             ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
                 expected,
@@ -845,6 +1115,162 @@ impl Eth for EthHandler {
         Ok(estimation.estimated_gas_limit)
     }
 
+    fn create_access_list(
+        &self, mut request: CallRequest,
+        block_number_or_hash: Option<BlockNumber>,
+    ) -> RpcResult<AccessListWithGasUsed> {
+        info!(
+            "RPC Request: eth_createAccessList request={:?}, block_num={:?}",
+            request, block_number_or_hash
+        );
+
+        // pin the block, so that all the rounds run against the same state
+        let block_number_or_hash = match block_number_or_hash
+            .unwrap_or_default()
+        {
+            block @ (BlockNumber::Hash { .. } | BlockNumber::Num(_)) => block,
+            block => BlockNumber::Num(
+                self.consensus_graph()
+                    .get_height_from_epoch_number(block.try_into()?)
+                    .map_err(RpcError::invalid_params)?,
+            ),
+        };
+
+        let initial = request.access_list.take().unwrap_or_default();
+        access_list_fixpoint(initial, |access_list| {
+            request.access_list = Some(access_list);
+            let (execution_outcome, _) = self.exec_transaction(
+                request.clone(),
+                Some(block_number_or_hash.clone()),
+                true,
+            )?;
+            let (executed, error) = match execution_outcome {
+                ExecutionOutcome::NotExecutedDrop(e) => {
+                    bail!(invalid_input_rpc_err(format! {"err: {:?}", e}))
+                }
+                ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+                    bail!(invalid_input_rpc_err(format! {"err: {:?}", e}))
+                }
+                ExecutionOutcome::ExecutionErrorBumpNonce(
+                    ExecutionError::VmError(VmError::Reverted),
+                    executed,
+                ) => {
+                    let error = format!(
+                        "execution reverted: {}",
+                        revert_reason_decode(&executed.output)
+                    );
+                    (executed, Some(error))
+                }
+                ExecutionOutcome::ExecutionErrorBumpNonce(
+                    ExecutionError::VmError(e),
+                    executed,
+                ) => (executed, Some(format!("execution reverted: {}", e))),
+                ExecutionOutcome::ExecutionErrorBumpNonce(e, executed) => {
+                    (executed, Some(format!("execution failed: {:?}", e)))
+                }
+                ExecutionOutcome::Finished(executed) => (executed, None),
+            };
+
+            let recorded = executed
+                .ext_result
+                .get::<AccessListKey>()
+                .cloned()
+                .unwrap_or_else(|| request.access_list.clone().unwrap());
+            Ok(AccessListRound {
+                recorded,
+                gas_used: executed.gas_used,
+                error,
+            })
+        })
+    }
+
+    fn simulate_v1(
+        &self, payload: SimulatePayload,
+        block_number_or_hash: Option<BlockNumber>,
+    ) -> RpcResult<Vec<SimulatedBlock>> {
+        info!(
+            "RPC Request: eth_simulateV1 payload={:?}, block_num={:?}",
+            payload, block_number_or_hash
+        );
+
+        if payload.trace_transfers {
+            bail!(invalid_params("traceTransfers", "not supported"));
+        }
+        // the counts are checked before the calls are converted and signed
+        check_simulate_counts(
+            payload
+                .block_state_calls
+                .iter()
+                .map(|block| block.calls.len()),
+        )
+        .map_err(|e| invalid_params("blockStateCalls", e))?;
+
+        let epoch = self.call_epoch(block_number_or_hash)?;
+        let chain_id = self.consensus.best_chain_id().in_evm_space();
+
+        let mut blocks = Vec::with_capacity(payload.block_state_calls.len());
+        for block in payload.block_state_calls {
+            let overrides = block.block_overrides.unwrap_or_default();
+            // the block number of the eSpace is the epoch height
+            let block_overrides = BlockOverrides {
+                number: None,
+                epoch_height: overrides
+                    .number
+                    .map(|number| override_to_u64(number, "number"))
+                    .transpose()?,
+                timestamp: overrides
+                    .time
+                    .map(|time| override_to_u64(time, "time"))
+                    .transpose()?,
+                gas_limit: overrides
+                    .gas_limit
+                    .map(|gas_limit| {
+                        override_to_u64(gas_limit, "gasLimit").map(U256::from)
+                    })
+                    .transpose()?,
+                base_gas_price: overrides.base_fee.map(from_alloy_u256),
+                author: overrides.coinbase.map(from_alloy_address),
+            };
+            let state_override = block
+                .state_overrides
+                .map(convert_state_override)
+                .transpose()?;
+
+            let mut calls = Vec::with_capacity(block.calls.len());
+            for mut request in block.calls {
+                // if gas_price is zero, it is considered as not set
+                request.unset_zero_gas_price();
+                let has_nonce = request.nonce.is_some();
+                let tx = request
+                    .sign_call(chain_id)
+                    .map_err(|e| invalid_params("calls", e))?;
+                calls.push(SimulateCall { tx, has_nonce });
+            }
+
+            blocks.push(SimulateBlock {
+                block_overrides,
+                state_override,
+                calls,
+            });
+        }
+
+        check_simulate_limits(&blocks)
+            .map_err(|e| invalid_params("blockStateCalls", e))?;
+
+        let simulated_blocks = self.consensus_graph().simulate_virtual(
+            blocks,
+            epoch,
+            Space::Ethereum,
+            payload.validation,
+        )?;
+        simulated_blocks
+            .into_iter()
+            .map(|block| {
+                SimulatedBlock::try_from(block).map_err(invalid_input_rpc_err)
+            })
+            .collect()
+    }
+
     fn fee_history(
         &self, block_count: HexU64, newest_block: BlockNumber,
         reward_percentiles: Vec<f64>,
@@ -1023,75 +1449,6 @@ impl Eth for EthHandler {
         Ok(block_tx_by_index(phantom_block, idx.value()))
     }
 
-    fn transaction_receipt(&self, tx_hash: H256) -> RpcResult<Option<Receipt>> {
-        info!(
-            "RPC Request: eth_getTransactionReceipt tx_hash={:?}",
-            tx_hash
-        );
-
-        let tx_index =
-            match self.consensus.get_data_manager().transaction_index_by_hash(
-                &tx_hash, false, /* update_cache */
-            ) {
-                None => return Ok(None),
-                Some(tx_index) => tx_index,
-            };
-
-        let epoch_num =
-            match self.consensus.get_block_epoch_number(&tx_index.block_hash) {
-                None => return Ok(None),
-                Some(n) => n,
-            };
-
-        if epoch_num > self.consensus_graph().best_executed_state_epoch_number()
-        {
-            // The receipt is only visible to optimistic execution.
-            return Ok(None);
-        }
-
-        let maybe_block = self
-            .consensus_graph()
-            .get_phantom_block_by_number(
-                EpochNumber::Number(epoch_num),
-                None,
-                false, /* include_traces */
-            )
-            .map_err(RpcError::invalid_params)?;
-
-        let phantom_block = match maybe_block {
-            None => return Ok(None),
-            Some(b) => b,
-        };
-
-        let mut prior_log_index = 0;
-
-        for (idx, tx) in phantom_block.transactions.iter().enumerate() {
-            if tx.hash() == tx_hash {
-                let receipt = self.construct_rpc_receipt(
-                    &phantom_block,
-                    idx,
-                    &mut prior_log_index,
-                )?;
-                // A skipped transaction is not available to clients if accessed
-                // by its hash.
-                if receipt.status_code
-                    == TransactionStatus::Skipped
-                        .in_space(Space::Ethereum)
-                        .into()
-                {
-                    return Ok(None);
-                }
-
-                return Ok(Some(receipt));
-            }
-
-            // if the if-branch was not entered, we do the bookeeping here
-            prior_log_index += phantom_block.receipts[idx].logs.len();
-        }
-
-        Ok(None)
-    }
-
     fn uncle_by_block_hash_and_index(
         &self, hash: H256, idx: Index,
     ) -> RpcResult<Option<RpcBlock>> {
@@ -1111,31 +1468,6 @@ impl Eth for EthHandler {
         Ok(None)
     }
 
-    fn logs(&self, filter: EthRpcLogFilter) -> RpcResult<Vec<Log>> {
-        info!("RPC Request: eth_getLogs({:?})", filter);
-
-        let filter: LogFilter =
-            filter.into_primitive(self.consensus.clone())?;
-
-        let logs = self
-            .consensus_graph()
-            .logs(filter)
-            .map_err(|err| CfxRpcError::from(err))?;
-
-        // If the results does not fit into `max_limit`, report an error
-        if let Some(max_limit) = self.config.get_logs_filter_max_limit {
-            if logs.len() > max_limit {
-                bail!(invalid_params("filter", format!("This query results in too many logs, max limitation is {}, please use a smaller block range", max_limit)));
-            }
-        }
-
-        Ok(logs
-            .iter()
-            .cloned()
-            .map(|l| Log::try_from_localized(l, self.consensus.clone(), false))
-            .collect::<Result<_, _>>()?)
-    }
-
     fn submit_hashrate(&self, _: U256, _: H256) -> RpcResult<bool> {
         info!("RPC Request: eth_submitHashrate");
         // We do not care mining
@@ -1183,7 +1515,7 @@ impl Eth for EthHandler {
         let mut prior_log_index = 0;
 
         for idx in 0..b.receipts.len() {
-            block_receipts.push(self.construct_rpc_receipt(
+            block_receipts.push(construct_rpc_receipt(
                 &b,
                 idx,
                 &mut prior_log_index,
@@ -1222,4 +1554,181 @@ impl Eth for EthHandler {
             pending_count: pending_count.into(),
         })
     }
+
+    fn transactions_by_address(
+        &self, address: H160, cursor: Option<U64>, limit: Option<U64>,
+    ) -> RpcResult<AddressTransactions> {
+        info!(
+            "RPC Request: eth_getTransactionsByAddress(address={:?}, cursor={:?}, limit={:?})",
+            address, cursor, limit
+        );
+
+        let data_man = self.consensus.get_data_manager();
+        if !data_man.address_tx_index_enabled() {
+            bail!(internal_error_msg(
+                "the address transaction index is not enabled"
+            ));
+        }
+
+        let limit = limit.map_or(ADDRESS_TX_PAGE_SIZE, |limit| limit.as_u64());
+        if limit == 0 {
+            bail!(invalid_params("limit", "limit should be positive"));
+        }
+        if limit > MAX_ADDRESS_TX_PAGE_SIZE {
+            bail!(invalid_params(
+                "limit",
+                format!("limit should be at most {}", MAX_ADDRESS_TX_PAGE_SIZE)
+            ));
+        }
+
+        let (txs, next_cursor) = data_man.transactions_by_address(
+            &address.with_evm_space(),
+            cursor.map(|cursor| cursor.as_u64()),
+            limit,
+        );
+
+        let mut transactions = vec![];
+        for (entry, tx_index) in txs {
+            // The block of an eSpace transaction is the pivot block of its
+            // epoch.
+            let block_hash = match self.consensus.get_hash_from_epoch_number(
+                EpochNumber::Number(entry.epoch_number),
+            ) {
+                Ok(hash) => hash,
+                Err(_) => continue,
+            };
+            transactions.push(AddressTransaction {
+                transaction_hash: entry.tx_hash,
+                block_hash,
+                block_number: entry.epoch_number.into(),
+                transaction_index: tx_index
+                    .rpc_index
+                    .unwrap_or(tx_index.real_index)
+                    .into(),
+            });
+        }
+
+        Ok(AddressTransactions {
+            transactions,
+            next_cursor: next_cursor.map(Into::into),
+        })
+    }
+}
+
+/// The most executions of `eth_createAccessList`. The recorded list only
+/// grows and usually settles after two rounds, unless the accessed slots keep
+/// depending on the gas left.
+const MAX_ACCESS_LIST_ROUNDS: usize = 10;
+
+/// The outcome of executing a transaction with a given access list.
+struct AccessListRound {
+    /// The accounts and slots accessed by the execution.
+    recorded: AccessList,
+    gas_used: U256,
+    error: Option<String>,
+}
+
+/// Executes the transaction with the recorded access list until the list
+/// doesn't change, as the accessed slots may depend on the gas left, and
+/// reports the gas used with the final list.
+fn access_list_fixpoint(
+    initial: AccessList,
+    mut execute: impl FnMut(AccessList) -> RpcResult<AccessListRound>,
+) -> RpcResult<AccessListWithGasUsed> {
+    let mut access_list = initial;
+    for _ in 0..MAX_ACCESS_LIST_ROUNDS {
+        let round = execute(access_list.clone())?;
+        if round.recorded == access_list {
+            return Ok(AccessListWithGasUsed {
+                access_list,
+                gas_used: round.gas_used,
+                error: round.error,
+            });
+        }
+        access_list = round.recorded;
+    }
+    bail!(internal_error_msg(&format!(
+        "access list did not settle after {} executions",
+        MAX_ACCESS_LIST_ROUNDS
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        access_list_fixpoint, AccessListRound, MAX_ACCESS_LIST_ROUNDS,
+    };
+    use cfx_types::{Address, H256, U256};
+    use primitives::{AccessList, AccessListItem};
+
+    fn item(address: u64, keys: &[u64]) -> AccessListItem {
+        AccessListItem {
+            address: Address::from_low_u64_be(address),
+            storage_keys: keys
+                .iter()
+                .map(|k| H256::from_low_u64_be(*k))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_access_list_fixpoint() {
+        // the second execution reads one more slot with the first list
+        let lists: Vec<AccessList> = vec![
+            vec![item(1, &[1])],
+            vec![item(1, &[1, 2])],
+            vec![item(1, &[1, 2])],
+        ];
+        let mut rounds = 0;
+        let result = access_list_fixpoint(vec![], |access_list| {
+            let expected_input = if rounds == 0 {
+                vec![]
+            } else {
+                lists[rounds - 1].clone()
+            };
+            assert_eq!(access_list, expected_input);
+            rounds += 1;
+            Ok(AccessListRound {
+                recorded: lists[rounds - 1].clone(),
+                gas_used: U256::from(21_000 + rounds),
+                error: None,
+            })
+        })
+        .unwrap();
+
+        assert_eq!(rounds, 3);
+        assert_eq!(result.access_list, lists[2]);
+        // the gas used by the execution with the final list
+        assert_eq!(result.gas_used, U256::from(21_003));
+    }
+
+    #[test]
+    fn test_access_list_fixpoint_keeps_given_list() {
+        let given = vec![item(1, &[1])];
+        let result = access_list_fixpoint(given.clone(), |access_list| {
+            Ok(AccessListRound {
+                recorded: access_list,
+                gas_used: U256::from(30_000),
+                error: Some("execution reverted: ".into()),
+            })
+        })
+        .unwrap();
+        assert_eq!(result.access_list, given);
+        assert_eq!(result.error.as_deref(), Some("execution reverted: "));
+    }
+
+    #[test]
+    fn test_access_list_fixpoint_rounds_are_capped() {
+        let mut rounds = 0;
+        let result = access_list_fixpoint(vec![], |_| {
+            rounds += 1;
+            Ok(AccessListRound {
+                recorded: vec![item(rounds, &[])],
+                gas_used: U256::zero(),
+                error: None,
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(rounds as usize, MAX_ACCESS_LIST_ROUNDS);
+    }
 }
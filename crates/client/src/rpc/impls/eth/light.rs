@@ -0,0 +1,356 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::{
+    errors::{self, invalid_params},
+    impls::eth::eth_handler::{
+        call_output, phantom_block_receipt, prepare_call_request,
+    },
+    traits::eth_space::eth::Eth,
+    types::{
+        eth::{
+            AccessListWithGasUsed, AccountPendingTransactions, AccountProof,
+            AddressTransactions, Block as RpcBlock, BlockNumber, CallRequest,
+            EthRpcLogFilter, Log, Receipt, SimulatePayload, SimulatedBlock,
+            SyncStatus, Transaction,
+        },
+        Bytes, FeeHistory, Index, U64 as HexU64,
+    },
+};
+use cfx_types::{
+    AddressSpaceUtil, BigEndianHash, Space, H160, H256, U256, U64,
+};
+use cfxcore::{
+    light_protocol::{Error as LightError, ErrorKind},
+    rpc_errors::{invalid_params_check, Result as CfxRpcResult},
+    LightQueryService, SharedConsensusGraph,
+};
+use clap::crate_version;
+use futures::future::{FutureExt, TryFutureExt};
+use jsonrpc_core::{BoxFuture, Error as RpcError, Result as JsonRpcResult};
+use primitives::EpochNumber;
+use std::{convert::TryInto, sync::Arc};
+
+// macro for reducing boilerplate for unsupported methods
+macro_rules! not_supported {
+    () => {};
+    ( fn $fn:ident ( &self $(, $name:ident : $type:ty)* ) $( -> $ret:ty )? ; $($tail:tt)* ) => {
+        #[allow(unused_variables)]
+        fn $fn ( &self $(, $name : $type)* ) $( -> $ret )? {
+            Err(errors::unimplemented(Some("Tracking issue: https://github.com/Conflux-Chain/conflux-rust/issues/1461".to_string())))
+        }
+
+        not_supported!($($tail)*);
+    };
+}
+
+/// The eSpace RPCs of light nodes. The states, receipts and logs are retrieved
+/// from the peers on demand and verified against the local headers.
+pub struct EthHandler {
+    // consensus graph
+    consensus: SharedConsensusGraph,
+
+    // helper API for retrieving verified information from peers
+    light: Arc<LightQueryService>,
+}
+
+impl EthHandler {
+    pub fn new(
+        consensus: SharedConsensusGraph, light: Arc<LightQueryService>,
+    ) -> Self {
+        EthHandler { consensus, light }
+    }
+
+    /// Resolves the epoch of `block_number_or_hash`. As in the full nodes, a
+    /// block hash must be the hash of a pivot block.
+    fn epoch_number(
+        &self, block_number_or_hash: Option<BlockNumber>,
+    ) -> CfxRpcResult<EpochNumber> {
+        let epoch = match block_number_or_hash.unwrap_or_default() {
+            BlockNumber::Hash { hash, .. } => {
+                match self.consensus.get_block_epoch_number(&hash) {
+                    Some(e) => {
+                        // do not expose non-pivot blocks in eth RPC
+                        let pivot = self
+                            .consensus
+                            .get_block_hashes_by_epoch(EpochNumber::Number(e))?
+                            .last()
+                            .cloned();
+
+                        if Some(hash) != pivot {
+                            bail!("Block {:?} not found", hash);
+                        }
+
+                        EpochNumber::Number(e)
+                    }
+                    None => bail!("Block {:?} not found", hash),
+                }
+            }
+            epoch => epoch.try_into()?,
+        };
+        Ok(epoch)
+    }
+}
+
+impl Eth for EthHandler {
+    // TODO: add support for these
+    not_supported! {
+        fn syncing(&self) -> JsonRpcResult<SyncStatus>;
+        fn gas_price(&self) -> JsonRpcResult<U256>;
+        fn max_priority_fee_per_gas(&self) -> JsonRpcResult<U256>;
+        fn fee_history(&self, block_count: HexU64, newest_block: BlockNumber, reward_percentiles: Vec<f64>) -> JsonRpcResult<FeeHistory>;
+        fn proof(&self, address: H160, storage_keys: Vec<H256>, block: Option<BlockNumber>) -> JsonRpcResult<AccountProof>;
+        fn block_by_hash(&self, block_hash: H256, hydrated_transactions: bool) -> JsonRpcResult<Option<RpcBlock>>;
+        fn block_by_number(&self, block: BlockNumber, hydrated_transactions: bool) -> JsonRpcResult<Option<RpcBlock>>;
+        fn transaction_count(&self, address: H160, block: Option<BlockNumber>) -> JsonRpcResult<U256>;
+        fn block_transaction_count_by_hash(&self, block_hash: H256) -> JsonRpcResult<Option<U256>>;
+        fn block_transaction_count_by_number(&self, block: BlockNumber) -> JsonRpcResult<Option<U256>>;
+        fn block_uncles_count_by_hash(&self, block_hash: H256) -> JsonRpcResult<Option<U256>>;
+        fn block_uncles_count_by_number(&self, block: BlockNumber) -> JsonRpcResult<Option<U256>>;
+        fn code_at(&self, address: H160, block: Option<BlockNumber>) -> JsonRpcResult<Bytes>;
+        fn send_raw_transaction(&self, transaction: Bytes) -> JsonRpcResult<H256>;
+        fn submit_transaction(&self, transaction: Bytes) -> JsonRpcResult<H256>;
+        fn estimate_gas(&self, transaction: CallRequest, block: Option<BlockNumber>) -> JsonRpcResult<U256>;
+        fn create_access_list(&self, transaction: CallRequest, block: Option<BlockNumber>) -> JsonRpcResult<AccessListWithGasUsed>;
+        fn simulate_v1(&self, payload: SimulatePayload, block: Option<BlockNumber>) -> JsonRpcResult<Vec<SimulatedBlock>>;
+        fn transaction_by_hash(&self, transaction_hash: H256) -> JsonRpcResult<Option<Transaction>>;
+        fn transaction_by_block_hash_and_index(&self, block_hash: H256, transaction_index: Index) -> JsonRpcResult<Option<Transaction>>;
+        fn transaction_by_block_number_and_index(&self, block: BlockNumber, transaction_index: Index) -> JsonRpcResult<Option<Transaction>>;
+        fn block_receipts(&self, block: Option<BlockNumber>) -> JsonRpcResult<Vec<Receipt>>;
+        fn account_pending_transactions(&self, address: H160, maybe_start_nonce: Option<U256>, maybe_limit: Option<U64>) -> JsonRpcResult<AccountPendingTransactions>;
+        fn transactions_by_address(&self, address: H160, cursor: Option<U64>, limit: Option<U64>) -> JsonRpcResult<AddressTransactions>;
+    }
+
+    fn client_version(&self) -> JsonRpcResult<String> {
+        info!("RPC Request: web3_clientVersion");
+        Ok(parity_version::version(crate_version!()))
+    }
+
+    fn net_version(&self) -> JsonRpcResult<String> {
+        info!("RPC Request: net_version");
+        Ok(format!("{}", self.consensus.best_chain_id().in_evm_space()))
+    }
+
+    fn protocol_version(&self) -> JsonRpcResult<String> {
+        info!("RPC Request: eth_protocolVersion");
+        // 65 is a common ETH version now
+        Ok(format!("{}", 65))
+    }
+
+    fn hashrate(&self) -> JsonRpcResult<U256> {
+        info!("RPC Request: eth_hashrate");
+        // We do not mine
+        Ok(U256::zero())
+    }
+
+    fn author(&self) -> JsonRpcResult<H160> {
+        info!("RPC Request: eth_coinbase");
+        // We do not care this, just return zero address
+        Ok(H160::zero())
+    }
+
+    fn is_mining(&self) -> JsonRpcResult<bool> {
+        info!("RPC Request: eth_mining");
+        // We do not mine from ETH perspective
+        Ok(false)
+    }
+
+    fn chain_id(&self) -> JsonRpcResult<Option<U64>> {
+        info!("RPC Request: eth_chainId");
+        Ok(Some(self.consensus.best_chain_id().in_evm_space().into()))
+    }
+
+    fn accounts(&self) -> JsonRpcResult<Vec<H160>> {
+        info!("RPC Request: eth_accounts");
+        // Conflux eSpace does not manage accounts
+        Ok(vec![])
+    }
+
+    fn block_number(&self) -> JsonRpcResult<U256> {
+        info!("RPC Request: eth_blockNumber()");
+        match self.light.get_latest_verifiable_epoch_number() {
+            Ok(height) => Ok(height.into()),
+            Err(e) => Err(RpcError::invalid_params(e.to_string())),
+        }
+    }
+
+    fn balance(
+        &self, address: H160, num: Option<BlockNumber>,
+    ) -> BoxFuture<U256> {
+        info!(
+            "RPC Request: eth_getBalance address={:?} epoch_num={:?}",
+            address, num
+        );
+
+        let epoch = self.epoch_number(num);
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            let account = invalid_params_check(
+                "address",
+                light
+                    .get_account_with_space(epoch?, address.with_evm_space())
+                    .await,
+            )?;
+
+            Ok(account.map_or(U256::zero(), |acc| acc.balance))
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn storage_at(
+        &self, address: H160, position: U256, block_num: Option<BlockNumber>,
+    ) -> BoxFuture<H256> {
+        info!(
+            "RPC Request: eth_getStorageAt address={:?}, position={:?}, block_num={:?})",
+            address, position, block_num
+        );
+
+        let epoch = self.epoch_number(block_num);
+        let position = H256::from_uint(&position);
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            let value = invalid_params_check(
+                "address",
+                light.get_eth_storage(epoch?, address, position).await,
+            )?;
+
+            Ok(value.unwrap_or_default())
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn call(
+        &self, request: CallRequest, block_number_or_hash: Option<BlockNumber>,
+    ) -> BoxFuture<Bytes> {
+        info!(
+            "RPC Request: eth_call request={:?}, block_num={:?}",
+            request, block_number_or_hash
+        );
+
+        let epoch = self.epoch_number(block_number_or_hash);
+        let evm_chain_id = self.consensus.best_chain_id().in_evm_space();
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            let (signed_tx, estimate_request) =
+                prepare_call_request(request, evm_chain_id, false)?;
+
+            trace!("call tx {:?}, request {:?}", signed_tx, estimate_request);
+            let (execution_outcome, _estimation) = light
+                .call_virtual(signed_tx, epoch?, estimate_request)
+                .await?;
+
+            call_output(execution_outcome)
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<Option<Receipt>> {
+        info!(
+            "RPC Request: eth_getTransactionReceipt tx_hash={:?}",
+            tx_hash
+        );
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            // return `null` on timeout
+            let tx_info = match light.get_tx_info(tx_hash).await {
+                Ok(t) => t,
+                Err(LightError(ErrorKind::Timeout(_), _)) => return Ok(None),
+                Err(LightError(e, _)) => {
+                    bail!(RpcError::invalid_params(e.to_string()))
+                }
+            };
+
+            if tx_info.tx.space() != Space::Ethereum {
+                return Ok(None);
+            }
+
+            let epoch = match tx_info.maybe_epoch {
+                None => return Ok(None),
+                Some(epoch) => epoch,
+            };
+
+            let phantom_block = light
+                .get_phantom_block(EpochNumber::Number(epoch))
+                .await
+                .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+
+            phantom_block_receipt(&phantom_block, tx_hash)
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn uncle_by_block_hash_and_index(
+        &self, hash: H256, idx: Index,
+    ) -> JsonRpcResult<Option<RpcBlock>> {
+        info!(
+            "RPC Request: eth_getUncleByBlockHashAndIndex hash={:?}, idx={:?}",
+            hash, idx
+        );
+        // We do not have uncle block
+        Ok(None)
+    }
+
+    fn uncle_by_block_number_and_index(
+        &self, block_num: BlockNumber, idx: Index,
+    ) -> JsonRpcResult<Option<RpcBlock>> {
+        info!("RPC Request: eth_getUncleByBlockNumberAndIndex block_num={:?}, idx={:?}", block_num, idx);
+        // We do not have uncle block
+        Ok(None)
+    }
+
+    fn logs(&self, filter: EthRpcLogFilter) -> BoxFuture<Vec<Log>> {
+        info!("RPC Request: eth_getLogs({:?})", filter);
+
+        let filter = filter.into_primitive(self.consensus.clone());
+
+        // clone `self.light` to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+        let consensus = self.consensus.clone();
+
+        let fut = async move {
+            let logs = light
+                .get_logs(filter?)
+                .await
+                .map_err(|e| e.to_string()) // TODO(thegaram): return meaningful error
+                .map_err(RpcError::invalid_params)?;
+
+            // If the results does not fit into `max_limit`, report an error
+            if let Some(max_limit) =
+                consensus.get_config().get_logs_filter_max_limit
+            {
+                if logs.len() > max_limit {
+                    bail!(invalid_params("filter", format!("This query results in too many logs, max limitation is {}, please use a smaller block range", max_limit)));
+                }
+            }
+
+            logs.into_iter()
+                .map(|l| Log::try_from_localized(l, consensus.clone(), false))
+                .collect::<Result<_, _>>()
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn submit_hashrate(&self, _: U256, _: H256) -> JsonRpcResult<bool> {
+        info!("RPC Request: eth_submitHashrate");
+        // We do not care mining
+        Ok(false)
+    }
+}
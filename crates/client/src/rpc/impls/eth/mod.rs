@@ -2,6 +2,7 @@ pub mod debug;
 pub mod eth_filter;
 pub mod eth_handler;
 pub mod eth_pubsub;
+pub mod light;
 
 pub use debug::GethDebugHandler;
 pub use eth_handler::EthHandler;
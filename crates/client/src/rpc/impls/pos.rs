@@ -9,6 +9,7 @@ use crate::{
             build_rpc_server_error, call_execution_error,
             codes::POS_NOT_ENABLED,
         },
+        interceptor::Caller,
         traits::pos::Pos,
         types::{
             call_request::rpc_call_request_network,
@@ -63,7 +64,7 @@ impl PoSInterceptor {
 }
 
 impl RpcInterceptor for PoSInterceptor {
-    fn before(&self, _name: &String) -> JsonRpcResult<()> {
+    fn before(&self, _name: &String, _caller: &Caller) -> JsonRpcResult<()> {
         match self.pos_handler.pos_option() {
             Some(_) => Ok(()),
             None => bail!(build_rpc_server_error(
@@ -256,6 +257,7 @@ impl PosHandler {
             has_gas_price: request.gas_price.is_some(),
             has_nonce: request.nonce.is_some(),
             has_storage_limit: request.storage_limit.is_some(),
+            collect_access_list: false,
         };
 
         let epoch_height = consensus_graph
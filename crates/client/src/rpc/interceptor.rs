@@ -8,10 +8,32 @@ use jsonrpc_core::{
     RpcMethod,
 };
 use serde_json::Value;
-use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use std::{collections::HashMap, marker::PhantomData, net::IpAddr, sync::Arc};
+
+/// What the transport tells about the caller of a request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Caller {
+    /// Token of the `Authorization: Bearer` header
+    pub bearer_token: Option<String>,
+    /// Value of the `X-Api-Key` header
+    pub api_key: Option<String>,
+    /// Address of the connected peer
+    pub remote_ip: Option<IpAddr>,
+    /// Last address of the `X-Forwarded-For` header
+    pub forwarded_ip: Option<IpAddr>,
+    /// Id of the WebSocket session
+    pub ws_session: Option<u64>,
+}
+
+/// Request metadata that may tell about the caller.
+pub trait CallerMetadata {
+    fn caller(&self) -> Caller { Caller::default() }
+}
+
+impl CallerMetadata for () {}
 
 pub trait RpcInterceptor: Send + Sync + 'static {
-    fn before(&self, _name: &String) -> RpcResult<()>;
+    fn before(&self, _name: &String, _caller: &Caller) -> RpcResult<()>;
 
     fn around(
         &self, _name: &String, method_call: BoxFuture<Value>,
@@ -22,7 +44,7 @@ pub trait RpcInterceptor: Send + Sync + 'static {
 
 pub struct RpcProxy<M, T, I>
 where
-    M: Metadata,
+    M: Metadata + CallerMetadata,
     T: IntoIterator<Item = (String, RemoteProcedure<M>)>,
     I: RpcInterceptor,
 {
@@ -33,7 +55,7 @@ where
 
 impl<M, T, I> RpcProxy<M, T, I>
 where
-    M: Metadata,
+    M: Metadata + CallerMetadata,
     T: IntoIterator<Item = (String, RemoteProcedure<M>)>,
     I: RpcInterceptor,
 {
@@ -48,7 +70,7 @@ where
 
 impl<M, T, I> IntoIterator for RpcProxy<M, T, I>
 where
-    M: Metadata,
+    M: Metadata + CallerMetadata,
     T: IntoIterator<Item = (String, RemoteProcedure<M>)>,
     I: RpcInterceptor,
 {
@@ -78,7 +100,7 @@ where
 
 struct RpcMethodWithInterceptor<M, I>
 where
-    M: Metadata,
+    M: Metadata + CallerMetadata,
     I: RpcInterceptor,
 {
     name: String,
@@ -88,7 +110,7 @@ where
 
 impl<M, I> RpcMethodWithInterceptor<M, I>
 where
-    M: Metadata,
+    M: Metadata + CallerMetadata,
     I: RpcInterceptor,
 {
     pub fn new(
@@ -104,13 +126,14 @@ where
 
 impl<M, I> RpcMethod<M> for RpcMethodWithInterceptor<M, I>
 where
-    M: Metadata,
+    M: Metadata + CallerMetadata,
     I: RpcInterceptor,
 {
     fn call(&self, params: Params, meta: M) -> BoxFuture<Value> {
         let name = self.name.clone();
         let interceptor = self.interceptor.clone();
-        let before_future = lazy(move || interceptor.before(&name));
+        let caller = meta.caller();
+        let before_future = lazy(move || interceptor.before(&name, &caller));
 
         let method = self.method.clone();
         let method_call = self.interceptor.around(
@@ -125,7 +148,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::interceptor::{RpcInterceptor, RpcProxy};
+    use crate::rpc::interceptor::{Caller, RpcInterceptor, RpcProxy};
     use jsonrpc_core::{Error as RpcError, MetaIoHandler, Result as RpcResult};
     use jsonrpc_derive::rpc;
     use std::sync::{
@@ -152,7 +175,7 @@ mod tests {
     }
 
     impl RpcInterceptor for Bar {
-        fn before(&self, _name: &String) -> RpcResult<()> {
+        fn before(&self, _name: &String, _caller: &Caller) -> RpcResult<()> {
             self.handled.store(true, Ordering::SeqCst);
             match self.error {
                 Some(ref err) => Err(err.clone()),
@@ -198,6 +221,32 @@ mod tests {
         );
     }
 
+    struct Auth;
+
+    impl RpcInterceptor for Auth {
+        fn before(&self, _name: &String, caller: &Caller) -> RpcResult<()> {
+            match caller.bearer_token {
+                Some(_) => Ok(()),
+                None => Err(RpcError::invalid_request()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_interceptor_authenticate() {
+        let foo = FooImpl.to_delegate();
+
+        // interceptor rejecting the requests without credentials
+        let mut handler: MetaIoHandler<()> = MetaIoHandler::default();
+        handler.extend_with(RpcProxy::new(foo, Auth));
+
+        let request = r#"{"jsonrpc": "2.0", "method": "cfx_balance", "params": [8], "id": 1}"#;
+        assert_eq!(
+            handler.handle_request_sync(request, ()),
+            Some(r#"{"jsonrpc":"2.0","error":{"code":-32600,"message":"Invalid request"},"id":1}"#.to_string()),
+        );
+    }
+
     #[test]
     fn test_interceptor_embedded() {
         let foo = FooImpl.to_delegate();
@@ -0,0 +1,193 @@
+// Copyright 2023 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! HS256 JWT authentication of the admin RPC endpoint, following the
+//! convention of the Ethereum engine API: the caller signs a token whose
+//! `iat` claim is close to the current time with a 256-bit secret shared
+//! through a hex-encoded file, and presents it as a bearer token.
+
+use crate::rpc::{
+    errors::request_rejected_unauthorized, interceptor::Caller, RpcInterceptor,
+};
+use hmac::{Hmac, Mac};
+use jsonrpc_core::Result as JsonRpcResult;
+use rustc_hex::FromHex;
+use serde::{de::DeserializeOwned, Deserialize};
+use sha2::Sha256;
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The length of the shared secret in bytes.
+pub const JWT_SECRET_LENGTH: usize = 32;
+
+/// The maximum difference in seconds allowed between the `iat` claim of a
+/// token and the local time.
+pub const JWT_IAT_TOLERANCE_SECONDS: u64 = 60;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Clone)]
+pub struct JwtSecret([u8; JWT_SECRET_LENGTH]);
+
+impl JwtSecret {
+    /// Loads the secret from a file containing its hex encoding, optionally
+    /// prefixed with `0x`.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            format!("failed to read JWT secret {}: {}", path.display(), e)
+        })?;
+        Self::from_hex(content.trim()).map_err(|e| {
+            format!("invalid JWT secret {}: {}", path.display(), e)
+        })
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        let bytes: Vec<u8> = hex.from_hex().map_err(|e| format!("{}", e))?;
+        if bytes.len() != JWT_SECRET_LENGTH {
+            return Err(format!(
+                "expect {} bytes, got {}",
+                JWT_SECRET_LENGTH,
+                bytes.len()
+            ));
+        }
+        let mut secret = [0u8; JWT_SECRET_LENGTH];
+        secret.copy_from_slice(&bytes);
+        Ok(JwtSecret(secret))
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.0)
+            .expect("HMAC accepts keys of any length")
+    }
+
+    /// Checks the signature and the `iat` claim of `token` at the time `now`
+    /// in seconds since the unix epoch.
+    pub fn validate(&self, token: &str, now: u64) -> Result<(), String> {
+        let (message, signature) = token
+            .rsplit_once('.')
+            .ok_or_else(|| "malformed token".to_string())?;
+        let (header, claims) = message
+            .split_once('.')
+            .ok_or_else(|| "malformed token".to_string())?;
+
+        let header: JwtHeader = decode_part(header)?;
+        if header.alg != "HS256" {
+            return Err(format!("unsupported algorithm {}", header.alg));
+        }
+
+        let signature =
+            base64::decode_config(signature, base64::URL_SAFE_NO_PAD)
+                .map_err(|_| "malformed signature".to_string())?;
+        let mut mac = self.mac();
+        mac.update(message.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| "invalid signature".to_string())?;
+
+        let claims: JwtClaims = decode_part(claims)?;
+        if claims.iat.abs_diff(now) > JWT_IAT_TOLERANCE_SECONDS {
+            return Err("stale token".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+}
+
+#[derive(Deserialize)]
+struct JwtClaims {
+    iat: u64,
+}
+
+fn decode_part<T: DeserializeOwned>(part: &str) -> Result<T, String> {
+    let json = base64::decode_config(part, base64::URL_SAFE_NO_PAD)
+        .map_err(|_| "malformed token".to_string())?;
+    serde_json::from_slice(&json).map_err(|_| "malformed token".to_string())
+}
+
+/// Rejects the calls that do not carry a valid bearer token signed with the
+/// secret.
+pub struct JwtInterceptor {
+    secret: JwtSecret,
+}
+
+impl JwtInterceptor {
+    pub fn new(secret: JwtSecret) -> Self { JwtInterceptor { secret } }
+}
+
+impl RpcInterceptor for JwtInterceptor {
+    fn before(&self, _name: &String, caller: &Caller) -> JsonRpcResult<()> {
+        let token = caller.bearer_token.as_deref().ok_or_else(|| {
+            request_rejected_unauthorized(Some("missing bearer token".into()))
+        })?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time is after the unix epoch")
+            .as_secs();
+        self.secret
+            .validate(token, now)
+            .map_err(|e| request_rejected_unauthorized(Some(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HmacSha256, JwtSecret, JWT_SECRET_LENGTH};
+    use hmac::Mac;
+
+    const SECRET: &str =
+        "0x7365637265747365637265747365637265747365637265747365637265747365";
+
+    fn sign(secret: &[u8], header: &str, claims: &str) -> String {
+        let encode =
+            |s: &[u8]| base64::encode_config(s, base64::URL_SAFE_NO_PAD);
+        let message = format!(
+            "{}.{}",
+            encode(header.as_bytes()),
+            encode(claims.as_bytes())
+        );
+        let mut mac = HmacSha256::new_from_slice(secret).unwrap();
+        mac.update(message.as_bytes());
+        format!("{}.{}", message, encode(&mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_parse_secret() {
+        assert!(JwtSecret::from_hex(SECRET).is_ok());
+        assert!(JwtSecret::from_hex(&SECRET[2..]).is_ok());
+        assert!(JwtSecret::from_hex(&SECRET[4..]).is_err());
+        assert!(JwtSecret::from_hex("not hex").is_err());
+    }
+
+    #[test]
+    fn test_validate_token() {
+        let secret = JwtSecret::from_hex(SECRET).unwrap();
+        let key = b"secretsecretsecretsecretsecretse";
+        let header = r#"{"alg":"HS256","typ":"JWT"}"#;
+
+        let token = sign(key, header, r#"{"iat":1000}"#);
+        assert_eq!(secret.validate(&token, 1000), Ok(()));
+        assert_eq!(secret.validate(&token, 1060), Ok(()));
+        assert!(secret.validate(&token, 1061).is_err());
+        assert!(secret.validate(&token, 939).is_err());
+
+        let forged = sign(&[0u8; JWT_SECRET_LENGTH], header, r#"{"iat":1000}"#);
+        assert!(secret.validate(&forged, 1000).is_err());
+
+        let none_alg = sign(key, r#"{"alg":"none"}"#, r#"{"iat":1000}"#);
+        assert!(secret.validate(&none_alg, 1000).is_err());
+
+        let no_iat = sign(key, header, r#"{"exp":1000}"#);
+        assert!(secret.validate(&no_iat, 1000).is_err());
+
+        assert!(secret.validate("a.b", 1000).is_err());
+        assert!(secret.validate(&format!("{}.x", token), 1000).is_err());
+    }
+}
@@ -15,7 +15,10 @@
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Parity RPC requests Metadata.
-use super::types::Origin;
+use super::{
+    interceptor::{Caller, CallerMetadata},
+    types::Origin,
+};
 use jsonrpc_core;
 use jsonrpc_pubsub::{PubSubMetadata, Session};
 use std::sync::Arc;
@@ -27,10 +30,16 @@ pub struct Metadata {
     pub origin: Origin,
     /// Request PubSub Session
     pub session: Option<Arc<Session>>,
+    /// Request caller
+    pub caller: Caller,
 }
 
 impl jsonrpc_core::Metadata for Metadata {}
 
+impl CallerMetadata for Metadata {
+    fn caller(&self) -> Caller { self.caller.clone() }
+}
+
 impl PubSubMetadata for Metadata {
     fn session(&self) -> Option<Arc<Session>> { self.session.clone() }
 }
@@ -4,13 +4,14 @@
 
 use crate::rpc::types::{
     pos::PoSEpochReward, Account as RpcAccount, AccountPendingInfo,
-    AccountPendingTransactions, Block, BlockHashOrEpochNumber, Bytes,
-    CallRequest, CfxFeeHistory, CfxFilterChanges, CfxRpcLogFilter,
-    CheckBalanceAgainstTransactionResponse, EpochNumber,
-    EstimateGasAndCollateralResponse, Log as RpcLog, PoSEconomics,
+    AccountPendingTransactions, AddressTransactions, Block,
+    BlockHashOrEpochNumber, Bytes, CallRequest, CfxFeeHistory,
+    CfxFilterChanges, CfxRpcLogFilter, CheckBalanceAgainstTransactionResponse,
+    EpochNumber, EstimateGasAndCollateralResponse, Log as RpcLog, PoSEconomics,
     Receipt as RpcReceipt, RewardInfo as RpcRewardInfo, RpcAddress,
-    SponsorInfo, Status as RpcStatus, StorageCollateralInfo, TokenSupplyInfo,
-    Transaction, VoteParamsInfo, U64 as HexU64,
+    SimulatePayload, SimulatedBlock, SponsorInfo, Status as RpcStatus,
+    StorageCollateralInfo, TokenSupplyInfo, Transaction, VoteParamsInfo,
+    U64 as HexU64,
 };
 use cfx_types::{H128, H256, U256, U64};
 use jsonrpc_core::{BoxFuture, Result as JsonRpcResult};
@@ -170,7 +171,7 @@ pub trait Cfx {
     fn call(
         &self, tx: CallRequest,
         block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
-    ) -> JsonRpcResult<Bytes>;
+    ) -> BoxFuture<Bytes>;
 
     /// Returns logs matching the filter provided.
     #[rpc(name = "cfx_getLogs")]
@@ -182,6 +183,14 @@ pub trait Cfx {
         &self, tx_hash: H256,
     ) -> BoxFuture<Option<Transaction>>;
 
+    /// Returns a page of the transactions involving the address, from the
+    /// newest to the oldest, starting from `cursor`. It requires the address
+    /// transaction index, see `persist_address_tx_index`.
+    #[rpc(name = "cfx_getTransactionsByAddress")]
+    fn transactions_by_address(
+        &self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>,
+    ) -> BoxFuture<AddressTransactions>;
+
     /// Get transaction pending info by account address
     #[rpc(name = "cfx_getAccountPendingInfo")]
     fn account_pending_info(
@@ -199,7 +208,15 @@ pub trait Cfx {
     #[rpc(name = "cfx_estimateGasAndCollateral")]
     fn estimate_gas_and_collateral(
         &self, request: CallRequest, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<EstimateGasAndCollateralResponse>;
+    ) -> BoxFuture<EstimateGasAndCollateralResponse>;
+
+    /// Simulates a sequence of blocks of calls on top of the given epoch,
+    /// with optional block and state overrides for each simulated block.
+    #[rpc(name = "cfx_simulate")]
+    fn simulate(
+        &self, payload: SimulatePayload,
+        block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
+    ) -> JsonRpcResult<Vec<SimulatedBlock>>;
 
     #[rpc(name = "cfx_feeHistory")]
     fn fee_history(
@@ -18,7 +18,7 @@ pub trait TransactionPool {
     fn txpool_status(&self) -> JsonRpcResult<TxPoolStatus>;
 
     #[rpc(name = "txpool_nextNonce")]
-    fn txpool_next_nonce(&self, address: RpcAddress) -> JsonRpcResult<U256>;
+    fn txpool_next_nonce(&self, address: RpcAddress) -> BoxFuture<U256>;
 
     #[rpc(name = "txpool_transactionByAddressAndNonce")]
     fn txpool_transaction_by_address_and_nonce(
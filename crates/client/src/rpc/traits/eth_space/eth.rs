@@ -21,13 +21,15 @@
 //! Eth rpc interface.
 use crate::rpc::types::U64 as HexU64;
 use cfx_types::{H128, H160, H256, U256, U64};
-use jsonrpc_core::Result;
+use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 
 use crate::rpc::types::{
     eth::{
-        AccountPendingTransactions, Block, BlockNumber, CallRequest,
-        EthRpcLogFilter, FilterChanges, Log, Receipt, SyncStatus, Transaction,
+        AccessListWithGasUsed, AccountPendingTransactions, AccountProof,
+        AddressTransactions, Block, BlockNumber, CallRequest, EthRpcLogFilter,
+        FilterChanges, Log, Receipt, SimulatePayload, SimulatedBlock,
+        SyncStatus, Transaction,
     },
     Bytes, FeeHistory, Index,
 };
@@ -93,18 +95,21 @@ pub trait Eth {
     #[rpc(name = "eth_getBalance")]
     fn balance(
         &self, address: H160, block: Option<BlockNumber>,
-    ) -> Result<U256>;
+    ) -> BoxFuture<U256>;
 
-    // /// Returns the account- and storage-values of the specified account
-    // including the Merkle-proof #[rpc(name = "eth_getProof")]
-    // fn proof(&self, _: H160, _: Vec<H256>, _: Option<BlockNumber>) ->
-    // BoxFuture<EthAccount>;
+    /// Returns the account- and storage-values of the specified account
+    /// including the Merkle-proof.
+    #[rpc(name = "eth_getProof")]
+    fn proof(
+        &self, address: H160, storage_keys: Vec<H256>,
+        block: Option<BlockNumber>,
+    ) -> Result<AccountProof>;
 
     /// Returns content of the storage at given address.
     #[rpc(name = "eth_getStorageAt")]
     fn storage_at(
         &self, address: H160, storage_slot: U256, block: Option<BlockNumber>,
-    ) -> jsonrpc_core::Result<H256>;
+    ) -> BoxFuture<H256>;
 
     /// Returns block with given hash.
     #[rpc(name = "eth_getBlockByHash")]
@@ -167,7 +172,7 @@ pub trait Eth {
     #[rpc(name = "eth_call")]
     fn call(
         &self, transaction: CallRequest, block: Option<BlockNumber>,
-    ) -> Result<Bytes>;
+    ) -> BoxFuture<Bytes>;
 
     /// Estimate gas needed for execution of given contract.
     #[rpc(name = "eth_estimateGas")]
@@ -175,6 +180,20 @@ pub trait Eth {
         &self, transaction: CallRequest, block: Option<BlockNumber>,
     ) -> Result<U256>;
 
+    /// Creates an access list for the given transaction, along with the gas
+    /// needed by the transaction with the list attached.
+    #[rpc(name = "eth_createAccessList")]
+    fn create_access_list(
+        &self, transaction: CallRequest, block: Option<BlockNumber>,
+    ) -> Result<AccessListWithGasUsed>;
+
+    /// Simulates a sequence of blocks of calls on top of the given block, with
+    /// optional block and state overrides for each simulated block.
+    #[rpc(name = "eth_simulateV1")]
+    fn simulate_v1(
+        &self, payload: SimulatePayload, block: Option<BlockNumber>,
+    ) -> Result<Vec<SimulatedBlock>>;
+
     /// Get transaction by its hash.
     #[rpc(name = "eth_getTransactionByHash")]
     fn transaction_by_hash(
@@ -197,7 +216,7 @@ pub trait Eth {
     #[rpc(name = "eth_getTransactionReceipt")]
     fn transaction_receipt(
         &self, transaction_hash: H256,
-    ) -> Result<Option<Receipt>>;
+    ) -> BoxFuture<Option<Receipt>>;
 
     /// Returns an uncles at given block and index.
     #[rpc(name = "eth_getUncleByBlockHashAndIndex")]
@@ -233,7 +252,7 @@ pub trait Eth {
 
     /// Returns logs matching given filter object.
     #[rpc(name = "eth_getLogs")]
-    fn logs(&self, filter: EthRpcLogFilter) -> Result<Vec<Log>>;
+    fn logs(&self, filter: EthRpcLogFilter) -> BoxFuture<Vec<Log>>;
 
     // /// Returns the hash of the current block, the seedHash, and the boundary
     // condition to be met. #[rpc(name = "eth_getWork")]
@@ -257,6 +276,14 @@ pub trait Eth {
         &self, address: H160, maybe_start_nonce: Option<U256>,
         maybe_limit: Option<U64>,
     ) -> Result<AccountPendingTransactions>;
+
+    /// Returns a page of the transactions involving the address, from the
+    /// newest to the oldest, starting from `cursor`. It requires the address
+    /// transaction index, see `persist_address_tx_index`.
+    #[rpc(name = "eth_getTransactionsByAddress")]
+    fn transactions_by_address(
+        &self, address: H160, cursor: Option<U64>, limit: Option<U64>,
+    ) -> Result<AddressTransactions>;
 }
 
 /// Eth filters rpc api (polling).
@@ -34,6 +34,7 @@ pub use self::{
         pos_economics::PoSEconomics,
         receipt::Receipt,
         reward_info::RewardInfo,
+        simulate::{SimulatePayload, SimulatedBlock},
         stat_on_gas_load::StatOnGasLoad,
         status::Status,
         storage_collateral_info::StorageCollateralInfo,
@@ -45,7 +46,8 @@ pub use self::{
             TxPoolPendingNonceRange, TxPoolStatus, TxWithPoolInfo,
         },
         vote_params_info::VoteParamsInfo,
-        Account, CfxFeeHistory, SponsorInfo,
+        Account, AddressTransaction, AddressTransactions, CfxFeeHistory,
+        SponsorInfo,
     },
     fee_history::FeeHistory,
     index::Index,
@@ -0,0 +1,24 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_types::{H256, U64};
+
+/// The location of a transaction involving an address.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTransaction {
+    pub transaction_hash: H256,
+    pub block_hash: H256,
+    pub epoch_number: U64,
+    pub transaction_index: U64,
+}
+
+/// A page of the transactions involving an address, from the newest to the
+/// oldest. `next_cursor` is the cursor of the next page, if any.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTransactions {
+    pub transactions: Vec<AddressTransaction>,
+    pub next_cursor: Option<U64>,
+}
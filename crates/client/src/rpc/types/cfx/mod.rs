@@ -1,6 +1,7 @@
 mod access_list;
 pub mod account;
 pub mod address;
+mod address_tx;
 pub mod blame_info;
 pub mod block;
 pub mod call_request;
@@ -12,6 +13,7 @@ pub mod log;
 pub mod pos_economics;
 pub mod receipt;
 pub mod reward_info;
+pub mod simulate;
 pub mod sponsor_info;
 pub mod stat_on_gas_load;
 pub mod status;
@@ -28,6 +30,7 @@ pub use address::{
     check_rpc_address_network, RcpAddressNetworkInconsistent, RpcAddress,
     UnexpectedRpcAddressNetwork,
 };
+pub use address_tx::{AddressTransaction, AddressTransactions};
 pub use fee_history::CfxFeeHistory;
 pub use sponsor_info::SponsorInfo;
 pub use tx_pool::*;
@@ -0,0 +1,203 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::types::{
+    cfx::receipt::StorageChange, Bytes, CallRequest, Log, RpcAddress,
+};
+use alloy_rpc_types::state::AccountOverride;
+use cfx_addr::Network;
+use cfx_execute_helper::simulation::{
+    SimulatedBlock as PrimitiveSimulatedBlock,
+    SimulatedCall as PrimitiveSimulatedCall,
+};
+use cfx_executor::executive::{ExecutionError, ExecutionOutcome};
+use cfx_types::{H256, U256, U64};
+use cfx_vm_types::Error as VmError;
+use primitives::TransactionStatus;
+use rustc_hex::ToHex;
+use std::collections::HashMap;
+
+/// The request of `cfx_simulate`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, executed in order on top of the base epoch.
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to check the nonce, the balance, the storage limit and the
+    /// base fee of the calls like real transactions.
+    #[serde(default)]
+    pub validation: bool,
+}
+
+/// A simulated block with its overrides and calls.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBlock {
+    pub block_overrides: Option<SimBlockOverrides>,
+    pub state_overrides: Option<HashMap<RpcAddress, AccountOverride>>,
+    #[serde(default)]
+    pub calls: Vec<CallRequest>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBlockOverrides {
+    pub block_number: Option<U64>,
+    pub epoch_number: Option<U64>,
+    pub timestamp: Option<U64>,
+    pub gas_limit: Option<U256>,
+    pub base_fee_per_gas: Option<U256>,
+    pub miner: Option<RpcAddress>,
+}
+
+/// The response of `cfx_simulate` for a simulated block.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub block_number: U64,
+    pub epoch_number: U64,
+    pub timestamp: U64,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    pub miner: RpcAddress,
+    pub base_fee_per_gas: U256,
+    /// The hashes of the simulated transactions.
+    pub transactions: Vec<H256>,
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The outcome of a simulated call.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallResult {
+    /// 0 for success, 1 for failure, as in the receipt.
+    pub outcome_status: U64,
+    pub return_data: Bytes,
+    pub gas_used: U256,
+    pub gas_covered_by_sponsor: bool,
+    /// The storage collateral newly charged from the sender.
+    pub storage_collateralized: U64,
+    pub storage_released: Vec<StorageChange>,
+    pub storage_covered_by_sponsor: bool,
+    pub logs: Vec<Log>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_exec_error_msg: Option<String>,
+}
+
+impl SimulatedBlock {
+    /// Fails if a call is not executed, e.g., its nonce or balance doesn't
+    /// pass the validation.
+    pub fn try_from(
+        block: PrimitiveSimulatedBlock, network: Network,
+    ) -> Result<Self, String> {
+        let mut log_index = 0;
+        let mut transactions = Vec::with_capacity(block.calls.len());
+        let mut calls = Vec::with_capacity(block.calls.len());
+        for (idx, call) in block.calls.into_iter().enumerate() {
+            transactions.push(call.tx.hash());
+            calls.push(SimCallResult::try_from(
+                call,
+                block.hash,
+                block.epoch_height,
+                idx,
+                &mut log_index,
+                network,
+            )?);
+        }
+
+        Ok(SimulatedBlock {
+            hash: block.hash,
+            parent_hash: block.parent_hash,
+            block_number: block.number.into(),
+            epoch_number: block.epoch_height.into(),
+            timestamp: block.timestamp.into(),
+            gas_limit: block.gas_limit,
+            gas_used: block.gas_used,
+            miner: RpcAddress::try_from_h160(block.author, network)?,
+            base_fee_per_gas: block.base_gas_price,
+            transactions,
+            calls,
+        })
+    }
+}
+
+impl SimCallResult {
+    fn try_from(
+        call: PrimitiveSimulatedCall, block_hash: H256, epoch_number: u64,
+        idx: usize, log_index: &mut usize, network: Network,
+    ) -> Result<Self, String> {
+        let PrimitiveSimulatedCall { tx, outcome } = call;
+        let (executed, tx_exec_error_msg) = match outcome {
+            ExecutionOutcome::NotExecutedDrop(e) => {
+                return Err(format!("call {} is not executed: {:?}", idx, e));
+            }
+            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+                return Err(format!("call {} is not executed: {:?}", idx, e));
+            }
+            ExecutionOutcome::ExecutionErrorBumpNonce(
+                ExecutionError::VmError(VmError::Reverted),
+                executed,
+            ) => {
+                let error = format!(
+                    "Vm reverted, 0x{}",
+                    executed.output.to_hex::<String>()
+                );
+                (executed, Some(error))
+            }
+            ExecutionOutcome::ExecutionErrorBumpNonce(e, executed) => {
+                (executed, Some(format!("{:?}", e)))
+            }
+            ExecutionOutcome::Finished(executed) => (executed, None),
+        };
+
+        let outcome_status = if tx_exec_error_msg.is_none() {
+            TransactionStatus::Success
+        } else {
+            TransactionStatus::Failure
+        };
+
+        let transaction_hash = tx.hash();
+        let logs = executed
+            .logs
+            .into_iter()
+            .enumerate()
+            .map(|(tx_log_idx, log)| {
+                let mut log = Log::try_from(log, network, false)?;
+                log.block_hash = Some(block_hash);
+                log.epoch_number = Some(epoch_number.into());
+                log.transaction_hash = Some(transaction_hash);
+                log.transaction_index = Some(idx.into());
+                log.log_index = Some((*log_index + tx_log_idx).into());
+                log.transaction_log_index = Some(tx_log_idx.into());
+                Ok(log)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        *log_index += logs.len();
+
+        // only the collateral of the sender is charged
+        let storage_collateralized = executed
+            .storage_collateralized
+            .first()
+            .map(|sc| sc.collaterals)
+            .unwrap_or_default();
+
+        Ok(SimCallResult {
+            outcome_status: U64::from(outcome_status.in_space(tx.space())),
+            return_data: Bytes(executed.output),
+            gas_used: executed.gas_used,
+            gas_covered_by_sponsor: executed.gas_sponsor_paid,
+            storage_collateralized,
+            storage_released: executed
+                .storage_released
+                .into_iter()
+                .map(|sc| StorageChange::try_from(sc, network))
+                .collect::<Result<_, _>>()?,
+            storage_covered_by_sponsor: executed.storage_sponsor_paid,
+            logs,
+            tx_exec_error_msg,
+        })
+    }
+}
@@ -0,0 +1,19 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_types::U256;
+use primitives::AccessList;
+
+/// The response of `eth_createAccessList`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListWithGasUsed {
+    /// The accounts and storage slots accessed by the transaction.
+    pub access_list: AccessList,
+    /// The gas needed by the transaction with the access list attached.
+    pub gas_used: U256,
+    /// The error message if the transaction failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
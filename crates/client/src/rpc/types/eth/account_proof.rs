@@ -0,0 +1,54 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::types::Bytes;
+use cfx_types::{H160, H256, U256};
+use primitives::StateRoot;
+
+/// The response of `eth_getProof`.
+///
+/// Unlike EIP-1186, Conflux keeps the whole state in one MPT triple (snapshot,
+/// intermediate and delta MPT), so each proof is the rlp encoding of a
+/// `cfx_storage::StateProof` proving the raw state entry against `stateRoot`.
+/// It can be checked offline with `cfx_storage::verify_state_proof`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    /// Address of the account.
+    pub address: H160,
+    /// Balance of the account.
+    pub balance: U256,
+    /// Nonce of the account.
+    pub nonce: U256,
+    /// Code hash of the account.
+    pub code_hash: H256,
+    /// The raw rlp-encoded account entry, or `None` if the account doesn't
+    /// exist.
+    pub account_value: Option<Bytes>,
+    /// The rlp-encoded state proof of the account entry.
+    pub account_proof: Bytes,
+    /// The proofs of the requested storage slots.
+    pub storage_proof: Vec<StorageProof>,
+    /// The epoch number of the proved state.
+    pub epoch_number: U256,
+    /// The state root of the proved state.
+    pub state_root: StateRoot,
+    /// The state root one snapshot period before the proved state, which is
+    /// used to pad the intermediate MPT keys. `None` in the first snapshot
+    /// period.
+    pub prev_snapshot_state_root: Option<StateRoot>,
+}
+
+/// The proof of a storage slot.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageProof {
+    /// Storage slot.
+    pub key: H256,
+    /// Value of the slot. An empty slot is proved to be absent from the
+    /// state, otherwise the raw entry is `rlp(value)`.
+    pub value: U256,
+    /// The rlp-encoded state proof of the storage entry.
+    pub proof: Bytes,
+}
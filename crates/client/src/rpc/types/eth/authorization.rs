@@ -0,0 +1,45 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_types::{H160, U256, U64};
+use primitives::AuthorizationListItem;
+
+/// An EIP-7702 authorization, with the integer fields hex encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorization {
+    pub chain_id: U256,
+    pub address: H160,
+    pub nonce: U64,
+    pub y_parity: U64,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl From<AuthorizationListItem> for Authorization {
+    fn from(item: AuthorizationListItem) -> Self {
+        Authorization {
+            chain_id: item.chain_id,
+            address: item.address,
+            nonce: item.nonce.into(),
+            y_parity: item.y_parity.into(),
+            r: item.r,
+            s: item.s,
+        }
+    }
+}
+
+impl From<Authorization> for AuthorizationListItem {
+    fn from(auth: Authorization) -> Self {
+        AuthorizationListItem {
+            chain_id: auth.chain_id,
+            address: auth.address,
+            nonce: auth.nonce.as_u64(),
+            // An out-of-range y parity is kept invalid.
+            y_parity: u8::try_from(auth.y_parity.as_u64()).unwrap_or(u8::MAX),
+            r: auth.r,
+            s: auth.s,
+        }
+    }
+}
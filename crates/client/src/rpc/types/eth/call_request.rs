@@ -18,20 +18,20 @@
 // You should have received a copy of the GNU General Public License
 // along with OpenEthereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::rpc::types::{Bytes, MAX_GAS_CALL_REQUEST};
+use crate::rpc::types::{eth::Authorization, Bytes, MAX_GAS_CALL_REQUEST};
 use cfx_types::{Address, AddressSpaceUtil, H160, U256, U64};
 use primitives::{
     transaction::{
         Action, Eip1559Transaction, Eip155Transaction, Eip2930Transaction,
-        EthereumTransaction::*, SignedTransaction, EIP1559_TYPE, EIP2930_TYPE,
-        LEGACY_TX_TYPE,
+        Eip7702Transaction, EthereumTransaction::*, SignedTransaction,
+        EIP1559_TYPE, EIP2930_TYPE, EIP7702_TYPE, LEGACY_TX_TYPE,
     },
     AccessList,
 };
 use std::cmp::min;
 
 /// Call request
-#[derive(Debug, Default, PartialEq, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CallRequest {
     /// From
@@ -53,6 +53,7 @@ pub struct CallRequest {
     /// Miner bribe
     pub max_priority_fee_per_gas: Option<U256>,
     pub access_list: Option<AccessList>,
+    pub authorization_list: Option<Vec<Authorization>>,
     #[serde(rename = "type")]
     pub transaction_type: Option<U64>,
 }
@@ -73,7 +74,9 @@ impl CallRequest {
             request.to.map_or(Action::Create, |addr| Action::Call(addr));
         let value = request.value.unwrap_or_default();
 
-        let default_type_id = if request.max_fee_per_gas.is_some()
+        let default_type_id = if request.authorization_list.is_some() {
+            EIP7702_TYPE
+        } else if request.max_fee_per_gas.is_some()
             || request.max_priority_fee_per_gas.is_some()
         {
             EIP1559_TYPE
@@ -127,6 +130,30 @@ impl CallRequest {
                 data,
                 access_list,
             }),
+            EIP7702_TYPE => {
+                if action == Action::Create {
+                    return Err(
+                        "Set-code transaction can not create a contract".into(),
+                    );
+                }
+                Eip7702(Eip7702Transaction {
+                    chain_id,
+                    nonce,
+                    max_priority_fee_per_gas,
+                    max_fee_per_gas,
+                    gas,
+                    action,
+                    value,
+                    data,
+                    access_list,
+                    authorization_list: request
+                        .authorization_list
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                })
+            }
             x => {
                 return Err(
                     format!("Unrecognized transaction type: {x}").into()
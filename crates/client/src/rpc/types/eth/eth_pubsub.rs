@@ -4,14 +4,15 @@
 
 //! Pub-Sub types.
 
-use super::{EthRpcLogFilter, Header, Log};
+use super::{EthRpcLogFilter, Header, Log, Transaction};
 use cfx_types::H256;
+use cfxcore::transaction_pool::DropReason;
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_json::{from_value, Value};
 
 /// Subscription result.
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged, rename_all = "camelCase")]
 // NOTE: rename_all does not apply to enum member fields
 // see: https://github.com/serde-rs/serde/issues/1061
@@ -24,6 +25,20 @@ pub enum Result {
 
     /// Transaction hash
     TransactionHash(H256),
+
+    /// Transaction
+    Transaction(Transaction),
+
+    /// Transaction dropped from the pool, with the fields of the reason
+    #[serde(rename_all = "camelCase")]
+    DroppedTransaction {
+        transaction_hash: H256,
+        #[serde(flatten)]
+        reason: DropReason,
+    },
+
+    /// Sync status
+    SyncState { syncing: bool },
 }
 
 /// Subscription kind.
@@ -39,6 +54,8 @@ pub enum Kind {
     NewPendingTransactions,
     /// Node syncing status subscription.
     Syncing,
+    /// Transactions dropped from the pool subscription.
+    DroppedTransactions,
 }
 
 /// Subscription kind.
@@ -48,6 +65,8 @@ pub enum Params {
     None,
     /// Log parameters.
     Logs(EthRpcLogFilter),
+    /// Whether to publish the full transactions instead of the hashes.
+    FullTransactions(bool),
 }
 
 impl Default for Params {
@@ -65,6 +84,10 @@ impl<'a> Deserialize<'a> for Params {
             return Ok(Params::None);
         }
 
+        if let Value::Bool(full) = v {
+            return Ok(Params::FullTransactions(full));
+        }
+
         // try to interpret as a log filter
         from_value(v.clone()).map(Params::Logs).map_err(|e| {
             D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e))
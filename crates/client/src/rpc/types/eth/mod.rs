@@ -2,6 +2,10 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+mod access_list;
+mod account_proof;
+mod address_tx;
+mod authorization;
 mod block;
 mod block_number;
 mod call_request;
@@ -9,6 +13,7 @@ pub mod eth_pubsub;
 mod filter;
 mod log;
 mod receipt;
+mod simulate;
 mod sync;
 mod trace;
 mod trace_filter;
@@ -16,12 +21,17 @@ mod transaction;
 mod tx_pool;
 
 pub use self::{
+    access_list::AccessListWithGasUsed,
+    account_proof::{AccountProof, StorageProof},
+    address_tx::{AddressTransaction, AddressTransactions},
+    authorization::Authorization,
     block::{Block, Header},
     block_number::BlockNumber,
     call_request::CallRequest,
     filter::{EthRpcLogFilter, FilterChanges},
     log::Log,
     receipt::Receipt,
+    simulate::{SimulatePayload, SimulatedBlock},
     sync::{SyncInfo, SyncStatus},
     trace::{LocalizedTrace, Res},
     trace_filter::TraceFilter,
@@ -0,0 +1,194 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::types::{
+    eth::{CallRequest, Log},
+    Bytes,
+};
+use alloy_rpc_types::{
+    error::EthRpcErrorCode, state::StateOverride, BlockOverrides,
+};
+use cfx_execute_helper::simulation::{
+    SimulatedBlock as PrimitiveSimulatedBlock,
+    SimulatedCall as PrimitiveSimulatedCall,
+};
+use cfx_executor::executive::{
+    revert_reason_decode, ExecutionError, ExecutionOutcome,
+};
+use cfx_types::{H160, H256, U256, U64};
+use cfx_vm_types::Error as VmError;
+
+/// The error code of a call failed with a VM error other than revert, which is
+/// the same as geth.
+const VM_ERROR_CODE: i64 = -32015;
+
+/// The request of `eth_simulateV1`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, executed in order on top of the base block.
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to check the nonce, the balance and the base fee of the calls
+    /// like real transactions.
+    #[serde(default)]
+    pub validation: bool,
+    /// Whether to record the value transfers as logs, which is not supported.
+    #[serde(default)]
+    pub trace_transfers: bool,
+}
+
+/// A simulated block with its overrides and calls.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBlock {
+    pub block_overrides: Option<BlockOverrides>,
+    pub state_overrides: Option<StateOverride>,
+    #[serde(default)]
+    pub calls: Vec<CallRequest>,
+}
+
+/// The response of `eth_simulateV1` for a simulated block.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub number: U256,
+    pub timestamp: U256,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    pub miner: H160,
+    pub base_fee_per_gas: U256,
+    /// The hashes of the simulated transactions.
+    pub transactions: Vec<H256>,
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The outcome of a simulated call.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallResult {
+    pub status: U64,
+    pub return_data: Bytes,
+    pub gas_used: U256,
+    pub logs: Vec<Log>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SimulateError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl SimulatedBlock {
+    /// Fails if a call is not executed, e.g., its nonce or balance doesn't
+    /// pass the validation.
+    pub fn try_from(block: PrimitiveSimulatedBlock) -> Result<Self, String> {
+        let number: U256 = block.epoch_height.into();
+        let mut log_index = 0;
+        let mut transactions = Vec::with_capacity(block.calls.len());
+        let mut calls = Vec::with_capacity(block.calls.len());
+        for (idx, call) in block.calls.into_iter().enumerate() {
+            transactions.push(call.tx.hash());
+            calls.push(SimCallResult::try_from(
+                call,
+                block.hash,
+                number,
+                idx,
+                &mut log_index,
+            )?);
+        }
+
+        Ok(SimulatedBlock {
+            hash: block.hash,
+            parent_hash: block.parent_hash,
+            number,
+            timestamp: block.timestamp.into(),
+            gas_limit: block.gas_limit,
+            gas_used: block.gas_used,
+            miner: block.author,
+            base_fee_per_gas: block.base_gas_price,
+            transactions,
+            calls,
+        })
+    }
+}
+
+impl SimCallResult {
+    fn try_from(
+        call: PrimitiveSimulatedCall, block_hash: H256, block_number: U256,
+        idx: usize, log_index: &mut usize,
+    ) -> Result<Self, String> {
+        let PrimitiveSimulatedCall { tx, outcome } = call;
+        let (executed, error) = match outcome {
+            ExecutionOutcome::NotExecutedDrop(e) => {
+                return Err(format!("call {} is not executed: {:?}", idx, e));
+            }
+            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+                return Err(format!("call {} is not executed: {:?}", idx, e));
+            }
+            ExecutionOutcome::ExecutionErrorBumpNonce(
+                ExecutionError::VmError(VmError::Reverted),
+                executed,
+            ) => {
+                let error = SimulateError {
+                    code: EthRpcErrorCode::ExecutionError.code() as i64,
+                    message: format!(
+                        "execution reverted: {}",
+                        revert_reason_decode(&executed.output)
+                    ),
+                };
+                (executed, Some(error))
+            }
+            ExecutionOutcome::ExecutionErrorBumpNonce(
+                ExecutionError::VmError(e),
+                executed,
+            ) => {
+                let error = SimulateError {
+                    code: VM_ERROR_CODE,
+                    message: e.to_string(),
+                };
+                (executed, Some(error))
+            }
+            ExecutionOutcome::ExecutionErrorBumpNonce(e, executed) => {
+                let error = SimulateError {
+                    code: VM_ERROR_CODE,
+                    message: format!("execution failed: {:?}", e),
+                };
+                (executed, Some(error))
+            }
+            ExecutionOutcome::Finished(executed) => (executed, None),
+        };
+
+        let transaction_hash = tx.hash();
+        let logs = executed
+            .logs
+            .into_iter()
+            .enumerate()
+            .map(|(tx_log_idx, log)| Log {
+                address: log.address,
+                topics: log.topics,
+                data: Bytes(log.data),
+                block_hash,
+                block_number,
+                transaction_hash,
+                transaction_index: idx.into(),
+                log_index: Some((*log_index + tx_log_idx).into()),
+                transaction_log_index: Some(tx_log_idx.into()),
+                removed: false,
+            })
+            .collect::<Vec<_>>();
+        *log_index += logs.len();
+
+        Ok(SimCallResult {
+            status: U64::from(error.is_none() as u64),
+            return_data: Bytes(executed.output),
+            gas_used: executed.gas_used,
+            logs,
+            error,
+        })
+    }
+}
@@ -18,7 +18,7 @@
 // You should have received a copy of the GNU General Public License
 // along with OpenEthereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::rpc::types::Bytes;
+use crate::rpc::types::{eth::Authorization, Bytes};
 use cfx_types::{H160, H256, H512, U256, U64};
 use cfx_vm_types::{contract_address, CreateContractAddress};
 use primitives::{
@@ -88,6 +88,9 @@ pub struct Transaction {
     pub max_priority_fee_per_gas: Option<U256>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub y_parity: Option<U64>,
+    /// The EIP-7702 authorizations of a set-code transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorization_list: Option<Vec<Authorization>>,
     /* /// Transaction activates at specified block.
      * pub condition: Option<TransactionCondition>, */
 }
@@ -135,6 +138,9 @@ impl Transaction {
                 .after_1559()
                 .then_some(*t.max_priority_gas_price()),
             y_parity: t.is_2718().then_some(U64::from(signature.v())),
+            authorization_list: t.authorization_list().map(|list| {
+                list.iter().cloned().map(Authorization::from).collect()
+            }),
             transaction_type: Some(U64::from(t.type_id())),
         }
     }
@@ -20,14 +20,15 @@
 
 //! Pub-Sub types.
 
-use super::{CfxRpcLogFilter, Header, Log};
+use super::{CfxRpcLogFilter, Header, Log, Transaction};
 use cfx_types::{H256, U256};
+use cfxcore::transaction_pool::DropReason;
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_json::{from_value, Value};
 
 /// Subscription result.
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged, rename_all = "camelCase")]
 // NOTE: rename_all does not apply to enum member fields
 // see: https://github.com/serde-rs/serde/issues/1061
@@ -41,6 +42,20 @@ pub enum Result {
     /// Transaction hash
     TransactionHash(H256),
 
+    /// Transaction
+    Transaction(Transaction),
+
+    /// Transaction dropped from the pool, with the fields of the reason
+    #[serde(rename_all = "camelCase")]
+    DroppedTransaction {
+        transaction_hash: H256,
+        #[serde(flatten)]
+        reason: DropReason,
+    },
+
+    /// Sync status
+    SyncState { syncing: bool },
+
     /// Epoch
     #[serde(rename_all = "camelCase")]
     Epoch {
@@ -68,6 +83,8 @@ pub enum Kind {
     Syncing,
     /// Epoch
     Epochs,
+    /// Transactions dropped from the pool subscription.
+    DroppedTransactions,
 }
 
 /// Subscription epoch.
@@ -90,6 +107,8 @@ pub enum Params {
     Logs(CfxRpcLogFilter),
     /// Epoch parameters.
     Epochs(SubscriptionEpoch),
+    /// Whether to publish the full transactions instead of the hashes.
+    FullTransactions(bool),
 }
 
 impl Default for Params {
@@ -107,6 +126,10 @@ impl<'a> Deserialize<'a> for Params {
             return Ok(Params::None);
         }
 
+        if let Value::Bool(full) = v {
+            return Ok(Params::FullTransactions(full));
+        }
+
         // try to interpret as a log filter
         if let Ok(v) = from_value(v.clone()).map(Params::Logs) {
             return Ok(v);
@@ -137,6 +137,16 @@ impl StorageStateTrait for MockStorage {
 
         Ok(Some(kvs))
     }
+
+    fn iterate_all(
+        &mut self, callback: &mut dyn FnMut(MptKeyValue) -> Result<()>,
+    ) -> Result<()> {
+        for (k, v) in &self.contents {
+            *self.num_reads.get_mut() += 1;
+            callback((k.clone(), v.clone()))?;
+        }
+        Ok(())
+    }
 }
 
 type StateDbTest = StateDbGeneric;
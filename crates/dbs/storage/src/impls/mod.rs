@@ -6,6 +6,7 @@
 pub(super) mod merkle_patricia_trie;
 pub(super) mod delta_mpt;
 pub(super) mod node_merkle_proof;
+pub(super) mod partial_storage;
 pub(super) mod proof_merger;
 pub(super) mod recording_storage;
 pub(super) mod replicated_state;
@@ -0,0 +1,118 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+// `PartialStorage` serves a subset of the key-value pairs of a state, e.g. the
+// entries retrieved and verified by a light node. The keys which are read but
+// not in the subset are recorded, so that the caller can retrieve them and run
+// the execution again. Unlike `RecordingStorage`, it does not need a complete
+// state underneath.
+
+pub type MissingKeys = Arc<Mutex<BTreeSet<Vec<u8>>>>;
+
+pub struct PartialStorage {
+    // `None` is an entry known to be absent from the state.
+    entries: HashMap<Vec<u8>, Option<Box<[u8]>>>,
+
+    // note: the storage is moved into the `State` during the execution, so the
+    // missing keys are shared with the caller.
+    missing_keys: MissingKeys,
+}
+
+impl PartialStorage {
+    pub fn new(entries: HashMap<Vec<u8>, Option<Box<[u8]>>>) -> Self {
+        Self {
+            entries,
+            missing_keys: Default::default(),
+        }
+    }
+
+    pub fn missing_keys(&self) -> MissingKeys { self.missing_keys.clone() }
+
+    fn not_supported<T>(&self, method: &str) -> Result<T> {
+        Err(ErrorKind::Msg(format!(
+            "PartialStorage does not support {}",
+            method
+        ))
+        .into())
+    }
+}
+
+impl StateTrait for PartialStorage {
+    fn get(
+        &self, access_key: StorageKeyWithSpace,
+    ) -> Result<Option<Box<[u8]>>> {
+        let key = access_key.to_key_bytes();
+        match self.entries.get(&key) {
+            Some(value) => Ok(value.clone()),
+            None => {
+                self.missing_keys.lock().insert(key);
+                Ok(None)
+            }
+        }
+    }
+
+    fn set(
+        &mut self, access_key: StorageKeyWithSpace, value: Box<[u8]>,
+    ) -> Result<()> {
+        self.entries.insert(access_key.to_key_bytes(), Some(value));
+        Ok(())
+    }
+
+    fn delete(&mut self, access_key: StorageKeyWithSpace) -> Result<()> {
+        self.entries.insert(access_key.to_key_bytes(), None);
+        Ok(())
+    }
+
+    fn delete_test_only(
+        &mut self, access_key: StorageKeyWithSpace,
+    ) -> Result<Option<Box<[u8]>>> {
+        let old_value = self.get(access_key)?;
+        self.delete(access_key)?;
+        Ok(old_value)
+    }
+
+    // The entries under a prefix can't be enumerated from a subset of the
+    // state.
+    fn delete_all(
+        &mut self, _access_key_prefix: StorageKeyWithSpace,
+    ) -> Result<Option<Vec<MptKeyValue>>> {
+        self.not_supported("delete_all")
+    }
+
+    fn read_all(
+        &mut self, _access_key_prefix: StorageKeyWithSpace,
+    ) -> Result<Option<Vec<MptKeyValue>>> {
+        self.not_supported("read_all")
+    }
+
+    fn iterate_all(
+        &mut self, _callback: &mut dyn FnMut(MptKeyValue) -> Result<()>,
+    ) -> Result<()> {
+        self.not_supported("iterate_all")
+    }
+
+    fn compute_state_root(&mut self) -> Result<StateRootWithAuxInfo> {
+        self.not_supported("compute_state_root")
+    }
+
+    fn get_state_root(&self) -> Result<StateRootWithAuxInfo> {
+        self.not_supported("get_state_root")
+    }
+
+    fn commit(&mut self, _epoch: EpochId) -> Result<StateRootWithAuxInfo> {
+        self.not_supported("commit")
+    }
+}
+
+use crate::{
+    impls::{errors::*, merkle_patricia_trie::MptKeyValue},
+    state::*,
+};
+use cfx_internal_common::StateRootWithAuxInfo;
+use parking_lot::Mutex;
+use primitives::{EpochId, StorageKeyWithSpace};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
@@ -89,6 +89,23 @@ impl<Storage: StateTrait + StateTraitExt> StateTrait
             }
         }
     }
+
+    fn iterate_all(
+        &mut self, callback: &mut dyn FnMut(MptKeyValue) -> Result<()>,
+    ) -> Result<()> {
+        // The proofs are retrieved from the storage, which can't be accessed
+        // during the iteration.
+        let mut kvs = Vec::new();
+        self.storage.iterate_all(&mut |kv| {
+            kvs.push(kv);
+            Ok(())
+        })?;
+        self.record_kvs(&kvs)?;
+        for kv in kvs {
+            callback(kv)?;
+        }
+        Ok(())
+    }
 }
 
 use crate::{
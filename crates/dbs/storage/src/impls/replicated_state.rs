@@ -303,6 +303,12 @@ impl<Main: StateTrait> StateTrait for ReplicatedState<Main> {
         self.state.read_all(access_key_prefix)
     }
 
+    fn iterate_all(
+        &mut self, callback: &mut dyn FnMut(MptKeyValue) -> Result<()>,
+    ) -> Result<()> {
+        self.state.iterate_all(callback)
+    }
+
     fn compute_state_root(&mut self) -> Result<StateRootWithAuxInfo> {
         self.replication_handler
             .send_op(StateOperation::ComputeStateRoot);
@@ -376,6 +376,26 @@ impl StateTrait for SingleMptState {
         self.delete_all_impl::<access_mode::Read>(access_key_prefix)
     }
 
+    fn iterate_all(
+        &mut self, callback: &mut dyn FnMut(MptKeyValue) -> Result<()>,
+    ) -> Result<()> {
+        self.ensure_temp_slab_for_db_load();
+
+        let kvs = SubTrieVisitor::new(
+            &self.trie,
+            self.trie_root.clone(),
+            &mut self.owned_node_set,
+        )?
+        .traversal(&[], &[])?;
+        for (k, v) in kvs.unwrap_or_default() {
+            if v.len() > 0 {
+                let storage_key = StorageKeyWithSpace::from_delta_mpt_key(&k);
+                callback((storage_key.to_key_bytes(), v))?;
+            }
+        }
+        Ok(())
+    }
+
     fn compute_state_root(&mut self) -> Result<StateRootWithAuxInfo> {
         self.ensure_temp_slab_for_db_load();
 
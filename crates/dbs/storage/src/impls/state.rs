@@ -309,6 +309,59 @@ impl StateTrait for State {
         self.delete_all_impl::<access_mode::Read>(access_key_prefix)
     }
 
+    fn iterate_all(
+        &mut self, callback: &mut dyn FnMut(MptKeyValue) -> Result<()>,
+    ) -> Result<()> {
+        self.ensure_temp_slab_for_db_load();
+
+        // The key value pairs in the intermediate trie and the delta trie
+        // override the ones in the snapshot, and the empty values are
+        // tombstones of the deleted keys. The tries are small enough to be
+        // loaded into memory.
+        let mut updated_kvs = HashMap::new();
+        if let (Some(trie), Some(root_node)) =
+            (&self.maybe_intermediate_trie, &self.intermediate_trie_root)
+        {
+            let kvs = SubTrieVisitor::new(
+                trie,
+                root_node.clone(),
+                &mut self.owned_node_set,
+            )?
+            .traversal(&[], &[])?;
+            for (k, v) in kvs.unwrap_or_default() {
+                let storage_key = StorageKeyWithSpace::from_delta_mpt_key(&k);
+                updated_kvs.insert(storage_key.to_key_bytes(), v);
+            }
+        }
+        if let Some(root_node) = &self.delta_trie_root {
+            let kvs = SubTrieVisitor::new(
+                &self.delta_trie,
+                root_node.clone(),
+                &mut self.owned_node_set,
+            )?
+            .traversal(&[], &[])?;
+            for (k, v) in kvs.unwrap_or_default() {
+                let storage_key = StorageKeyWithSpace::from_delta_mpt_key(&k);
+                updated_kvs.insert(storage_key.to_key_bytes(), v);
+            }
+        }
+
+        let mut kv_iterator = self.snapshot_db.snapshot_kv_iterator()?.take();
+        let lower_bound_incl: &[u8] = &[];
+        let mut kvs = kv_iterator.iter_range(lower_bound_incl, None)?.take();
+        while let Some((key, value)) = kvs.next()? {
+            if !updated_kvs.contains_key(&key) {
+                callback((key, value))?;
+            }
+        }
+        for (key, value) in updated_kvs {
+            if value.len() > 0 {
+                callback((key, value))?;
+            }
+        }
+        Ok(())
+    }
+
     fn compute_state_root(&mut self) -> Result<StateRootWithAuxInfo> {
         self.ensure_temp_slab_for_db_load();
 
@@ -982,7 +1035,7 @@ use primitives::{
 use rustc_hex::ToHex;
 use std::{
     cell::UnsafeCell,
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     hint::unreachable_unchecked,
     sync::{atomic::Ordering, Arc},
 };
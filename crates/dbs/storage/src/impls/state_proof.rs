@@ -138,6 +138,38 @@ impl StateProof {
     }
 }
 
+/// Verifies offline that `key` maps to `value` in the state with `state_root`,
+/// or that `key` doesn't exist in the state if `value` is `None`.
+///
+/// `key` is the raw bytes of a `StorageKeyWithSpace` (e.g.,
+/// `StorageKey::new_account_key(&address).with_evm_space().to_key_bytes()`)
+/// and `value` is the raw rlp-encoded entry as stored in the state.
+///
+/// The intermediate delta MPT keys are padded with a value derived from the
+/// state root one snapshot period earlier (`prev_snapshot_state_root`), which
+/// is `None` in the first snapshot period. Both state roots must be checked by
+/// the caller against trusted block headers: the hash of a state root
+/// (`StateRoot::compute_state_root_hash`) is the `deferred_state_root` of the
+/// pivot block `DEFERRED_STATE_EPOCH_COUNT` epochs later.
+pub fn verify_state_proof(
+    key: &[u8], value: Option<&[u8]>, proof: &StateProof,
+    state_root: &StateRoot, prev_snapshot_state_root: Option<&StateRoot>,
+) -> bool {
+    let maybe_intermediate_padding = prev_snapshot_state_root.map(|root| {
+        StorageKeyWithSpace::delta_mpt_padding(
+            &root.snapshot_root,
+            &root.intermediate_delta_root,
+        )
+    });
+
+    proof.is_valid_kv(
+        &key.to_vec(),
+        value,
+        state_root.clone(),
+        maybe_intermediate_padding,
+    )
+}
+
 use crate::impls::merkle_patricia_trie::TrieProof;
 use primitives::{
     CheckInput, DeltaMptKeyPadding, MptValue, StateRoot, StorageKeyWithSpace,
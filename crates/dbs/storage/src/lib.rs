@@ -207,10 +207,11 @@ pub use self::{
             MptKeyValue, TrieProof, VanillaChildrenTable,
         },
         node_merkle_proof::{NodeMerkleProof, StorageRootProof},
+        partial_storage::{MissingKeys, PartialStorage},
         proof_merger::StateProofMerger,
         recording_storage::RecordingStorage,
         snapshot_sync::{FullSyncVerifier, MptSlicer},
-        state_proof::StateProof,
+        state_proof::{verify_state_proof, StateProof},
         storage_db::{
             kvdb_rocksdb::KvdbRocksdb,
             kvdb_sqlite::{KvdbSqlite, KvdbSqliteStatements},
@@ -38,6 +38,12 @@ pub trait StateTrait: Sync + Send {
     fn read_all(
         &mut self, access_key_prefix: StorageKeyWithSpace,
     ) -> Result<Option<Vec<MptKeyValue>>>;
+    /// Visits all key value pairs in the state, with the keys in the format of
+    /// `StorageKeyWithSpace::to_key_bytes`. Unlike `read_all`, the pairs in
+    /// the snapshot are visited without loading them into memory.
+    fn iterate_all(
+        &mut self, callback: &mut dyn FnMut(MptKeyValue) -> Result<()>,
+    ) -> Result<()>;
 
     // Finalize
     /// It's costly to compute state root however it's only necessary to compute
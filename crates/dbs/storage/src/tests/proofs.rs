@@ -30,6 +30,22 @@ fn portion<T>(v: &Vec<T>, k: usize, n: usize) -> &[T] {
 fn generate_random_state(
     rng: &mut ChaChaRng,
 ) -> (FakeStateManager, State, DeltaMptKeyPadding, Vec<Vec<u8>>) {
+    let (state_manager, state, prev_snapshot_root, keys) =
+        generate_random_state_with_prev_snapshot_root(rng);
+
+    let intermediate_padding = StorageKeyWithSpace::delta_mpt_padding(
+        &prev_snapshot_root.snapshot_root,
+        &prev_snapshot_root.intermediate_delta_root,
+    );
+
+    (state_manager, state, intermediate_padding, keys)
+}
+
+// same as `generate_random_state`, but returns the state root of the previous
+// snapshot period instead of the intermediate padding derived from it
+fn generate_random_state_with_prev_snapshot_root(
+    rng: &mut ChaChaRng,
+) -> (FakeStateManager, State, StateRoot, Vec<Vec<u8>>) {
     let snapshot_epoch_count = 1;
     let state_manager =
         new_state_manager_for_unit_test_with_snapshot_epoch_count(
@@ -201,11 +217,6 @@ fn generate_random_state(
 
     keys.shuffle(rng);
 
-    let intermediate_padding = StorageKeyWithSpace::delta_mpt_padding(
-        &root_2.state_root.snapshot_root,
-        &root_2.state_root.intermediate_delta_root,
-    );
-
     let new_state = state_manager
         .get_state_for_next_epoch_inner(
             StateIndex::new_for_next_epoch(
@@ -220,7 +231,7 @@ fn generate_random_state(
         .unwrap()
         .unwrap();
 
-    (state_manager, new_state, intermediate_padding, keys)
+    (state_manager, new_state, root_2.state_root, keys)
 }
 
 fn select_keys(
@@ -382,6 +393,59 @@ fn test_valid_state_proof_for_nonexistent_key() {
     }
 }
 
+#[test]
+fn test_verify_state_proof_offline() {
+    let mut rng = get_rng_for_test();
+
+    // note: do not drop state_manager (_mgr)
+    let (_mgr, state, prev_root, keys) =
+        generate_random_state_with_prev_snapshot_root(&mut rng);
+    let root = state.get_state_root().unwrap().state_root;
+    let nonexistent_keys = generate_nonexistent_keys(&mut rng, &keys);
+
+    for key in keys {
+        let (value, proof) = state
+            .get_with_proof(StorageKey::AccountKey(&key).with_native_space())
+            .expect("kv lookup should succeed");
+
+        // the proof is transferred in rlp
+        let proof: StateProof = rlp::decode(&rlp::encode(&proof)).unwrap();
+        let value = value.as_ref().map(|b| &**b);
+
+        assert!(verify_state_proof(
+            &key,
+            value,
+            &proof,
+            &root,
+            Some(&prev_root)
+        ));
+
+        // a wrong value should be rejected
+        assert!(!verify_state_proof(
+            &key,
+            Some(&[0x01][..]),
+            &proof,
+            &root,
+            Some(&prev_root)
+        ));
+    }
+
+    for key in nonexistent_keys {
+        let (value, proof) = state
+            .get_with_proof(StorageKey::AccountKey(&key).with_native_space())
+            .expect("kv lookup should succeed");
+
+        assert_eq!(value, None);
+        assert!(verify_state_proof(
+            &key,
+            None,
+            &proof,
+            &root,
+            Some(&prev_root)
+        ));
+    }
+}
+
 #[test]
 fn test_invalid_state_proof() {
     let mut rng = get_rng_for_test();
@@ -702,7 +766,7 @@ use crate::{
         new_state_manager_for_unit_test_with_snapshot_epoch_count,
         FakeStateManager, TEST_NUMBER_OF_KEYS,
     },
-    RecordingStorage,
+    verify_state_proof, RecordingStorage, StateProof,
 };
 use cfx_types::H256;
 use primitives::{
@@ -567,6 +567,62 @@ fn test_set_delete_all() {
     assert_eq!(state_root, empty_state_root);
 }
 
+#[test]
+fn test_iterate_all() {
+    let state_manager = new_state_manager_for_unit_test();
+    let addresses: Vec<Address> =
+        (0..100).map(Address::from_low_u64_be).collect();
+
+    let mut state = state_manager.get_state_for_genesis_write();
+    for address in &addresses {
+        state
+            .set(
+                StorageKey::new_account_key(address).with_native_space(),
+                address.as_bytes().into(),
+            )
+            .unwrap();
+    }
+    let mut epoch_id = H256::default();
+    epoch_id.as_bytes_mut()[0] = 1;
+    state.compute_state_root().unwrap();
+    state.commit(epoch_id).unwrap();
+
+    // Overwrite, delete and add keys in the next epoch.
+    let mut state = state_manager
+        .get_state_for_next_epoch(
+            StateIndex::new_for_test_only_delta_mpt(&epoch_id),
+            false,
+        )
+        .unwrap()
+        .unwrap();
+    let mut expected: HashMap<Vec<u8>, Box<[u8]>> = HashMap::new();
+    for (i, address) in addresses.iter().enumerate() {
+        let key = StorageKey::new_account_key(address).with_native_space();
+        match i % 3 {
+            0 => {
+                state.set(key, vec![1u8].into()).unwrap();
+                expected.insert(key.to_key_bytes(), vec![1u8].into());
+            }
+            1 => state.delete(key).unwrap(),
+            _ => {
+                expected.insert(key.to_key_bytes(), address.as_bytes().into());
+            }
+        }
+    }
+    let evm_key = StorageKey::new_account_key(&addresses[0]).with_evm_space();
+    state.set(evm_key, vec![2u8].into()).unwrap();
+    expected.insert(evm_key.to_key_bytes(), vec![2u8].into());
+
+    let mut visited = HashMap::new();
+    state
+        .iterate_all(&mut |(key, value)| {
+            assert!(visited.insert(key, value).is_none());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(visited, expected);
+}
+
 #[test]
 fn test_set_order() {
     let mut rng = get_rng_for_test();
@@ -744,6 +800,36 @@ fn test_set_order_concurrent() {
     }
 }
 
+#[test]
+fn test_partial_storage() {
+    let known = Address::from_low_u64_be(1);
+    let absent = Address::from_low_u64_be(2);
+    let unknown = Address::from_low_u64_be(3);
+    let key_of = |address: &Address| {
+        StorageKey::new_account_key(address).with_evm_space()
+    };
+
+    let mut entries = HashMap::new();
+    entries.insert(key_of(&known).to_key_bytes(), Some(vec![1u8].into()));
+    entries.insert(key_of(&absent).to_key_bytes(), None);
+    let mut state = PartialStorage::new(entries);
+    let missing_keys = state.missing_keys();
+
+    assert_eq!(state.get(key_of(&known)).unwrap(), Some(vec![1u8].into()));
+    assert_eq!(state.get(key_of(&absent)).unwrap(), None);
+    assert!(missing_keys.lock().is_empty());
+
+    assert_eq!(state.get(key_of(&unknown)).unwrap(), None);
+    assert_eq!(
+        missing_keys.lock().iter().cloned().collect::<Vec<_>>(),
+        vec![key_of(&unknown).to_key_bytes()]
+    );
+
+    state.set(key_of(&unknown), vec![3u8].into()).unwrap();
+    assert_eq!(state.get(key_of(&unknown)).unwrap(), Some(vec![3u8].into()));
+    assert!(state.iterate_all(&mut |_| Ok(())).is_err());
+}
+
 use crate::{
     state::*,
     state_manager::*,
@@ -751,7 +837,7 @@ use crate::{
         generate_keys, get_rng_for_test, new_state_manager_for_unit_test,
         FakeStateManager, TEST_NUMBER_OF_KEYS,
     },
-    StateRootWithAuxInfo,
+    PartialStorage, StateRootWithAuxInfo,
 };
 use cfx_types::{
     address_util::AddressUtil, Address, AddressSpaceUtil, H256, U256,
@@ -764,6 +850,7 @@ use rand::{
 };
 use rlp::Rlp;
 use std::{
+    collections::HashMap,
     sync::Arc,
     thread,
     time::{Duration, Instant},
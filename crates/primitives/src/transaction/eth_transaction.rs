@@ -1,10 +1,12 @@
 use crate::{
-    transaction::AccessList, Action, SignedTransaction, Transaction,
-    TransactionWithSignature, TransactionWithSignatureSerializePart,
+    hash::keccak, transaction::AccessList, Action, SignedTransaction,
+    Transaction, TransactionWithSignature,
+    TransactionWithSignatureSerializePart,
 };
 use bytes::Bytes;
-use cfx_types::{AddressWithSpace, H256, U256};
-use rlp::{Encodable, RlpStream};
+use cfx_types::{Address, AddressWithSpace, BigEndianHash, H256, U256};
+use keylib::{public_to_address, recover, Signature};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 use serde_derive::{Deserialize, Serialize};
 
 impl Eip155Transaction {
@@ -161,11 +163,124 @@ impl Encodable for Eip1559Transaction {
     }
 }
 
+/// The magic byte prepended to the signed message of an authorization, which
+/// prevents it from colliding with other signed messages.
+pub const EIP7702_AUTH_MAGIC: u8 = 0x05;
+
+/// An authorization of EIP-7702, which sets the code of the signer (the
+/// authority) to a delegation designator pointing to `address`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationListItem {
+    /// The chain id the authorization is valid on, or 0 for all chains.
+    pub chain_id: U256,
+    /// The delegated address. The zero address clears the delegation.
+    pub address: Address,
+    /// The expected nonce of the authority.
+    pub nonce: u64,
+    pub y_parity: u8,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl Encodable for AuthorizationListItem {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(6);
+        s.append(&self.chain_id);
+        s.append(&self.address);
+        s.append(&self.nonce);
+        s.append(&self.y_parity);
+        s.append(&self.r);
+        s.append(&self.s);
+    }
+}
+
+impl Decodable for AuthorizationListItem {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 6 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            address: rlp.val_at(1)?,
+            nonce: rlp.val_at(2)?,
+            y_parity: rlp.val_at(3)?,
+            r: rlp.val_at(4)?,
+            s: rlp.val_at(5)?,
+        })
+    }
+}
+
+impl AuthorizationListItem {
+    /// The hash signed by the authority, i.e.,
+    /// `keccak(MAGIC || rlp([chain_id, address, nonce]))`.
+    pub fn signature_hash(&self) -> H256 {
+        let mut s = RlpStream::new_list(3);
+        s.append(&self.chain_id);
+        s.append(&self.address);
+        s.append(&self.nonce);
+        let mut message = vec![EIP7702_AUTH_MAGIC];
+        message.extend_from_slice(s.as_raw());
+        keccak(&message)
+    }
+
+    /// Recovers the authority of the authorization. Returns `None` if the
+    /// signature is invalid, in which case the authorization is skipped.
+    pub fn authority(&self) -> Option<Address> {
+        if self.y_parity > 1 {
+            return None;
+        }
+        let r: H256 = BigEndianHash::from_uint(&self.r);
+        let s: H256 = BigEndianHash::from_uint(&self.s);
+        let signature = Signature::from_rsv(&r, &s, self.y_parity);
+        if !signature.is_low_s() {
+            return None;
+        }
+        let public = recover(&signature, &self.signature_hash()).ok()?;
+        Some(public_to_address(&public, /* type_nibble */ false))
+    }
+}
+
+pub type AuthorizationList = Vec<AuthorizationListItem>;
+
+/// The set-code transaction of EIP-7702. Unlike the other types, it can not
+/// create a contract, so its `action` is always a call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eip7702Transaction {
+    pub chain_id: u32,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas: U256,
+    pub action: Action,
+    pub value: U256,
+    pub data: Bytes,
+    pub access_list: AccessList,
+    pub authorization_list: AuthorizationList,
+}
+
+impl Encodable for Eip7702Transaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(10);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas);
+        s.append(&self.action);
+        s.append(&self.value);
+        s.append(&self.data);
+        s.append_list(&self.access_list);
+        s.append_list(&self.authorization_list);
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EthereumTransaction {
     Eip155(Eip155Transaction),
     Eip1559(Eip1559Transaction),
     Eip2930(Eip2930Transaction),
+    Eip7702(Eip7702Transaction),
 }
 use EthereumTransaction::*;
 
@@ -196,6 +311,7 @@ macro_rules! eth_access_common_ref {
                 EthereumTransaction::Eip155(tx) => &tx.$field,
                 EthereumTransaction::Eip2930(tx) => &tx.$field,
                 EthereumTransaction::Eip1559(tx) => &tx.$field,
+                EthereumTransaction::Eip7702(tx) => &tx.$field,
             }
         }
     };
@@ -217,6 +333,7 @@ impl EthereumTransaction {
             Eip155(tx) => &tx.gas_price,
             Eip1559(tx) => &tx.max_fee_per_gas,
             Eip2930(tx) => &tx.gas_price,
+            Eip7702(tx) => &tx.max_fee_per_gas,
         }
     }
 
@@ -225,6 +342,7 @@ impl EthereumTransaction {
             Eip155(tx) => &tx.gas_price,
             Eip1559(tx) => &tx.max_priority_fee_per_gas,
             Eip2930(tx) => &tx.gas_price,
+            Eip7702(tx) => &tx.max_priority_fee_per_gas,
         }
     }
 
@@ -233,6 +351,7 @@ impl EthereumTransaction {
             Eip155(tx) => tx.chain_id,
             Eip1559(tx) => Some(tx.chain_id),
             Eip2930(tx) => Some(tx.chain_id),
+            Eip7702(tx) => Some(tx.chain_id),
         }
     }
 
@@ -241,6 +360,7 @@ impl EthereumTransaction {
             Eip155(tx) => &mut tx.nonce,
             Eip2930(tx) => &mut tx.nonce,
             Eip1559(tx) => &mut tx.nonce,
+            Eip7702(tx) => &mut tx.nonce,
         }
     }
 
@@ -249,6 +369,14 @@ impl EthereumTransaction {
             Eip155(_tx) => None,
             Eip2930(tx) => Some(&tx.access_list),
             Eip1559(tx) => Some(&tx.access_list),
+            Eip7702(tx) => Some(&tx.access_list),
+        }
+    }
+
+    pub fn authorization_list(&self) -> Option<&AuthorizationList> {
+        match self {
+            Eip7702(tx) => Some(&tx.authorization_list),
+            _ => None,
         }
     }
 }
@@ -6,7 +6,8 @@ pub mod eth_transaction;
 pub mod native_transaction;
 
 pub use eth_transaction::{
-    Eip1559Transaction, Eip155Transaction, Eip2930Transaction,
+    AuthorizationList, AuthorizationListItem, Eip1559Transaction,
+    Eip155Transaction, Eip2930Transaction, Eip7702Transaction,
     EthereumTransaction,
 };
 pub use native_transaction::{
@@ -40,6 +41,7 @@ pub const TYPED_NATIVE_TX_PREFIX_BYTE: u8 = TYPED_NATIVE_TX_PREFIX[0];
 pub const LEGACY_TX_TYPE: u8 = 0x00;
 pub const EIP2930_TYPE: u8 = 0x01;
 pub const EIP1559_TYPE: u8 = 0x02;
+pub const EIP7702_TYPE: u8 = 0x04;
 pub const CIP2930_TYPE: u8 = 0x01;
 pub const CIP1559_TYPE: u8 = 0x02;
 
@@ -128,6 +130,8 @@ pub enum TransactionError {
     InvalidReceiver,
     /// Transaction nonce exceeds local limit.
     TooLargeNonce,
+    /// Set-code transaction without any authorization.
+    EmptyAuthorizationList,
 }
 
 impl From<keylib::Error> for TransactionError {
@@ -192,6 +196,7 @@ impl fmt::Display for TransactionError {
             FutureTransactionType => "Ethereum like transaction should have u64::MAX storage limit".into(),
             InvalidReceiver => "Sending transaction to invalid address. The first four bits of address must be 0x0, 0x1, or 0x8.".into(),
             TooLargeNonce => "Transaction nonce is too large.".into(),
+            EmptyAuthorizationList => "Set-code transaction with an empty authorization list".into(),
         };
 
         f.write_fmt(format_args!("Transaction error ({})", msg))
@@ -352,6 +357,8 @@ impl Transaction {
 
             Transaction::Native(TypedNativeTransaction::Cip1559(_))
             | Transaction::Ethereum(EthereumTransaction::Eip1559(_)) => 2,
+
+            Transaction::Ethereum(EthereumTransaction::Eip7702(_)) => 4,
         }
     }
 
@@ -370,6 +377,7 @@ impl Transaction {
             self,
             Transaction::Native(TypedNativeTransaction::Cip1559(_))
                 | Transaction::Ethereum(EthereumTransaction::Eip1559(_))
+                | Transaction::Ethereum(EthereumTransaction::Eip7702(_))
         )
     }
 
@@ -379,6 +387,13 @@ impl Transaction {
             Transaction::Ethereum(tx) => tx.access_list(),
         }
     }
+
+    pub fn authorization_list(&self) -> Option<&AuthorizationList> {
+        match self {
+            Transaction::Native(_tx) => None,
+            Transaction::Ethereum(tx) => tx.authorization_list(),
+        }
+    }
 }
 
 impl Transaction {
@@ -424,6 +439,10 @@ impl Transaction {
                 s.append(tx);
                 type_prefix.push(EIP2930_TYPE);
             }
+            Transaction::Ethereum(EthereumTransaction::Eip7702(tx)) => {
+                s.append(tx);
+                type_prefix.push(EIP7702_TYPE);
+            }
         };
         let encoded = s.as_raw();
         let mut out = vec![0; type_prefix.len() + encoded.len()];
@@ -551,6 +570,23 @@ impl Encodable for TransactionWithSignatureSerializePart {
                 s.append(&self.r);
                 s.append(&self.s);
             }
+            Transaction::Ethereum(EthereumTransaction::Eip7702(ref tx)) => {
+                s.append_raw(&[EIP7702_TYPE], 0);
+                s.begin_list(13);
+                s.append(&tx.chain_id);
+                s.append(&tx.nonce);
+                s.append(&tx.max_priority_fee_per_gas);
+                s.append(&tx.max_fee_per_gas);
+                s.append(&tx.gas);
+                s.append(&tx.action);
+                s.append(&tx.value);
+                s.append(&tx.data);
+                s.append_list(&tx.access_list);
+                s.append_list(&tx.authorization_list);
+                s.append(&self.v);
+                s.append(&self.r);
+                s.append(&self.s);
+            }
             Transaction::Native(TypedNativeTransaction::Cip2930(ref tx)) => {
                 s.append_raw(TYPED_NATIVE_TX_PREFIX, 0);
                 s.append_raw(&[CIP2930_TYPE], 0);
@@ -744,6 +780,37 @@ impl Decodable for TransactionWithSignatureSerializePart {
                         s,
                     })
                 }
+                EIP7702_TYPE => {
+                    let rlp = Rlp::new(&rlp.as_raw()[1..]);
+                    if rlp.item_count()? != 13 {
+                        return Err(DecoderError::RlpIncorrectListLen);
+                    }
+
+                    // A set-code transaction can not create a contract.
+                    let tx = Eip7702Transaction {
+                        chain_id: rlp.val_at(0)?,
+                        nonce: rlp.val_at(1)?,
+                        max_priority_fee_per_gas: rlp.val_at(2)?,
+                        max_fee_per_gas: rlp.val_at(3)?,
+                        gas: rlp.val_at(4)?,
+                        action: Action::Call(rlp.val_at(5)?),
+                        value: rlp.val_at(6)?,
+                        data: rlp.val_at(7)?,
+                        access_list: rlp.list_at(8)?,
+                        authorization_list: rlp.list_at(9)?,
+                    };
+                    let v = rlp.val_at(10)?;
+                    let r = rlp.val_at(11)?;
+                    let s = rlp.val_at(12)?;
+                    Ok(TransactionWithSignatureSerializePart {
+                        unsigned: Transaction::Ethereum(
+                            EthereumTransaction::Eip7702(tx),
+                        ),
+                        v,
+                        r,
+                        s,
+                    })
+                }
                 _ => Err(DecoderError::RlpInvalidLength),
             }
         }
@@ -5,6 +5,7 @@
 use crate::{
     report::{report_async, FileReporter, Reportable},
     report_influxdb::{InfluxdbReportable, InfluxdbReporter},
+    report_prometheus::{self, PrometheusReportable},
 };
 use std::{
     sync::atomic::{AtomicBool, Ordering},
@@ -19,7 +20,9 @@ pub fn is_enabled() -> bool { ENABLED.load(ORDER) }
 
 fn enable() { ENABLED.store(true, ORDER); }
 
-pub trait Metric: Send + Sync + Reportable + InfluxdbReportable {
+pub trait Metric:
+    Send + Sync + Reportable + InfluxdbReportable + PrometheusReportable
+{
     fn get_type(&self) -> &str;
 }
 
@@ -34,11 +37,13 @@ pub struct MetricsConfiguration {
     pub influxdb_report_username: Option<String>,
     pub influxdb_report_password: Option<String>,
     pub influxdb_report_node: Option<String>,
+
+    pub prometheus_listen_addr: Option<String>,
 }
 
-pub fn initialize(config: MetricsConfiguration) {
+pub fn initialize(config: MetricsConfiguration) -> std::io::Result<()> {
     if !config.enabled {
-        return;
+        return Ok(());
     }
 
     enable();
@@ -75,4 +80,11 @@ pub fn initialize(config: MetricsConfiguration) {
 
         report_async(reporter, config.report_interval);
     }
+
+    // prometheus reporter
+    if let Some(addr) = config.prometheus_listen_addr {
+        report_prometheus::serve_async(addr)?;
+    }
+
+    Ok(())
 }
@@ -0,0 +1,408 @@
+// Copyright 2024 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{
+    counter::{Counter, CounterUsize},
+    gauge::{Gauge, GaugeUsize},
+    histogram::Histogram,
+    meter::{Meter, StandardMeter},
+    metrics::is_enabled,
+    registry::{DEFAULT_GROUPING_REGISTRY, DEFAULT_REGISTRY},
+};
+use log::{debug, error, info};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+const METRICS_PATH: &str = "/metrics";
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+const REQUEST_TIMEOUT_SECONDS: u64 = 3;
+/// The connections beyond this limit are closed without a response.
+const MAX_CONCURRENT_SCRAPES: usize = 8;
+
+const QUANTILES: [(f64, &str); 6] = [
+    (0.5, "0.5"),
+    (0.75, "0.75"),
+    (0.9, "0.9"),
+    (0.95, "0.95"),
+    (0.99, "0.99"),
+    (0.999, "0.999"),
+];
+
+/// Serves the registered metrics in the Prometheus text format on
+/// `http://<addr>/metrics`, where the metrics are collected on each scrape.
+/// Returns the error if the address can't be bound.
+pub fn serve_async(addr: String) -> std::io::Result<()> {
+    if !is_enabled() {
+        return Ok(());
+    }
+
+    let listener = match TcpListener::bind(addr.as_str()) {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to serve prometheus metrics on {}: {}", addr, e);
+            return Err(e);
+        }
+    };
+    info!(
+        "Serving prometheus metrics on http://{}{}",
+        addr, METRICS_PATH
+    );
+
+    thread::spawn(move || {
+        // Each connection is served in its own thread, so that a slow client
+        // doesn't block the other scrapes.
+        let active = Arc::new(AtomicUsize::new(0));
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    debug!("failed to accept prometheus scrape, {:?}", e);
+                    continue;
+                }
+            };
+            if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONCURRENT_SCRAPES {
+                active.fetch_sub(1, Ordering::SeqCst);
+                debug!("too many prometheus scrapes, drop the connection");
+                continue;
+            }
+            let active = active.clone();
+            thread::spawn(move || {
+                if let Err(e) = handle_connection(stream) {
+                    debug!("failed to serve prometheus scrape, {:?}", e);
+                }
+                active.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream) -> std::io::Result<()> {
+    stream
+        .set_read_timeout(Some(Duration::from_secs(REQUEST_TIMEOUT_SECONDS)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(
+        REQUEST_TIMEOUT_SECONDS,
+    )))?;
+
+    // Only the request line is needed, and the body of a GET is ignored.
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n")
+        && request.len() < MAX_REQUEST_SIZE
+    {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next().unwrap_or("").split(' ');
+    let method = request_line.next().unwrap_or("");
+    let path = request_line.next().unwrap_or("");
+
+    let (status, content_type, body) = if method != "GET" {
+        ("405 Method Not Allowed", "text/plain", String::new())
+    } else if path != METRICS_PATH && path != "/" {
+        ("404 Not Found", "text/plain", String::new())
+    } else {
+        ("200 OK", CONTENT_TYPE, encode_registries())
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Encodes all the registered metrics. The metrics registered in a group are
+/// labeled with `group="<group name>"`.
+pub fn encode_registries() -> String {
+    let mut encoder = PrometheusEncoder::default();
+
+    for (name, metric) in DEFAULT_REGISTRY.read().get_all() {
+        metric.encode(&sanitize_name(name), &[], &mut encoder);
+    }
+
+    for (group_name, metrics) in DEFAULT_GROUPING_REGISTRY.read().get_all() {
+        let labels = [("group", group_name.as_str())];
+        for (metric_name, metric) in metrics {
+            metric.encode(&sanitize_name(metric_name), &labels, &mut encoder);
+        }
+    }
+
+    encoder.finish()
+}
+
+/// Collects the samples of the metrics by families, so that the samples of a
+/// family are written together after its `# TYPE` line.
+#[derive(Default)]
+pub struct PrometheusEncoder {
+    families: BTreeMap<String, Family>,
+}
+
+struct Family {
+    metric_type: &'static str,
+    samples: Vec<String>,
+}
+
+impl PrometheusEncoder {
+    /// Adds a sample named `family` + `suffix` to the given family. The
+    /// sample is dropped if the family was added with another type.
+    fn add_sample<V: std::fmt::Display>(
+        &mut self, family: &str, metric_type: &'static str, suffix: &str,
+        labels: &[(&str, &str)], value: V,
+    ) {
+        let entry =
+            self.families
+                .entry(family.into())
+                .or_insert_with(|| Family {
+                    metric_type,
+                    samples: Vec::new(),
+                });
+        if entry.metric_type != metric_type {
+            debug!(
+                "prometheus metric {} is both {} and {}",
+                family, entry.metric_type, metric_type
+            );
+            return;
+        }
+
+        let mut sample = format!("{}{}", family, suffix);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+                .collect();
+            let _ = write!(sample, "{{{}}}", labels.join(","));
+        }
+        let _ = write!(sample, " {}", value);
+        entry.samples.push(sample);
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        for (name, family) in self.families {
+            let _ = writeln!(out, "# TYPE {} {}", name, family.metric_type);
+            for sample in family.samples {
+                out.push_str(&sample);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// Metric names may only contain `[a-zA-Z0-9_:]` and not start with a digit.
+fn sanitize_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub trait PrometheusReportable {
+    fn encode(
+        &self, name: &str, labels: &[(&str, &str)],
+        encoder: &mut PrometheusEncoder,
+    );
+}
+
+impl PrometheusReportable for CounterUsize {
+    fn encode(
+        &self, name: &str, labels: &[(&str, &str)],
+        encoder: &mut PrometheusEncoder,
+    ) {
+        encoder.add_sample(name, "counter", "", labels, self.count());
+    }
+}
+
+impl PrometheusReportable for GaugeUsize {
+    fn encode(
+        &self, name: &str, labels: &[(&str, &str)],
+        encoder: &mut PrometheusEncoder,
+    ) {
+        encoder.add_sample(name, "gauge", "", labels, self.value());
+    }
+}
+
+/// A meter is a counter of the events, and a gauge of the event rates labeled
+/// by the `window`.
+impl PrometheusReportable for StandardMeter {
+    fn encode(
+        &self, name: &str, labels: &[(&str, &str)],
+        encoder: &mut PrometheusEncoder,
+    ) {
+        let snapshot = self.snapshot();
+        let total = format!("{}_total", name);
+        encoder.add_sample(&total, "counter", "", labels, snapshot.count());
+
+        let rate = format!("{}_rate", name);
+        let rates = [
+            ("m1", snapshot.rate1()),
+            ("m5", snapshot.rate5()),
+            ("m15", snapshot.rate15()),
+            ("mean", snapshot.rate_mean()),
+        ];
+        for (window, value) in rates {
+            let mut labels = labels.to_vec();
+            labels.push(("window", window));
+            encoder.add_sample(&rate, "gauge", "", &labels, value);
+        }
+    }
+}
+
+/// A histogram is a summary. The timers are reported as a meter and a
+/// histogram of nanoseconds in their group.
+impl<T: Histogram> PrometheusReportable for T {
+    fn encode(
+        &self, name: &str, labels: &[(&str, &str)],
+        encoder: &mut PrometheusEncoder,
+    ) {
+        let snapshot = self.snapshot();
+        for (quantile, quantile_label) in QUANTILES {
+            let mut labels = labels.to_vec();
+            labels.push(("quantile", quantile_label));
+            encoder.add_sample(
+                name,
+                "summary",
+                "",
+                &labels,
+                snapshot.percentile(quantile),
+            );
+        }
+        // The histogram only keeps the samples in the reservoir, so the sum
+        // is estimated from the mean.
+        let count = snapshot.count();
+        encoder.add_sample(
+            name,
+            "summary",
+            "_sum",
+            labels,
+            snapshot.mean() * count as f64,
+        );
+        encoder.add_sample(name, "summary", "_count", labels, count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        escape_label_value, handle_connection, sanitize_name,
+        PrometheusEncoder, PrometheusReportable,
+    };
+    use crate::{
+        counter::{Counter, CounterUsize},
+        gauge::{Gauge, GaugeUsize},
+    };
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("tx_pool:ready"), "tx_pool:ready");
+        assert_eq!(sanitize_name("sync.block-headers"), "sync_block_headers");
+        assert_eq!(sanitize_name("1m_rate"), "_1m_rate");
+        assert_eq!(sanitize_name("gas price"), "gas_price");
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("group"), "group");
+        assert_eq!(escape_label_value("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+
+    #[test]
+    fn test_encoder() {
+        let mut encoder = PrometheusEncoder::default();
+
+        let counter = CounterUsize::default();
+        counter.inc(3);
+        counter.encode("b_counter", &[("group", "sync")], &mut encoder);
+        let gauge = GaugeUsize::default();
+        gauge.update(7);
+        gauge.encode("a_gauge", &[], &mut encoder);
+        let gauge = GaugeUsize::default();
+        gauge.update(1);
+        gauge.encode("b_counter", &[("group", "other")], &mut encoder);
+        let counter = CounterUsize::default();
+        counter.encode("b_counter", &[("group", "\"quoted\"")], &mut encoder);
+
+        // The families are sorted by name, and the gauge sample of the counter
+        // family is dropped.
+        assert_eq!(
+            encoder.finish(),
+            "# TYPE a_gauge gauge\n\
+             a_gauge 7\n\
+             # TYPE b_counter counter\n\
+             b_counter{group=\"sync\"} 3\n\
+             b_counter{group=\"\\\"quoted\\\"\"} 0\n"
+        );
+    }
+
+    fn request(raw: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_connection(stream).unwrap();
+        });
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        client.write_all(raw.as_bytes()).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        server.join().unwrap();
+        response
+    }
+
+    #[test]
+    fn test_handle_connection() {
+        let response = request("GET /metrics HTTP/1.1\r\nHost: x\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+
+        let response = request("GET /other HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        let response = request("POST /metrics HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+    }
+}
@@ -125,6 +125,10 @@ impl TokenBucket {
         self.max_throttled_counter = max_throttled_counter;
     }
 
+    /// Returns the capacity of the bucket in CPU tokens, beyond which a cost
+    /// can never be acquired.
+    pub fn max_tokens(&self) -> u64 { self.cpu_tokens.max_tokens }
+
     fn refresh(&mut self, now: Instant) {
         let elapsed_secs = (now - self.last_update).as_secs();
         if elapsed_secs == 0 {
@@ -220,6 +224,12 @@ impl TokenBucket {
             }
         }
     }
+
+    /// Returns how long the bucket stays throttled, if it is throttled.
+    pub fn throttled_for(&self) -> Option<Duration> {
+        let until = self.throttled_until?;
+        until.checked_duration_since(Instant::now())
+    }
 }
 
 impl FromStr for TokenBucket {
@@ -416,9 +426,11 @@ mod tests {
 
         // already throttled
         assert_eq!(bucket.throttle(1, 1), ThrottleResult::AlreadyThrottled);
+        assert!(bucket.throttled_for().unwrap() <= Duration::from_secs(1));
 
         sleep(Duration::from_secs(1));
 
+        assert_eq!(bucket.throttled_for(), None);
         assert_eq!(bucket.throttle(1, 1), ThrottleResult::Success);
         assert_eq!(bucket.throttled_until, None);
         assert_eq!(bucket.throttled_counter, 0);
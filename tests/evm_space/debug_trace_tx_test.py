@@ -31,6 +31,10 @@ class DebugTraceTxTest(Web3Base):
         self.four_byte_tracer(erc20_transfer_hash)
         self.call_tracer(erc20_transfer_hash)
         self.check_opcode_trace_with_config(erc20_transfer_hash)
+        self.js_tracer(erc20_transfer_hash)
+        self.js_tracer_in_block_and_call(erc20_transfer_hash, erc20_addr)
+        self.invalid_js_tracer(erc20_transfer_hash, erc20_addr)
+        self.invalid_mux_tracer(erc20_transfer_hash, erc20_addr)
 
     def trace_tx(self, tx_hash, opts = None):
         trace = self.nodes[0].ethrpc.debug_traceTransaction(toHex(tx_hash), opts)
@@ -198,6 +202,85 @@ class DebugTraceTxTest(Web3Base):
 
 
 
+    JS_TRACER = """{
+        steps: 0,
+        ops: {},
+        step: function(log, db) {
+            this.steps++;
+            var op = log.op.toString();
+            this.ops[op] = (this.ops[op] || 0) + 1;
+        },
+        fault: function(log, db) {},
+        result: function(ctx, db) {
+            return {
+                steps: this.steps,
+                sstore: this.ops["SSTORE"] || 0,
+                type: ctx.type,
+                from: toHex(ctx.from),
+                gasUsed: ctx.gasUsed,
+                nonce: db.getNonce(ctx.from),
+            };
+        }
+    }"""
+
+    def js_tracer(self, tx_hash):
+        tx = self.w3.eth.get_transaction(tx_hash)
+        receipt = self.w3.eth.get_transaction_receipt(tx_hash)
+        trace = self.trace_tx(tx_hash, {"tracer": self.JS_TRACER})
+
+        # every instruction of the opcode logger is stepped
+        assert_equal(trace["steps"], 231)
+        # the balances of the sender and the receiver are written
+        assert_equal(trace["sstore"], 2)
+        assert_equal(trace["type"], "CALL")
+        assert_equal(trace["from"], self.evmAccount.address.lower())
+        assert_equal(trace["gasUsed"], receipt["gasUsed"])
+        # `db` reads the state after the transaction in `result`
+        assert_equal(trace["nonce"], tx["nonce"] + 1)
+
+    def js_tracer_in_block_and_call(self, tx_hash, erc20_address):
+        receipt = self.w3.eth.get_transaction_receipt(tx_hash)
+        traces = self.nodes[0].ethrpc.debug_traceBlockByNumber(hex(receipt["blockNumber"]), {"tracer": self.JS_TRACER})
+        assert_equal(len(traces), 1)
+        assert_equal(traces[0]["txHash"], toHex(tx_hash))
+        assert_equal(traces[0]["result"]["steps"], 231)
+
+        tx = self.w3.eth.get_transaction(tx_hash)
+        call_trace = self.nodes[0].ethrpc.debug_traceCall({
+            "from": self.evmAccount.address,
+            "to": erc20_address,
+            "data": tx["input"],
+        }, "latest", {"tracer": self.JS_TRACER})
+        assert_equal(call_trace["type"], "CALL")
+        assert_equal(call_trace["sstore"], 2)
+
+    def invalid_js_tracer(self, tx_hash, erc20_address):
+        receipt = self.w3.eth.get_transaction_receipt(tx_hash)
+        block_number = hex(receipt["blockNumber"])
+        missing_result = "{fault: function() {}}"
+        for call in [
+            lambda opts: self.trace_tx(tx_hash, opts),
+            lambda opts: self.nodes[0].ethrpc.debug_traceBlockByNumber(block_number, opts),
+            lambda opts: self.nodes[0].ethrpc.debug_traceCall({"to": erc20_address}, "latest", opts),
+        ]:
+            assert_raises_rpc_error(-32602, None, call, {"tracer": missing_result})
+            assert_raises_rpc_error(-32602, None, call, {"tracer": self.JS_TRACER, "timeout": "10"})
+
+        # a script never returning is interrupted at the timeout
+        endless = "{fault: function() {}, result: function() {}, step: function() { while (true) {} }}"
+        trace = self.trace_tx(tx_hash, {"tracer": endless, "timeout": "100ms"})
+        assert "execution timeout" in trace["error"], trace
+
+    def invalid_mux_tracer(self, tx_hash, erc20_address):
+        receipt = self.w3.eth.get_transaction_receipt(tx_hash)
+        block_number = hex(receipt["blockNumber"])
+        nested = {"tracer": "muxTracer", "tracerConfig": {"muxTracer": {"callTracer": None}}}
+        malformed = {"tracer": "muxTracer", "tracerConfig": {"callTracer": {"onlyTopCall": "yes"}}}
+        for opts in [nested, malformed]:
+            assert_raises_rpc_error(-32602, None, self.trace_tx, tx_hash, opts)
+            assert_raises_rpc_error(-32602, None, self.nodes[0].ethrpc.debug_traceBlockByNumber, block_number, opts)
+            assert_raises_rpc_error(-32602, None, self.nodes[0].ethrpc.debug_traceCall, {"to": erc20_address}, "latest", opts)
+
     def load_abi_from_contracts_folder(self, name):
         abi_file = os.path.join(os.path.dirname(os.path.realpath(__file__)), "..", "contracts", name + "_abi.json")
         with open(abi_file, 'r') as abi_file: